@@ -0,0 +1,83 @@
+//! times the recursion-heavy programs in `benches/programs` on each engine, by running the release build of beach
+//! on them. Run with `cargo bench`, optionally followed by part of a program's name to only run matching programs
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, Instant},
+};
+
+/// how many times each program is run on each engine
+const RUNS: u32 = 5;
+const ENGINES: [&str; 2] = ["tree", "vm"];
+
+fn main() {
+    // cargo passes `--bench` to benchmarks without a harness
+    let filter = env::args().skip(1).find(|arg| !arg.starts_with("--"));
+
+    let programs_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/programs");
+    let mut programs: Vec<PathBuf> = fs::read_dir(&programs_dir)
+        .expect("the benchmark programs should be readable")
+        .map(|entry| {
+            entry
+                .expect("the benchmark programs should be readable")
+                .path()
+        })
+        .filter(|path| path.extension().is_some_and(|extension| extension == "bch"))
+        .collect();
+    programs.sort();
+
+    println!(
+        "{:<16} {:<6} {:>10} {:>10}",
+        "program", "engine", "fastest", "mean"
+    );
+
+    for program in &programs {
+        let name = program.file_stem().unwrap().to_string_lossy();
+        if filter
+            .as_ref()
+            .is_some_and(|filter| !name.contains(filter.as_str()))
+        {
+            continue;
+        }
+
+        for engine in ENGINES {
+            let times: Vec<Duration> = (0..RUNS).map(|_| time_run(program, engine)).collect();
+            let fastest = times.iter().min().unwrap();
+            let mean = times.iter().sum::<Duration>() / RUNS;
+
+            println!(
+                "{:<16} {:<6} {:>8}ms {:>8}ms",
+                name,
+                engine,
+                fastest.as_millis(),
+                mean.as_millis()
+            );
+        }
+    }
+}
+
+/// how long beach takes to run `program` on `engine`, including loading and type checking it
+fn time_run(program: &Path, engine: &str) -> Duration {
+    let start = Instant::now();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_beach-lang"))
+        .arg("run")
+        .arg(format!("--engine={}", engine))
+        .arg(program)
+        .output()
+        .expect("beach should run");
+
+    let elapsed = start.elapsed();
+
+    assert!(
+        output.status.success(),
+        "{} failed on the {} engine: {}",
+        program.display(),
+        engine,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    elapsed
+}
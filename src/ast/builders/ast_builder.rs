@@ -1,15 +1,25 @@
 use std::collections::HashMap;
 
-use crate::ast::node::{Ast, Function, FunctionDeclaration, FunctionId, Node};
+use crate::ast::{
+    node::{
+        Ast, Constant, Function, FunctionDeclaration, FunctionId, Global, Node, Type, TypeAlias,
+    },
+    symbol::Symbol,
+};
 
 use super::{
-    function_declaration_builder::FunctionDeclarationBuilder, statement_builder::StatementBuilder,
+    constant_builder::ConstantBuilder, function_declaration_builder::FunctionDeclarationBuilder,
+    global_builder::GlobalBuilder, statement_builder::StatementBuilder,
 };
 
 #[derive(Default, Debug, PartialEq)]
 pub struct AstBuilder {
     pub(super) functions: Vec<FunctionDeclaration>,
+    pub(super) constants: Vec<Constant>,
+    pub(super) globals: Vec<Global>,
+    pub(super) type_aliases: Vec<TypeAlias>,
     pub(super) nodes: Vec<Node>,
+    pub(super) imports: Vec<String>,
 }
 
 impl AstBuilder {
@@ -29,20 +39,54 @@ impl AstBuilder {
         self
     }
 
+    pub fn constant(mut self, constant_fn: impl FnOnce(ConstantBuilder) -> Constant) -> AstBuilder {
+        self.constants.push(constant_fn(ConstantBuilder::default()));
+        self
+    }
+
+    pub fn global(mut self, global_fn: impl FnOnce(GlobalBuilder) -> Global) -> AstBuilder {
+        self.globals.push(global_fn(GlobalBuilder::default()));
+        self
+    }
+
+    pub fn type_alias(mut self, name: impl Into<Symbol>, aliased_type: Type) -> AstBuilder {
+        self.type_aliases.push(TypeAlias {
+            name: name.into(),
+            aliased_type: Box::new(aliased_type),
+        });
+        self
+    }
+
+    /// import the module at `path`, relative to the file being built
+    pub fn import(mut self, path: &str) -> AstBuilder {
+        self.imports.push(path.to_owned());
+        self
+    }
+
+    pub fn imports(&self) -> &[String] {
+        &self.imports
+    }
+
+    pub fn function_declarations(&self) -> &[FunctionDeclaration] {
+        &self.functions
+    }
+
     pub fn build(self) -> Ast {
         let functions: HashMap<FunctionId, Function> = self
             .functions
-            .iter()
-            .cloned()
+            .into_iter()
             .map(|function_declaration| {
                 (
-                    function_declaration.id.clone(),
+                    function_declaration.id,
                     Function::CustomFunction {
                         id: function_declaration.id,
                         name: function_declaration.name,
+                        type_parameters: function_declaration.type_parameters,
                         parameters: function_declaration.parameters,
                         return_type: function_declaration.return_type,
                         body: function_declaration.body,
+                        span: function_declaration.span,
+                        pure: function_declaration.pure,
                     },
                 )
             })
@@ -50,6 +94,9 @@ impl AstBuilder {
 
         Ast {
             functions,
+            constants: self.constants,
+            globals: self.globals,
+            type_aliases: self.type_aliases,
             nodes: self.nodes,
         }
     }
@@ -59,7 +106,7 @@ impl AstBuilder {
 mod tests {
     use std::collections::HashMap;
 
-    use crate::ast::node::{Ast, Function, FunctionId, FunctionReturnType, Node};
+    use crate::ast::node::{Ast, Function, FunctionId, FunctionReturnType, Node, Span};
 
     use super::AstBuilder;
 
@@ -71,7 +118,13 @@ mod tests {
 
         let expected = Ast {
             functions: HashMap::new(),
-            nodes: vec![Node::FunctionReturn { return_value: None }],
+            constants: Vec::new(),
+            globals: Vec::new(),
+            type_aliases: Vec::new(),
+            nodes: vec![Node::FunctionReturn {
+                return_value: None,
+                line: 0,
+            }],
         };
 
         assert_eq!(actual, expected);
@@ -91,18 +144,41 @@ mod tests {
 
         let expected = Ast {
             functions: HashMap::from_iter([(
-                FunctionId("my_function".to_owned()),
+                FunctionId("my_function".into()),
                 Function::CustomFunction {
-                    id: FunctionId("my_function".to_owned()),
-                    name: "my_function".to_owned(),
+                    id: FunctionId("my_function".into()),
+                    name: "my_function".into(),
+                    type_parameters: Vec::new(),
                     parameters: Vec::new(),
                     return_type: FunctionReturnType::Void,
                     body: Vec::new(),
+                    span: Span::default(),
+                    pure: false,
                 },
             )]),
+            constants: Vec::new(),
+            globals: Vec::new(),
+            type_aliases: Vec::new(),
             nodes: Vec::new(),
         };
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn import() {
+        let builder = AstBuilder::default().import("lib.bch").import("other.bch");
+
+        assert_eq!(builder.imports(), ["lib.bch", "other.bch"]);
+        assert_eq!(
+            builder.build(),
+            Ast {
+                functions: HashMap::new(),
+                constants: Vec::new(),
+                globals: Vec::new(),
+                type_aliases: Vec::new(),
+                nodes: Vec::new(),
+            }
+        );
+    }
 }
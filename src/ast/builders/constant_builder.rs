@@ -0,0 +1,74 @@
+use crate::ast::{
+    node::{Constant, Expression, Type},
+    symbol::Symbol,
+};
+
+use super::expression_builder::ExpressionBuilder;
+
+#[derive(Default)]
+pub struct ConstantBuilder {
+    name: Option<Symbol>,
+    const_type: Option<Type>,
+    line: u32,
+}
+
+impl ConstantBuilder {
+    pub fn name(mut self, name: impl Into<Symbol>) -> ConstantBuilder {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn const_type(mut self, const_type: Type) -> ConstantBuilder {
+        self.const_type = Some(const_type);
+        self
+    }
+
+    /// the line the declaration starts on
+    pub fn line(mut self, line: u32) -> ConstantBuilder {
+        self.line = line;
+        self
+    }
+
+    pub fn value(self, value_fn: impl FnOnce(ExpressionBuilder) -> Expression) -> Constant {
+        Constant {
+            name: self.name.expect("constant name to be set"),
+            const_type: self.const_type.expect("constant type to be set"),
+            value: value_fn(ExpressionBuilder {}),
+            line: self.line,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::node::{Constant, Type};
+
+    use super::ConstantBuilder;
+
+    #[test]
+    fn constant() {
+        let result = ConstantBuilder::default()
+            .name("LIMIT")
+            .const_type(Type::UInt)
+            .line(3)
+            .value(|expression| expression.value_literal(10.into()));
+
+        assert_eq!(
+            result,
+            Constant {
+                name: "LIMIT".into(),
+                const_type: Type::UInt,
+                value: 10.into(),
+                line: 3,
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn constant_missing_type() {
+        ConstantBuilder::default()
+            .name("LIMIT")
+            .value(|expression| expression.value_literal(10.into()));
+    }
+}
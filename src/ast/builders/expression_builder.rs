@@ -1,6 +1,12 @@
-use crate::ast::node::{Expression, FunctionCall, Operation, Value};
+use crate::ast::{
+    node::{Expression, FunctionCall, Lambda, Operation, Value},
+    symbol::Symbol,
+};
 
-use super::{function_call_builder::FunctionCallBuilder, operation_builder::OperationBuilder};
+use super::{
+    function_call_builder::FunctionCallBuilder, lambda_builder::LambdaBuilder,
+    operation_builder::OperationBuilder,
+};
 
 #[derive(Default)]
 pub struct ExpressionBuilder {}
@@ -15,8 +21,18 @@ impl ExpressionBuilder {
         Expression::FunctionCall(function_call)
     }
 
-    pub fn variable(self, variable_name: &str) -> Expression {
-        Expression::VariableAccess(variable_name.to_owned())
+    pub fn variable(self, variable_name: impl Into<Symbol>) -> Expression {
+        Expression::VariableAccess(variable_name.into())
+    }
+
+    /// a variable already resolved to the slot of its frame. Only the evaluator resolves slots, so nothing but its
+    /// tests builds one
+    #[cfg(test)]
+    pub fn local_variable(self, variable_name: impl Into<Symbol>, slot: usize) -> Expression {
+        Expression::LocalVariableAccess {
+            name: variable_name.into(),
+            slot,
+        }
     }
 
     pub fn value_literal(self, value: Value) -> Expression {
@@ -28,12 +44,40 @@ impl ExpressionBuilder {
 
         Expression::Operation(operation)
     }
+
+    pub fn lambda(self, lambda_fn: impl FnOnce(LambdaBuilder) -> Lambda) -> Expression {
+        Expression::Lambda(lambda_fn(LambdaBuilder::default()))
+    }
+
+    pub fn tuple(
+        self,
+        element_fns: Vec<Box<dyn FnOnce(ExpressionBuilder) -> Expression>>,
+    ) -> Expression {
+        Expression::Tuple(
+            element_fns
+                .into_iter()
+                .map(|element_fn| element_fn(ExpressionBuilder {}))
+                .collect(),
+        )
+    }
+
+    pub fn tuple_access(
+        self,
+        tuple_fn: impl FnOnce(ExpressionBuilder) -> Expression,
+        index: usize,
+    ) -> Expression {
+        Expression::TupleAccess {
+            tuple: Box::new(tuple_fn(ExpressionBuilder {})),
+            index,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::ast::node::{
-        Expression, FunctionCall, FunctionId, Operation, UnaryOperation,
+        Expression, FunctionCall, FunctionId, FunctionReturnType, Lambda, Operation, Span,
+        UnaryOperation,
     };
 
     use super::ExpressionBuilder;
@@ -48,18 +92,54 @@ mod tests {
         });
 
         let expected = Expression::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: Vec::new(),
+            line: 0,
+            slot: None,
         });
 
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn tuple() {
+        let actual = ExpressionBuilder::default()
+            .tuple(vec![Box::new(|_| 1.into()), Box::new(|_| true.into())]);
+
+        let expected = Expression::Tuple(vec![1.into(), true.into()]);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn tuple_access() {
+        let actual = ExpressionBuilder::default().tuple_access(|tuple| tuple.variable("pair"), 1);
+
+        let expected = Expression::TupleAccess {
+            tuple: Box::new(Expression::VariableAccess("pair".into())),
+            index: 1,
+        };
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn variable() {
         let actual = ExpressionBuilder::default().variable("var_name");
 
-        let expected = Expression::VariableAccess("var_name".to_owned());
+        let expected = Expression::VariableAccess("var_name".into());
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn local_variable() {
+        let actual = ExpressionBuilder::default().local_variable("var_name", 2);
+
+        let expected = Expression::LocalVariableAccess {
+            name: "var_name".into(),
+            slot: 2,
+        };
 
         assert_eq!(actual, expected);
     }
@@ -75,9 +155,8 @@ mod tests {
 
     #[test]
     fn operation() {
-        let actual = ExpressionBuilder::default().operation(|operation| {
-            operation.not(|not| not.value_literal(true.into()))
-        });
+        let actual = ExpressionBuilder::default()
+            .operation(|operation| operation.not(|not| not.value_literal(true.into())));
 
         let expected = Expression::Operation(Operation::Unary {
             operation: UnaryOperation::Not,
@@ -86,4 +165,24 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn lambda() {
+        let actual = ExpressionBuilder::default().lambda(|lambda| {
+            lambda
+                .parameters(Vec::new())
+                .void()
+                .body(|body| body.build())
+        });
+
+        let expected = Expression::Lambda(Lambda {
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Void,
+            body: Vec::new(),
+            span: Span::default(),
+            resolved: None,
+        });
+
+        assert_eq!(actual, expected);
+    }
 }
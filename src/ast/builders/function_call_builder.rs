@@ -1,4 +1,7 @@
-use crate::ast::node::{Expression, FunctionCall, FunctionId};
+use crate::ast::{
+    node::{Expression, FunctionCall, FunctionId},
+    symbol::Symbol,
+};
 
 use super::expression_builder::ExpressionBuilder;
 
@@ -6,11 +9,12 @@ use super::expression_builder::ExpressionBuilder;
 pub struct FunctionCallBuilder {
     pub function_id: Option<FunctionId>,
     pub parameters: Option<Vec<Expression>>,
+    pub line: u32,
 }
 
 impl FunctionCallBuilder {
-    pub fn function_id(mut self, function_id: &str) -> Self {
-        self.function_id = Some(FunctionId(function_id.to_owned()));
+    pub fn function_id(mut self, function_id: impl Into<Symbol>) -> Self {
+        self.function_id = Some(FunctionId(function_id.into()));
         self
     }
 
@@ -29,6 +33,12 @@ impl FunctionCallBuilder {
         self
     }
 
+    /// the line of the function's name
+    pub fn line(mut self, line: u32) -> Self {
+        self.line = line;
+        self
+    }
+
     pub fn no_parameters(mut self) -> Self {
         self.parameters = Some(Vec::new());
         self
@@ -38,6 +48,8 @@ impl FunctionCallBuilder {
         FunctionCall {
             function_id: self.function_id.expect("function id to be set"),
             parameters: self.parameters.expect("parameters to be set"),
+            line: self.line,
+            slot: None,
         }
     }
 }
@@ -56,8 +68,10 @@ mod tests {
             .build();
 
         let expected = FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: Vec::new(),
+            line: 0,
+            slot: None,
         };
 
         assert_eq!(result, expected)
@@ -72,8 +86,10 @@ mod tests {
             .build();
 
         let expected = FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: vec![true.into(), 10.into()],
+            line: 0,
+            slot: None,
         };
 
         assert_eq!(actual, expected);
@@ -1,5 +1,9 @@
-use crate::ast::node::{
-    Ast, FunctionDeclaration, FunctionId, FunctionParameter, FunctionReturnType, Node, Type,
+use crate::ast::{
+    node::{
+        Ast, FunctionDeclaration, FunctionId, FunctionParameter, FunctionReturnType, Node, Span,
+        Type,
+    },
+    symbol::Symbol,
 };
 
 use super::ast_builder::AstBuilder;
@@ -7,17 +11,26 @@ use super::ast_builder::AstBuilder;
 #[derive(Debug, PartialEq, Default)]
 pub struct FunctionDeclarationBuilder {
     pub(super) id: Option<FunctionId>,
-    pub(super) name: Option<String>,
+    pub(super) name: Option<Symbol>,
+    pub(super) type_parameters: Vec<Symbol>,
     pub(super) parameters: Option<Vec<FunctionParameter>>,
     pub(super) return_type: Option<FunctionReturnType>,
     pub(super) body: Option<Vec<Node>>,
+    pub(super) span: Span,
+    pub(super) pure: bool,
     // todo: local functions
 }
 
 impl FunctionDeclarationBuilder {
-    pub fn name(mut self, name: &str) -> Self {
-        self.id = Some(FunctionId(name.to_owned()));
-        self.name = Some(name.to_owned());
+    pub fn name(mut self, name: impl Into<Symbol>) -> Self {
+        let name = name.into();
+        self.id = Some(FunctionId(name));
+        self.name = Some(name);
+        self
+    }
+
+    pub fn type_parameters(mut self, type_parameters: Vec<Symbol>) -> Self {
+        self.type_parameters = type_parameters;
         self
     }
 
@@ -36,16 +49,34 @@ impl FunctionDeclarationBuilder {
         self
     }
 
+    /// the lines of the source file the declaration covers
+    pub fn span(mut self, start_line: u32, end_line: u32) -> Self {
+        self.span = Span {
+            start_line,
+            end_line,
+        };
+        self
+    }
+
+    /// declare the function pure, so the type checker verifies calling it has no side effects
+    pub fn pure(mut self) -> Self {
+        self.pure = true;
+        self
+    }
+
     pub fn body(mut self, builder: impl FnOnce(AstBuilder) -> Ast) -> FunctionDeclaration {
         self.body = Some(builder(AstBuilder::default()).nodes);
         FunctionDeclaration {
             id: self.id.expect("Function id should be set"),
             name: self.name.expect("function name should be set"),
+            type_parameters: self.type_parameters,
             parameters: self.parameters.expect("function parameters should be set"),
             return_type: self
                 .return_type
                 .expect("function return type should be set"),
             body: self.body.expect("function body should be set"),
+            span: self.span,
+            pure: self.pure,
         }
     }
 }
@@ -60,7 +91,7 @@ mod tests {
     fn function_declaration_parameters() {
         let result = FunctionDeclarationBuilder::default()
             .name("my_function")
-            .parameters(vec![(Type::Boolean, "param1".to_owned()).into()])
+            .parameters(vec![(Type::Boolean, "param1".into()).into()])
             .return_type(Type::UInt)
             .body(|builder: AstBuilder| {
                 builder
@@ -78,18 +109,22 @@ mod tests {
             });
 
         let expected = FunctionDeclaration {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: vec![FunctionParameter::FunctionParameter {
                 param_type: Type::Boolean,
-                param_name: "param1".to_owned(),
+                param_name: "param1".into(),
             }],
             return_type: FunctionReturnType::Type(Type::UInt),
             body: vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Type(Type::Boolean),
-                var_name: "my_var_name".to_owned(),
+                var_name: "my_var_name".into(),
                 value: true.into(),
+                line: 0,
             }],
+            span: Span::default(),
+            pure: false,
         };
 
         assert_eq!(result, expected);
@@ -109,13 +144,17 @@ mod tests {
             });
 
         let expected = FunctionDeclaration {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: Vec::new(),
             return_type: FunctionReturnType::Type(Type::UInt),
             body: vec![Node::FunctionReturn {
                 return_value: Some(10.into()),
+                line: 0,
             }],
+            span: Span::default(),
+            pure: false,
         };
 
         assert_eq!(actual, expected);
@@ -130,11 +169,17 @@ mod tests {
             .body(|body| body.statement(|statement| statement.return_void()).build());
 
         let expected = FunctionDeclaration {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: Vec::new(),
             return_type: FunctionReturnType::Void,
-            body: vec![Node::FunctionReturn { return_value: None }],
+            body: vec![Node::FunctionReturn {
+                return_value: None,
+                line: 0,
+            }],
+            span: Span::default(),
+            pure: false,
         };
 
         assert_eq!(actual, expected);
@@ -0,0 +1,93 @@
+use crate::ast::{
+    node::{Expression, Global, Type},
+    symbol::Symbol,
+};
+
+use super::expression_builder::ExpressionBuilder;
+
+#[derive(Default)]
+pub struct GlobalBuilder {
+    name: Option<Symbol>,
+    global_type: Option<Type>,
+    mutable: bool,
+    line: u32,
+}
+
+impl GlobalBuilder {
+    pub fn name(mut self, name: impl Into<Symbol>) -> GlobalBuilder {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn global_type(mut self, global_type: Type) -> GlobalBuilder {
+        self.global_type = Some(global_type);
+        self
+    }
+
+    pub fn mutable(mut self) -> GlobalBuilder {
+        self.mutable = true;
+        self
+    }
+
+    /// the line the declaration starts on
+    pub fn line(mut self, line: u32) -> GlobalBuilder {
+        self.line = line;
+        self
+    }
+
+    pub fn value(self, value_fn: impl FnOnce(ExpressionBuilder) -> Expression) -> Global {
+        Global {
+            name: self.name.expect("global name to be set"),
+            global_type: self.global_type.expect("global type to be set"),
+            mutable: self.mutable,
+            value: value_fn(ExpressionBuilder {}),
+            line: self.line,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::node::{Global, Type};
+
+    use super::GlobalBuilder;
+
+    #[test]
+    fn global() {
+        let result = GlobalBuilder::default()
+            .name("count")
+            .global_type(Type::UInt)
+            .line(2)
+            .value(|expression| expression.value_literal(0.into()));
+
+        assert_eq!(
+            result,
+            Global {
+                name: "count".into(),
+                global_type: Type::UInt,
+                mutable: false,
+                value: 0.into(),
+                line: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn mutable_global() {
+        let result = GlobalBuilder::default()
+            .name("count")
+            .global_type(Type::UInt)
+            .mutable()
+            .value(|expression| expression.value_literal(0.into()));
+
+        assert!(result.mutable);
+    }
+
+    #[test]
+    #[should_panic]
+    fn global_missing_name() {
+        GlobalBuilder::default()
+            .global_type(Type::UInt)
+            .value(|expression| expression.value_literal(0.into()));
+    }
+}
@@ -7,6 +7,7 @@ pub struct IfStatementBuilder {
     body: Option<Ast>,
     else_if_blocks: Vec<(Expression, Ast)>,
     else_block: Option<Ast>,
+    line: u32,
 }
 
 impl IfStatementBuilder {
@@ -16,9 +17,17 @@ impl IfStatementBuilder {
             check_expression: None,
             else_if_blocks: Vec::new(),
             else_block: None,
+            line: 0,
         }
     }
 
+    /// the line of the `if` keyword
+    pub fn line(mut self, line: u32) -> Self {
+        self.line = line;
+
+        self
+    }
+
     pub fn check_expression(
         mut self,
         expression_fn: impl FnOnce(ExpressionBuilder) -> Expression,
@@ -70,6 +79,7 @@ impl IfStatementBuilder {
                 })
                 .collect(),
             else_block: self.else_block.map(|ast| ast.nodes),
+            line: self.line,
         })
     }
 }
@@ -101,11 +111,14 @@ mod tests {
         let expected = Node::IfStatement(IfStatement {
             check_expression: true.into(),
             if_block: vec![Node::FunctionCall(FunctionCall {
-                function_id: FunctionId("my_function".to_owned()),
+                function_id: FunctionId("my_function".into()),
                 parameters: Vec::new(),
+                line: 0,
+                slot: None,
             })],
             else_block: None,
             else_if_blocks: Vec::new(),
+            line: 0,
         });
 
         assert_eq!(actual, expected);
@@ -145,17 +158,22 @@ mod tests {
         let expected = Node::IfStatement(IfStatement {
             check_expression: true.into(),
             if_block: vec![Node::FunctionCall(FunctionCall {
-                function_id: FunctionId("my_function".to_owned()),
+                function_id: FunctionId("my_function".into()),
                 parameters: Vec::new(),
+                line: 0,
+                slot: None,
             })],
             else_block: None,
             else_if_blocks: vec![ElseIfBlock {
                 check: true.into(),
                 block: vec![Node::FunctionCall(FunctionCall {
-                    function_id: FunctionId("my_function".to_owned()),
+                    function_id: FunctionId("my_function".into()),
                     parameters: Vec::new(),
+                    line: 0,
+                    slot: None,
                 })],
             }],
+            line: 0,
         });
 
         assert_eq!(actual, expected);
@@ -192,14 +210,19 @@ mod tests {
         let expected = Node::IfStatement(IfStatement {
             check_expression: true.into(),
             if_block: vec![Node::FunctionCall(FunctionCall {
-                function_id: FunctionId("my_function".to_owned()),
+                function_id: FunctionId("my_function".into()),
                 parameters: Vec::new(),
+                line: 0,
+                slot: None,
             })],
             else_block: Some(vec![Node::FunctionCall(FunctionCall {
-                function_id: FunctionId("my_function".to_owned()),
+                function_id: FunctionId("my_function".into()),
                 parameters: Vec::new(),
+                line: 0,
+                slot: None,
             })]),
             else_if_blocks: Vec::new(),
+            line: 0,
         });
 
         assert_eq!(actual, expected);
@@ -0,0 +1,98 @@
+use crate::ast::node::{Ast, FunctionParameter, FunctionReturnType, Lambda, Span, Type};
+
+use super::ast_builder::AstBuilder;
+
+#[derive(Debug, PartialEq, Default)]
+pub struct LambdaBuilder {
+    parameters: Option<Vec<FunctionParameter>>,
+    return_type: Option<FunctionReturnType>,
+    span: Span,
+}
+
+impl LambdaBuilder {
+    pub fn parameters(mut self, parameters: Vec<FunctionParameter>) -> Self {
+        self.parameters = Some(parameters);
+        self
+    }
+
+    pub fn return_type(mut self, return_type: Type) -> Self {
+        self.return_type = Some(FunctionReturnType::Type(return_type));
+        self
+    }
+
+    pub fn void(mut self) -> Self {
+        self.return_type = Some(FunctionReturnType::Void);
+        self
+    }
+
+    /// the lines of the source file the lambda covers
+    pub fn span(mut self, start_line: u32, end_line: u32) -> Self {
+        self.span = Span {
+            start_line,
+            end_line,
+        };
+        self
+    }
+
+    pub fn body(self, builder: impl FnOnce(AstBuilder) -> Ast) -> Lambda {
+        Lambda {
+            parameters: self.parameters.expect("lambda parameters should be set"),
+            return_type: self.return_type.expect("lambda return type should be set"),
+            body: builder(AstBuilder::default()).nodes,
+            span: self.span,
+            resolved: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::node::{Expression, Node};
+
+    use super::*;
+
+    #[test]
+    fn lambda_parameters() {
+        let actual = LambdaBuilder::default()
+            .parameters(vec![(Type::UInt, "value".into()).into()])
+            .return_type(Type::UInt)
+            .body(|body| {
+                body.statement(|statement| statement.return_value(|value| value.variable("value")))
+                    .build()
+            });
+
+        let expected = Lambda {
+            parameters: vec![FunctionParameter::FunctionParameter {
+                param_type: Type::UInt,
+                param_name: "value".into(),
+            }],
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![Node::FunctionReturn {
+                return_value: Some(Expression::VariableAccess("value".into())),
+                line: 0,
+            }],
+            span: Span::default(),
+            resolved: None,
+        };
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn lambda_void() {
+        let actual = LambdaBuilder::default()
+            .parameters(Vec::new())
+            .void()
+            .body(|body| body.build());
+
+        let expected = Lambda {
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Void,
+            body: Vec::new(),
+            span: Span::default(),
+            resolved: None,
+        };
+
+        assert_eq!(actual, expected);
+    }
+}
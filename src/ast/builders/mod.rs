@@ -1,8 +1,11 @@
 pub mod ast_builder;
+pub mod constant_builder;
 pub mod expression_builder;
 pub mod function_call_builder;
 pub mod function_declaration_builder;
+pub mod global_builder;
 pub mod if_statement_builder;
+pub mod lambda_builder;
 pub mod operation_builder;
 pub mod statement_builder;
 pub mod variable_declaration_builder;
@@ -1,4 +1,7 @@
-use crate::ast::node::{Expression, FunctionCall, Node};
+use crate::ast::{
+    node::{Expression, FunctionCall, Node},
+    symbol::Symbol,
+};
 
 use super::{
     expression_builder::ExpressionBuilder, function_call_builder::FunctionCallBuilder,
@@ -7,11 +10,22 @@ use super::{
 };
 
 #[derive(Default)]
-pub struct StatementBuilder {}
+pub struct StatementBuilder {
+    line: u32,
+}
 
 impl StatementBuilder {
+    /// the line of the source file the statement starts on
+    pub fn line(mut self, line: u32) -> Self {
+        self.line = line;
+        self
+    }
+
     pub fn return_void(self) -> Node {
-        Node::FunctionReturn { return_value: None }
+        Node::FunctionReturn {
+            return_value: None,
+            line: self.line,
+        }
     }
 
     pub fn var_declaration(
@@ -19,25 +33,40 @@ impl StatementBuilder {
         var_declaration_fn: impl FnOnce(VariableDeclarationBuilder) -> Node,
     ) -> Node {
         var_declaration_fn(VariableDeclarationBuilder {
-            var_name: None,
-            var_type: None,
+            line: self.line,
+            ..VariableDeclarationBuilder::default()
         })
     }
 
     pub fn if_statement(self, if_statement_fn: impl FnOnce(IfStatementBuilder) -> Node) -> Node {
-        if_statement_fn(IfStatementBuilder::new())
+        if_statement_fn(IfStatementBuilder::new().line(self.line))
     }
 
     pub fn function_call(
         self,
         function_call_fn: impl FnOnce(FunctionCallBuilder) -> FunctionCall,
     ) -> Node {
-        Node::FunctionCall(function_call_fn(FunctionCallBuilder::default()))
+        Node::FunctionCall(function_call_fn(
+            FunctionCallBuilder::default().line(self.line),
+        ))
     }
 
     pub fn return_value(self, expression: impl FnOnce(ExpressionBuilder) -> Expression) -> Node {
         Node::FunctionReturn {
             return_value: Some(expression(ExpressionBuilder {})),
+            line: self.line,
+        }
+    }
+
+    pub fn assignment(
+        self,
+        var_name: impl Into<Symbol>,
+        value: impl FnOnce(ExpressionBuilder) -> Expression,
+    ) -> Node {
+        Node::Assignment {
+            var_name: var_name.into(),
+            value: value(ExpressionBuilder {}),
+            line: self.line,
         }
     }
 }
@@ -60,8 +89,9 @@ mod tests {
 
         let expected = Node::VariableDeclaration {
             var_type: crate::ast::node::VariableDeclarationType::Infer,
-            var_name: "my_var".to_owned(),
+            var_name: "my_var".into(),
             value: true.into(),
+            line: 0,
         };
 
         assert_eq!(actual, expected);
@@ -81,6 +111,7 @@ mod tests {
             if_block: Vec::new(),
             else_if_blocks: Vec::new(),
             else_block: None,
+            line: 0,
         });
 
         assert_eq!(actual, expected);
@@ -96,8 +127,10 @@ mod tests {
         });
 
         let expected = Node::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: Vec::new(),
+            line: 0,
+            slot: None,
         });
 
         assert_eq!(actual, expected);
@@ -110,6 +143,7 @@ mod tests {
 
         let expected = Node::FunctionReturn {
             return_value: Some(true.into()),
+            line: 0,
         };
 
         assert_eq!(actual, expected);
@@ -119,7 +153,24 @@ mod tests {
     fn return_void() {
         let actual = StatementBuilder::default().return_void();
 
-        let expected = Node::FunctionReturn { return_value: None };
+        let expected = Node::FunctionReturn {
+            return_value: None,
+            line: 0,
+        };
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn assignment() {
+        let actual =
+            StatementBuilder::default().assignment("count", |value| value.value_literal(1.into()));
+
+        let expected = Node::Assignment {
+            var_name: "count".into(),
+            value: 1.into(),
+            line: 0,
+        };
 
         assert_eq!(actual, expected);
     }
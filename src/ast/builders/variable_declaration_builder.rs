@@ -1,11 +1,16 @@
-use crate::ast::node::{Expression, Node, Type, VariableDeclarationType};
+use crate::ast::{
+    node::{Expression, Node, Type, VariableDeclarationType},
+    symbol::Symbol,
+};
 
 use super::expression_builder::ExpressionBuilder;
 
 #[derive(Default)]
 pub struct VariableDeclarationBuilder {
-    pub(super) var_name: Option<String>,
+    pub(super) var_name: Option<Symbol>,
+    pub(super) var_names: Option<Vec<Symbol>>,
     pub(super) var_type: Option<VariableDeclarationType>,
+    pub(super) line: u32,
 }
 
 impl VariableDeclarationBuilder {
@@ -19,8 +24,17 @@ impl VariableDeclarationBuilder {
         self
     }
 
-    pub fn name(mut self, name: &str) -> VariableDeclarationBuilder {
-        self.var_name = Some(name.to_owned());
+    pub fn name(mut self, name: impl Into<Symbol>) -> VariableDeclarationBuilder {
+        self.var_name = Some(name.into());
+        self
+    }
+
+    /// declare a variable for each element of the tuple assigned, rather than a single variable
+    pub fn destructure<T: Into<Symbol> + Copy>(
+        mut self,
+        names: &[T],
+    ) -> VariableDeclarationBuilder {
+        self.var_names = Some(names.iter().map(|name| (*name).into()).collect());
         self
     }
 
@@ -28,24 +42,34 @@ impl VariableDeclarationBuilder {
         self,
         value_fn: TExpressionFn,
     ) -> Node {
-        let var_name = self.var_name.expect(
-            "variable declaration name is None, builder should not be able to get to this point",
-        );
         let var_type = self.var_type.expect(
             "Variable declaration type is None, builder should not be able to get to this point",
         );
 
+        if let Some(var_names) = self.var_names {
+            return Node::DestructuringDeclaration {
+                var_type,
+                var_names,
+                value: value_fn(ExpressionBuilder {}),
+                line: self.line,
+            };
+        }
+
+        let var_name = self.var_name.expect(
+            "variable declaration name is None, builder should not be able to get to this point",
+        );
+
         Node::VariableDeclaration {
             var_type,
             var_name,
             value: value_fn(ExpressionBuilder {}),
+            line: self.line,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    
 
     use super::*;
     #[test]
@@ -57,8 +81,26 @@ mod tests {
 
         let expected = Node::VariableDeclaration {
             var_type: VariableDeclarationType::Type(Type::Boolean),
-            var_name: "my_var_name".to_owned(),
+            var_name: "my_var_name".into(),
             value: true.into(),
+            line: 0,
+        };
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn destructuring_declaration() {
+        let result = VariableDeclarationBuilder::default()
+            .infer_type()
+            .destructure(&["first", "second"])
+            .with_assignment(|expression_builder| expression_builder.variable("pair"));
+
+        let expected = Node::DestructuringDeclaration {
+            var_type: VariableDeclarationType::Infer,
+            var_names: vec!["first".into(), "second".into()],
+            value: Expression::VariableAccess("pair".into()),
+            line: 0,
         };
 
         assert_eq!(result, expected);
@@ -73,8 +115,9 @@ mod tests {
 
         let expected = Node::VariableDeclaration {
             var_type: VariableDeclarationType::Infer,
-            var_name: "my_var_name".to_owned(),
+            var_name: "my_var_name".into(),
             value: true.into(),
+            line: 0,
         };
 
         assert_eq!(result, expected);
@@ -1,2 +1,5 @@
 pub mod builders;
 pub mod node;
+pub mod printer;
+pub mod scope;
+pub mod symbol;
@@ -1,17 +1,53 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
+
+use super::symbol::Symbol;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Node {
     VariableDeclaration {
         var_type: VariableDeclarationType,
-        var_name: String,
+        var_name: Symbol,
         value: Expression,
+        line: u32,
     },
     FunctionReturn {
         return_value: Option<Expression>,
+        line: u32,
     },
     FunctionCall(FunctionCall),
     IfStatement(IfStatement),
+    /// assign a new value to a mutable global variable, ie `count = count + 1;`
+    Assignment {
+        var_name: Symbol,
+        value: Expression,
+        line: u32,
+    },
+    /// declare a variable for each element of a tuple, ie `infer (quotient, remainder) = divide(7, 2);`
+    DestructuringDeclaration {
+        var_type: VariableDeclarationType,
+        var_names: Vec<Symbol>,
+        value: Expression,
+        line: u32,
+    },
+}
+
+impl Node {
+    /// the line of the source file the statement starts on, 0 when it wasn't parsed from a source file
+    pub fn line(&self) -> u32 {
+        match self {
+            Node::VariableDeclaration { line, .. }
+            | Node::FunctionReturn { line, .. }
+            | Node::Assignment { line, .. }
+            | Node::DestructuringDeclaration { line, .. }
+            | Node::FunctionCall(FunctionCall { line, .. })
+            | Node::IfStatement(IfStatement { line, .. }) => *line,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -20,6 +56,8 @@ pub struct IfStatement {
     pub if_block: Vec<Node>,
     pub else_if_blocks: Vec<ElseIfBlock>,
     pub else_block: Option<Vec<Node>>,
+    /// the line of the `if` keyword, 0 when the statement wasn't parsed from a source file
+    pub line: u32,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -31,13 +69,25 @@ pub struct ElseIfBlock {
 #[derive(Clone, Debug, PartialEq)]
 pub struct FunctionDeclaration {
     pub id: FunctionId,
-    pub name: String,
+    pub name: Symbol,
+    pub type_parameters: Vec<Symbol>,
     pub parameters: Vec<FunctionParameter>,
     pub return_type: FunctionReturnType,
     pub body: Vec<Node>,
+    pub span: Span,
+    /// whether the function is declared `pure`, which the type checker verifies
+    pub pure: bool,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// the lines of the source file a declaration covers, from its first line to the line of its closing brace.
+/// Both are 0 when it wasn't parsed from a source file
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Span {
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum FunctionReturnType {
     Type(Type),
     Void,
@@ -47,24 +97,26 @@ pub enum FunctionReturnType {
 pub enum FunctionParameter {
     FunctionParameter {
         param_type: Type,
-        param_name: String,
-    },
-    IntrinsicAny {
-        param_name: String,
+        param_name: Symbol,
     },
 }
 
 impl FunctionParameter {
-    pub fn name(&self) -> &str {
+    pub fn name(&self) -> Symbol {
+        match self {
+            FunctionParameter::FunctionParameter { param_name, .. } => *param_name,
+        }
+    }
+
+    pub fn param_type(&self) -> &Type {
         match self {
-            FunctionParameter::IntrinsicAny { param_name }
-            | FunctionParameter::FunctionParameter { param_name, .. } => &param_name,
+            FunctionParameter::FunctionParameter { param_type, .. } => param_type,
         }
     }
 }
 
-impl From<(Type, String)> for FunctionParameter {
-    fn from((param_type, name): (Type, String)) -> Self {
+impl From<(Type, Symbol)> for FunctionParameter {
+    fn from((param_type, name): (Type, Symbol)) -> Self {
         FunctionParameter::FunctionParameter {
             param_name: name,
             param_type,
@@ -77,7 +129,40 @@ pub enum Expression {
     ValueLiteral(Value),
     FunctionCall(FunctionCall),
     Operation(Operation),
-    VariableAccess(String),
+    VariableAccess(Symbol),
+    /// a local variable, resolved to its slot in the running function's frame. Variable accesses are only resolved
+    /// just before the program is evaluated
+    LocalVariableAccess {
+        name: Symbol,
+        slot: usize,
+    },
+    Lambda(Lambda),
+    /// a tuple literal, ie `(1, true)`
+    Tuple(Vec<Expression>),
+    /// access an element of a tuple by its position, ie `pair.0`
+    TupleAccess {
+        tuple: Box<Expression>,
+        index: usize,
+    },
+}
+
+/// An anonymous function expression, ie `function(uint value) -> uint { return value; }`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lambda {
+    pub parameters: Vec<FunctionParameter>,
+    pub return_type: FunctionReturnType,
+    pub body: Vec<Node>,
+    pub span: Span,
+    /// the function the lambda evaluates to, once the evaluator has resolved it. The body is moved into the function
+    pub resolved: Option<ResolvedLambda>,
+}
+
+/// a lambda resolved to slots, so evaluating it shares its function rather than building a new one
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedLambda {
+    pub function: Rc<Function>,
+    /// the slots of the enclosing frame's variables the lambda uses, which are the only ones it captures
+    pub captures: Vec<usize>,
 }
 
 impl From<bool> for Expression {
@@ -96,14 +181,19 @@ impl From<u32> for Expression {
 pub struct FunctionCall {
     pub function_id: FunctionId,
     pub parameters: Vec<Expression>,
+    /// the line of the function's name, 0 when the call wasn't parsed from a source file
+    pub line: u32,
+    /// the slot of the local variable holding the called function, once the evaluator has resolved it. `None` calls a
+    /// global, a constant or a declared function
+    pub slot: Option<usize>,
 }
 
-#[derive(Debug, PartialEq, Hash, Clone, Eq)]
-pub struct FunctionId(pub String);
+#[derive(Debug, PartialEq, Hash, Clone, Copy, Eq)]
+pub struct FunctionId(pub Symbol);
 
 impl Display for FunctionId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+        Display::fmt(&self.0, f)
     }
 }
 
@@ -111,6 +201,17 @@ impl Display for FunctionId {
 pub enum Value {
     UInt(UIntValue),
     Boolean(BoolValue),
+    Function(FunctionValue),
+    Tuple(Vec<Value>),
+}
+
+/// A function used as a value, along with the local variables it captured when it was created. The function is shared
+/// with the program's declarations, so passing it around doesn't copy its body
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionValue {
+    pub function: Rc<Function>,
+    /// the names and values of the local variables the function uses from the scope it was created in, in slot order
+    pub captured_variables: Vec<(Symbol, Value)>,
 }
 
 impl From<bool> for Value {
@@ -126,22 +227,20 @@ impl From<u32> for Value {
 }
 
 impl Value {
-    pub fn expect_uint(self, expect_message: &str) -> UIntValue {
-        if let Self::UInt(uint_value) = self {
-            Some(uint_value)
-        } else {
-            None
+    /// the uint value, or `expect_message` along with the value found if it isn't a uint
+    pub fn expect_uint(self, expect_message: &str) -> Result<UIntValue, String> {
+        match self {
+            Self::UInt(uint_value) => Ok(uint_value),
+            other => Err(format!("{}, but found {:?}", expect_message, other)),
         }
-        .expect(expect_message)
     }
 
-    pub fn expect_bool(self, expect_message: &str) -> BoolValue {
-        if let Self::Boolean(bool_value) = self {
-            Some(bool_value)
-        } else {
-            None
+    /// the boolean value, or `expect_message` along with the value found if it isn't a boolean
+    pub fn expect_bool(self, expect_message: &str) -> Result<BoolValue, String> {
+        match self {
+            Self::Boolean(bool_value) => Ok(bool_value),
+            other => Err(format!("{}, but found {:?}", expect_message, other)),
         }
-        .expect(expect_message)
     }
 }
 
@@ -151,10 +250,96 @@ pub enum VariableDeclarationType {
     Type(Type),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// Types are compared by what they resolve to, so an alias is equal to the type it aliases
+#[derive(Clone, Debug)]
 pub enum Type {
     UInt,
     Boolean,
+    Function(FunctionType),
+    /// a fixed size list of values of different types, ie `(uint, boolean)`
+    Tuple(Vec<Type>),
+    /// a type parameter of a generic function, ie the `T` in `function identity<T>(T value) -> T`
+    Generic(Symbol),
+    /// a type referred to by name, which is replaced by the type alias it names when the module is loaded
+    Named(Symbol),
+    /// a type declared with a type alias, ie `Count` after `type Count = uint;`
+    Alias(TypeAlias),
+    /// the type of an expression that failed to type check. The mistake has already been reported, so a value of this
+    /// type is accepted anywhere, rather than causing more errors wherever it is used
+    Unknown,
+}
+
+/// A type alias declaration, ie `type Count = uint;`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypeAlias {
+    pub name: Symbol,
+    pub aliased_type: Box<Type>,
+}
+
+impl Type {
+    /// the type with any aliases replaced by the type they alias
+    pub fn resolved(&self) -> &Type {
+        match self {
+            Type::Alias(alias) => alias.aliased_type.resolved(),
+            _ => self,
+        }
+    }
+
+    /// whether the type is, or is made up of, the type of an expression that failed to type check
+    pub fn contains_unknown(&self) -> bool {
+        match self.resolved() {
+            Type::Unknown => true,
+            Type::Function(function_type) => {
+                function_type.parameters.iter().any(Type::contains_unknown)
+                    || matches!(function_type.return_type.as_ref(), FunctionReturnType::Type(return_type) if return_type.contains_unknown())
+            }
+            Type::Tuple(element_types) => element_types.iter().any(Type::contains_unknown),
+            Type::UInt | Type::Boolean | Type::Generic(_) | Type::Named(_) | Type::Alias(_) => {
+                false
+            }
+        }
+    }
+}
+
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.resolved(), other.resolved()) {
+            (Type::UInt, Type::UInt)
+            | (Type::Boolean, Type::Boolean)
+            | (Type::Unknown, Type::Unknown) => true,
+            (Type::Function(function_type), Type::Function(other_function_type)) => {
+                function_type == other_function_type
+            }
+            (Type::Tuple(element_types), Type::Tuple(other_element_types)) => {
+                element_types == other_element_types
+            }
+            (Type::Generic(name), Type::Generic(other_name))
+            | (Type::Named(name), Type::Named(other_name)) => name == other_name,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Type {}
+
+impl Hash for Type {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let resolved = self.resolved();
+        std::mem::discriminant(resolved).hash(state);
+
+        match resolved {
+            Type::Function(function_type) => function_type.hash(state),
+            Type::Tuple(element_types) => element_types.hash(state),
+            Type::Generic(name) | Type::Named(name) => name.hash(state),
+            Type::UInt | Type::Boolean | Type::Alias(_) | Type::Unknown => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FunctionType {
+    pub parameters: Vec<Type>,
+    pub return_type: Box<FunctionReturnType>,
 }
 
 impl Display for Type {
@@ -162,6 +347,39 @@ impl Display for Type {
         match self {
             Type::UInt => f.write_str("UInt"),
             Type::Boolean => f.write_str("Boolean"),
+            Type::Unknown => f.write_str("unknown"),
+            Type::Generic(name) | Type::Named(name) => Display::fmt(name, f),
+            Type::Alias(alias) => f.write_fmt(format_args!(
+                "{} ({})",
+                alias.name,
+                alias.aliased_type.resolved()
+            )),
+            Type::Tuple(element_types) => f.write_fmt(format_args!(
+                "({})",
+                element_types
+                    .iter()
+                    .map(|element_type| element_type.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            Type::Function(function_type) => {
+                f.write_fmt(format_args!(
+                    "fn({})",
+                    function_type
+                        .parameters
+                        .iter()
+                        .map(|param| param.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))?;
+
+                match function_type.return_type.as_ref() {
+                    FunctionReturnType::Void => Ok(()),
+                    FunctionReturnType::Type(return_type) => {
+                        f.write_fmt(format_args!(" -> {}", return_type))
+                    }
+                }
+            }
         }
     }
 }
@@ -208,24 +426,55 @@ pub enum BinaryOperation {
     GreaterThan,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Ast {
     pub functions: HashMap<FunctionId, Function>,
+    pub constants: Vec<Constant>,
+    pub globals: Vec<Global>,
+    pub type_aliases: Vec<TypeAlias>,
     pub nodes: Vec<Node>,
 }
 
+/// A top level constant, ie `const LIMIT: uint = 10000;`. The value is evaluated when the program is type checked,
+/// and the constant is visible in every function body
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constant {
+    pub name: Symbol,
+    pub const_type: Type,
+    pub value: Expression,
+    /// the line the declaration starts on
+    pub line: u32,
+}
+
+/// A top level variable, ie `global mut count: uint = 0;`. Globals are initialised in declaration order before the
+/// program's statements run, and are visible in every function body. Only mutable globals can be assigned to
+#[derive(Clone, Debug, PartialEq)]
+pub struct Global {
+    pub name: Symbol,
+    pub global_type: Type,
+    pub mutable: bool,
+    pub value: Expression,
+    /// the line the declaration starts on
+    pub line: u32,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Function {
     CustomFunction {
         id: FunctionId,
-        name: String,
+        name: Symbol,
+        type_parameters: Vec<Symbol>,
         parameters: Vec<FunctionParameter>,
         return_type: FunctionReturnType,
         body: Vec<Node>,
+        span: Span,
+        /// whether the function is declared `pure`, ie `pure function add(uint left, uint right) -> uint`
+        pure: bool,
     },
     Intrinsic {
         id: FunctionId,
-        name: String,
+        name: Symbol,
+        type_parameters: Vec<Symbol>,
         parameters: Vec<FunctionParameter>,
         return_type: FunctionReturnType,
     },
@@ -238,9 +487,20 @@ impl Function {
         }
     }
 
-    pub fn name(&self) -> &String {
+    pub fn name(&self) -> Symbol {
         match self {
-            Function::CustomFunction { name, .. } | Function::Intrinsic { name, .. } => name,
+            Function::CustomFunction { name, .. } | Function::Intrinsic { name, .. } => *name,
+        }
+    }
+
+    pub fn type_parameters(&self) -> &[Symbol] {
+        match self {
+            Function::CustomFunction {
+                type_parameters, ..
+            }
+            | Function::Intrinsic {
+                type_parameters, ..
+            } => type_parameters,
         }
     }
 
@@ -259,30 +519,58 @@ impl Function {
     }
 }
 
+/// the id given to every anonymous function. It can't be written as an identifier, so can't clash with a declared function
+pub const LAMBDA_FUNCTION_ID: &str = "anonymous function";
+
+impl Lambda {
+    pub fn to_function(&self) -> Function {
+        self.clone().into_function()
+    }
+
+    pub fn into_function(self) -> Function {
+        Function::CustomFunction {
+            id: FunctionId(LAMBDA_FUNCTION_ID.into()),
+            name: LAMBDA_FUNCTION_ID.into(),
+            type_parameters: Vec::new(),
+            parameters: self.parameters,
+            return_type: self.return_type,
+            body: self.body,
+            span: self.span,
+            pure: false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Function, FunctionId, FunctionParameter, FunctionReturnType, Node, Type};
+    use super::{Function, FunctionId, FunctionParameter, FunctionReturnType, Node, Span, Type};
 
     #[test]
     fn custom_function_getters() {
         let function = Function::CustomFunction {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: vec![FunctionParameter::FunctionParameter {
                 param_type: Type::Boolean,
-                param_name: "my_param".to_owned(),
+                param_name: "my_param".into(),
             }],
             return_type: FunctionReturnType::Void,
-            body: vec![Node::FunctionReturn { return_value: None }],
+            body: vec![Node::FunctionReturn {
+                return_value: None,
+                line: 0,
+            }],
+            span: Span::default(),
+            pure: false,
         };
 
-        assert_eq!(function.id(), &FunctionId("my_function".to_owned()));
+        assert_eq!(function.id(), &FunctionId("my_function".into()));
         assert_eq!(function.name(), "my_function");
         assert_eq!(
             function.parameters(),
             &[FunctionParameter::FunctionParameter {
                 param_type: Type::Boolean,
-                param_name: "my_param".to_owned()
+                param_name: "my_param".into()
             }]
         );
         assert_eq!(function.return_type(), &FunctionReturnType::Void);
@@ -291,22 +579,23 @@ mod tests {
     #[test]
     fn intrinsic_function_getters() {
         let function = Function::Intrinsic {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: vec![FunctionParameter::FunctionParameter {
                 param_type: Type::Boolean,
-                param_name: "my_param".to_owned(),
+                param_name: "my_param".into(),
             }],
             return_type: FunctionReturnType::Void,
         };
 
-        assert_eq!(function.id(), &FunctionId("my_function".to_owned()));
+        assert_eq!(function.id(), &FunctionId("my_function".into()));
         assert_eq!(function.name(), "my_function");
         assert_eq!(
             function.parameters(),
             &[FunctionParameter::FunctionParameter {
                 param_type: Type::Boolean,
-                param_name: "my_param".to_owned()
+                param_name: "my_param".into()
             }]
         );
         assert_eq!(function.return_type(), &FunctionReturnType::Void);
@@ -0,0 +1,573 @@
+use std::fmt::Display;
+
+use super::node::{
+    Ast, BinaryOperation, BoolValue, Expression, Function, FunctionCall, FunctionParameter,
+    FunctionReturnType, IfStatement, Lambda, Node, Operation, Type, UIntValue, UnaryOperation,
+    Value, VariableDeclarationType, LAMBDA_FUNCTION_ID,
+};
+
+/// the ast written out as beach code, so the result of the passes over a program can be inspected. Declarations are
+/// grouped by kind, and operations are parenthesised wherever their grouping isn't the one beach parses them with
+impl Display for Ast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut printer = Printer::default();
+        printer.ast(self);
+
+        f.write_str(&printer.output)
+    }
+}
+
+#[derive(Default)]
+struct Printer {
+    output: String,
+    /// the number of blocks the line being written is nested in
+    depth: usize,
+}
+
+impl Printer {
+    fn ast(&mut self, ast: &Ast) {
+        let mut sections = Vec::new();
+
+        for alias in &ast.type_aliases {
+            self.line(&format!(
+                "type {} = {};",
+                alias.name,
+                source_type(&alias.aliased_type)
+            ));
+        }
+        sections.push(self.take_section());
+
+        for constant in &ast.constants {
+            let value = self.expression_text(&constant.value);
+            self.line(&format!(
+                "const {}: {} = {};",
+                constant.name,
+                source_type(&constant.const_type),
+                value
+            ));
+        }
+        sections.push(self.take_section());
+
+        for global in &ast.globals {
+            let value = self.expression_text(&global.value);
+            self.line(&format!(
+                "global {}{}: {} = {};",
+                if global.mutable { "mut " } else { "" },
+                global.name,
+                source_type(&global.global_type),
+                value
+            ));
+        }
+        sections.push(self.take_section());
+
+        // functions are kept in a map, so they're written in the order they were declared in
+        let mut functions: Vec<_> = ast.functions.values().collect();
+        functions.sort_by_key(|function| match function {
+            Function::CustomFunction { span, .. } => (span.start_line, function.id().0.as_str()),
+            Function::Intrinsic { .. } => (0, function.id().0.as_str()),
+        });
+        for function in functions {
+            self.function(function);
+            sections.push(self.take_section());
+        }
+
+        self.nodes(&ast.nodes);
+        sections.push(self.take_section());
+
+        self.output = sections
+            .into_iter()
+            .filter(|section| !section.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    /// the lines written since the last section was taken
+    fn take_section(&mut self) -> String {
+        std::mem::take(&mut self.output)
+    }
+
+    fn line(&mut self, text: &str) {
+        self.output.push_str(&"    ".repeat(self.depth));
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+
+    fn function(&mut self, function: &Function) {
+        let Function::CustomFunction {
+            id,
+            type_parameters,
+            parameters,
+            return_type,
+            body,
+            pure,
+            ..
+        } = function
+        else {
+            return;
+        };
+
+        let type_parameters = if type_parameters.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "<{}>",
+                type_parameters
+                    .iter()
+                    .map(|type_parameter| type_parameter.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+
+        self.line(&format!(
+            "{}function {}{}{} {{",
+            if *pure { "pure " } else { "" },
+            id,
+            type_parameters,
+            signature(parameters, return_type)
+        ));
+        self.block(body);
+        self.line("}");
+    }
+
+    fn block(&mut self, nodes: &[Node]) {
+        self.depth += 1;
+        self.nodes(nodes);
+        self.depth -= 1;
+    }
+
+    fn nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.node(node);
+        }
+    }
+
+    fn node(&mut self, node: &Node) {
+        match node {
+            Node::VariableDeclaration {
+                var_type,
+                var_name,
+                value,
+                ..
+            } => {
+                let value = self.expression_text(value);
+                self.line(&format!(
+                    "{} {} = {};",
+                    declaration_type(var_type),
+                    var_name,
+                    value
+                ));
+            }
+            Node::DestructuringDeclaration {
+                var_type,
+                var_names,
+                value,
+                ..
+            } => {
+                let value = self.expression_text(value);
+                self.line(&format!(
+                    "{} ({}) = {};",
+                    declaration_type(var_type),
+                    var_names
+                        .iter()
+                        .map(|var_name| var_name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    value
+                ));
+            }
+            Node::FunctionReturn { return_value, .. } => match return_value {
+                Some(return_value) => {
+                    let return_value = self.expression_text(return_value);
+                    self.line(&format!("return {};", return_value));
+                }
+                None => self.line("return;"),
+            },
+            Node::FunctionCall(function_call) => {
+                let call = self.function_call_text(function_call);
+                self.line(&format!("{};", call));
+            }
+            Node::IfStatement(if_statement) => self.if_statement(if_statement),
+            Node::Assignment {
+                var_name, value, ..
+            } => {
+                let value = self.expression_text(value);
+                self.line(&format!("{} = {};", var_name, value));
+            }
+        }
+    }
+
+    fn if_statement(&mut self, if_statement: &IfStatement) {
+        let check = self.expression_text(&if_statement.check_expression);
+        self.line(&format!("if ({}) {{", check));
+        self.block(&if_statement.if_block);
+
+        for else_if_block in &if_statement.else_if_blocks {
+            let check = self.expression_text(&else_if_block.check);
+            self.line(&format!("}} else if ({}) {{", check));
+            self.block(&else_if_block.block);
+        }
+
+        if let Some(else_block) = &if_statement.else_block {
+            self.line("} else {");
+            self.block(else_block);
+        }
+
+        self.line("}");
+    }
+
+    /// the text of an expression. A lambda's body is written on the lines after the line the expression is on, so
+    /// it is indented to the depth of that line
+    fn expression_text(&mut self, expression: &Expression) -> String {
+        match expression {
+            Expression::ValueLiteral(value) => self.value_text(value),
+            Expression::FunctionCall(function_call) => self.function_call_text(function_call),
+            Expression::Operation(Operation::Unary { operation, value }) => {
+                let value = self.operand_text(value);
+                match operation {
+                    UnaryOperation::Not => format!("!{}", value),
+                }
+            }
+            Expression::Operation(Operation::Binary {
+                operation,
+                left,
+                right,
+            }) => {
+                // beach groups operations to the right, so only an operation on the left needs parentheses
+                let left = self.operand_text(left);
+                let right = self.expression_text(right);
+                let operator = match operation {
+                    BinaryOperation::Plus => "+",
+                    BinaryOperation::GreaterThan => ">",
+                };
+
+                format!("{} {} {}", left, operator, right)
+            }
+            Expression::VariableAccess(name) | Expression::LocalVariableAccess { name, .. } => {
+                name.to_string()
+            }
+            Expression::Lambda(lambda) => self.lambda_text(lambda),
+            Expression::Tuple(elements) => {
+                let elements: Vec<_> = elements
+                    .iter()
+                    .map(|element| self.expression_text(element))
+                    .collect();
+
+                format!("({})", elements.join(", "))
+            }
+            Expression::TupleAccess { tuple, index } => {
+                format!("{}.{}", self.operand_text(tuple), index)
+            }
+        }
+    }
+
+    /// the text of an expression that an operator applies to, which is parenthesised if it's an operation itself
+    fn operand_text(&mut self, expression: &Expression) -> String {
+        let text = self.expression_text(expression);
+
+        match expression {
+            Expression::Operation(_) => format!("({})", text),
+            _ => text,
+        }
+    }
+
+    fn function_call_text(&mut self, function_call: &FunctionCall) -> String {
+        let parameters: Vec<_> = function_call
+            .parameters
+            .iter()
+            .map(|parameter| self.expression_text(parameter))
+            .collect();
+
+        format!("{}({})", function_call.function_id, parameters.join(", "))
+    }
+
+    fn lambda_text(&mut self, lambda: &Lambda) -> String {
+        self.function_value_text(&lambda.parameters, &lambda.return_type, &lambda.body)
+    }
+
+    fn function_value_text(
+        &mut self,
+        parameters: &[FunctionParameter],
+        return_type: &FunctionReturnType,
+        body: &[Node],
+    ) -> String {
+        // the body is written to its own output, so it isn't mixed up with the line the lambda is part of
+        let line = std::mem::take(&mut self.output);
+        self.block(body);
+        let body = std::mem::replace(&mut self.output, line);
+
+        format!(
+            "function{} {{\n{}{}}}",
+            signature(parameters, return_type),
+            body,
+            "    ".repeat(self.depth)
+        )
+    }
+
+    fn value_text(&mut self, value: &Value) -> String {
+        match value {
+            Value::UInt(UIntValue(value)) => value.to_string(),
+            Value::Boolean(BoolValue(value)) => value.to_string(),
+            Value::Tuple(elements) => {
+                let elements: Vec<_> = elements
+                    .iter()
+                    .map(|element| self.value_text(element))
+                    .collect();
+
+                format!("({})", elements.join(", "))
+            }
+            // a declared function is referred to by name, and a lambda is written out in full
+            Value::Function(function_value) => match function_value.function.as_ref() {
+                Function::CustomFunction {
+                    id,
+                    parameters,
+                    return_type,
+                    body,
+                    ..
+                } if id.0 == LAMBDA_FUNCTION_ID => {
+                    self.function_value_text(parameters, return_type, body)
+                }
+                function => function.id().to_string(),
+            },
+        }
+    }
+}
+
+/// the parameters and return type of a function, ie `(uint value) -> uint`
+fn signature(parameters: &[FunctionParameter], return_type: &FunctionReturnType) -> String {
+    let parameters = parameters
+        .iter()
+        .map(|parameter| {
+            format!(
+                "{} {}",
+                source_type(parameter.param_type()),
+                parameter.name()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match return_type {
+        FunctionReturnType::Type(return_type) => {
+            format!("({}) -> {}", parameters, source_type(return_type))
+        }
+        FunctionReturnType::Void => format!("({})", parameters),
+    }
+}
+
+fn declaration_type(var_type: &VariableDeclarationType) -> String {
+    match var_type {
+        VariableDeclarationType::Infer => "infer".to_owned(),
+        VariableDeclarationType::Type(var_type) => source_type(var_type),
+    }
+}
+
+/// the type as it's written in beach code. This differs from how types are displayed in diagnostics, which name
+/// the type an alias stands for
+fn source_type(type_: &Type) -> String {
+    match type_ {
+        Type::UInt => "uint".to_owned(),
+        Type::Boolean => "boolean".to_owned(),
+        Type::Unknown => "unknown".to_owned(),
+        Type::Generic(name) | Type::Named(name) => name.to_string(),
+        Type::Alias(alias) => alias.name.to_string(),
+        Type::Tuple(element_types) => format!(
+            "({})",
+            element_types
+                .iter()
+                .map(source_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Type::Function(function_type) => {
+            let parameters = function_type
+                .parameters
+                .iter()
+                .map(source_type)
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            match function_type.return_type.as_ref() {
+                FunctionReturnType::Type(return_type) => {
+                    format!("fn({}) -> {}", parameters, source_type(return_type))
+                }
+                FunctionReturnType::Void => format!("fn({})", parameters),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{
+        builders::{ast_builder::AstBuilder, lambda_builder::LambdaBuilder},
+        node::{Type, TypeAlias},
+    };
+
+    #[test]
+    fn print_declarations() {
+        let count = Type::Alias(TypeAlias {
+            name: "Count".into(),
+            aliased_type: Box::new(Type::UInt),
+        });
+        let ast = AstBuilder::default()
+            .type_alias("Count", Type::UInt)
+            .constant(|constant| {
+                constant
+                    .name("LIMIT")
+                    .const_type(count.clone())
+                    .value(|value| value.value_literal(10.into()))
+            })
+            .function_declaration(|function| {
+                function
+                    .name("double")
+                    .parameters(vec![(count, "value".into()).into()])
+                    .return_type(Type::UInt)
+                    .span(2, 4)
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.line(3).return_value(|value| {
+                                value.operation(|operation| {
+                                    operation.plus(
+                                        |left| left.variable("value"),
+                                        |right| right.variable("value"),
+                                    )
+                                })
+                            })
+                        })
+                        .build()
+                    })
+            })
+            .statement(|statement| {
+                statement.line(6).function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("double")
+                                    .parameter(|parameter| parameter.variable("LIMIT"))
+                                    .line(6)
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(
+            ast.to_string(),
+            "type Count = uint;
+
+const LIMIT: Count = 10;
+
+function double(Count value) -> uint {
+    return value + value;
+}
+
+print(double(LIMIT));
+"
+        );
+    }
+
+    #[test]
+    fn print_blocks_and_lambdas() {
+        let add_one = |lambda: LambdaBuilder| {
+            lambda
+                .parameters(vec![(Type::UInt, "value".into()).into()])
+                .return_type(Type::UInt)
+                .body(|body| {
+                    body.statement(|statement| {
+                        statement.return_value(|value| {
+                            value.operation(|operation| {
+                                operation.plus(
+                                    |left| left.variable("value"),
+                                    |right| right.value_literal(1.into()),
+                                )
+                            })
+                        })
+                    })
+                    .build()
+                })
+        };
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.value_literal(true.into()))
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.var_declaration(|declaration| {
+                                    declaration
+                                        .infer_type()
+                                        .name("add_one")
+                                        .with_assignment(|value| value.lambda(add_one))
+                                })
+                            })
+                            .build()
+                        })
+                        .else_block(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| {
+                                            parameter.value_literal(false.into())
+                                        })
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(
+            ast.to_string(),
+            "if (true) {
+    infer add_one = function(uint value) -> uint {
+        return value + 1;
+    };
+} else {
+    print(false);
+}
+"
+        );
+    }
+
+    #[test]
+    fn print_left_operation_in_parentheses() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.greater_than(
+                                    |left| {
+                                        left.operation(|operation| {
+                                            operation.plus(
+                                                |left| left.value_literal(1.into()),
+                                                |right| right.value_literal(2.into()),
+                                            )
+                                        })
+                                    },
+                                    |right| {
+                                        right.operation(|operation| {
+                                            operation.plus(
+                                                |left| left.value_literal(3.into()),
+                                                |right| right.value_literal(4.into()),
+                                            )
+                                        })
+                                    },
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(ast.to_string(), "print((1 + 2) > 3 + 4);\n");
+    }
+}
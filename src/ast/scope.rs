@@ -0,0 +1,251 @@
+//! Beach's scoping rules.
+//!
+//! - the body of a function or lambda, and each block of an if statement, is a scope
+//! - a variable is visible from its declaration to the end of the scope it's declared in, including any scopes nested
+//!   in it. Variables declared in a block aren't visible after the block ends
+//! - a variable can't shadow a variable that is still visible, so declaring a name twice in the same scope, or in a
+//!   nested scope, is an error. Once a block has ended its variables' names can be declared again
+//! - constants and global variables are visible in every scope
+
+use std::{collections::HashSet, fmt::Display};
+
+use super::{
+    node::{ElseIfBlock, Expression, FunctionCall, IfStatement, Node, Operation},
+    symbol::Symbol,
+};
+
+/// the kind of block a variable was declared in
+#[derive(Clone, Debug, PartialEq)]
+pub enum Block {
+    If,
+    ElseIf,
+    Else,
+}
+
+/// a variable declared in a block that has ended, so is no longer visible
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutOfScopeVariable {
+    pub name: Symbol,
+    pub block: Block,
+    /// the line of the if statement the block belongs to
+    pub line: u32,
+}
+
+impl Display for OutOfScopeVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let block = match self.block {
+            Block::If => format!("the if block at line {}", self.line),
+            Block::ElseIf => format!("an else if block of the if statement at line {}", self.line),
+            Block::Else => format!("the else block of the if statement at line {}", self.line),
+        };
+
+        write!(
+            f,
+            "`{}` is not in scope here; it was declared inside {}",
+            self.name, block
+        )
+    }
+}
+
+/// the variables declared in the blocks of the if statement, which aren't visible once it has ended.
+/// Variables declared in nested if statements are reported with the block they were declared in
+pub fn if_statement_declarations(if_statement: &IfStatement) -> Vec<OutOfScopeVariable> {
+    let mut declarations = Vec::new();
+
+    collect_block_declarations(
+        &if_statement.if_block,
+        Block::If,
+        if_statement.line,
+        &mut declarations,
+    );
+
+    for else_if_block in &if_statement.else_if_blocks {
+        collect_block_declarations(
+            &else_if_block.block,
+            Block::ElseIf,
+            if_statement.line,
+            &mut declarations,
+        );
+    }
+
+    if let Some(else_block) = &if_statement.else_block {
+        collect_block_declarations(
+            else_block,
+            Block::Else,
+            if_statement.line,
+            &mut declarations,
+        );
+    }
+
+    declarations
+}
+
+fn collect_block_declarations(
+    nodes: &[Node],
+    block: Block,
+    line: u32,
+    declarations: &mut Vec<OutOfScopeVariable>,
+) {
+    let declaration = |name: &Symbol| OutOfScopeVariable {
+        name: *name,
+        block: block.clone(),
+        line,
+    };
+
+    for node in nodes {
+        match node {
+            Node::VariableDeclaration { var_name, .. } => {
+                declarations.push(declaration(var_name));
+            }
+            Node::DestructuringDeclaration { var_names, .. } => {
+                declarations.extend(var_names.iter().map(declaration));
+            }
+            Node::IfStatement(if_statement) => {
+                declarations.extend(if_statement_declarations(if_statement));
+            }
+            Node::FunctionReturn { .. } | Node::FunctionCall(_) | Node::Assignment { .. } => {}
+        }
+    }
+}
+
+/// the names the nodes call or read, which include any declared function they call or use as a value
+pub fn referenced_names_in_nodes(nodes: &[Node], names: &mut HashSet<Symbol>) {
+    for node in nodes {
+        match node {
+            Node::VariableDeclaration { value, .. }
+            | Node::DestructuringDeclaration { value, .. }
+            | Node::Assignment { value, .. }
+            | Node::FunctionReturn {
+                return_value: Some(value),
+                ..
+            } => referenced_names(value, names),
+            Node::FunctionReturn {
+                return_value: None, ..
+            } => {}
+            Node::FunctionCall(function_call) => referenced_names_in_call(function_call, names),
+            Node::IfStatement(if_statement) => {
+                referenced_names(&if_statement.check_expression, names);
+                referenced_names_in_nodes(&if_statement.if_block, names);
+
+                for ElseIfBlock { check, block } in &if_statement.else_if_blocks {
+                    referenced_names(check, names);
+                    referenced_names_in_nodes(block, names);
+                }
+
+                if let Some(else_block) = &if_statement.else_block {
+                    referenced_names_in_nodes(else_block, names);
+                }
+            }
+        }
+    }
+}
+
+/// the names the expression calls or reads
+pub fn referenced_names(expression: &Expression, names: &mut HashSet<Symbol>) {
+    match expression {
+        Expression::ValueLiteral(_) => {}
+        Expression::VariableAccess(name) | Expression::LocalVariableAccess { name, .. } => {
+            names.insert(*name);
+        }
+        Expression::FunctionCall(function_call) => referenced_names_in_call(function_call, names),
+        Expression::Operation(Operation::Unary { value, .. }) => referenced_names(value, names),
+        Expression::Operation(Operation::Binary { left, right, .. }) => {
+            referenced_names(left, names);
+            referenced_names(right, names);
+        }
+        Expression::Lambda(lambda) => referenced_names_in_nodes(&lambda.body, names),
+        Expression::Tuple(elements) => {
+            for element in elements {
+                referenced_names(element, names);
+            }
+        }
+        Expression::TupleAccess { tuple, .. } => referenced_names(tuple, names),
+    }
+}
+
+fn referenced_names_in_call(function_call: &FunctionCall, names: &mut HashSet<Symbol>) {
+    names.insert(function_call.function_id.0);
+
+    for parameter in &function_call.parameters {
+        referenced_names(parameter, names);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{
+        builders::{ast_builder::AstBuilder, statement_builder::StatementBuilder},
+        node::{Ast, Node},
+    };
+
+    use super::{if_statement_declarations, Block, OutOfScopeVariable};
+
+    fn declaration(body: AstBuilder, name: &'static str) -> Ast {
+        body.statement(|statement| {
+            statement.var_declaration(|declaration| {
+                declaration
+                    .infer_type()
+                    .name(name)
+                    .with_assignment(|value| value.value_literal(1.into()))
+            })
+        })
+        .build()
+    }
+
+    #[test]
+    fn if_statement_declarations_of_each_block() {
+        let Node::IfStatement(if_statement) =
+            StatementBuilder::default().if_statement(|if_statement| {
+                if_statement
+                    .line(3)
+                    .check_expression(|check| check.value_literal(true.into()))
+                    .body(|body| declaration(body, "first"))
+                    .else_if(
+                        |check| check.value_literal(false.into()),
+                        |body| declaration(body, "second"),
+                    )
+                    .else_block(|body| declaration(body, "third"))
+                    .build()
+            })
+        else {
+            panic!("expected an if statement");
+        };
+
+        let declarations = if_statement_declarations(&if_statement);
+
+        assert_eq!(
+            declarations,
+            vec![
+                OutOfScopeVariable {
+                    name: "first".into(),
+                    block: Block::If,
+                    line: 3
+                },
+                OutOfScopeVariable {
+                    name: "second".into(),
+                    block: Block::ElseIf,
+                    line: 3
+                },
+                OutOfScopeVariable {
+                    name: "third".into(),
+                    block: Block::Else,
+                    line: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_scope_variable_display() {
+        let variable = OutOfScopeVariable {
+            name: "next".into(),
+            block: Block::If,
+            line: 4,
+        };
+
+        assert_eq!(
+            variable.to_string(),
+            "`next` is not in scope here; it was declared inside the if block at line 4"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+    sync::{Mutex, OnceLock},
+};
+
+/// An interned identifier, such as a variable or function name. Symbols are compared and hashed as integers, so the
+/// passes over a program never copy or rehash the names it uses. The text of a symbol can be recovered with `as_str`,
+/// for diagnostics
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+/// the text of every symbol interned so far. Each text is interned once, and lives for the rest of the process
+#[derive(Default)]
+struct Interner {
+    symbols: HashMap<&'static str, Symbol>,
+    texts: Vec<&'static str>,
+}
+
+/// the interner is shared by the whole process, as programs are loaded and run on different threads
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+
+    INTERNER.get_or_init(Mutex::default)
+}
+
+impl Symbol {
+    /// the symbol for `text`, which is the same symbol each time the same text is interned
+    pub fn intern(text: &str) -> Symbol {
+        let mut interner = interner().lock().unwrap_or_else(|error| error.into_inner());
+
+        if let Some(symbol) = interner.symbols.get(text) {
+            return *symbol;
+        }
+
+        let text: &'static str = Box::leak(text.to_owned().into_boxed_str());
+        let symbol = Symbol(interner.texts.len() as u32);
+        interner.texts.push(text);
+        interner.symbols.insert(text, symbol);
+
+        symbol
+    }
+
+    /// the text the symbol was interned from
+    pub fn as_str(self) -> &'static str {
+        let interner = interner().lock().unwrap_or_else(|error| error.into_inner());
+
+        interner.texts[self.0 as usize]
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(text: &str) -> Self {
+        Symbol::intern(text)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(text: &String) -> Self {
+        Symbol::intern(text)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// symbols are debugged as the text they were interned from, so they read the same as the strings they replace
+impl Debug for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Symbol;
+
+    #[test]
+    fn intern_same_text() {
+        let first = Symbol::intern("my_var");
+        let second = Symbol::from(&"my_var".to_owned());
+
+        assert_eq!(first, second);
+        assert_ne!(first, Symbol::intern("other_var"));
+    }
+
+    #[test]
+    fn symbol_text() {
+        let symbol = Symbol::intern("my_function");
+
+        assert_eq!(symbol.as_str(), "my_function");
+        assert_eq!(symbol.to_string(), "my_function");
+        assert_eq!(format!("{:?}", symbol), "\"my_function\"");
+    }
+}
@@ -0,0 +1,837 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        node::{
+            Ast, BinaryOperation, BoolValue, Expression, Function, FunctionCall, FunctionId,
+            FunctionParameter, FunctionValue, IfStatement, Lambda, Node, Operation, Type,
+            UIntValue, UnaryOperation, Value as AstValue, LAMBDA_FUNCTION_ID,
+        },
+        symbol::Symbol,
+    },
+    evaluation::intrinsics::get_intrinsic_functions,
+};
+
+use super::{Chunk, Closure, CompiledFunction, FunctionBody, Instruction, Program, Value};
+
+/// a name the compiler couldn't resolve, which the type checker should have already rejected
+#[derive(Debug, PartialEq)]
+pub struct CompileError {
+    pub message: String,
+    /// the line of the statement the name was used in
+    pub line: u32,
+}
+
+/// compile a type checked program, whose modules have been linked and whose constants have been folded
+pub fn compile(ast: &Ast) -> Result<Program, CompileError> {
+    let mut compiler = Compiler {
+        constants: Vec::new(),
+        functions: Vec::new(),
+        function_indices: HashMap::new(),
+        globals: HashMap::new(),
+        contexts: Vec::new(),
+        line: 0,
+    };
+
+    // intrinsics and declared functions are numbered before any body is compiled, so functions can call each other
+    // in any order. They're sorted so a program always compiles to the same bytecode
+    let mut declared: Vec<_> = get_intrinsic_functions()
+        .into_values()
+        .chain(ast.functions.values().cloned())
+        .collect();
+    declared.sort_by_key(|function| function.id().0.as_str());
+
+    for function in &declared {
+        compiler
+            .function_indices
+            .insert(*function.id(), compiler.functions.len());
+        compiler.functions.push(CompiledFunction {
+            id: *function.id(),
+            name: function.name().to_string(),
+            arity: function.parameters().len(),
+            body: FunctionBody::Intrinsic,
+        });
+    }
+
+    // constants are initialised before globals, and both are visible in every function
+    let declarations = ast
+        .constants
+        .iter()
+        .map(|constant| (constant.name, &constant.const_type))
+        .chain(
+            ast.globals
+                .iter()
+                .map(|global| (global.name, &global.global_type)),
+        );
+    for (name, declaration_type) in declarations {
+        let index = compiler.globals.len();
+        compiler.globals.entry(name).or_insert(GlobalSlot {
+            index,
+            is_function: matches!(declaration_type.resolved(), Type::Function(_)),
+        });
+    }
+
+    for (index, function) in declared.iter().enumerate() {
+        if let Function::CustomFunction {
+            parameters, body, ..
+        } = function
+        {
+            let (chunk, _) = compiler.function_body(parameters, body, Vec::new(), false)?;
+            compiler.functions[index].body = FunctionBody::Code(chunk);
+        }
+    }
+
+    compiler.contexts.push(Context::top_level());
+
+    for (name, value, line) in ast
+        .constants
+        .iter()
+        .map(|constant| (constant.name, &constant.value, constant.line))
+        .chain(
+            ast.globals
+                .iter()
+                .map(|global| (global.name, &global.value, global.line)),
+        )
+    {
+        compiler.line = line;
+        compiler.expression(value)?;
+        let index = compiler.globals[&name].index;
+        compiler.emit(Instruction::SetGlobal(index));
+    }
+
+    compiler.nodes(&ast.nodes)?;
+    compiler.emit(Instruction::ReturnVoid);
+
+    let main = compiler
+        .contexts
+        .pop()
+        .map(|context| context.chunk)
+        .unwrap_or_default();
+
+    Ok(Program {
+        constants: compiler.constants,
+        functions: compiler.functions,
+        globals: compiler.globals.len(),
+        main,
+    })
+}
+
+struct GlobalSlot {
+    index: usize,
+    /// whether the constant or global holds a function, so a call using its name calls its value
+    is_function: bool,
+}
+
+/// where a variable of the function being compiled is stored
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Variable {
+    Local(usize),
+    Capture(usize),
+}
+
+/// the code and variables of a function being compiled, or of the top level of the program
+struct Context {
+    chunk: Chunk,
+    /// the variables in scope, with the innermost block last
+    scopes: Vec<HashMap<Symbol, usize>>,
+    /// the names of the variables captured from the enclosing function
+    captures: Vec<Symbol>,
+    /// whether the function is a lambda, which captures the variables of the function it's declared in
+    nested: bool,
+    /// whether this is the top level, which has no function for a tail call to run in place of
+    top_level: bool,
+}
+
+impl Context {
+    fn top_level() -> Self {
+        Self {
+            chunk: Chunk::default(),
+            scopes: vec![HashMap::new()],
+            captures: Vec::new(),
+            nested: false,
+            top_level: true,
+        }
+    }
+
+    fn function(parameters: &[FunctionParameter], captures: Vec<Symbol>, nested: bool) -> Self {
+        Self {
+            chunk: Chunk {
+                slots: parameters.len(),
+                ..Chunk::default()
+            },
+            scopes: vec![parameters
+                .iter()
+                .enumerate()
+                .map(|(slot, parameter)| (parameter.name(), slot))
+                .collect()],
+            captures,
+            nested,
+            top_level: false,
+        }
+    }
+}
+
+/// how a call's result is used
+#[derive(Clone, Copy, PartialEq)]
+enum CallKind {
+    Value,
+    Statement,
+    /// the call is returned, so it runs in place of the function making it
+    Tail,
+}
+
+struct Compiler {
+    constants: Vec<Value>,
+    functions: Vec<CompiledFunction>,
+    function_indices: HashMap<FunctionId, usize>,
+    globals: HashMap<Symbol, GlobalSlot>,
+    /// the function being compiled last, after the functions it's nested in
+    contexts: Vec<Context>,
+    /// the line of the statement being compiled
+    line: u32,
+}
+
+impl Compiler {
+    fn context(&mut self) -> &mut Context {
+        let depth = self.contexts.len() - 1;
+        &mut self.contexts[depth]
+    }
+
+    fn emit(&mut self, instruction: Instruction) -> usize {
+        let line = self.line;
+        let chunk = &mut self.context().chunk;
+        chunk.instructions.push(instruction);
+        chunk.lines.push(line);
+        chunk.instructions.len() - 1
+    }
+
+    /// point the jump at `index` to the next instruction to be emitted
+    fn patch_jump(&mut self, index: usize) {
+        let chunk = &mut self.context().chunk;
+        let target = chunk.instructions.len();
+        match &mut chunk.instructions[index] {
+            Instruction::Jump(jump_target) | Instruction::JumpIfFalse(jump_target) => {
+                *jump_target = target
+            }
+            _ => {}
+        }
+    }
+
+    fn constant(&mut self, value: Value) -> usize {
+        if let Some(index) = self
+            .constants
+            .iter()
+            .position(|constant| *constant == value)
+        {
+            return index;
+        }
+
+        self.constants.push(value);
+        self.constants.len() - 1
+    }
+
+    fn error(&self, message: String) -> CompileError {
+        CompileError {
+            message,
+            line: self.line,
+        }
+    }
+
+    /// compile the body of a function, returning its code and the names of the variables it captures
+    fn function_body(
+        &mut self,
+        parameters: &[FunctionParameter],
+        body: &[Node],
+        captures: Vec<Symbol>,
+        nested: bool,
+    ) -> Result<(Chunk, Vec<Symbol>), CompileError> {
+        let line = self.line;
+        self.contexts
+            .push(Context::function(parameters, captures, nested));
+
+        let result = self.nodes(body);
+        // a function that reaches its closing brace returns without a value
+        self.emit(Instruction::ReturnVoid);
+
+        let context = self.contexts.pop();
+        self.line = line;
+        result?;
+
+        Ok(context
+            .map(|context| (context.chunk, context.captures))
+            .unwrap_or_default())
+    }
+
+    /// compile a function that isn't declared by name into a new entry of the function table, returning its index
+    /// and the names of the variables it captures
+    fn anonymous_function(
+        &mut self,
+        parameters: &[FunctionParameter],
+        body: &[Node],
+        captures: Vec<Symbol>,
+        nested: bool,
+    ) -> Result<(usize, Vec<Symbol>), CompileError> {
+        // the entry is added first, so the lambdas nested in this one are numbered after it
+        let index = self.functions.len();
+        self.functions.push(CompiledFunction {
+            id: FunctionId(LAMBDA_FUNCTION_ID.into()),
+            name: LAMBDA_FUNCTION_ID.to_owned(),
+            arity: parameters.len(),
+            body: FunctionBody::Code(Chunk::default()),
+        });
+
+        let (chunk, captures) = self.function_body(parameters, body, captures, nested)?;
+        self.functions[index].body = FunctionBody::Code(chunk);
+
+        Ok((index, captures))
+    }
+
+    /// find the variable `name` refers to in the function at `depth` of the contexts. A lambda captures the
+    /// variables it uses from the functions it's nested in
+    fn resolve_variable(&mut self, depth: usize, name: Symbol) -> Option<Variable> {
+        let context = &self.contexts[depth];
+
+        if let Some(slot) = context
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name))
+        {
+            return Some(Variable::Local(*slot));
+        }
+
+        if let Some(index) = context
+            .captures
+            .iter()
+            .position(|captured| *captured == name)
+        {
+            return Some(Variable::Capture(index));
+        }
+
+        if depth == 0 || !context.nested {
+            return None;
+        }
+
+        self.resolve_variable(depth - 1, name)?;
+
+        let captures = &mut self.contexts[depth].captures;
+        captures.push(name);
+        Some(Variable::Capture(captures.len() - 1))
+    }
+
+    fn local_variable(&mut self, name: Symbol) -> Option<Variable> {
+        self.resolve_variable(self.contexts.len() - 1, name)
+    }
+
+    fn load(&mut self, variable: Variable) {
+        match variable {
+            Variable::Local(slot) => self.emit(Instruction::GetLocal(slot)),
+            Variable::Capture(index) => self.emit(Instruction::GetCapture(index)),
+        };
+    }
+
+    fn declare(&mut self, name: Symbol) -> usize {
+        let context = self.context();
+        let slot = context.chunk.slots;
+        context.chunk.slots += 1;
+
+        if let Some(scope) = context.scopes.last_mut() {
+            scope.insert(name, slot);
+        }
+
+        slot
+    }
+
+    fn nodes(&mut self, nodes: &[Node]) -> Result<(), CompileError> {
+        for node in nodes {
+            self.node(node)?;
+        }
+
+        Ok(())
+    }
+
+    /// the variables declared in a block aren't visible once it has ended
+    fn block(&mut self, nodes: &[Node]) -> Result<(), CompileError> {
+        self.context().scopes.push(HashMap::new());
+        let result = self.nodes(nodes);
+        self.context().scopes.pop();
+
+        result
+    }
+
+    fn node(&mut self, node: &Node) -> Result<(), CompileError> {
+        self.line = node.line();
+
+        match node {
+            Node::VariableDeclaration {
+                var_name, value, ..
+            } => {
+                self.expression(value)?;
+                let slot = self.declare(*var_name);
+                self.emit(Instruction::SetLocal(slot));
+            }
+            Node::DestructuringDeclaration {
+                var_names, value, ..
+            } => {
+                self.expression(value)?;
+                self.emit(Instruction::Destructure(var_names.len()));
+
+                let slots: Vec<_> = var_names.iter().map(|name| self.declare(*name)).collect();
+                // the last element is on top of the stack
+                for slot in slots.into_iter().rev() {
+                    self.emit(Instruction::SetLocal(slot));
+                }
+            }
+            Node::FunctionReturn { return_value, .. } => match return_value {
+                Some(Expression::FunctionCall(function_call)) if !self.context().top_level => {
+                    self.function_call(function_call, CallKind::Tail)?;
+                }
+                Some(return_value) => {
+                    self.expression(return_value)?;
+                    self.emit(Instruction::Return);
+                }
+                None => {
+                    self.emit(Instruction::ReturnVoid);
+                }
+            },
+            Node::FunctionCall(function_call) => {
+                self.function_call(function_call, CallKind::Statement)?
+            }
+            Node::IfStatement(if_statement) => self.if_statement(if_statement)?,
+            Node::Assignment {
+                var_name, value, ..
+            } => {
+                self.expression(value)?;
+
+                let Some(global) = self.globals.get(var_name) else {
+                    return Err(self.error(format!(
+                        "Cannot assign to {}, only mutable globals can be assigned to",
+                        var_name
+                    )));
+                };
+                let index = global.index;
+                self.emit(Instruction::SetGlobal(index));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn if_statement(&mut self, if_statement: &IfStatement) -> Result<(), CompileError> {
+        self.expression(&if_statement.check_expression)?;
+        let mut next_check = self.emit(Instruction::JumpIfFalse(0));
+        self.block(&if_statement.if_block)?;
+        let mut end_jumps = vec![self.emit(Instruction::Jump(0))];
+
+        for else_if_block in &if_statement.else_if_blocks {
+            self.patch_jump(next_check);
+
+            // the checks are part of the if statement, rather than the block before them
+            self.line = if_statement.line;
+            self.expression(&else_if_block.check)?;
+            next_check = self.emit(Instruction::JumpIfFalse(0));
+            self.block(&else_if_block.block)?;
+            end_jumps.push(self.emit(Instruction::Jump(0)));
+        }
+
+        self.patch_jump(next_check);
+
+        if let Some(else_block) = &if_statement.else_block {
+            self.block(else_block)?;
+        }
+
+        for end_jump in end_jumps {
+            self.patch_jump(end_jump);
+        }
+
+        Ok(())
+    }
+
+    fn function_call(
+        &mut self,
+        function_call: &FunctionCall,
+        kind: CallKind,
+    ) -> Result<(), CompileError> {
+        let name = function_call.function_id.0;
+
+        // local variables take precedence over declared functions, followed by globals and constants
+        if let Some(variable) = self.local_variable(name) {
+            self.load(variable);
+        } else if let Some(global) = self.globals.get(&name).filter(|global| global.is_function) {
+            let index = global.index;
+            self.emit(Instruction::GetGlobal(index));
+        } else if let Some(&function) = self.function_indices.get(&function_call.function_id) {
+            self.emit(Instruction::Closure {
+                function,
+                captures: 0,
+            });
+        } else {
+            return Err(self.error(format!(
+                "Could not find function with name {}",
+                function_call.function_id
+            )));
+        }
+
+        for parameter in &function_call.parameters {
+            self.expression(parameter)?;
+        }
+
+        let arguments = function_call.parameters.len();
+        self.emit(match kind {
+            CallKind::Value => Instruction::Call {
+                arguments,
+                keep_result: true,
+            },
+            CallKind::Statement => Instruction::Call {
+                arguments,
+                keep_result: false,
+            },
+            CallKind::Tail => Instruction::TailCall { arguments },
+        });
+
+        Ok(())
+    }
+
+    fn expression(&mut self, expression: &Expression) -> Result<(), CompileError> {
+        match expression {
+            Expression::ValueLiteral(value) => {
+                let value = self.value(value)?;
+                let index = self.constant(value);
+                self.emit(Instruction::Constant(index));
+            }
+            Expression::FunctionCall(function_call) => {
+                self.function_call(function_call, CallKind::Value)?
+            }
+            Expression::Operation(Operation::Unary { operation, value }) => {
+                self.expression(value)?;
+                self.emit(match operation {
+                    UnaryOperation::Not => Instruction::Not,
+                });
+            }
+            Expression::Operation(Operation::Binary {
+                operation,
+                left,
+                right,
+            }) => {
+                self.expression(left)?;
+                self.expression(right)?;
+                self.emit(match operation {
+                    BinaryOperation::Plus => Instruction::Plus,
+                    BinaryOperation::GreaterThan => Instruction::GreaterThan,
+                });
+            }
+            Expression::VariableAccess(name) | Expression::LocalVariableAccess { name, .. } => {
+                self.variable_access(*name)?
+            }
+            Expression::Lambda(lambda) => self.lambda(lambda)?,
+            Expression::Tuple(elements) => {
+                for element in elements {
+                    self.expression(element)?;
+                }
+                self.emit(Instruction::Tuple(elements.len()));
+            }
+            Expression::TupleAccess { tuple, index } => {
+                self.expression(tuple)?;
+                self.emit(Instruction::TupleElement(*index));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn variable_access(&mut self, name: Symbol) -> Result<(), CompileError> {
+        if let Some(variable) = self.local_variable(name) {
+            self.load(variable);
+        } else if let Some(global) = self.globals.get(&name) {
+            let index = global.index;
+            self.emit(Instruction::GetGlobal(index));
+        } else if let Some(&function) = self.function_indices.get(&FunctionId(name)) {
+            // a declared function being used as a value
+            self.emit(Instruction::Closure {
+                function,
+                captures: 0,
+            });
+        } else {
+            return Err(self.error(format!("`{}` is not in scope here", name)));
+        }
+
+        Ok(())
+    }
+
+    fn lambda(&mut self, lambda: &Lambda) -> Result<(), CompileError> {
+        let (function, captures) =
+            self.anonymous_function(&lambda.parameters, &lambda.body, Vec::new(), true)?;
+
+        for name in &captures {
+            if let Some(variable) = self.local_variable(*name) {
+                self.load(variable);
+            }
+        }
+
+        self.emit(Instruction::Closure {
+            function,
+            captures: captures.len(),
+        });
+
+        Ok(())
+    }
+
+    /// the bytecode value of a value in the ast, which can be a function when it's the folded value of a constant
+    fn value(&mut self, value: &AstValue) -> Result<Value, CompileError> {
+        Ok(match value {
+            AstValue::UInt(UIntValue(uint_value)) => Value::UInt(*uint_value),
+            AstValue::Boolean(BoolValue(bool_value)) => Value::Boolean(*bool_value),
+            AstValue::Tuple(elements) => Value::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.value(element))
+                    .collect::<Result<_, _>>()?,
+            ),
+            AstValue::Function(function_value) => {
+                Value::Function(self.function_value(function_value)?)
+            }
+        })
+    }
+
+    fn function_value(&mut self, function_value: &FunctionValue) -> Result<Closure, CompileError> {
+        if let Some(&function) = self.function_indices.get(function_value.function.id()) {
+            return Ok(Closure {
+                function,
+                captures: Vec::new(),
+            });
+        }
+
+        let Function::CustomFunction {
+            parameters, body, ..
+        } = function_value.function.as_ref()
+        else {
+            return Err(self.error(format!(
+                "Could not find function with name {}",
+                function_value.function.id()
+            )));
+        };
+
+        // a lambda, which is compiled with the variables it captured as its captures. A later variable shadows an
+        // earlier one with the same name
+        let mut names = Vec::new();
+        let mut captures = Vec::new();
+        for (index, (name, value)) in function_value.captured_variables.iter().enumerate() {
+            let shadowed = function_value.captured_variables[index + 1..]
+                .iter()
+                .any(|(later, _)| later == name);
+            if !shadowed {
+                names.push(*name);
+                captures.push(self.value(value)?);
+            }
+        }
+        let (function, _) = self.anonymous_function(parameters, body, names, false)?;
+
+        Ok(Closure { function, captures })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{builders::ast_builder::AstBuilder, node::Type},
+        bytecode::{Chunk, Instruction, Value},
+    };
+
+    use super::{compile, CompileError};
+
+    #[test]
+    fn compile_variables() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.line(1).var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("count")
+                        .with_assignment(|value| value.value_literal(1.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.line(2).function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.plus(
+                                    |left| left.variable("count"),
+                                    |right| right.value_literal(1.into()),
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        let program = compile(&ast).unwrap();
+
+        assert_eq!(program.constants, vec![Value::UInt(1)]);
+        assert_eq!(
+            program.main,
+            Chunk {
+                instructions: vec![
+                    Instruction::Constant(0),
+                    Instruction::SetLocal(0),
+                    Instruction::Closure {
+                        function: 0,
+                        captures: 0
+                    },
+                    Instruction::GetLocal(0),
+                    Instruction::Constant(0),
+                    Instruction::Plus,
+                    Instruction::Call {
+                        arguments: 1,
+                        keep_result: false
+                    },
+                    Instruction::ReturnVoid,
+                ],
+                lines: vec![1, 1, 2, 2, 2, 2, 2, 2],
+                slots: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn compile_if_statement_jumps() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.line(1).if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.value_literal(true.into()))
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.line(2).var_declaration(|declaration| {
+                                    declaration
+                                        .infer_type()
+                                        .name("first")
+                                        .with_assignment(|value| value.value_literal(1.into()))
+                                })
+                            })
+                            .build()
+                        })
+                        .else_block(|body| {
+                            body.statement(|statement| {
+                                statement.line(4).var_declaration(|declaration| {
+                                    declaration
+                                        .infer_type()
+                                        .name("second")
+                                        .with_assignment(|value| value.value_literal(2.into()))
+                                })
+                            })
+                            .build()
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        let program = compile(&ast).unwrap();
+
+        assert_eq!(
+            program.main.instructions,
+            vec![
+                Instruction::Constant(0),
+                Instruction::JumpIfFalse(5),
+                Instruction::Constant(1),
+                Instruction::SetLocal(0),
+                Instruction::Jump(7),
+                Instruction::Constant(2),
+                Instruction::SetLocal(1),
+                Instruction::ReturnVoid,
+            ]
+        );
+    }
+
+    #[test]
+    fn compile_lambda_captures() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.line(1).var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("offset")
+                        .with_assignment(|value| value.value_literal(10.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.line(2).var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("add_offset")
+                        .with_assignment(|value| {
+                            value.lambda(|lambda| {
+                                lambda
+                                    .parameters(vec![(Type::UInt, "value".into()).into()])
+                                    .return_type(Type::UInt)
+                                    .body(|body| {
+                                        body.statement(|statement| {
+                                            statement.line(3).return_value(|value| {
+                                                value.operation(|operation| {
+                                                    operation.plus(
+                                                        |left| left.variable("value"),
+                                                        |right| right.variable("offset"),
+                                                    )
+                                                })
+                                            })
+                                        })
+                                        .build()
+                                    })
+                            })
+                        })
+                })
+            })
+            .build();
+
+        let program = compile(&ast).unwrap();
+
+        assert_eq!(
+            program.main.instructions[2..5],
+            [
+                Instruction::GetLocal(0),
+                Instruction::Closure {
+                    function: 1,
+                    captures: 1
+                },
+                Instruction::SetLocal(1),
+            ]
+        );
+        assert_eq!(
+            program.functions[1].body,
+            crate::bytecode::FunctionBody::Code(Chunk {
+                instructions: vec![
+                    Instruction::GetLocal(0),
+                    Instruction::GetCapture(0),
+                    Instruction::Plus,
+                    Instruction::Return,
+                    Instruction::ReturnVoid,
+                ],
+                lines: vec![3, 3, 3, 3, 3],
+                slots: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn compile_unknown_variable() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.line(4).function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.variable("missing"))
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(
+            compile(&ast),
+            Err(CompileError {
+                message: "`missing` is not in scope here".to_owned(),
+                line: 4,
+            })
+        );
+    }
+}
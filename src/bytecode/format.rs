@@ -0,0 +1,636 @@
+use std::fmt::Display;
+
+use crate::ast::{node::FunctionId, symbol::Symbol};
+
+use super::{Chunk, Closure, CompiledFunction, FunctionBody, Instruction, Program, Value};
+
+/// the bytes every `.bchc` file starts with
+const MAGIC: &[u8; 4] = b"BCHC";
+/// the version of the file format, which changes whenever the bytecode or its encoding does, so a file is only run by
+/// a beach that understands it
+pub const FORMAT_VERSION: u32 = 1;
+/// the version of beach that wrote the file, which is only used to explain an incompatible file
+const COMPILER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// set when each chunk is followed by the line of each of its instructions
+const LINE_INFO_FLAG: u8 = 1;
+/// how deeply a constant's tuples and closures can be nested. The compiler never nests them this deeply, and decoding
+/// a deeper value could exhaust the host stack
+const MAX_VALUE_DEPTH: usize = 1000;
+
+/// why a `.bchc` file couldn't be decoded
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    /// the file doesn't start with the magic bytes
+    NotCompiled,
+    /// the file was written by a beach with a different format version
+    IncompatibleVersion { version: u32, compiler: String },
+    /// the file ends early, or holds something that isn't valid bytecode
+    Corrupt(String),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::NotCompiled => f.write_str("not a compiled beach program"),
+            DecodeError::IncompatibleVersion { version, compiler } => write!(
+                f,
+                "compiled by beach {} (format version {}), but beach {} only runs format version {}. Rebuild it with `beach build`",
+                compiler, version, COMPILER_VERSION, FORMAT_VERSION
+            ),
+            DecodeError::Corrupt(message) => {
+                write!(f, "the compiled program is corrupt: {}", message)
+            }
+        }
+    }
+}
+
+/// encode a compiled program as the contents of a `.bchc` file. Without line info, runtime errors report line 0
+///
+/// numbers are little endian u32s, strings and lists are prefixed with their length, and values, instructions and
+/// function bodies start with a tag byte
+pub fn encode(program: &Program, line_info: bool) -> Vec<u8> {
+    let mut encoder = Encoder {
+        bytes: MAGIC.to_vec(),
+        line_info,
+    };
+
+    encoder.u32(FORMAT_VERSION);
+    encoder.string(COMPILER_VERSION);
+    encoder.u8(if line_info { LINE_INFO_FLAG } else { 0 });
+
+    encoder.list(&program.functions, Encoder::function);
+    encoder.list(&program.constants, Encoder::value);
+    encoder.usize(program.globals);
+    encoder.chunk(&program.main);
+
+    encoder.bytes
+}
+
+/// decode the contents of a `.bchc` file
+pub fn decode(bytes: &[u8]) -> Result<Program, DecodeError> {
+    let Some(contents) = bytes.strip_prefix(MAGIC) else {
+        return Err(DecodeError::NotCompiled);
+    };
+
+    let mut decoder = Decoder {
+        bytes: contents,
+        line_info: false,
+        depth: 0,
+    };
+
+    let version = decoder.u32()?;
+    let compiler = decoder.string()?;
+    if version != FORMAT_VERSION {
+        return Err(DecodeError::IncompatibleVersion { version, compiler });
+    }
+
+    let flags = decoder.u8()?;
+    decoder.line_info = flags & LINE_INFO_FLAG != 0;
+
+    let program = Program {
+        functions: decoder.list(Decoder::function)?,
+        constants: decoder.list(Decoder::value)?,
+        globals: decoder.usize()?,
+        main: decoder.chunk()?,
+    };
+
+    if !decoder.bytes.is_empty() {
+        return Err(DecodeError::Corrupt(format!(
+            "{} unexpected bytes after the program",
+            decoder.bytes.len()
+        )));
+    }
+
+    validate(&program)?;
+
+    Ok(program)
+}
+
+/// check the counts and indices the virtual machine trusts, so a corrupt file can't make it allocate more than the
+/// file could hold. Every global is set by an instruction of the top level, and every slot holds a parameter or is
+/// set by an instruction of its chunk
+fn validate(program: &Program) -> Result<(), DecodeError> {
+    if program.globals > program.main.instructions.len() {
+        return Err(DecodeError::Corrupt(format!(
+            "there are {} globals, but only {} top level instructions to initialise them",
+            program.globals,
+            program.main.instructions.len()
+        )));
+    }
+
+    let chunks = program
+        .functions
+        .iter()
+        .filter_map(|function| match &function.body {
+            FunctionBody::Code(chunk) => Some((chunk, function.arity)),
+            FunctionBody::Intrinsic => None,
+        })
+        .chain([(&program.main, 0)]);
+
+    for (chunk, arity) in chunks {
+        if chunk.slots > arity.saturating_add(chunk.instructions.len()) {
+            return Err(DecodeError::Corrupt(format!(
+                "a function uses {} slots, but only has {} parameters and {} instructions",
+                chunk.slots,
+                arity,
+                chunk.instructions.len()
+            )));
+        }
+
+        for instruction in &chunk.instructions {
+            let (kind, index, count) = match instruction {
+                Instruction::Constant(index) => ("constant", index, program.constants.len()),
+                Instruction::GetLocal(slot) | Instruction::SetLocal(slot) => {
+                    ("slot", slot, chunk.slots)
+                }
+                Instruction::GetGlobal(index) | Instruction::SetGlobal(index) => {
+                    ("global", index, program.globals)
+                }
+                Instruction::Closure { function, .. } => {
+                    ("function", function, program.functions.len())
+                }
+                _ => continue,
+            };
+
+            if *index >= count {
+                return Err(DecodeError::Corrupt(format!(
+                    "{} {} is out of range, as there are {}",
+                    kind, index, count
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct Encoder {
+    bytes: Vec<u8>,
+    line_info: bool,
+}
+
+impl Encoder {
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend(value.to_le_bytes());
+    }
+
+    fn usize(&mut self, value: usize) {
+        self.u32(value as u32);
+    }
+
+    fn string(&mut self, value: &str) {
+        self.usize(value.len());
+        self.bytes.extend(value.as_bytes());
+    }
+
+    fn list<T>(&mut self, items: &[T], mut encode_item: impl FnMut(&mut Self, &T)) {
+        self.usize(items.len());
+        for item in items {
+            encode_item(self, item);
+        }
+    }
+
+    fn function(&mut self, function: &CompiledFunction) {
+        self.string(function.id.0.as_str());
+        self.string(&function.name);
+        self.usize(function.arity);
+        match &function.body {
+            FunctionBody::Intrinsic => self.u8(0),
+            FunctionBody::Code(chunk) => {
+                self.u8(1);
+                self.chunk(chunk);
+            }
+        }
+    }
+
+    fn chunk(&mut self, chunk: &Chunk) {
+        self.usize(chunk.slots);
+        self.list(&chunk.instructions, Encoder::instruction);
+        if self.line_info {
+            self.list(&chunk.lines, |encoder, line| encoder.u32(*line));
+        }
+    }
+
+    fn value(&mut self, value: &Value) {
+        match value {
+            Value::UInt(uint_value) => {
+                self.u8(0);
+                self.u32(*uint_value);
+            }
+            Value::Boolean(bool_value) => {
+                self.u8(1);
+                self.u8(*bool_value as u8);
+            }
+            Value::Tuple(values) => {
+                self.u8(2);
+                self.list(values, Encoder::value);
+            }
+            Value::Function(Closure { function, captures }) => {
+                self.u8(3);
+                self.usize(*function);
+                self.list(captures, Encoder::value);
+            }
+        }
+    }
+
+    fn instruction(&mut self, instruction: &Instruction) {
+        let (opcode, operands): (u8, &[usize]) = match instruction {
+            Instruction::Constant(index) => (0, &[*index]),
+            Instruction::GetLocal(slot) => (1, &[*slot]),
+            Instruction::SetLocal(slot) => (2, &[*slot]),
+            Instruction::GetCapture(index) => (3, &[*index]),
+            Instruction::GetGlobal(index) => (4, &[*index]),
+            Instruction::SetGlobal(index) => (5, &[*index]),
+            Instruction::Closure { function, captures } => (6, &[*function, *captures]),
+            Instruction::Tuple(length) => (7, &[*length]),
+            Instruction::TupleElement(index) => (8, &[*index]),
+            Instruction::Destructure(length) => (9, &[*length]),
+            Instruction::Not => (10, &[]),
+            Instruction::Plus => (11, &[]),
+            Instruction::GreaterThan => (12, &[]),
+            Instruction::Jump(target) => (13, &[*target]),
+            Instruction::JumpIfFalse(target) => (14, &[*target]),
+            Instruction::Call {
+                arguments,
+                keep_result,
+            } => (15, &[*arguments, *keep_result as usize]),
+            Instruction::TailCall { arguments } => (16, &[*arguments]),
+            Instruction::Return => (17, &[]),
+            Instruction::ReturnVoid => (18, &[]),
+        };
+
+        self.u8(opcode);
+        for operand in operands {
+            self.usize(*operand);
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    line_info: bool,
+    /// how many values the value being decoded is nested in
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < length {
+            return Err(DecodeError::Corrupt("the file ends early".to_owned()));
+        }
+
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn usize(&mut self) -> Result<usize, DecodeError> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn bool(&mut self) -> Result<bool, DecodeError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(DecodeError::Corrupt(format!("{} is not a boolean", value))),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, DecodeError> {
+        let length = self.usize()?;
+        let bytes = self.take(length)?;
+
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| DecodeError::Corrupt("a name is not valid utf-8".to_owned()))
+    }
+
+    fn list<T>(
+        &mut self,
+        mut decode_item: impl FnMut(&mut Self) -> Result<T, DecodeError>,
+    ) -> Result<Vec<T>, DecodeError> {
+        let length = self.usize()?;
+
+        // the length isn't trusted to size the list up front, a corrupt file runs out of bytes instead
+        let mut items = Vec::new();
+        for _ in 0..length {
+            items.push(decode_item(self)?);
+        }
+
+        Ok(items)
+    }
+
+    fn function(&mut self) -> Result<CompiledFunction, DecodeError> {
+        Ok(CompiledFunction {
+            id: FunctionId(Symbol::intern(&self.string()?)),
+            name: self.string()?,
+            arity: self.usize()?,
+            body: match self.u8()? {
+                0 => FunctionBody::Intrinsic,
+                1 => FunctionBody::Code(self.chunk()?),
+                tag => {
+                    return Err(DecodeError::Corrupt(format!(
+                        "unknown function body {}",
+                        tag
+                    )))
+                }
+            },
+        })
+    }
+
+    fn chunk(&mut self) -> Result<Chunk, DecodeError> {
+        let slots = self.usize()?;
+        let instructions = self.list(Decoder::instruction)?;
+        let lines = if self.line_info {
+            self.list(Decoder::u32)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Chunk {
+            instructions,
+            lines,
+            slots,
+        })
+    }
+
+    fn value(&mut self) -> Result<Value, DecodeError> {
+        if self.depth == MAX_VALUE_DEPTH {
+            return Err(DecodeError::Corrupt(format!(
+                "a value is nested more than {} deep",
+                MAX_VALUE_DEPTH
+            )));
+        }
+
+        self.depth += 1;
+        let value = self.nested_value();
+        self.depth -= 1;
+
+        value
+    }
+
+    /// a value, whose tuple elements or closure captures are decoded one level deeper
+    fn nested_value(&mut self) -> Result<Value, DecodeError> {
+        Ok(match self.u8()? {
+            0 => Value::UInt(self.u32()?),
+            1 => Value::Boolean(self.bool()?),
+            2 => Value::Tuple(self.list(Decoder::value)?),
+            3 => Value::Function(Closure {
+                function: self.usize()?,
+                captures: self.list(Decoder::value)?,
+            }),
+            tag => return Err(DecodeError::Corrupt(format!("unknown value {}", tag))),
+        })
+    }
+
+    fn instruction(&mut self) -> Result<Instruction, DecodeError> {
+        Ok(match self.u8()? {
+            0 => Instruction::Constant(self.usize()?),
+            1 => Instruction::GetLocal(self.usize()?),
+            2 => Instruction::SetLocal(self.usize()?),
+            3 => Instruction::GetCapture(self.usize()?),
+            4 => Instruction::GetGlobal(self.usize()?),
+            5 => Instruction::SetGlobal(self.usize()?),
+            6 => Instruction::Closure {
+                function: self.usize()?,
+                captures: self.usize()?,
+            },
+            7 => Instruction::Tuple(self.usize()?),
+            8 => Instruction::TupleElement(self.usize()?),
+            9 => Instruction::Destructure(self.usize()?),
+            10 => Instruction::Not,
+            11 => Instruction::Plus,
+            12 => Instruction::GreaterThan,
+            13 => Instruction::Jump(self.usize()?),
+            14 => Instruction::JumpIfFalse(self.usize()?),
+            15 => Instruction::Call {
+                arguments: self.usize()?,
+                keep_result: self.usize()? != 0,
+            },
+            16 => Instruction::TailCall {
+                arguments: self.usize()?,
+            },
+            17 => Instruction::Return,
+            18 => Instruction::ReturnVoid,
+            opcode => {
+                return Err(DecodeError::Corrupt(format!(
+                    "unknown instruction {}",
+                    opcode
+                )))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::node::FunctionId,
+        bytecode::{
+            format::{decode, encode, DecodeError, FORMAT_VERSION, MAX_VALUE_DEPTH},
+            Chunk, Closure, CompiledFunction, FunctionBody, Instruction, Program, Value,
+        },
+    };
+
+    fn program() -> Program {
+        Program {
+            constants: vec![
+                Value::UInt(7),
+                Value::Tuple(vec![
+                    Value::Boolean(true),
+                    Value::Function(Closure {
+                        function: 0,
+                        captures: vec![Value::UInt(1)],
+                    }),
+                ]),
+            ],
+            functions: vec![
+                CompiledFunction {
+                    id: FunctionId("print".into()),
+                    name: "print".to_owned(),
+                    arity: 1,
+                    body: FunctionBody::Intrinsic,
+                },
+                CompiledFunction {
+                    id: FunctionId("identity".into()),
+                    name: "identity".to_owned(),
+                    arity: 1,
+                    body: FunctionBody::Code(Chunk {
+                        instructions: vec![Instruction::GetLocal(0), Instruction::Return],
+                        lines: vec![2, 2],
+                        slots: 1,
+                    }),
+                },
+            ],
+            globals: 1,
+            main: Chunk {
+                instructions: vec![
+                    Instruction::Closure {
+                        function: 0,
+                        captures: 0,
+                    },
+                    Instruction::Constant(0),
+                    Instruction::Call {
+                        arguments: 1,
+                        keep_result: false,
+                    },
+                    Instruction::ReturnVoid,
+                ],
+                lines: vec![4, 4, 4, 4],
+                slots: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn encode_and_decode() {
+        let program = program();
+
+        let result = decode(&encode(&program, true));
+
+        assert_eq!(result, Ok(program));
+    }
+
+    #[test]
+    fn encode_without_line_info() {
+        let program = program();
+
+        let result = decode(&encode(&program, false)).unwrap();
+
+        assert!(result.main.lines.is_empty());
+        assert_eq!(result.main.instructions, program.main.instructions);
+    }
+
+    #[test]
+    fn decode_source_file() {
+        let result = decode(b"print(1);");
+
+        assert_eq!(result, Err(DecodeError::NotCompiled));
+    }
+
+    #[test]
+    fn decode_incompatible_version() {
+        let mut bytes = encode(&program(), true);
+        bytes[4..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+
+        let result = decode(&bytes);
+
+        assert_eq!(
+            result,
+            Err(DecodeError::IncompatibleVersion {
+                version: FORMAT_VERSION + 1,
+                compiler: env!("CARGO_PKG_VERSION").to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn decode_truncated_file() {
+        let bytes = encode(&program(), true);
+
+        let result = decode(&bytes[..bytes.len() - 1]);
+
+        assert_eq!(
+            result,
+            Err(DecodeError::Corrupt("the file ends early".to_owned()))
+        );
+    }
+
+    #[test]
+    fn decode_too_many_globals() {
+        let program = Program {
+            globals: 100_000,
+            ..program()
+        };
+
+        let result = decode(&encode(&program, true));
+
+        assert_eq!(
+            result,
+            Err(DecodeError::Corrupt(
+                "there are 100000 globals, but only 4 top level instructions to initialise them"
+                    .to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn decode_too_many_slots() {
+        let mut program = program();
+        program.main.slots = 100_000;
+
+        let result = decode(&encode(&program, true));
+
+        assert_eq!(
+            result,
+            Err(DecodeError::Corrupt(
+                "a function uses 100000 slots, but only has 0 parameters and 4 instructions"
+                    .to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn decode_slot_out_of_range() {
+        let mut program = program();
+        program.main.instructions[1] = Instruction::SetLocal(5_000_000);
+
+        let result = decode(&encode(&program, true));
+
+        assert_eq!(
+            result,
+            Err(DecodeError::Corrupt(
+                "slot 5000000 is out of range, as there are 0".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn decode_deeply_nested_value() {
+        let program = Program {
+            functions: Vec::new(),
+            constants: Vec::new(),
+            ..program()
+        };
+        let mut bytes = encode(&program, false);
+        // the header, followed by no functions and a single constant
+        bytes.truncate(13 + env!("CARGO_PKG_VERSION").len());
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend(1u32.to_le_bytes());
+        // alternating tuples and closures, each holding the next
+        for depth in 0..100_000 {
+            if depth % 2 == 0 {
+                bytes.push(2);
+            } else {
+                bytes.push(3);
+                bytes.extend(0u32.to_le_bytes());
+            }
+            bytes.extend(1u32.to_le_bytes());
+        }
+        bytes.push(0);
+        bytes.extend(0u32.to_le_bytes());
+
+        let result = decode(&bytes);
+
+        assert_eq!(
+            result,
+            Err(DecodeError::Corrupt(format!(
+                "a value is nested more than {} deep",
+                MAX_VALUE_DEPTH
+            )))
+        );
+    }
+}
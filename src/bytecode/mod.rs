@@ -0,0 +1,121 @@
+//! Beach's bytecode, which a type checked `Ast` is compiled to so it can be run by the virtual machine.
+//!
+//! - every function, including the intrinsic functions and lambdas, has an index in the program's function table
+//! - a running function has numbered local slots, which hold its parameters followed by the variables it declares,
+//!   and the values captured by the closure it was called as
+//! - constants and globals are numbered slots shared by the whole program, initialised before the program's
+//!   statements run
+//! - a compiled program can be saved as a `.bchc` file, and run later without being parsed or type checked again
+//! - instructions operate on a stack of values, which holds the operands of an instruction and the result it pushes
+
+mod compiler;
+mod format;
+mod vm;
+
+pub use self::{
+    compiler::compile,
+    format::{decode, encode},
+    vm::run,
+};
+
+use crate::ast::node::FunctionId;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Instruction {
+    /// push the value at this index of the constants pool
+    Constant(usize),
+    /// push the value of a local slot of the running function
+    GetLocal(usize),
+    /// pop a value into a local slot of the running function
+    SetLocal(usize),
+    /// push a value captured by the closure the running function was called as
+    GetCapture(usize),
+    /// push the value of a constant or global
+    GetGlobal(usize),
+    /// pop a value into a global
+    SetGlobal(usize),
+    /// push the function at this index of the function table, capturing the `captures` values on top of the stack
+    Closure {
+        function: usize,
+        captures: usize,
+    },
+    /// pop this number of values into a tuple
+    Tuple(usize),
+    /// replace the tuple on top of the stack with its element at this index
+    TupleElement(usize),
+    /// replace the tuple on top of the stack with its elements, which it must have this many of. The last element
+    /// ends up on top of the stack
+    Destructure(usize),
+    Not,
+    Plus,
+    GreaterThan,
+    /// continue from the instruction at this index
+    Jump(usize),
+    /// pop a boolean, and continue from the instruction at this index if it's false
+    JumpIfFalse(usize),
+    /// call the function below the `arguments` on top of the stack, pushing its return value if `keep_result` is set
+    Call {
+        arguments: usize,
+        keep_result: bool,
+    },
+    /// call the function below the `arguments` on top of the stack in place of the running function, so its return
+    /// value is returned by the running function
+    TailCall {
+        arguments: usize,
+    },
+    /// return the value on top of the stack
+    Return,
+    /// return without a value
+    ReturnVoid,
+}
+
+/// a sequence of instructions, and the line of the statement each instruction was compiled from
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Chunk {
+    pub instructions: Vec<Instruction>,
+    pub lines: Vec<u32>,
+    /// the number of local slots the code uses
+    pub slots: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FunctionBody {
+    Code(Chunk),
+    /// a function built into beach, which is run by the virtual machine itself
+    Intrinsic,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompiledFunction {
+    pub id: FunctionId,
+    pub name: String,
+    /// the number of parameters, which are the function's first local slots
+    pub arity: usize,
+    pub body: FunctionBody,
+}
+
+/// a compiled program. `main` initialises the constants and globals, then runs the program's statements
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Program {
+    pub constants: Vec<Value>,
+    pub functions: Vec<CompiledFunction>,
+    /// the number of constants and globals
+    pub globals: usize,
+    pub main: Chunk,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    UInt(u32),
+    Boolean(bool),
+    Tuple(Vec<Value>),
+    Function(Closure),
+}
+
+/// a function used as a value, along with the values it captured when it was created
+#[derive(Clone, Debug, PartialEq)]
+pub struct Closure {
+    /// the index of the function in the program's function table
+    pub function: usize,
+    pub captures: Vec<Value>,
+}
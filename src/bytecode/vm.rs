@@ -0,0 +1,623 @@
+use crate::evaluation::{BacktraceEntry, EvaluationOptions, RuntimeError, UNINITIALISED_GLOBAL};
+
+use super::{Chunk, Closure, CompiledFunction, FunctionBody, Instruction, Program, Value};
+
+/// run a compiled program until it returns from its top level
+pub fn run(program: &Program, options: &EvaluationOptions) -> Result<(), RuntimeError> {
+    Vm::new(program, options).run()
+}
+
+/// a call that hasn't returned yet, or the top level of the program when `function` is `None`
+struct Frame {
+    function: Option<usize>,
+    /// the index of the next instruction to run
+    ip: usize,
+    /// the local slots, which are empty until the variable they hold is declared
+    slots: Vec<Option<Value>>,
+    /// the values captured by the closure the function was called as
+    captures: Vec<Value>,
+    /// whether the caller uses the function's return value
+    keep_result: bool,
+}
+
+struct Vm<'a> {
+    program: &'a Program,
+    max_call_depth: usize,
+    stack: Vec<Value>,
+    globals: Vec<Option<Value>>,
+    frames: Vec<Frame>,
+}
+
+impl<'a> Vm<'a> {
+    fn new(program: &'a Program, options: &EvaluationOptions) -> Self {
+        Self {
+            program,
+            max_call_depth: options.max_call_depth,
+            stack: Vec::new(),
+            globals: vec![None; program.globals],
+            frames: vec![Frame {
+                function: None,
+                ip: 0,
+                slots: vec![None; program.main.slots],
+                captures: Vec::new(),
+                keep_result: false,
+            }],
+        }
+    }
+
+    fn run(&mut self) -> Result<(), RuntimeError> {
+        let program = self.program;
+
+        while let Some(frame) = self.frames.last_mut() {
+            let chunk = chunk(program, frame.function);
+            let Some(instruction) = chunk.instructions.get(frame.ip) else {
+                return Err(self.error("ran past the end of the function's code"));
+            };
+            frame.ip += 1;
+
+            match instruction {
+                Instruction::Constant(index) => {
+                    let value = self.program.constants.get(*index).cloned();
+                    let value = self.expect(value, "the constant doesn't exist")?;
+                    self.stack.push(value);
+                }
+                Instruction::GetLocal(slot) => {
+                    let value = frame.slots.get(*slot).cloned().flatten();
+                    let value = self.expect(value, "a variable was read before it was declared")?;
+                    self.stack.push(value);
+                }
+                Instruction::SetLocal(slot) => {
+                    let value = self.pop()?;
+                    let frame = self.frame();
+                    if *slot >= frame.slots.len() {
+                        frame.slots.resize(*slot + 1, None);
+                    }
+                    frame.slots[*slot] = Some(value);
+                }
+                Instruction::GetCapture(index) => {
+                    let value = frame.captures.get(*index).cloned();
+                    let value = self.expect(value, "the captured variable doesn't exist")?;
+                    self.stack.push(value);
+                }
+                Instruction::GetGlobal(index) => {
+                    let value = self.globals.get(*index).cloned().flatten();
+                    let value = self.expect(value, UNINITIALISED_GLOBAL)?;
+                    self.stack.push(value);
+                }
+                Instruction::SetGlobal(index) => {
+                    let value = self.pop()?;
+                    if *index >= self.globals.len() {
+                        self.globals.resize(*index + 1, None);
+                    }
+                    self.globals[*index] = Some(value);
+                }
+                Instruction::Closure { function, captures } => {
+                    let captures = self.pop_many(*captures)?;
+                    self.stack.push(Value::Function(Closure {
+                        function: *function,
+                        captures,
+                    }));
+                }
+                Instruction::Tuple(length) => {
+                    let elements = self.pop_many(*length)?;
+                    self.stack.push(Value::Tuple(elements));
+                }
+                Instruction::TupleElement(index) => {
+                    let value = self.pop()?;
+                    let Value::Tuple(mut elements) = value else {
+                        return Err(self.error(format!(
+                            "only tuples can be accessed by index, but found {:?}",
+                            value
+                        )));
+                    };
+
+                    if *index >= elements.len() {
+                        return Err(self.error(format!(
+                            "cannot access element {} of a tuple with {} elements",
+                            index,
+                            elements.len()
+                        )));
+                    }
+
+                    self.stack.push(elements.swap_remove(*index));
+                }
+                Instruction::Destructure(length) => {
+                    let value = self.pop()?;
+                    let Value::Tuple(elements) = value else {
+                        return Err(self.error(format!(
+                            "only tuples can be destructured, but found {:?}",
+                            value
+                        )));
+                    };
+
+                    if elements.len() != *length {
+                        return Err(self.error(format!(
+                            "cannot destructure a tuple with {} elements into {} variables",
+                            elements.len(),
+                            length
+                        )));
+                    }
+
+                    self.stack.extend(elements);
+                }
+                Instruction::Not => {
+                    let value = self.pop()?;
+                    let Value::Boolean(bool_value) = value else {
+                        return Err(self.error(format!(
+                            "not only operates on booleans, but found {:?}",
+                            value
+                        )));
+                    };
+                    self.stack.push(Value::Boolean(!bool_value));
+                }
+                Instruction::Plus => {
+                    let (left, right) = self.pop_uints("plus only operates on uint")?;
+                    let Some(sum) = left.checked_add(right) else {
+                        return Err(
+                            self.error(format!("{} + {} is too large for a uint", left, right))
+                        );
+                    };
+                    self.stack.push(Value::UInt(sum));
+                }
+                Instruction::GreaterThan => {
+                    let (left, right) = self.pop_uints("greater_than only operates on uint")?;
+                    self.stack.push(Value::Boolean(left > right));
+                }
+                Instruction::Jump(target) => frame.ip = *target,
+                Instruction::JumpIfFalse(target) => {
+                    let value = self.pop()?;
+                    let Value::Boolean(bool_value) = value else {
+                        return Err(self.error(format!(
+                            "Expected if statement check value to be boolean, but found {:?}",
+                            value
+                        )));
+                    };
+
+                    if !bool_value {
+                        self.frame().ip = *target;
+                    }
+                }
+                Instruction::Call {
+                    arguments,
+                    keep_result,
+                } => self.call(*arguments, *keep_result, false)?,
+                Instruction::TailCall { arguments } => {
+                    let keep_result = frame.keep_result;
+                    self.call(*arguments, keep_result, true)?
+                }
+                Instruction::Return => {
+                    let value = self.pop()?;
+                    self.return_value(Some(value))?;
+                }
+                Instruction::ReturnVoid => self.return_value(None)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// the frame of the running function
+    fn frame(&mut self) -> &mut Frame {
+        let depth = self.frames.len() - 1;
+        &mut self.frames[depth]
+    }
+
+    fn call(
+        &mut self,
+        arguments: usize,
+        keep_result: bool,
+        tail_call: bool,
+    ) -> Result<(), RuntimeError> {
+        let arguments = self.pop_many(arguments)?;
+        let callee = self.pop()?;
+        let Value::Function(Closure { function, captures }) = callee else {
+            return Err(self.error(format!(
+                "only functions can be called, but found {:?}",
+                callee
+            )));
+        };
+
+        let program = self.program;
+        let Some(compiled_function) = program.functions.get(function) else {
+            return Err(self.error(format!("there is no function {}", function)));
+        };
+
+        if arguments.len() != compiled_function.arity {
+            return Err(self.error(format!(
+                "Expected {} parameters, but found {} for {}",
+                compiled_function.arity,
+                arguments.len(),
+                compiled_function.name
+            )));
+        }
+
+        let FunctionBody::Code(chunk) = &compiled_function.body else {
+            let value = self.intrinsic(compiled_function, arguments)?;
+
+            return if tail_call {
+                self.return_value(value)
+            } else {
+                self.push_result(compiled_function, value, keep_result)
+            };
+        };
+
+        let mut slots: Vec<_> = arguments.into_iter().map(Some).collect();
+        slots.resize(chunk.slots.max(slots.len()), None);
+
+        // a tail call runs in place of the function that made it, so a chain of tail calls doesn't grow the stack
+        if tail_call && self.frame().function.is_some() {
+            let frame = self.frame();
+            frame.function = Some(function);
+            frame.ip = 0;
+            frame.slots = slots;
+            frame.captures = captures;
+            return Ok(());
+        }
+
+        // the top level isn't a call
+        let depth = self.frames.len() - 1;
+        if depth >= self.max_call_depth {
+            return Err(self.error(format!(
+                "stack overflow in {} (depth {})",
+                compiled_function.id,
+                depth + 1
+            )));
+        }
+
+        self.frames.push(Frame {
+            function: Some(function),
+            ip: 0,
+            slots,
+            captures,
+            keep_result,
+        });
+
+        Ok(())
+    }
+
+    fn return_value(&mut self, value: Option<Value>) -> Result<(), RuntimeError> {
+        let Some(frame) = self.frames.pop() else {
+            return Ok(());
+        };
+
+        let (Some(function), false) = (frame.function, self.frames.is_empty()) else {
+            // returning from the top level ends the program
+            self.frames.clear();
+            return Ok(());
+        };
+
+        let program = self.program;
+        self.push_result(&program.functions[function], value, frame.keep_result)
+    }
+
+    fn push_result(
+        &mut self,
+        function: &CompiledFunction,
+        value: Option<Value>,
+        keep_result: bool,
+    ) -> Result<(), RuntimeError> {
+        match (value, keep_result) {
+            (Some(value), true) => self.stack.push(value),
+            (None, true) => {
+                return Err(self.error(format!(
+                    "{} returned void, but a value was expected",
+                    function.id
+                )))
+            }
+            (_, false) => {}
+        }
+
+        Ok(())
+    }
+
+    fn intrinsic(
+        &self,
+        function: &CompiledFunction,
+        arguments: Vec<Value>,
+    ) -> Result<Option<Value>, RuntimeError> {
+        match (function.id.0.as_str(), arguments.as_slice()) {
+            ("print", [value]) => {
+                println!("{}", self.format_value(value));
+                Ok(None)
+            }
+            _ => Err(self.error(format!("unknown intrinsic function {}", function.id))),
+        }
+    }
+
+    fn format_value(&self, value: &Value) -> String {
+        match value {
+            Value::UInt(uint_value) => uint_value.to_string(),
+            Value::Boolean(bool_value) => bool_value.to_string(),
+            Value::Function(closure) => match self.program.functions.get(closure.function) {
+                Some(function) => format!("<{}>", function.name),
+                None => "<unknown function>".to_owned(),
+            },
+            Value::Tuple(elements) => format!(
+                "({})",
+                elements
+                    .iter()
+                    .map(|element| self.format_value(element))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    fn pop(&mut self) -> Result<Value, RuntimeError> {
+        let value = self.stack.pop();
+        self.expect(value, "the stack is empty")
+    }
+
+    fn pop_many(&mut self, count: usize) -> Result<Vec<Value>, RuntimeError> {
+        if count > self.stack.len() {
+            return Err(self.error("the stack is empty"));
+        }
+
+        Ok(self.stack.split_off(self.stack.len() - count))
+    }
+
+    /// the two uints on top of the stack, with the right operand on top
+    fn pop_uints(&mut self, message: &str) -> Result<(u32, u32), RuntimeError> {
+        let right = self.pop()?;
+        let left = self.pop()?;
+
+        match (left, right) {
+            (Value::UInt(left), Value::UInt(right)) => Ok((left, right)),
+            (Value::UInt(_), other) | (other, _) => {
+                Err(self.error(format!("{}, but found {:?}", message, other)))
+            }
+        }
+    }
+
+    /// the value, or an error if the compiled code is inconsistent and it's missing
+    fn expect(&self, value: Option<Value>, message: &str) -> Result<Value, RuntimeError> {
+        value.ok_or_else(|| self.error(message))
+    }
+
+    /// an error at the running instruction, with a backtrace of the calls that led to it
+    fn error(&self, message: impl Into<String>) -> RuntimeError {
+        let backtrace = self
+            .frames
+            .iter()
+            .rev()
+            .map(|frame| BacktraceEntry {
+                function_id: frame
+                    .function
+                    .and_then(|function| self.program.functions.get(function))
+                    .map(|function| function.id),
+                line: chunk(self.program, frame.function)
+                    .lines
+                    .get(frame.ip.saturating_sub(1))
+                    .copied()
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        RuntimeError {
+            message: message.into(),
+            backtrace,
+        }
+    }
+}
+
+/// the code of the function, or of the top level when `function` is `None`
+fn chunk(program: &Program, function: Option<usize>) -> &Chunk {
+    match function.and_then(|function| program.functions.get(function)) {
+        Some(CompiledFunction {
+            body: FunctionBody::Code(chunk),
+            ..
+        }) => chunk,
+        _ => &program.main,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::node::FunctionId,
+        bytecode::{Chunk, CompiledFunction, FunctionBody, Instruction, Program, Value},
+        evaluation::{BacktraceEntry, EvaluationOptions},
+    };
+
+    use super::{run, Vm};
+
+    fn chunk(instructions: Vec<Instruction>, lines: Vec<u32>, slots: usize) -> Chunk {
+        Chunk {
+            instructions,
+            lines,
+            slots,
+        }
+    }
+
+    /// `count(uint value) -> uint`, which calls itself without ever returning
+    fn count() -> CompiledFunction {
+        CompiledFunction {
+            id: FunctionId("count".into()),
+            name: "count".to_owned(),
+            arity: 1,
+            body: FunctionBody::Code(chunk(
+                vec![
+                    Instruction::Closure {
+                        function: 0,
+                        captures: 0,
+                    },
+                    Instruction::GetLocal(0),
+                    Instruction::Call {
+                        arguments: 1,
+                        keep_result: true,
+                    },
+                    Instruction::Return,
+                ],
+                vec![2, 2, 2, 2],
+                1,
+            )),
+        }
+    }
+
+    fn call_count() -> Chunk {
+        chunk(
+            vec![
+                Instruction::Closure {
+                    function: 0,
+                    captures: 0,
+                },
+                Instruction::Constant(0),
+                Instruction::Call {
+                    arguments: 1,
+                    keep_result: false,
+                },
+                Instruction::ReturnVoid,
+            ],
+            vec![4, 4, 4, 4],
+            0,
+        )
+    }
+
+    #[test]
+    fn run_stack_overflow() {
+        let program = Program {
+            constants: vec![Value::UInt(1)],
+            functions: vec![count()],
+            globals: 0,
+            main: call_count(),
+        };
+
+        let result = run(&program, &EvaluationOptions { max_call_depth: 2 });
+
+        assert!(
+            result.is_err_and(|error| error.message == "stack overflow in count (depth 3)"
+                && error.backtrace
+                    == vec![
+                        BacktraceEntry {
+                            function_id: Some(FunctionId("count".into())),
+                            line: 2,
+                        },
+                        BacktraceEntry {
+                            function_id: Some(FunctionId("count".into())),
+                            line: 2,
+                        },
+                        BacktraceEntry {
+                            function_id: None,
+                            line: 4,
+                        },
+                    ])
+        );
+    }
+
+    #[test]
+    fn run_tail_calls_in_place() {
+        // count_up(uint value) -> uint, which tail calls itself until value is greater than 3
+        let count_up = CompiledFunction {
+            id: FunctionId("count_up".into()),
+            name: "count_up".to_owned(),
+            arity: 1,
+            body: FunctionBody::Code(chunk(
+                vec![
+                    Instruction::GetLocal(0),
+                    Instruction::Constant(1),
+                    Instruction::GreaterThan,
+                    Instruction::JumpIfFalse(6),
+                    Instruction::GetLocal(0),
+                    Instruction::Return,
+                    Instruction::Closure {
+                        function: 0,
+                        captures: 0,
+                    },
+                    Instruction::GetLocal(0),
+                    Instruction::Constant(0),
+                    Instruction::Plus,
+                    Instruction::TailCall { arguments: 1 },
+                    Instruction::ReturnVoid,
+                ],
+                vec![2; 12],
+                1,
+            )),
+        };
+        let program = Program {
+            constants: vec![Value::UInt(1), Value::UInt(3)],
+            functions: vec![count_up],
+            globals: 1,
+            main: chunk(
+                vec![
+                    Instruction::Closure {
+                        function: 0,
+                        captures: 0,
+                    },
+                    Instruction::Constant(0),
+                    Instruction::Call {
+                        arguments: 1,
+                        keep_result: true,
+                    },
+                    Instruction::SetGlobal(0),
+                    Instruction::ReturnVoid,
+                ],
+                vec![4; 5],
+                0,
+            ),
+        };
+
+        let mut vm = Vm::new(&program, &EvaluationOptions { max_call_depth: 1 });
+        let result = vm.run();
+
+        assert!(result.is_ok());
+        assert_eq!(vm.globals, vec![Some(Value::UInt(4))]);
+    }
+
+    #[test]
+    fn run_plus_overflow() {
+        let program = Program {
+            constants: vec![Value::UInt(u32::MAX), Value::UInt(1)],
+            functions: Vec::new(),
+            globals: 1,
+            main: chunk(
+                vec![
+                    Instruction::Constant(0),
+                    Instruction::Constant(1),
+                    Instruction::Plus,
+                    Instruction::SetGlobal(0),
+                    Instruction::ReturnVoid,
+                ],
+                vec![3, 3, 3, 3, 3],
+                0,
+            ),
+        };
+
+        let result = run(&program, &EvaluationOptions::default());
+
+        assert!(result.is_err_and(|error| error.message
+            == "4294967295 + 1 is too large for a uint"
+            && error.line() == 3));
+    }
+
+    #[test]
+    fn run_globals_and_tuples() {
+        let program = Program {
+            constants: vec![Value::UInt(1), Value::Boolean(true)],
+            functions: Vec::new(),
+            globals: 1,
+            main: chunk(
+                vec![
+                    Instruction::Constant(0),
+                    Instruction::Constant(1),
+                    Instruction::Tuple(2),
+                    Instruction::SetGlobal(0),
+                    Instruction::GetGlobal(0),
+                    Instruction::Destructure(2),
+                    Instruction::SetLocal(1),
+                    Instruction::SetLocal(0),
+                    Instruction::GetLocal(1),
+                    Instruction::JumpIfFalse(11),
+                    Instruction::ReturnVoid,
+                    Instruction::GetLocal(5),
+                ],
+                vec![0; 12],
+                2,
+            ),
+        };
+
+        // the jump isn't taken, so the missing variable is never read
+        let result = run(&program, &EvaluationOptions::default());
+
+        assert!(result.is_ok());
+    }
+}
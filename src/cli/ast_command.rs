@@ -0,0 +1,233 @@
+use std::{fs, io, path::Path};
+
+use crate::{evaluation::EvaluationOptions, optimisation::OptimisationOptions};
+
+use super::{
+    run_command::{load, on_program_thread, parse_inline_threshold, LintOptions, RunError},
+    BeachCommand, CommandError,
+};
+
+pub(super) struct AstCommand;
+
+impl BeachCommand for AstCommand {
+    fn name(&self) -> &'static str {
+        "ast"
+    }
+
+    fn usage(&self) -> &'static str {
+        "beach ast [--allow <lint>] [--deny warnings] [--optimized] [--inline-threshold <size>] [program].bch"
+    }
+
+    fn description(&self) -> &'static str {
+        "print a beach program as it is run, after its modules are linked"
+    }
+
+    fn run(&self, args: Vec<String>) -> Result<(), CommandError> {
+        let mut options = AstOptions::default();
+        let mut program_file = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if options.lints.parse_option(&arg, &mut args, self.usage())? {
+                continue;
+            }
+
+            match arg.as_str() {
+                "--optimized" => options.optimise = true,
+                "--inline-threshold" => {
+                    options.optimisation.inline_threshold =
+                        parse_inline_threshold(&mut args, self.usage())?;
+                }
+                option if option.starts_with("--") => {
+                    return Err(
+                        format!("unknown option {}\nusage: {}", option, self.usage()).into(),
+                    );
+                }
+                _ if program_file.is_none() => program_file = Some(arg),
+                _ => {
+                    return Err(format!(
+                        "the ast command does not take any more sub commands or options\nusage: {}",
+                        self.usage()
+                    )
+                    .into())
+                }
+            }
+        }
+
+        let Some(program_file) = program_file else {
+            return Err(format!("usage: {}", self.usage()).into());
+        };
+
+        if !program_file.ends_with(".bch") {
+            return Err("a beach program file must have .bch extension"
+                .to_owned()
+                .into());
+        }
+
+        let output = on_program_thread(EvaluationOptions::default().stack_size(), move || {
+            ast(&program_file, &options, |path| fs::read_to_string(path))
+        })??;
+
+        print!("{}", output);
+
+        Ok(())
+    }
+}
+
+/// how the lints found in a program are reported, and which passes run before it's printed
+#[derive(Default)]
+struct AstOptions {
+    lints: LintOptions,
+    /// whether the ast is printed as `beach run -O` would run it
+    optimise: bool,
+    optimisation: OptimisationOptions,
+}
+
+/// the linked program, written out as beach code
+fn ast(
+    program_file: &str,
+    options: &AstOptions,
+    read_file: impl Fn(&Path) -> io::Result<String>,
+) -> Result<String, RunError> {
+    let mut ast = load(program_file, &options.lints, read_file)?;
+    if options.optimise {
+        ast.optimise(&options.optimisation);
+    }
+
+    Ok(ast.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::BeachCommand;
+
+    use super::AstCommand;
+
+    #[test]
+    fn ast_command_name() {
+        let command = AstCommand;
+
+        assert_eq!(command.name(), "ast");
+    }
+
+    #[test]
+    fn ast_command_usage() {
+        let command = AstCommand;
+
+        assert_eq!(
+            command.usage(),
+            "beach ast [--allow <lint>] [--deny warnings] [--optimized] [--inline-threshold <size>] [program].bch"
+        );
+    }
+
+    mod command_run {
+        use crate::cli::{ast_command::AstCommand, BeachCommand};
+
+        #[test]
+        fn no_program_file() {
+            let command = AstCommand;
+
+            let result = command.run(Vec::new());
+
+            assert!(
+                matches!(result, Err(error) if error.message == "usage: beach ast [--allow <lint>] [--deny warnings] [--optimized] [--inline-threshold <size>] [program].bch")
+            )
+        }
+
+        #[test]
+        fn unknown_option() {
+            let command = AstCommand;
+
+            let result = command.run(vec!["--optimised".to_owned(), "main.bch".to_owned()]);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "unknown option --optimised\nusage: beach ast [--allow <lint>] [--deny warnings] [--optimized] [--inline-threshold <size>] [program].bch")
+            )
+        }
+    }
+
+    mod ast_function {
+        use std::{io, path::Path};
+
+        use crate::cli::{
+            ast_command::{ast, AstOptions},
+            run_command::RunError,
+        };
+
+        fn read_main(code: &'static str) -> impl Fn(&Path) -> io::Result<String> {
+            move |_| Ok(code.to_owned())
+        }
+
+        const CODE: &str = "const LIMIT: uint = 2 + 3;
+if (LIMIT > 10) {
+    print(0);
+} else {
+    print(LIMIT + 1);
+}";
+
+        #[test]
+        fn ast_unoptimised() {
+            let result = ast("main.bch", &AstOptions::default(), read_main(CODE));
+
+            assert_eq!(
+                result.unwrap(),
+                "const LIMIT: uint = 5;
+
+if (LIMIT > 10) {
+    print(0);
+} else {
+    print(LIMIT + 1);
+}
+"
+            );
+        }
+
+        #[test]
+        fn ast_optimised() {
+            let options = AstOptions {
+                optimise: true,
+                ..AstOptions::default()
+            };
+
+            let result = ast("main.bch", &options, read_main(CODE));
+
+            assert_eq!(result.unwrap(), "const LIMIT: uint = 5;\n\nprint(6);\n");
+        }
+
+        #[test]
+        fn ast_inline_threshold() {
+            let code = "function add(uint left, uint right) -> uint {
+    return left + right;
+}
+print(add(1, 2));";
+            let mut options = AstOptions {
+                optimise: true,
+                ..AstOptions::default()
+            };
+
+            let inlined = ast("main.bch", &options, read_main(code));
+
+            options.optimisation.inline_threshold = 0;
+            let not_inlined = ast("main.bch", &options, read_main(code));
+
+            let add = "function add(uint left, uint right) -> uint {
+    return left + right;
+}
+";
+            assert_eq!(inlined.unwrap(), format!("{}\nprint(3);\n", add));
+            assert_eq!(
+                not_inlined.unwrap(),
+                format!("{}\nprint(add(1, 2));\n", add)
+            );
+        }
+
+        #[test]
+        fn ast_type_checking_error() {
+            let code = "print(true + 1);";
+
+            let result = ast("main.bch", &AstOptions::default(), read_main(code));
+
+            assert!(matches!(result, Err(RunError::Invalid(errors)) if errors.len() == 1));
+        }
+    }
+}
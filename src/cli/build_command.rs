@@ -0,0 +1,228 @@
+use std::{fs, io, path::Path};
+
+use crate::{bytecode, evaluation::EvaluationOptions};
+
+use super::{
+    run_command::{compile, load, on_program_thread, LintOptions, RunError},
+    BeachCommand, CommandError,
+};
+
+pub(super) struct BuildCommand;
+
+impl BeachCommand for BuildCommand {
+    fn name(&self) -> &'static str {
+        "build"
+    }
+
+    fn usage(&self) -> &'static str {
+        "beach build [--allow <lint>] [--deny warnings] [--strip-lines] [--output <file>.bchc] [program].bch"
+    }
+
+    fn description(&self) -> &'static str {
+        "compile a beach program to a .bchc file, which beach run can run"
+    }
+
+    fn run(&self, args: Vec<String>) -> Result<(), CommandError> {
+        let mut options = BuildOptions::default();
+        let mut program_file = None;
+        let mut output_file = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if options.lints.parse_option(&arg, &mut args, self.usage())? {
+                continue;
+            }
+
+            match arg.as_str() {
+                "--strip-lines" => options.line_info = false,
+                "--output" => {
+                    let Some(output) = args.next().filter(|output| output.ends_with(".bchc"))
+                    else {
+                        return Err(format!(
+                            "--output expects a file with .bchc extension\nusage: {}",
+                            self.usage()
+                        )
+                        .into());
+                    };
+                    output_file = Some(output);
+                }
+                option if option.starts_with("--") => {
+                    return Err(
+                        format!("unknown option {}\nusage: {}", option, self.usage()).into(),
+                    );
+                }
+                _ if program_file.is_none() => program_file = Some(arg),
+                _ => {
+                    return Err(format!(
+                    "the build command does not take any more sub commands or options\nusage: {}",
+                    self.usage()
+                )
+                    .into())
+                }
+            }
+        }
+
+        let Some(program_file) = program_file else {
+            return Err(format!("usage: {}", self.usage()).into());
+        };
+
+        if !program_file.ends_with(".bch") {
+            return Err("a beach program file must have .bch extension"
+                .to_owned()
+                .into());
+        }
+
+        let output_file = output_file.unwrap_or_else(|| format!("{}c", program_file));
+
+        let bytes = on_program_thread(EvaluationOptions::default().stack_size(), move || {
+            build(&program_file, &options, |path| fs::read_to_string(path))
+        })??;
+
+        fs::write(&output_file, bytes)
+            .map_err(|err| format!("Failed to write {}: {}", output_file, err).into())
+    }
+}
+
+/// how the lints found in a program are reported, and what is saved with its bytecode
+struct BuildOptions {
+    lints: LintOptions,
+    /// whether the line of each instruction is saved, so runtime errors can report where they occurred
+    line_info: bool,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            lints: LintOptions::default(),
+            line_info: true,
+        }
+    }
+}
+
+/// the contents of the `.bchc` file the program compiles to
+fn build(
+    program_file: &str,
+    options: &BuildOptions,
+    read_file: impl Fn(&Path) -> io::Result<String>,
+) -> Result<Vec<u8>, RunError> {
+    let ast = load(program_file, &options.lints, read_file)?;
+    let program = compile(&ast, program_file)?;
+
+    Ok(bytecode::encode(&program, options.line_info))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::BeachCommand;
+
+    use super::BuildCommand;
+
+    #[test]
+    fn build_command_name() {
+        let command = BuildCommand;
+
+        assert_eq!(command.name(), "build");
+    }
+
+    #[test]
+    fn build_command_usage() {
+        let command = BuildCommand;
+
+        assert_eq!(
+            command.usage(),
+            "beach build [--allow <lint>] [--deny warnings] [--strip-lines] [--output <file>.bchc] [program].bch"
+        );
+    }
+
+    mod command_run {
+        use crate::cli::{build_command::BuildCommand, BeachCommand};
+
+        #[test]
+        fn no_program_file() {
+            let command = BuildCommand;
+
+            let result = command.run(Vec::new());
+
+            assert!(
+                matches!(result, Err(error) if error.message == "usage: beach build [--allow <lint>] [--deny warnings] [--strip-lines] [--output <file>.bchc] [program].bch")
+            )
+        }
+
+        #[test]
+        fn invalid_output() {
+            let command = BuildCommand;
+
+            let args = vec![
+                "--output".to_owned(),
+                "hello.bch".to_owned(),
+                "hello.bch".to_owned(),
+            ];
+
+            let result = command.run(args);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "--output expects a file with .bchc extension\nusage: beach build [--allow <lint>] [--deny warnings] [--strip-lines] [--output <file>.bchc] [program].bch")
+            )
+        }
+
+        #[test]
+        fn incorrect_file_extension() {
+            let command = BuildCommand;
+
+            let result = command.run(vec!["hello.bchc".to_owned()]);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "a beach program file must have .bch extension")
+            )
+        }
+    }
+
+    mod build_function {
+        use std::{io, path::Path};
+
+        use crate::{
+            bytecode::decode,
+            cli::{
+                build_command::{build, BuildOptions},
+                run_command::RunError,
+            },
+        };
+
+        fn read_main(code: &'static str) -> impl Fn(&Path) -> io::Result<String> {
+            move |_| Ok(code.to_owned())
+        }
+
+        #[test]
+        fn build_ok() {
+            let code = "print(1);";
+
+            let result = build("main.bch", &BuildOptions::default(), read_main(code));
+
+            let program = decode(&result.unwrap()).unwrap();
+            assert_eq!(program.main.lines.len(), program.main.instructions.len());
+        }
+
+        #[test]
+        fn build_without_line_info() {
+            let code = "print(1);";
+            let options = BuildOptions {
+                line_info: false,
+                ..BuildOptions::default()
+            };
+
+            let result = build("main.bch", &options, read_main(code));
+
+            let program = decode(&result.unwrap()).unwrap();
+            assert!(program.main.lines.is_empty());
+        }
+
+        #[test]
+        fn build_type_checking_error() {
+            let code = "print(true + 1);";
+
+            let result = build("main.bch", &BuildOptions::default(), read_main(code));
+
+            assert!(matches!(result, Err(RunError::Invalid(errors)) if errors.len() == 1));
+        }
+    }
+}
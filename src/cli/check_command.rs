@@ -0,0 +1,250 @@
+use std::{fs, io, path::Path};
+
+use crate::{ast::node::Function, evaluation::EvaluationOptions};
+
+use super::{
+    run_command::{load, on_program_thread, LintOptions, RunError},
+    BeachCommand, CommandError,
+};
+
+pub(super) struct CheckCommand;
+
+impl BeachCommand for CheckCommand {
+    fn name(&self) -> &'static str {
+        "check"
+    }
+
+    fn usage(&self) -> &'static str {
+        "beach check [--allow <lint>] [--deny warnings] [--explain-effects] [program].bch"
+    }
+
+    fn description(&self) -> &'static str {
+        "check a beach program for errors without running it"
+    }
+
+    fn run(&self, args: Vec<String>) -> Result<(), CommandError> {
+        let mut options = CheckOptions::default();
+        let mut program_file = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if options.lints.parse_option(&arg, &mut args, self.usage())? {
+                continue;
+            }
+
+            match arg.as_str() {
+                "--explain-effects" => options.explain_effects = true,
+                option if option.starts_with("--") => {
+                    return Err(
+                        format!("unknown option {}\nusage: {}", option, self.usage()).into(),
+                    );
+                }
+                _ if program_file.is_none() => program_file = Some(arg),
+                _ => {
+                    return Err(format!(
+                    "the check command does not take any more sub commands or options\nusage: {}",
+                    self.usage()
+                )
+                    .into())
+                }
+            }
+        }
+
+        let Some(program_file) = program_file else {
+            return Err(format!("usage: {}", self.usage()).into());
+        };
+
+        if !program_file.ends_with(".bch") {
+            return Err("a beach program file must have .bch extension"
+                .to_owned()
+                .into());
+        }
+
+        let output = on_program_thread(EvaluationOptions::default().stack_size(), move || {
+            check(&program_file, &options, |path| fs::read_to_string(path))
+        })??;
+
+        print!("{}", output);
+
+        Ok(())
+    }
+}
+
+/// how the lints found in a program are reported, and what is printed once it's checked
+#[derive(Default)]
+struct CheckOptions {
+    lints: LintOptions,
+    /// whether each function is listed as pure or effectful, along with why it's effectful
+    explain_effects: bool,
+}
+
+/// type check the program, returning what should be printed about it
+fn check(
+    program_file: &str,
+    options: &CheckOptions,
+    read_file: impl Fn(&Path) -> io::Result<String>,
+) -> Result<String, RunError> {
+    let ast = load(program_file, &options.lints, read_file)?;
+    if !options.explain_effects {
+        return Ok(String::new());
+    }
+
+    let effects = ast.function_effects();
+
+    let mut functions: Vec<_> = ast
+        .functions
+        .values()
+        .filter_map(|function| match function {
+            Function::CustomFunction { id, span, .. } => Some((id, span)),
+            Function::Intrinsic { .. } => None,
+        })
+        .collect();
+    functions.sort_by_key(|(id, span)| (span.start_line, id.0.as_str()));
+
+    Ok(functions
+        .into_iter()
+        .map(|(id, _)| {
+            if effects.is_pure(id) {
+                format!("{} is pure\n", id)
+            } else {
+                let explanation = effects.explain(id).unwrap_or_default();
+                format!("{} is effectful: it {}\n", id, explanation)
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::BeachCommand;
+
+    use super::CheckCommand;
+
+    #[test]
+    fn check_command_name() {
+        let command = CheckCommand;
+
+        assert_eq!(command.name(), "check");
+    }
+
+    #[test]
+    fn check_command_usage() {
+        let command = CheckCommand;
+
+        assert_eq!(
+            command.usage(),
+            "beach check [--allow <lint>] [--deny warnings] [--explain-effects] [program].bch"
+        );
+    }
+
+    mod command_run {
+        use crate::cli::{check_command::CheckCommand, BeachCommand};
+
+        #[test]
+        fn no_program_file() {
+            let command = CheckCommand;
+
+            let result = command.run(Vec::new());
+
+            assert!(
+                matches!(result, Err(error) if error.message == "usage: beach check [--allow <lint>] [--deny warnings] [--explain-effects] [program].bch")
+            )
+        }
+
+        #[test]
+        fn unknown_option() {
+            let command = CheckCommand;
+
+            let result = command.run(vec!["--explain".to_owned(), "main.bch".to_owned()]);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "unknown option --explain\nusage: beach check [--allow <lint>] [--deny warnings] [--explain-effects] [program].bch")
+            )
+        }
+    }
+
+    mod check_function {
+        use std::{io, path::Path};
+
+        use crate::{
+            cli::{
+                check_command::{check, CheckOptions},
+                run_command::{on_program_thread, RunError},
+            },
+            evaluation::EvaluationOptions,
+        };
+
+        fn read_main(code: &'static str) -> impl Fn(&Path) -> io::Result<String> {
+            move |_| Ok(code.to_owned())
+        }
+
+        const CODE: &str = "function log(uint value) {
+    print(value);
+}
+function add(uint left, uint right) -> uint {
+    return left + right;
+}
+function report(uint value) {
+    log(add(value, 1));
+}
+report(1);";
+
+        #[test]
+        fn check_valid_program() {
+            let result = check("main.bch", &CheckOptions::default(), read_main(CODE));
+
+            assert_eq!(result.unwrap(), "");
+        }
+
+        #[test]
+        fn check_explain_effects() {
+            let options = CheckOptions {
+                explain_effects: true,
+                ..CheckOptions::default()
+            };
+
+            let result = check("main.bch", &options, read_main(CODE));
+
+            assert_eq!(
+                result.unwrap(),
+                "log is effectful: it calls print at line 2
+add is pure
+report is effectful: it calls log at line 8, which calls print at line 2
+"
+            );
+        }
+
+        #[test]
+        fn check_constant_stack_overflow() {
+            let code = "function count(uint value) -> uint {
+    return count(value) + 1;
+}
+const COUNT: uint = count(1);";
+
+            let result = on_program_thread(EvaluationOptions::default().stack_size(), move || {
+                check("main.bch", &CheckOptions::default(), read_main(code))
+            })
+            .unwrap();
+
+            assert!(
+                matches!(result, Err(RunError::Invalid(errors)) if errors.len() == 1 && errors[0].to_string().contains("stack overflow in count (depth 10001)"))
+            );
+        }
+
+        #[test]
+        fn check_pure_declaration() {
+            let code = "function log(uint value) {
+    print(value);
+}
+pure function report(uint value) {
+    log(value);
+}";
+
+            let result = check("main.bch", &CheckOptions::default(), read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Invalid(errors)) if errors.len() == 1 && errors[0].to_string().contains("function `report` at line 4 is declared pure, but it calls log at line 5, which calls print at line 2"))
+            );
+        }
+    }
+}
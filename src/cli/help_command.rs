@@ -1,6 +1,6 @@
 use crate::cli::get_commands;
 
-use super::BeachCommand;
+use super::{BeachCommand, CommandError};
 
 pub struct HelpCommand;
 
@@ -17,7 +17,7 @@ impl BeachCommand for HelpCommand {
         "prints help information for the beach cli"
     }
 
-    fn run(&self, _: Vec<String>) -> Result<(), String> {
+    fn run(&self, _: Vec<String>) -> Result<(), CommandError> {
         let commands = get_commands();
         println!(
             "usage: beach [command] [command_args]\n\t{}",
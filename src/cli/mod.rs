@@ -1,24 +1,67 @@
+mod ast_command;
+mod build_command;
+mod check_command;
 mod help_command;
 mod run_command;
 
-use self::{help_command::HelpCommand, run_command::RunCommand};
+use self::{
+    ast_command::AstCommand, build_command::BuildCommand, check_command::CheckCommand,
+    help_command::HelpCommand, run_command::RunCommand,
+};
+
+/// the status beach exits with when a command fails
+const FAILURE_EXIT_CODE: i32 = 1;
+/// the status beach exits with when a program stops because of a runtime error, so it can be told apart from a program
+/// that couldn't be run
+const RUNTIME_ERROR_EXIT_CODE: i32 = 2;
+
+/// why a command failed, and the status beach exits with because of it
+#[derive(Debug, PartialEq)]
+pub struct CommandError {
+    pub message: String,
+    pub exit_code: i32,
+}
+
+impl CommandError {
+    fn runtime_error(message: String) -> Self {
+        Self {
+            message,
+            exit_code: RUNTIME_ERROR_EXIT_CODE,
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            exit_code: FAILURE_EXIT_CODE,
+        }
+    }
+}
 
 pub trait BeachCommand {
     fn name(&self) -> &'static str;
     fn usage(&self) -> &'static str;
     fn description(&self) -> &'static str;
-    fn run(&self, args: Vec<String>) -> Result<(), String>;
+    fn run(&self, args: Vec<String>) -> Result<(), CommandError>;
 }
 
 fn get_commands() -> Box<[Box<dyn BeachCommand>]> {
-    let commands: Vec<Box<dyn BeachCommand>> = vec![Box::new(HelpCommand), Box::new(RunCommand)];
+    let commands: Vec<Box<dyn BeachCommand>> = vec![
+        Box::new(HelpCommand),
+        Box::new(BuildCommand),
+        Box::new(RunCommand),
+        Box::new(AstCommand),
+        Box::new(CheckCommand),
+    ];
 
     commands.into_boxed_slice()
 }
 
-// this function has no real output
+/// run the command named by the first argument, returning the status to exit with if it fails
 #[cfg(not(tarpaulin_include))]
-pub fn match_command(args: Vec<String>) -> Result<(), ()> {
+pub fn match_command(args: Vec<String>) -> Result<(), i32> {
     let mut args = args.into_iter();
     let commands = get_commands();
 
@@ -33,12 +76,12 @@ pub fn match_command(args: Vec<String>) -> Result<(), ()> {
 
     let Some(Some(found_command)) = found_command else {
         _ = help_command.run(args.collect());
-        return Err(());
+        return Err(FAILURE_EXIT_CODE);
     };
 
     if let Err(error) = found_command.run(args.collect()) {
-        println!("{error}");
-        Err(())
+        println!("{}", error.message);
+        Err(error.exit_code)
     } else {
         Ok(())
     }
@@ -1,8 +1,15 @@
-use std::{fs, ops::Range};
+use std::{fs, io, ops::Range, path::Path};
 
-use crate::{ast::builders::ast_builder::AstBuilder, parsing::parse_program};
+use crate::{
+    ast::node::Ast,
+    bytecode::{self, Program},
+    evaluation::{run_with_stack, EvaluationOptions, RuntimeError},
+    modules::{load_program, LoadError},
+    optimisation::OptimisationOptions,
+    type_checking::Lint,
+};
 
-use super::BeachCommand;
+use super::{BeachCommand, CommandError};
 
 pub(super) struct RunCommand;
 
@@ -12,45 +19,143 @@ impl BeachCommand for RunCommand {
     }
 
     fn usage(&self) -> &'static str {
-        "beach run [program].bch"
+        "beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch"
     }
 
     fn description(&self) -> &'static str {
         "run a beach program"
     }
 
-    fn run(&self, args: Vec<String>) -> Result<(), String> {
-        let Some(program_file) = args.first() else {
-            return Err(format!("usage: {}", self.usage()));
-        };
+    fn run(&self, args: Vec<String>) -> Result<(), CommandError> {
+        let mut options = RunOptions::default();
+        let mut program_file = None;
+        // the options that change how a program is checked or built, so can't apply to a compiled one
+        let mut source_options = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if options.lints.parse_option(&arg, &mut args, self.usage())? {
+                source_options.push(arg);
+                continue;
+            }
 
-        if !program_file.ends_with(".bch") {
-            return Err("a beach program file must have .bch extension".to_owned());
+            match arg.as_str() {
+                "--max-call-depth" => {
+                    let Some(max_call_depth) = args
+                        .next()
+                        .and_then(|depth| depth.parse().ok())
+                        .filter(|depth| *depth > 0)
+                    else {
+                        return Err(format!(
+                            "--max-call-depth expects a positive number of calls\nusage: {}",
+                            self.usage()
+                        )
+                        .into());
+                    };
+                    options.evaluation.max_call_depth = max_call_depth;
+                }
+                option if option.starts_with("--engine=") => {
+                    options.engine = match &option["--engine=".len()..] {
+                        "tree" => Engine::Tree,
+                        "vm" => Engine::Vm,
+                        engine => {
+                            return Err(format!(
+                                "unknown engine `{}`, expected tree or vm\nusage: {}",
+                                engine,
+                                self.usage()
+                            )
+                            .into())
+                        }
+                    };
+                    if options.engine == Engine::Tree {
+                        source_options.push(arg);
+                    }
+                }
+                "-O" => {
+                    options.optimise = true;
+                    source_options.push(arg);
+                }
+                "--inline-threshold" => {
+                    options.optimisation.inline_threshold =
+                        parse_inline_threshold(&mut args, self.usage())?;
+                    source_options.push(arg);
+                }
+                option if option.starts_with("--") => {
+                    return Err(
+                        format!("unknown option {}\nusage: {}", option, self.usage()).into(),
+                    );
+                }
+                _ if program_file.is_none() => program_file = Some(arg),
+                _ => {
+                    return Err(format!(
+                        "the run command does not take any more sub commands or options\nusage: {}",
+                        self.usage()
+                    )
+                    .into())
+                }
+            }
         }
 
-        if args.len() > 1 {
+        let Some(program_file) = program_file else {
+            return Err(format!("usage: {}", self.usage()).into());
+        };
+
+        let compiled = program_file.ends_with(".bchc");
+        if !compiled && !program_file.ends_with(".bch") {
+            return Err("a beach program file must have .bch or .bchc extension"
+                .to_owned()
+                .into());
+        }
+        if let Some(option) = source_options.first().filter(|_| compiled) {
             return Err(format!(
-                "the run command does not take any more sub commands or options\nusage: {}",
+                "{} can't be used with a compiled program, which runs on the virtual machine as it was built\nusage: {}",
+                option,
                 self.usage()
-            ));
+            )
+            .into());
         }
 
-        let code = match fs::read_to_string(program_file) {
-            Err(err) => {
-                return Err(format!("{:?}", err));
-            }
-            Ok(code) => code,
+        // the virtual machine doesn't nest calls on the host stack, so its stack only needs to be large enough to fold
+        // the program's constants
+        let stack_size = match (compiled, options.engine) {
+            (false, Engine::Tree) => options.evaluation.stack_size(),
+            _ => EvaluationOptions::default().stack_size(),
         };
 
-        run(&code).map_err(|err| {
-            format!(
+        let result = on_program_thread(stack_size, move || {
+            if compiled {
+                run_compiled(&program_file, &options, |path| fs::read(path))
+            } else {
+                run(&program_file, &options, |path| fs::read_to_string(path))
+            }
+        })?;
+
+        result.map_err(CommandError::from)
+    }
+}
+
+impl From<RunError> for CommandError {
+    fn from(error: RunError) -> Self {
+        match error {
+            RunError::Invalid(errors) => format!(
                 "Failed to run beach program: {}",
-                err.into_iter()
+                errors
+                    .into_iter()
                     .map(|e| e.to_string())
                     .collect::<Vec<_>>()
                     .join("\n")
             )
-        })
+            .into(),
+            RunError::Unreadable { file, message } => {
+                format!("Failed to run {}: {}", file, message).into()
+            }
+            RunError::Runtime { file, error } => CommandError::runtime_error(format!(
+                "Runtime error in {} at line {}: {}",
+                file,
+                error.line(),
+                error
+            )),
+        }
     }
 }
 
@@ -60,8 +165,109 @@ struct Position {
     character: u32,
 }
 
+/// how a program's statements are run
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Engine {
+    /// evaluate the ast directly
+    #[default]
+    Tree,
+    /// compile the ast to bytecode, and run it on the virtual machine
+    Vm,
+}
+
+/// how the lints found in a program are reported
+#[derive(Default)]
+pub(super) struct LintOptions {
+    /// the lints that aren't reported
+    allowed: Vec<Lint>,
+    /// whether warnings stop the program, as if they were errors
+    deny_warnings: bool,
+}
+
+impl LintOptions {
+    /// apply an `--allow` or `--deny` option, taking its value from `args`. Returns false for any other argument
+    pub(super) fn parse_option(
+        &mut self,
+        arg: &str,
+        args: &mut impl Iterator<Item = String>,
+        usage: &str,
+    ) -> Result<bool, CommandError> {
+        match arg {
+            "--allow" => {
+                let lint = args.next().unwrap_or_default();
+                let Some(lint) = Lint::from_name(&lint) else {
+                    return Err(format!(
+                        "unknown lint `{}`, expected one of {}",
+                        lint,
+                        Lint::ALL
+                            .iter()
+                            .map(Lint::name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                    .into());
+                };
+                self.allowed.push(lint);
+            }
+            "--deny" => {
+                if args.next().as_deref() != Some("warnings") {
+                    return Err(format!("--deny only accepts warnings\nusage: {}", usage).into());
+                }
+                self.deny_warnings = true;
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+/// run `task` on its own thread, with a host stack of `stack_size` bytes. Loading a program evaluates its constants,
+/// so every command loads it on a thread large enough for their calls
+pub(super) fn on_program_thread<T: Send + 'static>(
+    stack_size: usize,
+    task: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, CommandError> {
+    run_with_stack(stack_size, task)
+        .map_err(|err| format!("Failed to start beach program: {}", err).into())
+}
+
+/// the value of an `--inline-threshold` option, taken from `args`
+pub(super) fn parse_inline_threshold(
+    args: &mut impl Iterator<Item = String>,
+    usage: &str,
+) -> Result<usize, CommandError> {
+    args.next()
+        .and_then(|size| size.parse().ok())
+        .ok_or_else(|| {
+            format!(
+                "--inline-threshold expects a number of expressions\nusage: {}",
+                usage
+            )
+            .into()
+        })
+}
+
+/// how the lints found in a program are reported, and how the program is run
+#[derive(Default)]
+struct RunOptions {
+    lints: LintOptions,
+    evaluation: EvaluationOptions,
+    engine: Engine,
+    /// whether the ast is optimised before it runs. A compiled program is run as it was built
+    optimise: bool,
+    optimisation: OptimisationOptions,
+}
+
 #[derive(PartialEq, Debug)]
-struct BeachError {
+enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(PartialEq, Debug)]
+pub(super) struct BeachError {
+    severity: Severity,
     error: String,
     file: String,
     range: Range<Position>,
@@ -74,66 +280,181 @@ impl ToString for BeachError {
     }
 }
 
-fn run(code: &str) -> Result<(), Vec<BeachError>> {
-    let tokens = parse_program(code)
-        .map_err(|err| {
-            err.into_iter()
-                .map(|e| BeachError {
-                    range: Position {
-                        line: e.line,
-                        character: e.character_range.start,
-                    }..Position {
-                        line: e.line,
-                        character: e.character_range.end,
-                    },
-                    error: format!("Parsing error: {}", e.error),
-                    file: e.file,
-                })
-                .collect::<Vec<_>>()
-        })?
-        .into_iter()
-        .map(|source| source.token().clone())
-        .collect();
+/// why a program stopped without finishing
+#[derive(Debug)]
+pub(super) enum RunError {
+    /// the program couldn't be loaded, or didn't type check
+    Invalid(Vec<BeachError>),
+    /// the compiled program couldn't be read, or was compiled by an incompatible version of beach
+    Unreadable { file: String, message: String },
+    /// the program stopped with an error while it was running. `file` is the module the error occurred in
+    Runtime { file: String, error: RuntimeError },
+}
+
+/// the range used for errors that aren't tied to a position in the file
+fn file_start() -> Range<Position> {
+    Position {
+        line: 1,
+        character: 1,
+    }..Position {
+        line: 1,
+        character: 1,
+    }
+}
+
+fn run(
+    program_file: &str,
+    options: &RunOptions,
+    read_file: impl Fn(&Path) -> io::Result<String>,
+) -> Result<(), RunError> {
+    let mut ast = load(program_file, &options.lints, read_file)?;
+    if options.optimise {
+        ast.optimise(&options.optimisation);
+    }
+
+    let result = match options.engine {
+        Engine::Tree => ast.evaluate(&options.evaluation).map(|_| ()),
+        Engine::Vm => bytecode::run(&compile(&ast, program_file)?, &options.evaluation),
+    };
+
+    result.map_err(|error| RunError::Runtime {
+        file: runtime_error_file(&error, program_file),
+        error,
+    })
+}
 
-    let ast = AstBuilder::from_token_stream(tokens)
-        .map_err(|errors| {
+/// run a program compiled by `beach build`, which always runs on the virtual machine
+fn run_compiled(
+    program_file: &str,
+    options: &RunOptions,
+    read_file: impl Fn(&Path) -> io::Result<Vec<u8>>,
+) -> Result<(), RunError> {
+    let unreadable = |message: String| RunError::Unreadable {
+        file: program_file.to_owned(),
+        message,
+    };
+
+    let bytes = read_file(Path::new(program_file)).map_err(|err| unreadable(err.to_string()))?;
+    let program = bytecode::decode(&bytes).map_err(|err| unreadable(err.to_string()))?;
+
+    bytecode::run(&program, &options.evaluation).map_err(|error| RunError::Runtime {
+        file: runtime_error_file(&error, program_file),
+        error,
+    })
+}
+
+/// load, lint and type check a program, then link its modules together
+pub(super) fn load(
+    program_file: &str,
+    options: &LintOptions,
+    read_file: impl Fn(&Path) -> io::Result<String>,
+) -> Result<Ast, RunError> {
+    let mut program = load_program(program_file, read_file).map_err(|errors| {
+        RunError::Invalid(
             errors
                 .into_iter()
-                .map(|err| BeachError {
-                    error: err.message,
-                    file: "".to_owned(),
-                    range: Position {
-                        line: 1,
-                        character: 1,
-                    }..Position {
-                        line: 1,
-                        character: 1,
+                .map(|error| match error {
+                    LoadError::Parse(e) => BeachError {
+                        severity: Severity::Error,
+                        range: Position {
+                            line: e.line,
+                            character: e.character_range.start,
+                        }..Position {
+                            line: e.line,
+                            character: e.character_range.end,
+                        },
+                        error: format!("Parsing error: {}", e.error),
+                        file: e.file,
+                    },
+                    LoadError::Module(e) => BeachError {
+                        severity: Severity::Error,
+                        error: e.message,
+                        file: e.file,
+                        range: file_start(),
                     },
                 })
-                .collect::<Vec<_>>()
-        })?
-        .build();
+                .collect::<Vec<_>>(),
+        )
+    })?;
+
+    let warnings: Vec<_> = program
+        .find_warnings()
+        .into_iter()
+        .filter(|warning| !options.allowed.contains(&warning.lint))
+        .map(|warning| BeachError {
+            severity: if options.deny_warnings {
+                Severity::Error
+            } else {
+                Severity::Warning
+            },
+            error: warning.message,
+            file: warning.file,
+            range: Position {
+                line: warning.span.start_line,
+                character: 1,
+            }..Position {
+                line: warning.span.end_line,
+                character: 1,
+            },
+        })
+        .collect();
 
-    ast.type_check().map_err(|errors| {
+    let type_check_result = program.type_check().map_err(|errors| {
         errors
             .into_iter()
             .map(|err| BeachError {
+                severity: Severity::Error,
                 error: err.message,
-                file: "".to_owned(),
-                range: Position {
-                    line: 1,
-                    character: 1,
-                }..Position {
-                    line: 1,
-                    character: 1,
-                },
+                file: err.file,
+                range: file_start(),
             })
             .collect::<Vec<_>>()
-    })?;
+    });
 
-    ast.evaluate();
+    if options.deny_warnings && !warnings.is_empty() {
+        let mut errors = warnings;
+        errors.extend(type_check_result.err().unwrap_or_default());
+        return Err(RunError::Invalid(errors));
+    }
 
-    Ok(())
+    for warning in warnings {
+        eprintln!("{}", warning.to_string());
+    }
+
+    type_check_result.map_err(RunError::Invalid)?;
+
+    Ok(program.link())
+}
+
+/// compile a loaded program to bytecode. The type checker rejects any program the compiler can't compile, so an error
+/// isn't tied to a module, and is reported against the program file
+pub(super) fn compile(ast: &Ast, program_file: &str) -> Result<Program, RunError> {
+    bytecode::compile(ast).map_err(|error| {
+        RunError::Invalid(vec![BeachError {
+            severity: Severity::Error,
+            error: error.message,
+            file: program_file.to_owned(),
+            range: Position {
+                line: error.line,
+                character: 1,
+            }..Position {
+                line: error.line,
+                character: 1,
+            },
+        }])
+    })
+}
+
+/// the module the runtime error occurred in. Functions declared in imported modules have ids namespaced by the module's
+/// file, and everything else is in the entry module
+fn runtime_error_file(error: &RuntimeError, program_file: &str) -> String {
+    error
+        .backtrace
+        .first()
+        .and_then(|entry| entry.function_id.as_ref())
+        .and_then(|function_id| function_id.0.as_str().split_once("::"))
+        .map_or(program_file, |(file, _)| file)
+        .to_owned()
 }
 
 #[cfg(test)]
@@ -160,7 +481,10 @@ mod tests {
     fn run_command_usage() {
         let command = RunCommand;
 
-        assert_eq!(command.usage(), "beach run [program].bch");
+        assert_eq!(
+            command.usage(),
+            "beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch"
+        );
     }
 
     mod command_run {
@@ -173,7 +497,9 @@ mod tests {
 
             let result = command.run(args);
 
-            assert!(matches!(result, Err(error) if error == "usage: beach run [program].bch"));
+            assert!(
+                matches!(result, Err(error) if error.message == "usage: beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch")
+            );
         }
 
         #[test]
@@ -185,10 +511,126 @@ mod tests {
             let result = command.run(args);
 
             assert!(
-                matches!(result, Err(error) if error == "the run command does not take any more sub commands or options\nusage: beach run [program].bch")
+                matches!(result, Err(error) if error.message == "the run command does not take any more sub commands or options\nusage: beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch")
+            )
+        }
+
+        #[test]
+        fn unknown_lint() {
+            let command = RunCommand;
+
+            let args = vec![
+                "--allow".to_owned(),
+                "unused".to_owned(),
+                "hello.bch".to_owned(),
+            ];
+
+            let result = command.run(args);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "unknown lint `unused`, expected one of unreachable-code, unused-variables, unused-parameters, unused-functions")
+            )
+        }
+
+        #[test]
+        fn deny_requires_warnings() {
+            let command = RunCommand;
+
+            let args = vec!["--deny".to_owned(), "hello.bch".to_owned()];
+
+            let result = command.run(args);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "--deny only accepts warnings\nusage: beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch")
+            )
+        }
+
+        #[test]
+        fn unknown_option() {
+            let command = RunCommand;
+
+            let args = vec!["--fast".to_owned(), "hello.bch".to_owned()];
+
+            let result = command.run(args);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "unknown option --fast\nusage: beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch")
+            )
+        }
+
+        #[test]
+        fn invalid_max_call_depth() {
+            let command = RunCommand;
+
+            let args = vec![
+                "--max-call-depth".to_owned(),
+                "0".to_owned(),
+                "hello.bch".to_owned(),
+            ];
+
+            let result = command.run(args);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "--max-call-depth expects a positive number of calls\nusage: beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch")
+            )
+        }
+
+        #[test]
+        fn invalid_inline_threshold() {
+            let command = RunCommand;
+
+            let result = command.run(vec![
+                "-O".to_owned(),
+                "--inline-threshold".to_owned(),
+                "small".to_owned(),
+                "hello.bch".to_owned(),
+            ]);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "--inline-threshold expects a number of expressions\nusage: beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch")
+            )
+        }
+
+        #[test]
+        fn unknown_engine() {
+            let command = RunCommand;
+
+            let args = vec!["--engine=jit".to_owned(), "hello.bch".to_owned()];
+
+            let result = command.run(args);
+
+            assert!(
+                matches!(result, Err(error) if error.message == "unknown engine `jit`, expected tree or vm\nusage: beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch")
             )
         }
 
+        #[test]
+        fn compiled_program_with_source_options() {
+            let command = RunCommand;
+
+            for args in [
+                vec!["-O"],
+                vec!["--engine=tree"],
+                vec!["--allow", "unused-variables"],
+                vec!["--deny", "warnings"],
+                vec!["--inline-threshold", "10"],
+            ] {
+                let option = args[0];
+                let args = args
+                    .into_iter()
+                    .chain(["hello.bchc"])
+                    .map(str::to_owned)
+                    .collect();
+
+                let result = command.run(args);
+
+                assert!(
+                    matches!(result, Err(error) if error.message == format!("{option} can't be used with a compiled program, which runs on the virtual machine as it was built\nusage: beach run [--allow <lint>] [--deny warnings] [--max-call-depth <depth>] [--engine=tree|vm] [-O] [--inline-threshold <size>] [program].bch")),
+                    "{option}"
+                );
+            }
+        }
+
         #[test]
         fn incorrect_file_extension() {
             let command = RunCommand;
@@ -198,24 +640,39 @@ mod tests {
             let result = command.run(args);
 
             assert!(
-                matches!(result, Err(error) if error == "a beach program file must have .bch extension")
+                matches!(result, Err(error) if error.message == "a beach program file must have .bch or .bchc extension")
             )
         }
     }
 
     mod run_function {
-        use crate::cli::run_command::{run, BeachError, Position};
+        use std::{io, path::Path};
+
+        use crate::{
+            bytecode::{self, encode},
+            cli::run_command::{
+                load, on_program_thread, run, run_compiled, BeachError, Engine, LintOptions,
+                Position, RunError, RunOptions, Severity,
+            },
+            evaluation::EvaluationOptions,
+            type_checking::Lint,
+        };
+
+        fn read_main(code: &'static str) -> impl Fn(&Path) -> io::Result<String> {
+            move |_| Ok(code.to_owned())
+        }
 
         #[test]
         fn parsing_error() {
             let code = "~";
 
-            let result = run(code);
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
 
             assert!(
-                matches!(result, Err(e) if e.len() == 1 && e[0] == BeachError{
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0] == BeachError{
+                    severity: Severity::Error,
                     error:"Parsing error: Unexpected character `~`".to_owned(),
-                     file: "my_file".to_owned(),
+                     file: "main.bch".to_owned(),
                       range: Position {
                         line: 1,
                         character: 1,
@@ -230,10 +687,10 @@ mod tests {
         fn token_stream_error() {
             let code = "infer my_var = true";
 
-            let result = run(code);
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
 
             assert!(
-                matches!(result, Err(e) if e.len() == 1 && e[0] == BeachError{error:"expected ;".to_owned(), file: "".to_owned(), range: Position {
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0] == BeachError{severity: Severity::Error, error:"expected ;".to_owned(), file: "main.bch".to_owned(), range: Position {
                         line: 1,
                         character: 1,
                     }..Position {
@@ -247,10 +704,10 @@ mod tests {
         fn type_checking_error() {
             let code = "if (1) {}";
 
-            let result = run(code);
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
 
             assert!(
-                matches!(result, Err(e) if e.len() == 1 && e[0] == BeachError {error:"Expected type to be Boolean, but found UInt".to_owned(), file: "".to_owned(), range: Position {
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0] == BeachError {severity: Severity::Error, error:"Expected type to be Boolean, but found UInt".to_owned(), file: "main.bch".to_owned(), range: Position {
                         line: 1,
                         character: 1,
                     }..Position {
@@ -260,11 +717,300 @@ mod tests {
             )
         }
 
+        #[test]
+        fn out_of_scope_error() {
+            let code = "infer limit = 3;
+if (limit > 2) {
+    infer next = limit + 1;
+}
+print(next);";
+
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0].error == "`next` is not in scope here; it was declared inside the if block at line 2")
+            )
+        }
+
+        #[test]
+        fn missing_return_error() {
+            let code = "function sign(uint value) -> uint {
+    if (value > 0) {
+        return 1;
+    }
+}
+print(sign(2));";
+
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0].error == "not all paths of sign return a value; its closing brace at line 5 can be reached without a return")
+            )
+        }
+
+        #[test]
+        fn duplicate_function_error() {
+            let code = "function helper() {}
+print(1);
+function helper() {}
+helper();";
+
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0].error == "function `helper` is declared twice, at line 1 and line 3")
+            )
+        }
+
+        #[test]
+        fn duplicate_lambda_parameter_error() {
+            let code = "infer add = function(uint value, uint value) -> uint {
+    return value;
+};
+print(add(1, 2));";
+
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0].error == "the lambda at line 1 declares parameter `value` twice, as parameters 1 and 2")
+            )
+        }
+
+        #[test]
+        fn misspelled_function_error() {
+            let code = "function fibonnacci(uint limit) -> uint {
+    return limit;
+}
+print(fibonacci(10));";
+
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0].error == "Could not find function with name fibonacci\nhelp: did you mean \"fibonnacci\"?")
+            )
+        }
+
+        #[test]
+        fn unknown_variable_reported_once() {
+            let code = "infer total = count;
+infer (first, second) = total;
+if (first > second) {
+    print(total);
+}";
+
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0].error == "Could not find variable with name count")
+            )
+        }
+
+        #[test]
+        fn deny_warnings() {
+            let code = "infer unused = 1;\nprint(2);";
+            let options = RunOptions {
+                lints: LintOptions {
+                    deny_warnings: true,
+                    ..LintOptions::default()
+                },
+                ..RunOptions::default()
+            };
+
+            let result = run("main.bch", &options, read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Invalid(e)) if e.len() == 1 && e[0] == BeachError {
+                    severity: Severity::Error,
+                    error: "variable `unused` is never used".to_owned(),
+                    file: "main.bch".to_owned(),
+                    range: Position {
+                        line: 1,
+                        character: 1,
+                    }..Position {
+                        line: 1,
+                        character: 1,
+                    },
+                })
+            )
+        }
+
+        #[test]
+        fn deny_warnings_with_lint_allowed() {
+            let code = "infer unused = 1;\nprint(2);";
+            let options = RunOptions {
+                lints: LintOptions {
+                    allowed: vec![Lint::UnusedVariables],
+                    deny_warnings: true,
+                },
+                ..RunOptions::default()
+            };
+
+            let result = run("main.bch", &options, read_main(code));
+
+            assert!(result.is_ok());
+        }
+
+        #[test]
+        fn runtime_error() {
+            let code = "function add(uint left, uint right) -> uint {
+    return left + right;
+}
+print(add(1, 4294967295));";
+
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Runtime { file, error }) if file == "main.bch" && error.line() == 2 && error.message == "1 + 4294967295 is too large for a uint")
+            )
+        }
+
+        #[test]
+        fn optimised_runtime_error() {
+            let code = "uint big = 4294967295;
+print(big + 0);
+print(4294967295 + 1);";
+            let options = RunOptions {
+                optimise: true,
+                ..RunOptions::default()
+            };
+
+            let result = run("main.bch", &options, read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Runtime { error, .. }) if error.line() == 3 && error.message == "4294967295 + 1 is too large for a uint")
+            )
+        }
+
+        #[test]
+        fn stack_overflow() {
+            let code = "function count(uint value) -> uint {
+    return count(value + 1) + 1;
+}
+print(count(0));";
+            let options = RunOptions {
+                evaluation: EvaluationOptions { max_call_depth: 5 },
+                ..RunOptions::default()
+            };
+
+            let result = run("main.bch", &options, read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Runtime { error, .. }) if error.message == "stack overflow in count (depth 6)" && error.backtrace.len() == 6)
+            )
+        }
+
+        #[test]
+        fn stack_overflow_when_host_stack_runs_out() {
+            let code = "function count(uint value) -> uint {
+    return count(value + 1) + 1;
+}
+print(count(0));";
+            let options = RunOptions {
+                evaluation: EvaluationOptions {
+                    max_call_depth: usize::MAX,
+                },
+                ..RunOptions::default()
+            };
+
+            // the stack runs out long before the maximum depth is reached
+            let result = on_program_thread(16 * 1024 * 1024, move || {
+                run("main.bch", &options, read_main(code))
+            })
+            .unwrap();
+
+            assert!(
+                matches!(result, Err(RunError::Runtime { error, .. }) if error.message.starts_with("stack overflow in count (depth "))
+            )
+        }
+
+        #[test]
+        fn vm_runtime_error() {
+            let code = "function add(uint left, uint right) -> uint {
+    return left + right;
+}
+print(add(1, 4294967295));";
+            let options = RunOptions {
+                engine: Engine::Vm,
+                ..RunOptions::default()
+            };
+
+            let result = run("main.bch", &options, read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Runtime { file, error }) if file == "main.bch" && error.line() == 2 && error.message == "1 + 4294967295 is too large for a uint")
+            )
+        }
+
+        #[test]
+        fn vm_stack_overflow() {
+            let code = "function count(uint value) -> uint {
+    return count(value + 1) + 1;
+}
+print(count(0));";
+            let options = RunOptions {
+                evaluation: EvaluationOptions { max_call_depth: 5 },
+                engine: Engine::Vm,
+                ..RunOptions::default()
+            };
+
+            let result = run("main.bch", &options, read_main(code));
+
+            assert!(
+                matches!(result, Err(RunError::Runtime { error, .. }) if error.message == "stack overflow in count (depth 6)" && error.backtrace.len() == 6)
+            )
+        }
+
+        /// the contents of the `.bchc` file the code compiles to
+        fn build(code: &'static str) -> Vec<u8> {
+            let ast = load("main.bch", &LintOptions::default(), read_main(code)).unwrap();
+
+            encode(&bytecode::compile(&ast).unwrap(), true)
+        }
+
+        #[test]
+        fn run_compiled_runtime_error() {
+            let bytes = build(
+                "function add(uint left, uint right) -> uint {
+    return left + right;
+}
+print(add(1, 4294967295));",
+            );
+
+            let result = run_compiled("main.bchc", &RunOptions::default(), |_| Ok(bytes.clone()));
+
+            assert!(
+                matches!(result, Err(RunError::Runtime { file, error }) if file == "main.bchc" && error.line() == 2 && error.message == "1 + 4294967295 is too large for a uint")
+            )
+        }
+
+        #[test]
+        fn run_compiled_incompatible_version() {
+            let mut bytes = build("print(1);");
+            bytes[4..8].copy_from_slice(&0u32.to_le_bytes());
+
+            let result = run_compiled("main.bchc", &RunOptions::default(), |_| Ok(bytes.clone()));
+
+            assert!(
+                matches!(result, Err(RunError::Unreadable { file, message }) if file == "main.bchc" && message.starts_with("compiled by beach 0.1.0 (format version 0)"))
+            )
+        }
+
+        #[test]
+        fn run_compiled_source_file() {
+            let result = run_compiled("main.bchc", &RunOptions::default(), |_| {
+                Ok(b"print(1);".to_vec())
+            });
+
+            assert!(
+                matches!(result, Err(RunError::Unreadable { message, .. }) if message == "not a compiled beach program")
+            )
+        }
+
         #[test]
         fn run_ok() {
             let code = "if (true) { print(1); }";
 
-            let result = run(code);
+            let result = run("main.bch", &RunOptions::default(), read_main(code));
 
             assert!(result.is_ok());
         }
@@ -1,56 +1,205 @@
-use std::collections::HashMap;
+use std::rc::Rc;
 
-use crate::ast::node::{Ast, FunctionId, Node, Value};
+use crate::ast::{
+    node::{Ast, Constant, Expression, Global, Node},
+    scope::if_statement_declarations,
+};
 
-use super::{intrinsics::get_intrinsic_functions, Functions, NodeResult};
+use super::{
+    intrinsics::get_intrinsic_functions,
+    slots::{resolve_expression, resolve_function, resolve_slots},
+    EvaluationOptions, Frame, Functions, NodeResult, Runtime, RuntimeError, CONSTANT_CALL_BUDGET,
+    UNINITIALISED_GLOBAL,
+};
 
 impl Ast {
-    pub fn evaluate(&self) -> NodeResult {
+    /// run the program. The ast is consumed, as its variables are resolved to slots and its functions are moved into
+    /// the table that calls share
+    pub fn evaluate(mut self, options: &EvaluationOptions) -> Result<NodeResult, RuntimeError> {
+        resolve_slots(&mut self);
+
         let functions = get_intrinsic_functions()
             .into_iter()
+            .chain(self.functions)
+            .map(|(id, function)| (id, Rc::new(function)))
+            .collect();
+
+        let mut runtime = Runtime {
+            max_call_depth: options.max_call_depth,
+            ..Runtime::default()
+        };
+
+        evaluate_constants(&self.constants, &functions, &mut runtime)?;
+
+        // globals are initialised in declaration order, before any of the program's statements run
+        for (index, global) in self.globals.iter().enumerate() {
+            runtime.line = global.line;
+            let value = global
+                .value
+                .evaluate(&functions, &Frame::default(), &mut runtime)
+                .map_err(|error| {
+                    explain_uninitialised(error, &self.globals[index..], &mut runtime)
+                })?;
+
+            runtime.globals.insert(global.name, value);
+        }
+
+        evaluate_nodes(&self.nodes, &mut Frame::default(), &mut runtime, &functions)
+    }
+
+    /// evaluate each constant and replace its initialiser with the resulting value.
+    /// `imports` holds the already folded constants, and the functions, of the modules this ast can refer to
+    pub fn fold_constants(&mut self, imports: &Ast) -> Result<(), RuntimeError> {
+        // the functions are resolved in a copy, as the ast keeps its declarations for the passes after this one
+        let functions: Functions = get_intrinsic_functions()
+            .into_iter()
+            .chain(imports.functions.clone())
             .chain(self.functions.clone())
+            .map(|(id, mut function)| {
+                resolve_function(&mut function);
+                (id, Rc::new(function))
+            })
             .collect();
 
-        let mut call_stack: Vec<FunctionId> = Vec::new();
+        let mut runtime = Runtime {
+            call_budget: Some(CONSTANT_CALL_BUDGET),
+            ..Runtime::default()
+        };
 
-        let mut local_variables = HashMap::new();
+        evaluate_constants(&imports.constants, &functions, &mut runtime)?;
 
-        evaluate_nodes(
-            &self.nodes,
-            &mut local_variables,
-            &mut call_stack,
-            &functions,
-        )
+        for constant in &mut self.constants {
+            runtime.line = constant.line;
+            let mut value = constant.value.clone();
+            resolve_expression(&mut value);
+
+            let value = value.evaluate(&functions, &Frame::default(), &mut runtime)?;
+
+            runtime.constants.insert(constant.name, value.clone());
+            constant.value = Expression::ValueLiteral(value);
+        }
+
+        Ok(())
+    }
+}
+
+/// evaluate the constants in declaration order, so each constant can use the constants declared before it. The
+/// constants' variables have already been resolved to slots
+fn evaluate_constants(
+    constants: &[Constant],
+    functions: &Functions,
+    runtime: &mut Runtime,
+) -> Result<(), RuntimeError> {
+    for constant in constants {
+        runtime.line = constant.line;
+        let value = constant
+            .value
+            .evaluate(functions, &Frame::default(), runtime)?;
+
+        runtime.constants.insert(constant.name, value);
     }
+
+    Ok(())
 }
 
+/// evaluate a block's statements. The variables the block declares are pushed to the frame, and end with the block
 pub(super) fn evaluate_nodes(
     nodes: &[Node],
-    local_variables: &HashMap<String, Value>,
-    call_stack: &mut Vec<FunctionId>,
+    frame: &mut Frame,
+    runtime: &mut Runtime,
     functions: &Functions,
-) -> NodeResult {
-    let mut local_variables = local_variables.clone();
-    for node in nodes {
-        let return_value = node.evaluate(&mut local_variables, call_stack, &functions);
-        if return_value.is_return() {
-            return return_value;
+) -> Result<NodeResult, RuntimeError> {
+    let slot_count = frame.slot_count();
+    let depth = runtime.call_stack.len();
+    let mut result = NodeResult::None;
+    for (index, node) in nodes.iter().enumerate() {
+        runtime.line = node.line();
+        result = match node.evaluate(frame, runtime, functions) {
+            Ok(result) => result,
+            Err(error) => {
+                return Err(explain_out_of_scope(error, &nodes[..index], depth, runtime));
+            }
+        };
+        if result.is_return() {
+            break;
+        }
+    }
+
+    frame.truncate(slot_count);
+
+    Ok(result)
+}
+
+/// explain an error for a variable that isn't in scope if the variable is one of the `uninitialised` globals, whose
+/// initialisers haven't run yet
+fn explain_uninitialised(
+    mut error: RuntimeError,
+    uninitialised: &[Global],
+    runtime: &mut Runtime,
+) -> RuntimeError {
+    if let Some((name, _)) = runtime.out_of_scope {
+        if uninitialised.iter().any(|global| global.name == name) {
+            error.message = UNINITIALISED_GLOBAL.to_owned();
+            runtime.out_of_scope = None;
         }
     }
 
-    NodeResult::None
+    error
+}
+
+/// explain an error for a variable that isn't in scope if the variable was declared in one of the if statements that
+/// ended before it was used. Only errors raised by this block's function, at `depth`, are explained. The declarations
+/// are only looked up once an error happens, so evaluating an if statement doesn't have to record them
+fn explain_out_of_scope(
+    mut error: RuntimeError,
+    evaluated: &[Node],
+    depth: usize,
+    runtime: &mut Runtime,
+) -> RuntimeError {
+    let Some((name, error_depth)) = runtime.out_of_scope else {
+        return error;
+    };
+
+    if error_depth != depth {
+        return error;
+    }
+
+    let declaration = evaluated
+        .iter()
+        .rev()
+        .filter_map(|node| match node {
+            Node::IfStatement(if_statement) => Some(if_statement),
+            _ => None,
+        })
+        .find_map(|if_statement| {
+            if_statement_declarations(if_statement)
+                .into_iter()
+                .rev()
+                .find(|variable| variable.name == name)
+        });
+
+    if let Some(variable) = declaration {
+        error.message = variable.to_string();
+        runtime.out_of_scope = None;
+    }
+
+    error
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, rc::Rc};
 
     use crate::{
-        ast::node::{
-            Ast, Expression, Function, FunctionCall, FunctionId, FunctionParameter,
-            FunctionReturnType, IfStatement, Node, Type, VariableDeclarationType,
+        ast::{
+            builders::ast_builder::AstBuilder,
+            node::{
+                Ast, BinaryOperation, Constant, Expression, Function, FunctionCall, FunctionId,
+                FunctionParameter, FunctionReturnType, Global, IfStatement, Node, Operation, Span,
+                Type, VariableDeclarationType,
+            },
         },
-        evaluation::NodeResult,
+        evaluation::{EvaluationOptions, Frame, NodeResult, Runtime},
     };
 
     use super::evaluate_nodes;
@@ -60,32 +209,46 @@ mod tests {
         let nodes = vec![
             Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             },
             Node::FunctionCall(FunctionCall {
-                function_id: FunctionId("my_function".to_owned()),
-                parameters: vec![Expression::VariableAccess("my_var".to_owned())],
+                function_id: FunctionId("my_function".into()),
+                parameters: vec![Expression::LocalVariableAccess {
+                    name: "my_var".into(),
+                    slot: 0,
+                }],
+                line: 0,
+                slot: None,
             }),
         ];
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
-            Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+            FunctionId("my_function".into()),
+            Rc::new(Function::CustomFunction {
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::Boolean,
-                    param_name: "param".to_owned(),
+                    param_name: "param".into(),
                 }],
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
-            },
+                span: Span::default(),
+                pure: false,
+            }),
         )]);
 
-        let result = evaluate_nodes(&nodes, &HashMap::new(), &mut Vec::new(), &functions);
+        let result = evaluate_nodes(
+            &nodes,
+            &mut Frame::default(),
+            &mut Runtime::default(),
+            &functions,
+        );
 
-        assert_eq!(result, NodeResult::None)
+        assert_eq!(result, Ok(NodeResult::None))
     }
 
     #[test]
@@ -93,64 +256,390 @@ mod tests {
         let nodes = vec![
             Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             },
             Node::IfStatement(IfStatement {
                 check_expression: true.into(),
                 if_block: vec![Node::FunctionReturn {
-                    return_value: Some(Expression::VariableAccess("my_var".to_owned())),
+                    return_value: Some(Expression::LocalVariableAccess {
+                        name: "my_var".into(),
+                        slot: 0,
+                    }),
+                    line: 0,
                 }],
                 else_if_blocks: Vec::new(),
                 else_block: None,
+                line: 0,
             }),
             // extra return to check we return out early if we get a return value
             Node::FunctionReturn {
                 return_value: Some(false.into()),
+                line: 0,
             },
         ];
 
-        let result = evaluate_nodes(&nodes, &HashMap::new(), &mut Vec::new(), &HashMap::new());
+        let result = evaluate_nodes(
+            &nodes,
+            &mut Frame::default(),
+            &mut Runtime::default(),
+            &HashMap::new(),
+        );
 
         assert_eq!(
             result,
-            NodeResult::FunctionReturn {
+            Ok(NodeResult::FunctionReturn {
                 value: Some(true.into())
-            }
+            })
+        );
+    }
+
+    #[test]
+    fn test_evaluate_nodes_variable_out_of_scope() {
+        let nodes = vec![
+            Node::IfStatement(IfStatement {
+                check_expression: false.into(),
+                if_block: Vec::new(),
+                else_if_blocks: Vec::new(),
+                else_block: Some(vec![Node::VariableDeclaration {
+                    var_type: VariableDeclarationType::Infer,
+                    var_name: "my_var".into(),
+                    value: true.into(),
+                    line: 0,
+                }]),
+                line: 5,
+            }),
+            Node::FunctionReturn {
+                return_value: Some(Expression::VariableAccess("my_var".into())),
+                line: 0,
+            },
+        ];
+
+        let result = evaluate_nodes(
+            &nodes,
+            &mut Frame::default(),
+            &mut Runtime::default(),
+            &HashMap::new(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.message == "`my_var` is not in scope here; it was declared inside the else block of the if statement at line 5")
         );
     }
 
+    #[test]
+    fn test_evaluate_nodes_variable_out_of_scope_in_called_function() {
+        let read_my_var = Function::CustomFunction {
+            id: FunctionId("read_my_var".into()),
+            name: "read_my_var".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Type(Type::Boolean),
+            body: vec![Node::FunctionReturn {
+                return_value: Some(Expression::VariableAccess("my_var".into())),
+                line: 2,
+            }],
+            span: Span::default(),
+            pure: false,
+        };
+        let functions = HashMap::from_iter([(*read_my_var.id(), Rc::new(read_my_var))]);
+
+        let nodes = vec![
+            Node::IfStatement(IfStatement {
+                check_expression: true.into(),
+                if_block: vec![Node::VariableDeclaration {
+                    var_type: VariableDeclarationType::Infer,
+                    var_name: "my_var".into(),
+                    value: true.into(),
+                    line: 5,
+                }],
+                else_if_blocks: Vec::new(),
+                else_block: None,
+                line: 4,
+            }),
+            Node::FunctionCall(FunctionCall {
+                function_id: FunctionId("read_my_var".into()),
+                parameters: Vec::new(),
+                line: 7,
+                slot: None,
+            }),
+        ];
+
+        let result = evaluate_nodes(
+            &nodes,
+            &mut Frame::default(),
+            &mut Runtime::default(),
+            &functions,
+        );
+
+        // the caller's if statement isn't visible from the function, so it doesn't explain the error
+        assert!(matches!(result, Err(e) if e.message == "`my_var` is not in scope here"));
+    }
+
     #[test]
     fn test_ast_evaluate() {
         let ast_functions = HashMap::from_iter([(
-            FunctionId("function_1".to_owned()),
+            FunctionId("function_1".into()),
             Function::CustomFunction {
-                id: FunctionId("function_1".to_owned()),
-                name: "function_1".to_owned(),
+                id: FunctionId("function_1".into()),
+                name: "function_1".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
         let nodes = vec![
             Node::FunctionCall(FunctionCall {
-                function_id: FunctionId("function_1".to_owned()),
+                function_id: FunctionId("function_1".into()),
                 parameters: Vec::new(),
+                line: 0,
+                slot: None,
             }),
             Node::FunctionCall(FunctionCall {
-                function_id: FunctionId("print".to_owned()),
+                function_id: FunctionId("print".into()),
                 parameters: vec![10.into()],
+                line: 0,
+                slot: None,
             }),
         ];
 
         let ast = Ast {
             functions: ast_functions,
+            constants: Vec::new(),
+            globals: Vec::new(),
+            type_aliases: Vec::new(),
             nodes,
         };
 
-        let result = ast.evaluate();
+        let result = ast.evaluate(&EvaluationOptions::default());
+
+        assert_eq!(result, Ok(NodeResult::None))
+    }
+
+    #[test]
+    fn test_ast_fold_constants() {
+        let limit_function = Function::CustomFunction {
+            id: FunctionId("limit".into()),
+            name: "limit".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![Node::FunctionReturn {
+                return_value: Some(Expression::VariableAccess("LIMIT".into())),
+                line: 0,
+            }],
+            span: Span::default(),
+            pure: false,
+        };
+
+        let imports = Ast {
+            constants: vec![Constant {
+                name: "LIMIT".into(),
+                const_type: Type::UInt,
+                value: 5.into(),
+                line: 0,
+            }],
+            ..Ast::default()
+        };
+
+        let mut ast = Ast {
+            functions: HashMap::from_iter([(*limit_function.id(), limit_function)]),
+            constants: vec![Constant {
+                name: "DOUBLE_LIMIT".into(),
+                const_type: Type::UInt,
+                value: Expression::Operation(Operation::Binary {
+                    operation: BinaryOperation::Plus,
+                    left: Box::new(Expression::VariableAccess("LIMIT".into())),
+                    right: Box::new(Expression::FunctionCall(FunctionCall {
+                        function_id: FunctionId("limit".into()),
+                        parameters: Vec::new(),
+                        line: 0,
+                        slot: None,
+                    })),
+                }),
+                line: 0,
+            }],
+            globals: Vec::new(),
+            type_aliases: Vec::new(),
+            nodes: Vec::new(),
+        };
+
+        ast.fold_constants(&imports).unwrap();
+
+        assert_eq!(
+            ast.constants,
+            vec![Constant {
+                name: "DOUBLE_LIMIT".into(),
+                const_type: Type::UInt,
+                value: 10.into(),
+                line: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_ast_fold_constants_call_budget() {
+        // the recursive call is a tail call, so it never overflows the stack
+        let forever = Function::CustomFunction {
+            id: FunctionId("forever".into()),
+            name: "forever".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![Node::FunctionReturn {
+                return_value: Some(Expression::FunctionCall(FunctionCall {
+                    function_id: FunctionId("forever".into()),
+                    parameters: Vec::new(),
+                    line: 0,
+                    slot: None,
+                })),
+                line: 0,
+            }],
+            span: Span::default(),
+            pure: false,
+        };
+
+        let mut ast = Ast {
+            functions: HashMap::from_iter([(*forever.id(), forever)]),
+            constants: vec![Constant {
+                name: "NEVER".into(),
+                const_type: Type::UInt,
+                value: Expression::FunctionCall(FunctionCall {
+                    function_id: FunctionId("forever".into()),
+                    parameters: Vec::new(),
+                    line: 0,
+                    slot: None,
+                }),
+                line: 0,
+            }],
+            ..Ast::default()
+        };
+
+        let result = ast.fold_constants(&Ast::default());
+
+        assert!(
+            matches!(result, Err(error) if error.message == "evaluation made too many calls without finishing, so the value can't be known at compile time")
+        );
+    }
+
+    #[test]
+    fn test_ast_evaluate_globals() {
+        let read_count = Function::CustomFunction {
+            id: FunctionId("read_count".into()),
+            name: "read_count".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![Node::FunctionReturn {
+                return_value: Some(Expression::VariableAccess("count".into())),
+                line: 0,
+            }],
+            span: Span::default(),
+            pure: false,
+        };
+
+        let ast = Ast {
+            functions: HashMap::from_iter([(*read_count.id(), read_count)]),
+            constants: Vec::new(),
+            globals: vec![Global {
+                name: "count".into(),
+                global_type: Type::UInt,
+                mutable: true,
+                value: 1.into(),
+                line: 0,
+            }],
+            nodes: vec![
+                Node::Assignment {
+                    var_name: "count".into(),
+                    value: 2.into(),
+                    line: 0,
+                },
+                Node::FunctionReturn {
+                    return_value: Some(Expression::FunctionCall(FunctionCall {
+                        function_id: FunctionId("read_count".into()),
+                        parameters: Vec::new(),
+                        line: 0,
+                        slot: None,
+                    })),
+                    line: 0,
+                },
+            ],
+            type_aliases: Vec::new(),
+        };
+
+        let result = ast.evaluate(&EvaluationOptions::default());
+
+        assert_eq!(
+            result,
+            Ok(NodeResult::FunctionReturn {
+                value: Some(2.into())
+            })
+        );
+    }
+
+    #[test]
+    fn test_ast_evaluate_global_error_line() {
+        let ast = AstBuilder::default()
+            .global(|global| {
+                global
+                    .name("count")
+                    .global_type(Type::UInt)
+                    .line(4)
+                    .value(|value| {
+                        value.operation(|operation| {
+                            operation.plus(
+                                |left| left.value_literal(u32::MAX.into()),
+                                |right| right.value_literal(1.into()),
+                            )
+                        })
+                    })
+            })
+            .build();
+
+        let result = ast.evaluate(&EvaluationOptions::default());
+
+        assert!(matches!(result, Err(error) if error.line() == 4));
+    }
 
-        assert_eq!(result, NodeResult::None)
+    #[test]
+    fn test_ast_evaluate_global_read_before_initialised() {
+        let ast = AstBuilder::default()
+            .function_declaration(|function| {
+                function
+                    .name("read_later")
+                    .parameters(Vec::new())
+                    .return_type(Type::UInt)
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.return_value(|value| value.variable("later"))
+                        })
+                        .build()
+                    })
+            })
+            .global(|global| {
+                global.name("first").global_type(Type::UInt).value(|value| {
+                    value.function_call(|call| {
+                        call.function_id("read_later").no_parameters().build()
+                    })
+                })
+            })
+            .global(|global| {
+                global
+                    .name("later")
+                    .global_type(Type::UInt)
+                    .value(|value| value.value_literal(1.into()))
+            })
+            .build();
+
+        let result = ast.evaluate(&EvaluationOptions::default());
+
+        assert!(
+            matches!(result, Err(error) if error.message == "a global was read before it was initialised")
+        );
     }
 }
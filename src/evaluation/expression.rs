@@ -1,28 +1,77 @@
-use std::collections::HashMap;
+use std::rc::Rc;
 
-use crate::ast::node::{Expression, FunctionCall, FunctionId, FunctionReturnType, Value};
+use crate::ast::node::{Expression, FunctionCall, FunctionId, FunctionValue, Value};
 
-use super::Functions;
+use super::{Frame, Functions, Runtime, RuntimeError};
 
 impl Expression {
     pub fn evaluate(
         &self,
         functions: &Functions,
-        local_variables: &HashMap<String, Value>,
-        call_stack: &mut Vec<FunctionId>,
-    ) -> Value {
+        frame: &Frame,
+        runtime: &mut Runtime,
+    ) -> Result<Value, RuntimeError> {
         match self {
-            Expression::ValueLiteral(value) => value.clone(),
+            Expression::ValueLiteral(value) => Ok(value.clone()),
             Expression::FunctionCall(function_call) => {
-                evaluate_function_call(function_call, functions, local_variables, call_stack)
+                evaluate_function_call(function_call, functions, frame, runtime)
             }
-            Expression::Operation(operation) => {
-                operation.evaluate(functions, local_variables, call_stack)
+            Expression::Operation(operation) => operation.evaluate(functions, frame, runtime),
+            Expression::LocalVariableAccess { name, slot } => match frame.get(*slot) {
+                Some(value) => Ok(value.clone()),
+                None => Err(runtime.not_in_scope(*name)),
+            },
+            // local variables have been resolved to slots, so the variable is a constant, a global or a function
+            Expression::VariableAccess(variable_name) => {
+                if let Some(value) = runtime.get_variable(*variable_name) {
+                    return Ok(value.clone());
+                }
+
+                // a declared function being used as a value
+                let Some(function) = functions.get(&FunctionId(*variable_name)) else {
+                    return Err(runtime.not_in_scope(*variable_name));
+                };
+
+                Ok(Value::Function(FunctionValue {
+                    function: Rc::clone(function),
+                    captured_variables: Vec::new(),
+                }))
+            }
+            Expression::Lambda(lambda) => {
+                let Some(resolved) = &lambda.resolved else {
+                    return Err(runtime.error("a lambda was evaluated before it was resolved"));
+                };
+
+                Ok(Value::Function(FunctionValue {
+                    function: Rc::clone(&resolved.function),
+                    captured_variables: frame.capture(&resolved.captures),
+                }))
+            }
+            Expression::Tuple(elements) => Ok(Value::Tuple(
+                elements
+                    .iter()
+                    .map(|element| element.evaluate(functions, frame, runtime))
+                    .collect::<Result<_, _>>()?,
+            )),
+            Expression::TupleAccess { tuple, index } => {
+                let value = tuple.evaluate(functions, frame, runtime)?;
+                let Value::Tuple(mut elements) = value else {
+                    return Err(runtime.error(format!(
+                        "only tuples can be accessed by index, but found {:?}",
+                        value
+                    )));
+                };
+
+                if *index >= elements.len() {
+                    return Err(runtime.error(format!(
+                        "cannot access element {} of a tuple with {} elements",
+                        index,
+                        elements.len()
+                    )));
+                }
+
+                Ok(elements.swap_remove(*index))
             }
-            Expression::VariableAccess(variable_name) => local_variables
-                .get(variable_name)
-                .expect("variable should exist")
-                .clone(),
         }
     }
 }
@@ -30,126 +79,154 @@ impl Expression {
 fn evaluate_function_call(
     function_call: &FunctionCall,
     functions: &Functions,
-    local_variables: &HashMap<String, Value>,
-    call_stack: &mut Vec<FunctionId>,
-) -> Value {
-    let function = &functions[&function_call.function_id];
-    if matches!(function.return_type(), FunctionReturnType::Void) {
-        panic!("Function expected to be value, but is void");
-    };
-
-    function
-        .evaluate(
-            function_call.parameters.clone(),
-            local_variables,
-            functions,
-            call_stack,
-        )
-        .expect("function has a non void return type")
+    frame: &Frame,
+    runtime: &mut Runtime,
+) -> Result<Value, RuntimeError> {
+    match function_call.evaluate(functions, frame, runtime)? {
+        Some(value) => Ok(value),
+        None => Err(runtime.error(format!(
+            "{} returned void, but a value was expected",
+            function_call.function_id
+        ))),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, rc::Rc};
 
     use crate::ast::node::{
         Expression, Function, FunctionCall, FunctionId, FunctionParameter, FunctionReturnType,
-        Node, Operation, Type, UnaryOperation,
+        Lambda, Node, Operation, ResolvedLambda, Span, Type, UnaryOperation, Value,
     };
 
+    use crate::evaluation::{Frame, Runtime};
+
     use super::evaluate_function_call;
 
     #[test]
     fn test_evaluate_function_call() {
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
-            Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+            FunctionId("my_function".into()),
+            Rc::new(Function::CustomFunction {
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::Boolean,
-                    param_name: "bool_param".to_owned(),
+                    param_name: "bool_param".into(),
                 }],
                 return_type: FunctionReturnType::Type(Type::UInt),
                 body: vec![Node::FunctionReturn {
                     return_value: Some(10.into()),
+                    line: 0,
                 }],
-            },
+                span: Span::default(),
+                pure: false,
+            }),
         )]);
 
         let function_call = FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: vec![true.into()],
+            line: 0,
+            slot: None,
         };
 
-        let result =
-            evaluate_function_call(&function_call, &functions, &HashMap::new(), &mut Vec::new());
+        let result = evaluate_function_call(
+            &function_call,
+            &functions,
+            &Frame::default(),
+            &mut Runtime::default(),
+        );
 
-        assert_eq!(result, 10.into())
+        assert_eq!(result, Ok(10.into()))
     }
 
     #[test]
-    #[should_panic]
     fn test_evaluate_function_call_void() {
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
-            Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+            FunctionId("my_function".into()),
+            Rc::new(Function::CustomFunction {
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::Boolean,
-                    param_name: "bool_param".to_owned(),
+                    param_name: "bool_param".into(),
                 }],
                 return_type: FunctionReturnType::Void,
-                body: vec![Node::FunctionReturn { return_value: None }],
-            },
+                body: vec![Node::FunctionReturn {
+                    return_value: None,
+                    line: 0,
+                }],
+                span: Span::default(),
+                pure: false,
+            }),
         )]);
 
         let function_call = FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: vec![true.into()],
+            line: 0,
+            slot: None,
         };
 
-        evaluate_function_call(&function_call, &functions, &HashMap::new(), &mut Vec::new());
+        let result = evaluate_function_call(
+            &function_call,
+            &functions,
+            &Frame::default(),
+            &mut Runtime::default(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.message == "my_function returned void, but a value was expected")
+        );
     }
 
     #[test]
     fn expression_value_literal() {
         let result = Expression::ValueLiteral(true.into()).evaluate(
             &HashMap::new(),
-            &HashMap::new(),
-            &mut Vec::new(),
+            &Frame::default(),
+            &mut Runtime::default(),
         );
 
-        assert_eq!(result, true.into())
+        assert_eq!(result, Ok(true.into()))
     }
 
     #[test]
     fn expression_function_call() {
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
-            Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+            FunctionId("my_function".into()),
+            Rc::new(Function::CustomFunction {
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::Boolean,
-                    param_name: "bool_param".to_owned(),
+                    param_name: "bool_param".into(),
                 }],
                 return_type: FunctionReturnType::Type(Type::UInt),
                 body: vec![Node::FunctionReturn {
                     return_value: Some(10.into()),
+                    line: 0,
                 }],
-            },
+                span: Span::default(),
+                pure: false,
+            }),
         )]);
 
         let function_call = Expression::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: vec![true.into()],
+            line: 0,
+            slot: None,
         });
 
-        let result = function_call.evaluate(&functions, &HashMap::new(), &mut Vec::new());
+        let result = function_call.evaluate(&functions, &Frame::default(), &mut Runtime::default());
 
-        assert_eq!(result, 10.into());
+        assert_eq!(result, Ok(10.into()));
     }
 
     #[test]
@@ -159,19 +236,113 @@ mod tests {
             value: Box::new(true.into()),
         });
 
-        let result = expression.evaluate(&HashMap::new(), &HashMap::new(), &mut Vec::new());
+        let result =
+            expression.evaluate(&HashMap::new(), &Frame::default(), &mut Runtime::default());
+
+        assert_eq!(result, Ok(false.into()));
+    }
+
+    #[test]
+    fn expression_local_variable_access() {
+        let expression = Expression::LocalVariableAccess {
+            name: "my_var".into(),
+            slot: 1,
+        };
+
+        let frame = Frame::from(vec![
+            ("other".into(), 1.into()),
+            ("my_var".into(), true.into()),
+        ]);
+
+        let result = expression.evaluate(&HashMap::new(), &frame, &mut Runtime::default());
+
+        assert_eq!(result, Ok(true.into()));
+    }
+
+    #[test]
+    fn expression_variable_access_global() {
+        let expression = Expression::VariableAccess("count".into());
+
+        let mut runtime = Runtime::default();
+        runtime.globals.insert("count".into(), 1.into());
+
+        let result = expression.evaluate(&HashMap::new(), &Frame::default(), &mut runtime);
+
+        assert_eq!(result, Ok(1.into()));
+    }
+
+    #[test]
+    fn expression_lambda_captures_local_variables() {
+        let lambda = Lambda {
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Void,
+            body: Vec::new(),
+            span: Span::default(),
+            resolved: None,
+        };
+        let function = Rc::new(lambda.to_function());
+        let expression = Expression::Lambda(Lambda {
+            resolved: Some(ResolvedLambda {
+                function: Rc::clone(&function),
+                captures: vec![1],
+            }),
+            ..lambda
+        });
+
+        let frame = Frame::from(vec![
+            ("unused".into(), 1.into()),
+            ("my_var".into(), true.into()),
+        ]);
 
-        assert_eq!(result, false.into());
+        let result = expression.evaluate(&HashMap::new(), &frame, &mut Runtime::default());
+
+        let Ok(Value::Function(function_value)) = result else {
+            panic!("expected a function value, but found {:?}", result);
+        };
+        // the value shares the lambda's function rather than building a new one
+        assert!(Rc::ptr_eq(&function_value.function, &function));
+        assert_eq!(
+            function_value.captured_variables,
+            vec![("my_var".into(), true.into())]
+        );
     }
 
     #[test]
-    fn expression_variable_access() {
-        let expression = Expression::VariableAccess("my_var".to_owned());
+    fn expression_variable_access_function() {
+        let function = Rc::new(Function::CustomFunction {
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Void,
+            body: Vec::new(),
+            span: Span::default(),
+            pure: false,
+        });
+        let functions = HashMap::from_iter([(*function.id(), Rc::clone(&function))]);
+
+        let expression = Expression::VariableAccess("my_function".into());
 
-        let local_variables = HashMap::from_iter([("my_var".to_owned(), true.into())]);
+        let result = expression.evaluate(&functions, &Frame::default(), &mut Runtime::default());
 
-        let result = expression.evaluate(&HashMap::new(), &local_variables, &mut Vec::new());
+        let Ok(Value::Function(function_value)) = result else {
+            panic!("expected a function value, but found {:?}", result);
+        };
+        // the value shares the declared function rather than copying it
+        assert!(Rc::ptr_eq(&function_value.function, &function));
+        assert!(function_value.captured_variables.is_empty());
+    }
+
+    #[test]
+    fn evaluate_tuple_access() {
+        let expression = Expression::TupleAccess {
+            tuple: Box::new(Expression::Tuple(vec![1.into(), true.into()])),
+            index: 1,
+        };
+
+        let result =
+            expression.evaluate(&HashMap::new(), &Frame::default(), &mut Runtime::default());
 
-        assert_eq!(result, true.into());
+        assert_eq!(result, Ok(true.into()));
     }
 }
@@ -0,0 +1,95 @@
+use crate::ast::{node::Value, symbol::Symbol};
+
+/// the local variables of a running function, each in the slot the resolution pass assigned it. A variable is pushed
+/// when it's declared, so its slot is the number of variables in scope before it, and a block's variables are
+/// truncated away when the block ends
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Frame {
+    variables: Vec<(Symbol, Value)>,
+}
+
+impl Frame {
+    /// an empty frame with room for `capacity` variables, such as a called function's parameters
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            variables: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&Value> {
+        self.variables.get(slot).map(|(_, value)| value)
+    }
+
+    pub fn push(&mut self, name: Symbol, value: Value) {
+        self.variables.push((name, value));
+    }
+
+    /// the number of variables in scope, which is the slot of the next variable declared
+    pub fn slot_count(&self) -> usize {
+        self.variables.len()
+    }
+
+    /// end the variables declared after the first `slot_count`
+    pub fn truncate(&mut self, slot_count: usize) {
+        self.variables.truncate(slot_count);
+    }
+
+    /// the variables in the slots a closure created in this frame captures
+    pub fn capture(&self, slots: &[usize]) -> Vec<(Symbol, Value)> {
+        slots
+            .iter()
+            .map(|slot| self.variables[*slot].clone())
+            .collect()
+    }
+}
+
+impl From<Vec<(Symbol, Value)>> for Frame {
+    fn from(variables: Vec<(Symbol, Value)>) -> Self {
+        Self { variables }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Frame;
+
+    #[test]
+    fn frame_get_slot() {
+        let frame = Frame::from(vec![
+            ("value".into(), 1.into()),
+            ("other".into(), 2.into()),
+            ("value".into(), 3.into()),
+        ]);
+
+        assert_eq!(frame.get(0), Some(&1.into()));
+        assert_eq!(frame.get(2), Some(&3.into()));
+        assert_eq!(frame.get(3), None);
+    }
+
+    #[test]
+    fn frame_truncate_ends_block_variables() {
+        let mut frame = Frame::default();
+        frame.push("outer".into(), 1.into());
+        let slot_count = frame.slot_count();
+        frame.push("inner".into(), 2.into());
+
+        frame.truncate(slot_count);
+
+        assert_eq!(frame.get(1), None);
+        assert_eq!(frame.slot_count(), 1);
+    }
+
+    #[test]
+    fn frame_capture_slots() {
+        let frame = Frame::from(vec![
+            ("first".into(), 1.into()),
+            ("second".into(), 2.into()),
+            ("third".into(), 3.into()),
+        ]);
+
+        assert_eq!(
+            frame.capture(&[0, 2]),
+            vec![("first".into(), 1.into()), ("third".into(), 3.into())]
+        );
+    }
+}
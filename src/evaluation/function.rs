@@ -1,176 +1,548 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, rc::Rc};
 
-use crate::ast::node::{Expression, Function, FunctionId, FunctionParameter, Node, Value};
+use crate::ast::{
+    node::{Expression, Function, FunctionCall, Value},
+    symbol::Symbol,
+};
 
-use super::{ast::evaluate_nodes, intrinsics::evaluate_intrinsic_function, Functions, NodeResult};
+use super::{
+    ast::evaluate_nodes, has_stack_left, intrinsics::evaluate_intrinsic_function, Frame, Functions,
+    NodeResult, Runtime, RuntimeError, StackFrame, TailCall,
+};
 
 fn evaluate_custom_function(
-    id: &FunctionId,
-    body: &[Node],
-    parameters: HashMap<String, Value>,
-    call_stack: &mut Vec<FunctionId>,
+    function: Rc<Function>,
+    frame: Frame,
+    runtime: &mut Runtime,
     functions: &Functions,
-) -> Option<Value> {
-    call_stack.push(id.clone());
-    if let NodeResult::FunctionReturn { value } =
-        evaluate_nodes(body, &parameters, call_stack, functions)
-    {
-        call_stack.pop();
-        value
-    } else {
-        call_stack.pop();
-        None
+) -> Result<Option<Value>, RuntimeError> {
+    if runtime.call_stack.len() >= runtime.max_call_depth || !has_stack_left() {
+        return Err(runtime.error(format!(
+            "stack overflow in {} (depth {})",
+            function.id(),
+            runtime.call_stack.len() + 1
+        )));
+    }
+
+    runtime.charge_call()?;
+    runtime.call_stack.push(StackFrame {
+        function: Rc::clone(&function),
+        call_line: runtime.line,
+    });
+
+    // a tail call runs in place of the function that made it, so a chain of tail calls is evaluated in this loop
+    // rather than nesting, and runs in constant host stack space
+    let mut function = function;
+    let mut frame = frame;
+    let result = loop {
+        // the stack is left as it is on an error, as the error's backtrace has already been built from it
+        let result = match function.as_ref() {
+            Function::CustomFunction { body, .. } => {
+                evaluate_nodes(body, &mut frame, runtime, functions)?
+            }
+            // intrinsic functions are evaluated where they're called, even in tail position
+            Function::Intrinsic { id, .. } => NodeResult::FunctionReturn {
+                value: evaluate_intrinsic_function(id, &frame, runtime)?,
+            },
+        };
+
+        match result {
+            NodeResult::TailCall(next) => {
+                runtime.charge_call()?;
+                if let Some(stack_frame) = runtime.call_stack.last_mut() {
+                    stack_frame.function = Rc::clone(&next.function);
+                }
+                function = next.function;
+                frame = next.frame;
+            }
+            result => break result,
+        }
+    };
+
+    if let Some(stack_frame) = runtime.call_stack.pop() {
+        runtime.line = stack_frame.call_line;
+    }
+
+    match result {
+        NodeResult::FunctionReturn { value } => Ok(value),
+        NodeResult::None | NodeResult::TailCall(_) => Ok(None),
     }
 }
 
+impl FunctionCall {
+    pub fn evaluate(
+        &self,
+        functions: &Functions,
+        frame: &Frame,
+        runtime: &mut Runtime,
+    ) -> Result<Option<Value>, RuntimeError> {
+        let callee = self.callee(functions, frame, runtime)?;
+
+        callee.function.evaluate(
+            &self.parameters,
+            &callee.captured_variables,
+            frame,
+            functions,
+            runtime,
+        )
+    }
+
+    /// evaluate the call's parameters for a tail call, which the calling function runs in its own place. Intrinsic
+    /// functions don't nest any evaluation, so they're called straight away
+    pub(super) fn evaluate_tail_call(
+        &self,
+        functions: &Functions,
+        frame: &Frame,
+        runtime: &mut Runtime,
+    ) -> Result<NodeResult, RuntimeError> {
+        let callee = self.callee(functions, frame, runtime)?;
+
+        let frame = callee.function.bind_parameters(
+            &self.parameters,
+            &callee.captured_variables,
+            frame,
+            functions,
+            runtime,
+        )?;
+
+        match callee.function.as_ref() {
+            Function::CustomFunction { .. } => Ok(NodeResult::TailCall(TailCall {
+                function: callee.function,
+                frame,
+            })),
+            Function::Intrinsic { id, .. } => Ok(NodeResult::FunctionReturn {
+                value: evaluate_intrinsic_function(id, &frame, runtime)?,
+            }),
+        }
+    }
+
+    /// the function the call refers to
+    fn callee<'a>(
+        &self,
+        functions: &'a Functions,
+        frame: &'a Frame,
+        runtime: &mut Runtime,
+    ) -> Result<Callee<'a>, RuntimeError> {
+        // local variables take precedence over declared functions, and have been resolved to slots
+        if let Some(slot) = self.slot {
+            return match frame.get(slot) {
+                Some(Value::Function(function_value)) => Ok(Callee {
+                    function: Rc::clone(&function_value.function),
+                    captured_variables: Cow::Borrowed(&function_value.captured_variables),
+                }),
+                Some(_) => Err(runtime.error(format!(
+                    "{} isn't a function, so it can't be called",
+                    self.function_id
+                ))),
+                None => Err(runtime.not_in_scope(self.function_id.0)),
+            };
+        }
+
+        // followed by globals and constants
+        if let Some(Value::Function(function_value)) = runtime.get_variable(self.function_id.0) {
+            return Ok(Callee {
+                function: Rc::clone(&function_value.function),
+                captured_variables: Cow::Owned(function_value.captured_variables.clone()),
+            });
+        }
+
+        let Some(function) = functions.get(&self.function_id) else {
+            return Err(runtime.error(format!(
+                "Could not find function with name {}",
+                self.function_id
+            )));
+        };
+
+        Ok(Callee {
+            function: Rc::clone(function),
+            captured_variables: Cow::Borrowed(&[]),
+        })
+    }
+}
+
+/// the function a call refers to. The captured variables of a global or constant are copied out of the runtime, which
+/// the call goes on to change
+struct Callee<'a> {
+    function: Rc<Function>,
+    /// the variables captured by the function, if it's a closure
+    captured_variables: Cow<'a, [(Symbol, Value)]>,
+}
+
 impl Function {
+    /// evaluate the function with the `captured_variables` of a closure, which are empty for a declared function,
+    /// visible in the function body. Parameters shadow captured variables with the same name
     pub fn evaluate(
+        self: &Rc<Self>,
+        parameter_expressions: &[Expression],
+        captured_variables: &[(Symbol, Value)],
+        frame: &Frame,
+        functions: &Functions,
+        runtime: &mut Runtime,
+    ) -> Result<Option<Value>, RuntimeError> {
+        let frame = self.bind_parameters(
+            parameter_expressions,
+            captured_variables,
+            frame,
+            functions,
+            runtime,
+        )?;
+
+        match self.as_ref() {
+            Function::CustomFunction { .. } => {
+                evaluate_custom_function(Rc::clone(self), frame, runtime, functions)
+            }
+            Function::Intrinsic { id, .. } => evaluate_intrinsic_function(id, &frame, runtime),
+        }
+    }
+
+    /// the frame the function body runs in: the `captured_variables`, followed by the values of the parameters, which
+    /// are evaluated in the caller's `frame`
+    fn bind_parameters(
         &self,
-        parameter_expressions: Vec<Expression>,
-        local_variables: &HashMap<String, Value>,
+        parameter_expressions: &[Expression],
+        captured_variables: &[(Symbol, Value)],
+        frame: &Frame,
         functions: &Functions,
-        call_stack: &mut Vec<FunctionId>,
-    ) -> Option<Value> {
+        runtime: &mut Runtime,
+    ) -> Result<Frame, RuntimeError> {
         if parameter_expressions.len() != self.parameters().len() {
-            panic!(
+            return Err(runtime.error(format!(
                 "Expected {} parameters, but found {} for {}",
                 self.parameters().len(),
                 parameter_expressions.len(),
                 self.name()
-            );
+            )));
         }
 
-        let parameter_values: Vec<Value> = parameter_expressions
-            .into_iter()
-            .map(|expression| expression.evaluate(functions, local_variables, call_stack))
-            .collect();
-
-        let local_variables = self
-            .parameters()
-            .iter()
-            .enumerate()
-            .map(|(i, function_parameter)| {
-                let param_name = match function_parameter {
-                    FunctionParameter::FunctionParameter { param_name, .. }
-                    | FunctionParameter::IntrinsicAny { param_name } => param_name,
-                };
-                (param_name.clone(), parameter_values[i].clone())
-            })
-            .collect();
-
-        match self {
-            Function::CustomFunction { id, body, .. } => {
-                evaluate_custom_function(id, body, local_variables, call_stack, functions)
-            }
-            Function::Intrinsic { id, .. } => evaluate_intrinsic_function(id, &local_variables),
+        let mut function_frame =
+            Frame::with_capacity(captured_variables.len() + parameter_expressions.len());
+        for (name, value) in captured_variables {
+            function_frame.push(*name, value.clone());
+        }
+
+        for (parameter, expression) in self.parameters().iter().zip(parameter_expressions) {
+            let value = expression.evaluate(functions, frame, runtime)?;
+            function_frame.push(parameter.name(), value);
         }
+
+        Ok(function_frame)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, rc::Rc};
 
     use crate::ast::node::{
-        Function, FunctionId, FunctionParameter, FunctionReturnType, Node, Type,
+        BinaryOperation, Expression, Function, FunctionCall, FunctionId, FunctionParameter,
+        FunctionReturnType, FunctionValue, IfStatement, Lambda, Node, Operation, Span, Type, Value,
     };
 
+    use crate::evaluation::{slots::resolve_function, BacktraceEntry, Frame, Runtime};
+
     use super::evaluate_custom_function;
 
+    fn my_function(body: Vec<Node>) -> Rc<Function> {
+        Rc::new(Function::CustomFunction {
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Void,
+            body,
+            span: Span::default(),
+            pure: false,
+        })
+    }
+
     #[test]
     fn test_custom_function_evaluation_return_value() {
         let result = evaluate_custom_function(
-            &FunctionId("my_function".to_owned()),
-            &[Node::FunctionReturn {
+            my_function(vec![Node::FunctionReturn {
                 return_value: Some(1.into()),
-            }],
-            HashMap::new(),
-            &mut Vec::new(),
+                line: 0,
+            }]),
+            Frame::default(),
+            &mut Runtime::default(),
             &HashMap::new(),
         );
 
-        assert_eq!(result, Some(1.into()))
+        assert_eq!(result, Ok(Some(1.into())))
     }
 
     #[test]
     fn test_custom_function_evaluation_no_return_value() {
         let result = evaluate_custom_function(
-            &FunctionId("my_function".to_owned()),
-            &[],
-            HashMap::new(),
-            &mut Vec::new(),
+            my_function(Vec::new()),
+            Frame::default(),
+            &mut Runtime::default(),
             &HashMap::new(),
         );
 
-        assert_eq!(result, None);
+        assert_eq!(result, Ok(None));
     }
 
     #[test]
     fn function_evaluate_custom_function() {
-        let function = Function::CustomFunction {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+        let function = Rc::new(Function::CustomFunction {
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: vec![FunctionParameter::FunctionParameter {
                 param_type: Type::UInt,
-                param_name: "param".to_owned(),
+                param_name: "param".into(),
             }],
             return_type: FunctionReturnType::Type(Type::Boolean),
             body: vec![Node::FunctionReturn {
                 return_value: Some(true.into()),
+                line: 0,
             }],
-        };
+            span: Span::default(),
+            pure: false,
+        });
 
         let result = function.evaluate(
-            vec![1.into()],
-            &HashMap::new(),
+            &[1.into()],
+            &[],
+            &Frame::default(),
             &HashMap::new(),
-            &mut Vec::new(),
+            &mut Runtime::default(),
         );
 
-        assert_eq!(result, Some(true.into()))
+        assert_eq!(result, Ok(Some(true.into())))
     }
 
     #[test]
     fn function_evaluate_intrinsic_function() {
-        let function = Function::Intrinsic {
-            id: FunctionId("print".to_owned()),
-            name: "print".to_owned(),
-            parameters: vec![FunctionParameter::IntrinsicAny {
-                param_name: "value".to_owned(),
+        let function = Rc::new(Function::Intrinsic {
+            id: FunctionId("print".into()),
+            name: "print".into(),
+            type_parameters: vec!["T".into()],
+            parameters: vec![FunctionParameter::FunctionParameter {
+                param_type: Type::Generic("T".into()),
+                param_name: "value".into(),
             }],
             return_type: FunctionReturnType::Void,
-        };
+        });
 
         let result = function.evaluate(
-            vec![1.into()],
-            &HashMap::new(),
+            &[1.into()],
+            &[],
+            &Frame::default(),
             &HashMap::new(),
-            &mut Vec::new(),
+            &mut Runtime::default(),
         );
 
-        assert_eq!(result, None);
+        assert_eq!(result, Ok(None));
     }
 
     #[test]
-    #[should_panic]
     fn function_evaluate_incorrect_parameter_number() {
-        let function = Function::CustomFunction {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+        let function = Rc::new(Function::CustomFunction {
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: Vec::new(),
             return_type: FunctionReturnType::Type(Type::Boolean),
             body: vec![Node::FunctionReturn {
                 return_value: Some(true.into()),
+                line: 0,
             }],
-        };
+            span: Span::default(),
+            pure: false,
+        });
 
         let result = function.evaluate(
-            vec![1.into()],
-            &HashMap::new(),
+            &[1.into()],
+            &[],
+            &Frame::default(),
             &HashMap::new(),
-            &mut Vec::new(),
+            &mut Runtime::default(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.message == "Expected 0 parameters, but found 1 for my_function")
         );
+    }
+
+    #[test]
+    fn function_call_evaluate_closure() {
+        let closure = Value::Function(FunctionValue {
+            function: Rc::new(
+                Lambda {
+                    parameters: vec![(Type::UInt, "value".into()).into()],
+                    return_type: FunctionReturnType::Type(Type::UInt),
+                    body: vec![Node::FunctionReturn {
+                        return_value: Some(Expression::Operation(Operation::Binary {
+                            operation: BinaryOperation::Plus,
+                            // the captured variables come before the parameters in the closure's frame
+                            left: Box::new(Expression::LocalVariableAccess {
+                                name: "value".into(),
+                                slot: 1,
+                            }),
+                            right: Box::new(Expression::LocalVariableAccess {
+                                name: "offset".into(),
+                                slot: 0,
+                            }),
+                        })),
+                        line: 0,
+                    }],
+                    span: Span::default(),
+                    resolved: None,
+                }
+                .to_function(),
+            ),
+            captured_variables: vec![("offset".into(), 10.into())],
+        });
+
+        let function_call = FunctionCall {
+            function_id: FunctionId("add_offset".into()),
+            parameters: vec![5.into()],
+            line: 0,
+            slot: Some(0),
+        };
 
-        assert_eq!(result, Some(true.into()))
+        let frame = Frame::from(vec![("add_offset".into(), closure)]);
+
+        let result = function_call.evaluate(&HashMap::new(), &frame, &mut Runtime::default());
+
+        assert_eq!(result, Ok(Some(15.into())));
+    }
+
+    /// `count_up(uint value) -> uint`, which tail calls itself until `value` is greater than `limit`
+    fn count_up(limit: u32) -> Function {
+        let mut function = Function::CustomFunction {
+            id: FunctionId("count_up".into()),
+            name: "count_up".into(),
+            type_parameters: Vec::new(),
+            parameters: vec![(Type::UInt, "value".into()).into()],
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![
+                Node::IfStatement(IfStatement {
+                    check_expression: Expression::Operation(Operation::Binary {
+                        operation: BinaryOperation::GreaterThan,
+                        left: Box::new(Expression::VariableAccess("value".into())),
+                        right: Box::new(limit.into()),
+                    }),
+                    if_block: vec![Node::FunctionReturn {
+                        return_value: Some(Expression::VariableAccess("value".into())),
+                        line: 2,
+                    }],
+                    else_if_blocks: Vec::new(),
+                    else_block: None,
+                    line: 1,
+                }),
+                Node::FunctionReturn {
+                    return_value: Some(Expression::FunctionCall(FunctionCall {
+                        function_id: FunctionId("count_up".into()),
+                        parameters: vec![Expression::Operation(Operation::Binary {
+                            operation: BinaryOperation::Plus,
+                            left: Box::new(Expression::VariableAccess("value".into())),
+                            right: Box::new(1.into()),
+                        })],
+                        line: 4,
+                        slot: None,
+                    })),
+                    line: 4,
+                },
+            ],
+            span: Span::default(),
+            pure: false,
+        };
+        resolve_function(&mut function);
+
+        function
+    }
+
+    #[test]
+    fn function_call_tail_calls_in_place() {
+        let functions =
+            HashMap::from_iter([(FunctionId("count_up".into()), Rc::new(count_up(100)))]);
+
+        let function_call = FunctionCall {
+            function_id: FunctionId("count_up".into()),
+            parameters: vec![0.into()],
+            line: 0,
+            slot: None,
+        };
+
+        let mut runtime = Runtime {
+            max_call_depth: 1,
+            ..Runtime::default()
+        };
+
+        let result = function_call.evaluate(&functions, &Frame::default(), &mut runtime);
+
+        assert_eq!(result, Ok(Some(101.into())));
+        assert!(runtime.call_stack.is_empty());
+    }
+
+    #[test]
+    fn function_call_tail_call_replaces_frame() {
+        let function = |name: &str, body: Vec<Node>| {
+            (
+                FunctionId(name.into()),
+                Rc::new(Function::CustomFunction {
+                    id: FunctionId(name.into()),
+                    name: name.into(),
+                    type_parameters: Vec::new(),
+                    parameters: Vec::new(),
+                    return_type: FunctionReturnType::Type(Type::UInt),
+                    body,
+                    span: Span::default(),
+                    pure: false,
+                }),
+            )
+        };
+        let functions = HashMap::from_iter([
+            function(
+                "start",
+                vec![Node::FunctionReturn {
+                    return_value: Some(Expression::FunctionCall(FunctionCall {
+                        function_id: FunctionId("fail".into()),
+                        parameters: Vec::new(),
+                        line: 2,
+                        slot: None,
+                    })),
+                    line: 2,
+                }],
+            ),
+            function(
+                "fail",
+                vec![Node::FunctionReturn {
+                    return_value: Some(Expression::VariableAccess("missing".into())),
+                    line: 6,
+                }],
+            ),
+        ]);
+
+        let function_call = FunctionCall {
+            function_id: FunctionId("start".into()),
+            parameters: Vec::new(),
+            line: 10,
+            slot: None,
+        };
+
+        let mut runtime = Runtime {
+            line: 10,
+            ..Runtime::default()
+        };
+
+        let result = function_call.evaluate(&functions, &Frame::default(), &mut runtime);
+
+        // fail runs in place of start, so start isn't part of the backtrace
+        assert_eq!(
+            result.map_err(|error| error.backtrace),
+            Err(vec![
+                BacktraceEntry {
+                    function_id: Some(FunctionId("fail".into())),
+                    line: 6,
+                },
+                BacktraceEntry {
+                    function_id: None,
+                    line: 10,
+                },
+            ])
+        );
     }
 }
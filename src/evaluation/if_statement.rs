@@ -1,50 +1,45 @@
-use std::collections::HashMap;
+use crate::ast::node::{BoolValue, IfStatement, Value};
 
-use crate::ast::node::{BoolValue, FunctionId, IfStatement, Value};
-
-use super::{ast::evaluate_nodes, Functions, NodeResult};
+use super::{ast::evaluate_nodes, Frame, Functions, NodeResult, Runtime, RuntimeError};
 
 impl IfStatement {
     pub fn evaluate(
         &self,
         functions: &Functions,
-        local_variables: &HashMap<String, Value>,
-        call_stack: &mut Vec<FunctionId>,
-    ) -> NodeResult {
-        let check_value = self
-            .check_expression
-            .evaluate(functions, local_variables, call_stack);
+        frame: &mut Frame,
+        runtime: &mut Runtime,
+    ) -> Result<NodeResult, RuntimeError> {
+        let check_value = self.check_expression.evaluate(functions, frame, runtime)?;
         let Value::Boolean(BoolValue(bool_value)) = check_value else {
-            panic!("Expected if statement check value to be boolean, but found {:?}", check_value)
+            return Err(runtime.error(format!(
+                "Expected if statement check value to be boolean, but found {:?}",
+                check_value
+            )));
         };
 
         if bool_value {
-            return evaluate_nodes(&self.if_block, local_variables, call_stack, functions);
+            return evaluate_nodes(&self.if_block, frame, runtime, functions);
         }
 
         for else_if_block in &self.else_if_blocks {
-            let check_value = else_if_block
-                .check
-                .evaluate(functions, local_variables, call_stack);
+            let check_value = else_if_block.check.evaluate(functions, frame, runtime)?;
             let Value::Boolean(BoolValue(bool_value)) = check_value else {
-                panic!("Expected if statement check value to be boolean, but found {:?}", check_value)
+                return Err(runtime.error(format!(
+                    "Expected if statement check value to be boolean, but found {:?}",
+                    check_value
+                )));
             };
 
             if bool_value {
-                return evaluate_nodes(
-                    &else_if_block.block,
-                    local_variables,
-                    call_stack,
-                    functions,
-                );
+                return evaluate_nodes(&else_if_block.block, frame, runtime, functions);
             }
         }
 
         if let Some(else_block) = &self.else_block {
-            return evaluate_nodes(&else_block, local_variables, call_stack, functions);
+            return evaluate_nodes(else_block, frame, runtime, functions);
         }
 
-        NodeResult::None
+        Ok(NodeResult::None)
     }
 }
 
@@ -54,7 +49,7 @@ mod tests {
 
     use crate::{
         ast::node::{ElseIfBlock, IfStatement, Node},
-        evaluation::NodeResult,
+        evaluation::{Frame, NodeResult, Runtime},
     };
 
     #[test]
@@ -63,18 +58,24 @@ mod tests {
             check_expression: true.into(),
             if_block: vec![Node::FunctionReturn {
                 return_value: Some(1.into()),
+                line: 0,
             }],
             else_if_blocks: Vec::new(),
             else_block: None,
+            line: 0,
         };
 
-        let result = if_statement.evaluate(&HashMap::new(), &HashMap::new(), &mut Vec::new());
+        let result = if_statement.evaluate(
+            &HashMap::new(),
+            &mut Frame::default(),
+            &mut Runtime::default(),
+        );
 
         assert_eq!(
             result,
-            NodeResult::FunctionReturn {
+            Ok(NodeResult::FunctionReturn {
                 value: Some(1.into())
-            }
+            })
         );
     }
 
@@ -84,14 +85,20 @@ mod tests {
             check_expression: false.into(),
             if_block: vec![Node::FunctionReturn {
                 return_value: Some(1.into()),
+                line: 0,
             }],
             else_if_blocks: Vec::new(),
             else_block: None,
+            line: 0,
         };
 
-        let result = if_statement.evaluate(&HashMap::new(), &HashMap::new(), &mut Vec::new());
+        let result = if_statement.evaluate(
+            &HashMap::new(),
+            &mut Frame::default(),
+            &mut Runtime::default(),
+        );
 
-        assert_eq!(result, NodeResult::None);
+        assert_eq!(result, Ok(NodeResult::None));
     }
 
     #[test]
@@ -100,20 +107,27 @@ mod tests {
             check_expression: false.into(),
             if_block: vec![Node::FunctionReturn {
                 return_value: Some(1.into()),
+                line: 0,
             }],
             else_if_blocks: Vec::new(),
             else_block: Some(vec![Node::FunctionReturn {
                 return_value: Some(2.into()),
+                line: 0,
             }]),
+            line: 0,
         };
 
-        let result = if_statement.evaluate(&HashMap::new(), &HashMap::new(), &mut Vec::new());
+        let result = if_statement.evaluate(
+            &HashMap::new(),
+            &mut Frame::default(),
+            &mut Runtime::default(),
+        );
 
         assert_eq!(
             result,
-            NodeResult::FunctionReturn {
+            Ok(NodeResult::FunctionReturn {
                 value: Some(2.into())
-            }
+            })
         )
     }
 
@@ -123,60 +137,87 @@ mod tests {
             check_expression: false.into(),
             if_block: vec![Node::FunctionReturn {
                 return_value: Some(1.into()),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: true.into(),
                 block: vec![Node::FunctionReturn {
                     return_value: Some(3.into()),
+                    line: 0,
                 }],
             }],
             else_block: Some(vec![Node::FunctionReturn {
                 return_value: Some(2.into()),
+                line: 0,
             }]),
+            line: 0,
         };
 
-        let result = if_statement.evaluate(&HashMap::new(), &HashMap::new(), &mut Vec::new());
+        let result = if_statement.evaluate(
+            &HashMap::new(),
+            &mut Frame::default(),
+            &mut Runtime::default(),
+        );
 
         assert_eq!(
             result,
-            NodeResult::FunctionReturn {
+            Ok(NodeResult::FunctionReturn {
                 value: Some(3.into())
-            }
+            })
         )
     }
 
     #[test]
-    #[should_panic]
     fn test_if_statement_incorrect_check() {
         let if_statement = IfStatement {
             check_expression: 10.into(),
             if_block: vec![Node::FunctionReturn {
                 return_value: Some(1.into()),
+                line: 0,
             }],
             else_if_blocks: Vec::new(),
             else_block: None,
+            line: 0,
         };
 
-        if_statement.evaluate(&HashMap::new(), &HashMap::new(), &mut Vec::new());
+        let result = if_statement.evaluate(
+            &HashMap::new(),
+            &mut Frame::default(),
+            &mut Runtime::default(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.message == "Expected if statement check value to be boolean, but found UInt(UIntValue(10))")
+        );
     }
 
     #[test]
-    #[should_panic]
     fn test_else_if_statement_incorrect_check() {
         let if_statement = IfStatement {
             check_expression: false.into(),
             if_block: vec![Node::FunctionReturn {
                 return_value: Some(1.into()),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: 10.into(),
                 block: vec![Node::FunctionReturn {
                     return_value: Some(3.into()),
+                    line: 0,
                 }],
             }],
             else_block: None,
+            line: 0,
         };
 
-        if_statement.evaluate(&HashMap::new(), &HashMap::new(), &mut Vec::new());
+        let result = if_statement.evaluate(
+            &HashMap::new(),
+            &mut Frame::default(),
+            &mut Runtime::default(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.message == "Expected if statement check value to be boolean, but found UInt(UIntValue(10))")
+        );
     }
 }
@@ -1,82 +1,115 @@
 use std::collections::HashMap;
 
 use crate::ast::node::{
-    BoolValue, Function, FunctionId, FunctionParameter, FunctionReturnType, UIntValue, Value,
+    BoolValue, Function, FunctionId, FunctionParameter, FunctionReturnType, Type, UIntValue, Value,
 };
 
+use super::{Frame, Runtime, RuntimeError};
+
+/// evaluate an intrinsic function, whose parameters are the first slots of the frame
 pub(super) fn evaluate_intrinsic_function(
     id: &FunctionId,
-    parameters: &HashMap<String, Value>,
-) -> Option<Value> {
-    match id.0.as_str() {
-        "print" => {
-            intrinsic_print(&parameters["value"]);
-            None
+    parameters: &Frame,
+    runtime: &Runtime,
+) -> Result<Option<Value>, RuntimeError> {
+    match (id.0.as_str(), parameters.get(0)) {
+        ("print", Some(value)) => {
+            intrinsic_print(value);
+            Ok(None)
         }
-        _ => panic!("unknown intrinsic function"),
+        _ => Err(runtime.error(format!("unknown intrinsic function {}", id))),
     }
 }
 
 pub fn get_intrinsic_functions() -> HashMap<FunctionId, Function> {
     [Function::Intrinsic {
-        id: FunctionId("print".to_owned()),
-        name: "print".to_owned(),
-        parameters: vec![FunctionParameter::IntrinsicAny {
-            param_name: "value".to_owned(),
+        id: FunctionId("print".into()),
+        name: "print".into(),
+        type_parameters: vec!["T".into()],
+        parameters: vec![FunctionParameter::FunctionParameter {
+            param_type: Type::Generic("T".into()),
+            param_name: "value".into(),
         }],
         return_type: FunctionReturnType::Void,
     }]
     .into_iter()
-    .map(|function| (function.id().clone(), function))
+    .map(|function| (*function.id(), function))
     .collect()
 }
 
 fn intrinsic_print(value: &Value) {
+    println!("{}", format_value(value));
+}
+
+fn format_value(value: &Value) -> String {
     match value {
-        Value::Boolean(BoolValue(bool_value)) => println!("{}", bool_value),
-        Value::UInt(UIntValue(uint_value)) => println!("{}", uint_value),
+        Value::Boolean(BoolValue(bool_value)) => bool_value.to_string(),
+        Value::UInt(UIntValue(uint_value)) => uint_value.to_string(),
+        Value::Function(function_value) => format!("<{}>", function_value.function.name()),
+        Value::Tuple(elements) => format!(
+            "({})",
+            elements
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use crate::ast::node::{FunctionId, Value};
 
-    use crate::ast::node::FunctionId;
+    use crate::evaluation::{Frame, Runtime};
 
-    use super::{evaluate_intrinsic_function, get_intrinsic_functions};
+    use super::{evaluate_intrinsic_function, format_value, get_intrinsic_functions};
 
     #[test]
     fn test_get_intrinsic_functions() {
         let functions = get_intrinsic_functions();
         let keys: Vec<_> = functions.keys().into_iter().collect();
 
-        assert_eq!(keys, vec![&FunctionId("print".to_owned())]);
+        assert_eq!(keys, vec![&FunctionId("print".into())]);
     }
 
     #[test]
     fn evaluate_print_bool() {
         let result = evaluate_intrinsic_function(
-            &FunctionId("print".to_owned()),
-            &[("value".to_owned(), true.into())].into_iter().collect(),
+            &FunctionId("print".into()),
+            &Frame::from(vec![("value".into(), true.into())]),
+            &Runtime::default(),
         );
 
-        assert!(matches!(result, None));
+        assert!(matches!(result, Ok(None)));
     }
 
     #[test]
     fn evaluate_print_uint() {
         let result = evaluate_intrinsic_function(
-            &FunctionId("print".to_owned()),
-            &[("value".to_owned(), 10.into())].into_iter().collect(),
+            &FunctionId("print".into()),
+            &Frame::from(vec![("value".into(), 10.into())]),
+            &Runtime::default(),
         );
 
-        assert!(matches!(result, None));
+        assert!(matches!(result, Ok(None)));
     }
 
     #[test]
-    #[should_panic]
     fn evaluate_missing_intrinsic() {
-        evaluate_intrinsic_function(&FunctionId("unknown".to_owned()), &HashMap::new());
+        let result = evaluate_intrinsic_function(
+            &FunctionId("unknown".into()),
+            &Frame::default(),
+            &Runtime::default(),
+        );
+
+        assert!(matches!(result, Err(e) if e.message == "unknown intrinsic function unknown"));
+    }
+
+    #[test]
+    fn format_tuple() {
+        let value = Value::Tuple(vec![1.into(), Value::Tuple(vec![true.into(), 2.into()])]);
+
+        assert_eq!(format_value(&value), "(1, (true, 2))");
     }
 }
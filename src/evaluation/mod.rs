@@ -1,25 +1,394 @@
 mod ast;
 mod expression;
+mod frame;
 mod function;
 mod if_statement;
 pub mod intrinsics;
 mod node;
 mod operation;
+mod slots;
 
-use std::collections::HashMap;
+use std::{cell::Cell, collections::HashMap, fmt::Display, io, panic, rc::Rc, thread};
 
-use crate::ast::node::{Function, FunctionId, Value};
+use crate::ast::{
+    node::{Function, FunctionId, Value},
+    symbol::Symbol,
+};
 
-type Functions = HashMap<FunctionId, Function>;
+pub use self::frame::Frame;
+
+/// the functions a program can call. They're shared with the call stack and function values, so a call never copies a
+/// function's body
+type Functions = HashMap<FunctionId, Rc<Function>>;
+
+/// how deeply function calls can be nested, unless the program is run with `--max-call-depth`
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 10_000;
+/// the host stack reserved for each nested call. A call nests several evaluation functions, and more for each
+/// expression nested in its arguments, so this leaves plenty of room over what a typical call uses
+const STACK_BYTES_PER_CALL: usize = 32 * 1024;
+/// the host stack reserved for loading and type checking the program, before any of its calls are evaluated
+const BASE_STACK_BYTES: usize = 8 * 1024 * 1024;
+/// the largest host stack a program is run with, however deep its calls can nest. The host can refuse to start a
+/// thread with a larger one
+const MAX_STACK_BYTES: usize = 1024 * 1024 * 1024;
+/// the host stack left when a call is stopped for running out of stack, which is room to evaluate the body of the
+/// call that's already running and to report the error
+const STACK_RESERVE_BYTES: usize = 1024 * 1024;
+/// the calls a constant's initialiser can make before it's stopped. Constants are evaluated at compile time, which has
+/// to finish even when an initialiser recurses forever in tail calls, which don't grow the call stack
+const CONSTANT_CALL_BUDGET: usize = 1_000_000;
+/// the error for reading a global before its initialiser has run, which both engines report the same way
+pub const UNINITIALISED_GLOBAL: &str = "a global was read before it was initialised";
+/// the number of entries at each end of a backtrace that are displayed, as a stack overflow has thousands of them
+const BACKTRACE_DISPLAY_ENTRIES: usize = 10;
+
+/// how a program is evaluated
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvaluationOptions {
+    /// calls nested deeper than this stop the program with a stack overflow error
+    pub max_call_depth: usize,
+}
+
+impl Default for EvaluationOptions {
+    fn default() -> Self {
+        Self {
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+        }
+    }
+}
+
+impl EvaluationOptions {
+    /// the size of the host stack the evaluator needs to reach the maximum call depth, up to `MAX_STACK_BYTES`. Calls
+    /// using more stack than expected are stopped when it runs out. Constants are folded with the default depth, so at
+    /// least that much is reserved
+    pub fn stack_size(&self) -> usize {
+        self.max_call_depth
+            .max(DEFAULT_MAX_CALL_DEPTH)
+            .saturating_mul(STACK_BYTES_PER_CALL)
+            .saturating_add(BASE_STACK_BYTES)
+            .min(MAX_STACK_BYTES)
+    }
+}
+
+thread_local! {
+    /// the lowest address of the host stack a call can start at, or 0 when the size of the thread's stack isn't known
+    static STACK_LIMIT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// run `task` on a thread with a host stack of `stack_size` bytes. The calls it evaluates are stopped with a stack
+/// overflow error when the stack is about to run out, rather than aborting the process
+pub fn run_with_stack<T: Send + 'static>(
+    stack_size: usize,
+    task: impl FnOnce() -> T + Send + 'static,
+) -> io::Result<T> {
+    let stack_size = stack_size.min(MAX_STACK_BYTES);
+
+    let handle = thread::Builder::new()
+        .stack_size(stack_size)
+        .spawn(move || {
+            let usable = stack_size.saturating_sub(STACK_RESERVE_BYTES);
+            STACK_LIMIT.set(stack_address().saturating_sub(usable));
+
+            task()
+        })?;
+
+    Ok(handle
+        .join()
+        .unwrap_or_else(|panic| panic::resume_unwind(panic)))
+}
+
+/// the address of the top of the host stack. The stack grows down on every platform beach runs on
+fn stack_address() -> usize {
+    let marker = 0u8;
+    &marker as *const u8 as usize
+}
+
+/// whether there's enough host stack left to start another call
+fn has_stack_left() -> bool {
+    stack_address() > STACK_LIMIT.get()
+}
+
+/// a call to a function that hasn't returned yet
+#[derive(Clone, Debug, PartialEq)]
+pub struct StackFrame {
+    pub function: Rc<Function>,
+    /// the line of the statement that made the call
+    pub call_line: u32,
+}
+
+/// an entry of a runtime error's backtrace: a function, or the top level of the program when `function_id` is `None`,
+/// and the line it was running when the error occurred
+#[derive(Clone, Debug, PartialEq)]
+pub struct BacktraceEntry {
+    pub function_id: Option<FunctionId>,
+    pub line: u32,
+}
+
+/// an error that stops a running program
+#[derive(Debug, PartialEq)]
+pub struct RuntimeError {
+    pub message: String,
+    /// the calls that led to the error, beginning with the function the error occurred in
+    pub backtrace: Vec<BacktraceEntry>,
+}
+
+impl RuntimeError {
+    /// the line of the statement that caused the error
+    pub fn line(&self) -> u32 {
+        self.backtrace.first().map_or(0, |entry| entry.line)
+    }
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+
+        let hidden = self
+            .backtrace
+            .len()
+            .saturating_sub(2 * BACKTRACE_DISPLAY_ENTRIES);
+
+        for (index, entry) in self.backtrace.iter().enumerate() {
+            if hidden > 0 && index >= BACKTRACE_DISPLAY_ENTRIES {
+                if index == BACKTRACE_DISPLAY_ENTRIES {
+                    write!(f, "\n    ... {} more calls", hidden)?;
+                }
+                if index < BACKTRACE_DISPLAY_ENTRIES + hidden {
+                    continue;
+                }
+            }
+
+            match &entry.function_id {
+                Some(function_id) => write!(f, "\n    at {} (line {})", function_id, entry.line)?,
+                None => write!(f, "\n    at top level (line {})", entry.line)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// the state of a running program that isn't tied to a single scope
+#[derive(Debug)]
+pub struct Runtime {
+    pub call_stack: Vec<StackFrame>,
+    /// how many calls `call_stack` can hold before a call is a stack overflow
+    pub max_call_depth: usize,
+    /// the line of the statement being evaluated
+    pub line: u32,
+    /// the values of the program's constants, which are visible from every scope
+    pub constants: HashMap<Symbol, Value>,
+    /// the current values of the program's global variables, which are visible from every scope
+    pub globals: HashMap<Symbol, Value>,
+    /// the variable of the last error raised for a variable that isn't in scope, along with the depth of the call it
+    /// was raised in, so the blocks the error is returned through can explain why
+    pub out_of_scope: Option<(Symbol, usize)>,
+    /// the calls left before evaluation is stopped, or `None` if it can run for as long as it takes
+    pub call_budget: Option<usize>,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self {
+            call_stack: Vec::new(),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            line: 0,
+            constants: HashMap::new(),
+            globals: HashMap::new(),
+            out_of_scope: None,
+            call_budget: None,
+        }
+    }
+}
+
+impl Runtime {
+    /// an error at the statement being evaluated, with a backtrace of the calls that led to it
+    fn error(&self, message: impl Into<String>) -> RuntimeError {
+        let mut backtrace = Vec::new();
+        let mut line = self.line;
+
+        for frame in self.call_stack.iter().rev() {
+            backtrace.push(BacktraceEntry {
+                function_id: Some(*frame.function.id()),
+                line,
+            });
+            line = frame.call_line;
+        }
+
+        backtrace.push(BacktraceEntry {
+            function_id: None,
+            line,
+        });
+
+        RuntimeError {
+            message: message.into(),
+            backtrace,
+        }
+    }
+
+    /// count a call, including a tail call, against the call budget
+    fn charge_call(&mut self) -> Result<(), RuntimeError> {
+        match &mut self.call_budget {
+            Some(0) => Err(self.error(
+                "evaluation made too many calls without finishing, so the value can't be known at compile time",
+            )),
+            Some(budget) => {
+                *budget -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// the value of the global variable or constant with the name `name`
+    fn get_variable(&self, name: Symbol) -> Option<&Value> {
+        self.globals
+            .get(&name)
+            .or_else(|| self.constants.get(&name))
+    }
+
+    /// an error for a variable that isn't in scope, which the type checker should have already rejected. If the
+    /// variable was declared in a block that has ended, the block the error is returned through explains it
+    fn not_in_scope(&mut self, name: Symbol) -> RuntimeError {
+        self.out_of_scope = Some((name, self.call_stack.len()));
+        self.error(format!("`{}` is not in scope here", name))
+    }
+}
+
+/// a call in tail position, with its parameters already evaluated, which the calling function runs in its own place
+#[derive(Debug, PartialEq)]
+pub struct TailCall {
+    pub function: Rc<Function>,
+    /// the variables captured by the function if it's a closure, followed by the values of the parameters
+    pub frame: Frame,
+}
 
 #[derive(Debug, PartialEq)]
 pub enum NodeResult {
     None,
     FunctionReturn { value: Option<Value> },
+    TailCall(TailCall),
 }
 
 impl NodeResult {
     fn is_return(&self) -> bool {
-        matches!(self, NodeResult::FunctionReturn { .. })
+        matches!(
+            self,
+            NodeResult::FunctionReturn { .. } | NodeResult::TailCall(_)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ast::node::{Function, FunctionId, FunctionReturnType, Span};
+
+    use super::{
+        BacktraceEntry, EvaluationOptions, Runtime, RuntimeError, StackFrame, MAX_STACK_BYTES,
+    };
+
+    fn stack_frame(name: &str, call_line: u32) -> StackFrame {
+        StackFrame {
+            function: Rc::new(Function::CustomFunction {
+                id: FunctionId(name.into()),
+                name: name.into(),
+                type_parameters: Vec::new(),
+                parameters: Vec::new(),
+                return_type: FunctionReturnType::Void,
+                body: Vec::new(),
+                span: Span::default(),
+                pure: false,
+            }),
+            call_line,
+        }
+    }
+
+    #[test]
+    fn runtime_error_backtrace() {
+        let runtime = Runtime {
+            call_stack: vec![stack_frame("total", 9), stack_frame("add", 6)],
+            line: 2,
+            ..Runtime::default()
+        };
+
+        let error = runtime.error("overflow");
+
+        assert_eq!(
+            error.backtrace,
+            vec![
+                BacktraceEntry {
+                    function_id: Some(FunctionId("add".into())),
+                    line: 2,
+                },
+                BacktraceEntry {
+                    function_id: Some(FunctionId("total".into())),
+                    line: 6,
+                },
+                BacktraceEntry {
+                    function_id: None,
+                    line: 9,
+                },
+            ]
+        );
+        assert_eq!(error.line(), 2);
+    }
+
+    #[test]
+    fn runtime_error_display() {
+        let error = RuntimeError {
+            message: "overflow".to_owned(),
+            backtrace: vec![
+                BacktraceEntry {
+                    function_id: Some(FunctionId("add".into())),
+                    line: 2,
+                },
+                BacktraceEntry {
+                    function_id: None,
+                    line: 9,
+                },
+            ],
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "overflow\n    at add (line 2)\n    at top level (line 9)"
+        );
+    }
+
+    #[test]
+    fn runtime_error_display_long_backtrace() {
+        let mut backtrace = vec![
+            BacktraceEntry {
+                function_id: Some(FunctionId("count".into())),
+                line: 2,
+            };
+            24
+        ];
+        backtrace.push(BacktraceEntry {
+            function_id: None,
+            line: 4,
+        });
+        let error = RuntimeError {
+            message: "stack overflow in count (depth 25)".to_owned(),
+            backtrace,
+        };
+
+        let lines: Vec<_> = error.to_string().lines().map(str::to_owned).collect();
+
+        assert_eq!(lines.len(), 22);
+        assert_eq!(lines[10], "    at count (line 2)");
+        assert_eq!(lines[11], "    ... 5 more calls");
+        assert_eq!(lines[21], "    at top level (line 4)");
+    }
+
+    #[test]
+    fn stack_size_is_capped() {
+        let options = EvaluationOptions {
+            max_call_depth: 2_000_000,
+        };
+
+        assert_eq!(options.stack_size(), MAX_STACK_BYTES);
     }
 }
@@ -1,128 +1,165 @@
-use std::collections::HashMap;
+use crate::ast::node::{Expression, Node, Value};
 
-use crate::ast::node::{FunctionCall, FunctionId, Node, Value};
-
-use super::{Functions, NodeResult};
+use super::{Frame, Functions, NodeResult, Runtime, RuntimeError};
 
 impl Node {
     pub fn evaluate(
         &self,
-        local_variables: &mut HashMap<String, Value>,
-        call_stack: &mut Vec<FunctionId>,
+        frame: &mut Frame,
+        runtime: &mut Runtime,
         functions: &Functions,
-    ) -> NodeResult {
+    ) -> Result<NodeResult, RuntimeError> {
         match self {
             Node::VariableDeclaration {
                 var_name, value, ..
             } => {
-                local_variables.insert(
-                    var_name.to_owned(),
-                    value.evaluate(functions, &local_variables, call_stack),
-                );
+                let value = value.evaluate(functions, frame, runtime)?;
+                frame.push(*var_name, value);
+            }
+            Node::DestructuringDeclaration {
+                var_names, value, ..
+            } => {
+                let value = value.evaluate(functions, frame, runtime)?;
+                let Value::Tuple(elements) = value else {
+                    return Err(runtime.error(format!(
+                        "only tuples can be destructured, but found {:?}",
+                        value
+                    )));
+                };
+
+                for (var_name, element) in var_names.iter().zip(elements) {
+                    frame.push(*var_name, element);
+                }
             }
-            Node::FunctionReturn { return_value } => {
+            Node::FunctionReturn { return_value, .. } => {
+                // a returned call is a tail call, unless it's at the top level, which has no function to run it in
+                // place of
+                if let Some(Expression::FunctionCall(function_call)) = return_value {
+                    if !runtime.call_stack.is_empty() {
+                        return function_call.evaluate_tail_call(functions, frame, runtime);
+                    }
+                }
+
                 let return_value = return_value
                     .as_ref()
-                    .map(|expression| expression.evaluate(functions, local_variables, call_stack));
+                    .map(|expression| expression.evaluate(functions, frame, runtime))
+                    .transpose()?;
 
-                return NodeResult::FunctionReturn {
+                return Ok(NodeResult::FunctionReturn {
                     value: return_value,
-                };
+                });
             }
-            Node::FunctionCall(FunctionCall {
-                function_id,
-                parameters,
-            }) => {
-                let function = &functions[function_id];
-                function.evaluate(parameters.clone(), &local_variables, functions, call_stack);
+            Node::FunctionCall(function_call) => {
+                function_call.evaluate(functions, frame, runtime)?;
             }
             Node::IfStatement(if_statement) => {
-                return if_statement.evaluate(functions, local_variables, call_stack);
+                return if_statement.evaluate(functions, frame, runtime);
+            }
+            Node::Assignment {
+                var_name, value, ..
+            } => {
+                let value = value.evaluate(functions, frame, runtime)?;
+                runtime.globals.insert(*var_name, value);
             }
         };
 
-        NodeResult::None
+        Ok(NodeResult::None)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, rc::Rc};
 
     use crate::{
         ast::node::{
-            Function, FunctionCall, FunctionId, FunctionReturnType, IfStatement, Node,
-            VariableDeclarationType,
+            Expression, Function, FunctionCall, FunctionId, FunctionReturnType, IfStatement, Node,
+            Span, Type, VariableDeclarationType,
         },
-        evaluation::NodeResult,
+        evaluation::{Frame, NodeResult, Runtime, StackFrame, TailCall},
     };
 
     #[test]
     fn test_variable_declaration() {
         let node = Node::VariableDeclaration {
             var_type: VariableDeclarationType::Infer,
-            var_name: "my_var".to_owned(),
+            var_name: "my_var".into(),
             value: true.into(),
+            line: 0,
         };
 
-        let mut local_variables = HashMap::new();
+        let mut frame = Frame::default();
 
-        let result = node.evaluate(&mut local_variables, &mut Vec::new(), &HashMap::new());
+        let result = node.evaluate(&mut frame, &mut Runtime::default(), &HashMap::new());
 
-        assert_eq!(result, NodeResult::None);
+        assert_eq!(result, Ok(NodeResult::None));
 
-        assert_eq!(
-            local_variables,
-            HashMap::from_iter([("my_var".to_owned(), true.into())])
-        );
+        assert_eq!(frame, Frame::from(vec![("my_var".into(), true.into())]));
     }
 
     #[test]
     fn test_function_return_with_value() {
         let node = Node::FunctionReturn {
             return_value: Some(true.into()),
+            line: 0,
         };
 
-        let result = node.evaluate(&mut HashMap::new(), &mut Vec::new(), &HashMap::new());
+        let result = node.evaluate(
+            &mut Frame::default(),
+            &mut Runtime::default(),
+            &HashMap::new(),
+        );
 
         assert_eq!(
             result,
-            NodeResult::FunctionReturn {
+            Ok(NodeResult::FunctionReturn {
                 value: Some(true.into())
-            }
+            })
         );
     }
 
     #[test]
     fn test_function_return_void() {
-        let node = Node::FunctionReturn { return_value: None };
+        let node = Node::FunctionReturn {
+            return_value: None,
+            line: 0,
+        };
 
-        let result = node.evaluate(&mut HashMap::new(), &mut Vec::new(), &HashMap::new());
+        let result = node.evaluate(
+            &mut Frame::default(),
+            &mut Runtime::default(),
+            &HashMap::new(),
+        );
 
-        assert_eq!(result, NodeResult::FunctionReturn { value: None });
+        assert_eq!(result, Ok(NodeResult::FunctionReturn { value: None }));
     }
 
     #[test]
     fn test_function_call() {
         let node = Node::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: Vec::new(),
+            line: 0,
+            slot: None,
         });
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
-            Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+            FunctionId("my_function".into()),
+            Rc::new(Function::CustomFunction {
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
-            },
+                span: Span::default(),
+                pure: false,
+            }),
         )]);
 
-        let result = node.evaluate(&mut HashMap::new(), &mut Vec::new(), &functions);
+        let result = node.evaluate(&mut Frame::default(), &mut Runtime::default(), &functions);
 
-        assert_eq!(result, NodeResult::None);
+        assert_eq!(result, Ok(NodeResult::None));
     }
 
     #[test]
@@ -131,18 +168,24 @@ mod tests {
             check_expression: true.into(),
             if_block: vec![Node::FunctionReturn {
                 return_value: Some(10.into()),
+                line: 0,
             }],
             else_if_blocks: Vec::new(),
             else_block: None,
+            line: 0,
         });
 
-        let result = node.evaluate(&mut HashMap::new(), &mut Vec::new(), &HashMap::new());
+        let result = node.evaluate(
+            &mut Frame::default(),
+            &mut Runtime::default(),
+            &HashMap::new(),
+        );
 
         assert_eq!(
             result,
-            NodeResult::FunctionReturn {
+            Ok(NodeResult::FunctionReturn {
                 value: Some(10.into())
-            }
+            })
         );
     }
 
@@ -153,10 +196,116 @@ mod tests {
             if_block: Vec::new(),
             else_if_blocks: Vec::new(),
             else_block: None,
+            line: 0,
+        });
+
+        let result = node.evaluate(
+            &mut Frame::default(),
+            &mut Runtime::default(),
+            &HashMap::new(),
+        );
+
+        assert_eq!(result, Ok(NodeResult::None));
+    }
+
+    #[test]
+    fn test_assignment() {
+        let node = Node::Assignment {
+            var_name: "count".into(),
+            value: 2.into(),
+            line: 0,
+        };
+
+        let mut runtime = Runtime::default();
+        runtime.globals.insert("count".into(), 1.into());
+
+        let result = node.evaluate(&mut Frame::default(), &mut runtime, &HashMap::new());
+
+        assert_eq!(result, Ok(NodeResult::None));
+        assert_eq!(
+            runtime.globals,
+            HashMap::from_iter([("count".into(), 2.into())])
+        );
+    }
+
+    #[test]
+    fn test_function_return_tail_call() {
+        let node = Node::FunctionReturn {
+            return_value: Some(Expression::FunctionCall(FunctionCall {
+                function_id: FunctionId("my_function".into()),
+                parameters: Vec::new(),
+                line: 0,
+                slot: None,
+            })),
+            line: 0,
+        };
+
+        let function = Rc::new(Function::CustomFunction {
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Void,
+            body: Vec::new(),
+            span: Span::default(),
+            pure: false,
+        });
+        let functions = HashMap::from_iter([(*function.id(), Rc::clone(&function))]);
+
+        // the function is running, and calls itself in tail position
+        let mut runtime = Runtime::default();
+        runtime.call_stack.push(StackFrame {
+            function: Rc::clone(&function),
+            call_line: 0,
         });
 
-        let result = node.evaluate(&mut HashMap::new(), &mut Vec::new(), &HashMap::new());
+        let result = node.evaluate(&mut Frame::default(), &mut runtime, &functions);
+
+        assert_eq!(
+            result,
+            Ok(NodeResult::TailCall(TailCall {
+                function,
+                frame: Frame::default(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_function_return_call_at_top_level() {
+        let node = Node::FunctionReturn {
+            return_value: Some(Expression::FunctionCall(FunctionCall {
+                function_id: FunctionId("my_function".into()),
+                parameters: Vec::new(),
+                line: 0,
+                slot: None,
+            })),
+            line: 0,
+        };
 
-        assert_eq!(result, NodeResult::None);
+        let functions = HashMap::from_iter([(
+            FunctionId("my_function".into()),
+            Rc::new(Function::CustomFunction {
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
+                parameters: Vec::new(),
+                return_type: FunctionReturnType::Type(Type::UInt),
+                body: vec![Node::FunctionReturn {
+                    return_value: Some(1.into()),
+                    line: 0,
+                }],
+                span: Span::default(),
+                pure: false,
+            }),
+        )]);
+
+        let result = node.evaluate(&mut Frame::default(), &mut Runtime::default(), &functions);
+
+        assert_eq!(
+            result,
+            Ok(NodeResult::FunctionReturn {
+                value: Some(1.into())
+            })
+        );
     }
 }
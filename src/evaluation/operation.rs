@@ -1,34 +1,25 @@
-use std::collections::HashMap;
-
 use crate::ast::node::{
-    BinaryOperation, BoolValue, Expression, FunctionId, Operation, UIntValue, UnaryOperation, Value,
+    BinaryOperation, BoolValue, Expression, Operation, UIntValue, UnaryOperation, Value,
 };
 
-use super::Functions;
+use super::{Frame, Functions, Runtime, RuntimeError};
 
 impl Operation {
     pub fn evaluate(
         &self,
         functions: &Functions,
-        local_variables: &HashMap<String, Value>,
-        call_stack: &mut Vec<FunctionId>,
-    ) -> Value {
+        frame: &Frame,
+        runtime: &mut Runtime,
+    ) -> Result<Value, RuntimeError> {
         match self {
             Operation::Unary { operation, value } => {
-                unary_operation(*operation, value, functions, local_variables, call_stack)
+                unary_operation(*operation, value, functions, frame, runtime)
             }
             Operation::Binary {
                 operation,
                 left,
                 right,
-            } => binary_operation(
-                *operation,
-                left,
-                right,
-                functions,
-                local_variables,
-                call_stack,
-            ),
+            } => binary_operation(*operation, left, right, functions, frame, runtime),
         }
     }
 }
@@ -37,18 +28,20 @@ fn unary_operation(
     operation: UnaryOperation,
     value: &Expression,
     functions: &Functions,
-    local_variables: &HashMap<String, Value>,
-    call_stack: &mut Vec<FunctionId>,
-) -> Value {
-    let value = value.evaluate(functions, local_variables, call_stack);
-    match operation {
+    frame: &Frame,
+    runtime: &mut Runtime,
+) -> Result<Value, RuntimeError> {
+    let value = value.evaluate(functions, frame, runtime)?;
+    let result = match operation {
         UnaryOperation::Not => not(value),
-    }
+    };
+
+    result.map_err(|message| runtime.error(message))
 }
 
-fn not(value: Value) -> Value {
-    let BoolValue(bool) = value.expect_bool("not only operates on booleans");
-    Value::Boolean(BoolValue(!bool))
+fn not(value: Value) -> Result<Value, String> {
+    let BoolValue(bool) = value.expect_bool("not only operates on booleans")?;
+    Ok(Value::Boolean(BoolValue(!bool)))
 }
 
 fn binary_operation(
@@ -56,29 +49,34 @@ fn binary_operation(
     left: &Expression,
     right: &Expression,
     functions: &Functions,
-    local_variables: &HashMap<String, Value>,
-    call_stack: &mut Vec<FunctionId>,
-) -> Value {
-    let left_value = left.evaluate(functions, local_variables, call_stack);
-    let right_value = right.evaluate(functions, local_variables, call_stack);
-    match operation {
+    frame: &Frame,
+    runtime: &mut Runtime,
+) -> Result<Value, RuntimeError> {
+    let left_value = left.evaluate(functions, frame, runtime)?;
+    let right_value = right.evaluate(functions, frame, runtime)?;
+    let result = match operation {
         BinaryOperation::Plus => plus(left_value, right_value),
         BinaryOperation::GreaterThan => greater_than(left_value, right_value),
-    }
+    };
+
+    result.map_err(|message| runtime.error(message))
 }
 
-fn greater_than(left: Value, right: Value) -> Value {
-    Value::Boolean(BoolValue(
-        left.expect_uint("greater_than only operates on uint").0
-            > right.expect_uint("greater_than only operates on uint").0,
-    ))
+fn greater_than(left: Value, right: Value) -> Result<Value, String> {
+    Ok(Value::Boolean(BoolValue(
+        left.expect_uint("greater_than only operates on uint")?.0
+            > right.expect_uint("greater_than only operates on uint")?.0,
+    )))
 }
 
-fn plus(left: Value, right: Value) -> Value {
-    Value::UInt(UIntValue(
-        left.expect_uint("plus only operates on uint").0
-            + right.expect_uint("plus only operates on uint").0,
-    ))
+fn plus(left: Value, right: Value) -> Result<Value, String> {
+    let left = left.expect_uint("plus only operates on uint")?.0;
+    let right = right.expect_uint("plus only operates on uint")?.0;
+
+    match left.checked_add(right) {
+        Some(sum) => Ok(Value::UInt(UIntValue(sum))),
+        None => Err(format!("{} + {} is too large for a uint", left, right)),
+    }
 }
 
 #[cfg(test)]
@@ -90,58 +88,73 @@ mod tests {
         evaluation::operation::greater_than,
     };
 
+    use crate::evaluation::{Frame, Runtime};
+
     use super::{binary_operation, not, plus, unary_operation};
 
     #[test]
     fn test_plus() {
         let result = plus(10.into(), 15.into());
 
-        assert_eq!(result, 25.into());
+        assert_eq!(result, Ok(25.into()));
     }
 
     #[test]
-    #[should_panic]
     fn test_plus_incorrect_left_value() {
-        plus(true.into(), 10.into());
+        let result = plus(true.into(), 10.into());
+
+        assert_eq!(
+            result,
+            Err("plus only operates on uint, but found Boolean(BoolValue(true))".to_owned())
+        );
     }
 
     #[test]
-    #[should_panic]
     fn test_plus_incorrect_right_value() {
-        plus(10.into(), true.into());
+        let result = plus(10.into(), true.into());
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_plus_overflow() {
+        let result = plus(u32::MAX.into(), 1.into());
+
+        assert_eq!(
+            result,
+            Err("4294967295 + 1 is too large for a uint".to_owned())
+        );
     }
 
     #[test]
     fn test_greater_than_false() {
         let result = greater_than(10.into(), 15.into());
 
-        assert_eq!(result, false.into());
+        assert_eq!(result, Ok(false.into()));
     }
 
     #[test]
     fn test_greater_than_false_equal() {
         let result = greater_than(10.into(), 10.into());
 
-        assert_eq!(result, false.into());
+        assert_eq!(result, Ok(false.into()));
     }
 
     #[test]
     fn test_greater_than_true() {
         let result = greater_than(15.into(), 10.into());
 
-        assert_eq!(result, true.into());
+        assert_eq!(result, Ok(true.into()));
     }
 
     #[test]
-    #[should_panic]
     fn test_greater_than_incorrect_left_value() {
-        greater_than(true.into(), 10.into());
+        assert!(greater_than(true.into(), 10.into()).is_err());
     }
 
     #[test]
-    #[should_panic]
     fn test_greater_than_incorrect_right_value() {
-        greater_than(10.into(), true.into());
+        assert!(greater_than(10.into(), true.into()).is_err());
     }
 
     #[test]
@@ -151,11 +164,11 @@ mod tests {
             &10.into(),
             &10.into(),
             &HashMap::new(),
-            &HashMap::new(),
-            &mut Vec::new(),
+            &Frame::default(),
+            &mut Runtime::default(),
         );
 
-        assert_eq!(result, 20.into());
+        assert_eq!(result, Ok(20.into()));
     }
 
     #[test]
@@ -165,23 +178,22 @@ mod tests {
             &10.into(),
             &10.into(),
             &HashMap::new(),
-            &HashMap::new(),
-            &mut Vec::new(),
+            &Frame::default(),
+            &mut Runtime::default(),
         );
 
-        assert_eq!(result, false.into());
+        assert_eq!(result, Ok(false.into()));
     }
 
     #[test]
     fn test_not() {
         let result = not(true.into());
-        assert_eq!(result, false.into());
+        assert_eq!(result, Ok(false.into()));
     }
 
     #[test]
-    #[should_panic]
     fn test_not_incorrect_value() {
-        not(10.into());
+        assert!(not(10.into()).is_err());
     }
 
     #[test]
@@ -190,11 +202,11 @@ mod tests {
             UnaryOperation::Not,
             &true.into(),
             &HashMap::new(),
-            &HashMap::new(),
-            &mut Vec::new(),
+            &Frame::default(),
+            &mut Runtime::default(),
         );
 
-        assert_eq!(result, false.into());
+        assert_eq!(result, Ok(false.into()));
     }
 
     #[test]
@@ -203,9 +215,9 @@ mod tests {
             operation: UnaryOperation::Not,
             value: Box::new(true.into()),
         }
-        .evaluate(&HashMap::new(), &HashMap::new(), &mut Vec::new());
+        .evaluate(&HashMap::new(), &Frame::default(), &mut Runtime::default());
 
-        assert_eq!(result, false.into())
+        assert_eq!(result, Ok(false.into()))
     }
 
     #[test]
@@ -215,8 +227,8 @@ mod tests {
             left: Box::new(10.into()),
             right: Box::new(10.into()),
         }
-        .evaluate(&HashMap::new(), &HashMap::new(), &mut Vec::new());
+        .evaluate(&HashMap::new(), &Frame::default(), &mut Runtime::default());
 
-        assert_eq!(result, 20.into());
+        assert_eq!(result, Ok(20.into()));
     }
 }
@@ -0,0 +1,394 @@
+use std::{collections::HashSet, rc::Rc};
+
+use crate::ast::{
+    node::{
+        Ast, ElseIfBlock, Expression, Function, FunctionCall, FunctionParameter, IfStatement,
+        Lambda, Node, Operation, ResolvedLambda,
+    },
+    scope::referenced_names_in_nodes,
+    symbol::Symbol,
+};
+
+/// resolve the local variable accesses of the whole program to slots, so evaluating them is an indexed read of the
+/// running function's frame. Calls of functions held in local variables are resolved the same way. Accesses of
+/// constants, globals and functions are left as names
+pub(super) fn resolve_slots(ast: &mut Ast) {
+    for function in ast.functions.values_mut() {
+        resolve_function(function);
+    }
+
+    for constant in &mut ast.constants {
+        resolve_expression(&mut constant.value);
+    }
+
+    for global in &mut ast.globals {
+        resolve_expression(&mut global.value);
+    }
+
+    Scope::default().resolve_nodes(&mut ast.nodes);
+}
+
+/// resolve the function body, whose frame starts with the function's parameters
+pub(super) fn resolve_function(function: &mut Function) {
+    if let Function::CustomFunction {
+        parameters, body, ..
+    } = function
+    {
+        let mut scope = Scope {
+            names: parameters.iter().map(FunctionParameter::name).collect(),
+        };
+
+        scope.resolve_nodes(body);
+    }
+}
+
+/// resolve an expression evaluated outside of any function, such as a constant's value
+pub(super) fn resolve_expression(expression: &mut Expression) {
+    Scope::default().resolve_expression(expression);
+}
+
+/// the names of the local variables in scope, in slot order
+#[derive(Clone, Default)]
+struct Scope {
+    names: Vec<Symbol>,
+}
+
+impl Scope {
+    /// the slot of the innermost variable named `name`, which shadows any outer variable with the same name
+    fn slot(&self, name: Symbol) -> Option<usize> {
+        self.names.iter().rposition(|variable| *variable == name)
+    }
+
+    /// resolve a block's statements. The variables it declares are in scope until the end of the block
+    fn resolve_nodes(&mut self, nodes: &mut [Node]) {
+        let slot_count = self.names.len();
+
+        for node in nodes {
+            match node {
+                Node::VariableDeclaration {
+                    var_name, value, ..
+                } => {
+                    self.resolve_expression(value);
+                    self.names.push(*var_name);
+                }
+                Node::DestructuringDeclaration {
+                    var_names, value, ..
+                } => {
+                    self.resolve_expression(value);
+                    self.names.extend(var_names.iter().copied());
+                }
+                Node::FunctionReturn { return_value, .. } => {
+                    if let Some(return_value) = return_value {
+                        self.resolve_expression(return_value);
+                    }
+                }
+                Node::FunctionCall(function_call) => self.resolve_function_call(function_call),
+                Node::IfStatement(if_statement) => self.resolve_if_statement(if_statement),
+                Node::Assignment { value, .. } => self.resolve_expression(value),
+            }
+        }
+
+        self.names.truncate(slot_count);
+    }
+
+    fn resolve_if_statement(&mut self, if_statement: &mut IfStatement) {
+        self.resolve_expression(&mut if_statement.check_expression);
+        self.resolve_nodes(&mut if_statement.if_block);
+
+        for ElseIfBlock { check, block } in &mut if_statement.else_if_blocks {
+            self.resolve_expression(check);
+            self.resolve_nodes(block);
+        }
+
+        if let Some(else_block) = &mut if_statement.else_block {
+            self.resolve_nodes(else_block);
+        }
+    }
+
+    /// resolve the call's parameters, and the call itself when it calls a local variable, which takes precedence over
+    /// a declared function with the same name
+    fn resolve_function_call(&mut self, function_call: &mut FunctionCall) {
+        function_call.slot = self.slot(function_call.function_id.0);
+
+        for parameter in &mut function_call.parameters {
+            self.resolve_expression(parameter);
+        }
+    }
+
+    /// resolve the lambda into the function it evaluates to. A closure only captures the variables in scope that its
+    /// body uses, and its parameters follow them
+    fn resolve_lambda(&self, lambda: &mut Lambda) {
+        if lambda.resolved.is_some() {
+            return;
+        }
+
+        let mut names = HashSet::new();
+        referenced_names_in_nodes(&lambda.body, &mut names);
+
+        let mut captures: Vec<_> = names
+            .into_iter()
+            .filter_map(|name| self.slot(name))
+            .collect();
+        captures.sort_unstable();
+
+        let mut scope = Scope {
+            names: captures.iter().map(|slot| self.names[*slot]).collect(),
+        };
+        scope
+            .names
+            .extend(lambda.parameters.iter().map(FunctionParameter::name));
+        scope.resolve_nodes(&mut lambda.body);
+
+        let function = Lambda {
+            body: std::mem::take(&mut lambda.body),
+            ..lambda.clone()
+        };
+        lambda.resolved = Some(ResolvedLambda {
+            function: Rc::new(function.into_function()),
+            captures,
+        });
+    }
+
+    fn resolve_expression(&mut self, expression: &mut Expression) {
+        match expression {
+            // a function value's body was resolved before the value was created
+            Expression::ValueLiteral(_) | Expression::LocalVariableAccess { .. } => {}
+            Expression::VariableAccess(name) => {
+                if let Some(slot) = self.slot(*name) {
+                    *expression = Expression::LocalVariableAccess { name: *name, slot };
+                }
+            }
+            Expression::FunctionCall(function_call) => self.resolve_function_call(function_call),
+            Expression::Operation(Operation::Unary { value, .. }) => self.resolve_expression(value),
+            Expression::Operation(Operation::Binary { left, right, .. }) => {
+                self.resolve_expression(left);
+                self.resolve_expression(right);
+            }
+            Expression::Lambda(lambda) => self.resolve_lambda(lambda),
+            Expression::Tuple(elements) => {
+                for element in elements {
+                    self.resolve_expression(element);
+                }
+            }
+            Expression::TupleAccess { tuple, .. } => self.resolve_expression(tuple),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{
+        builders::{ast_builder::AstBuilder, statement_builder::StatementBuilder},
+        node::{Expression, Function, Node, Type},
+    };
+
+    use super::resolve_slots;
+
+    #[test]
+    fn resolve_block_variables() {
+        let mut ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("first")
+                        .with_assignment(|value| value.value_literal(1.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.value_literal(true.into()))
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.var_declaration(|declaration| {
+                                    declaration
+                                        .infer_type()
+                                        .name("second")
+                                        .with_assignment(|value| value.value_literal(2.into()))
+                                })
+                            })
+                            .statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.variable("second"))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("third")
+                        .with_assignment(|value| value.value_literal(3.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.variable("third"))
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.variable("GLOBAL"))
+                        .build()
+                })
+            })
+            .build();
+
+        resolve_slots(&mut ast);
+
+        let Node::IfStatement(if_statement) = &ast.nodes[1] else {
+            panic!("expected an if statement");
+        };
+        // the if block's variable has ended, so its slot is reused
+        assert_eq!(
+            if_statement.if_block[1],
+            StatementBuilder::default().function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| parameter.local_variable("second", 1))
+                    .build()
+            })
+        );
+        assert_eq!(
+            ast.nodes[3],
+            StatementBuilder::default().function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| parameter.local_variable("third", 1))
+                    .build()
+            })
+        );
+        assert_eq!(
+            ast.nodes[4],
+            StatementBuilder::default().function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| parameter.variable("GLOBAL"))
+                    .build()
+            })
+        );
+    }
+
+    #[test]
+    fn resolve_lambda_after_captures() {
+        let mut ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("unused")
+                        .with_assignment(|value| value.value_literal(1.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("offset")
+                        .with_assignment(|value| value.value_literal(10.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("add_offset")
+                        .with_assignment(|value| {
+                            value.lambda(|lambda| {
+                                lambda
+                                    .parameters(vec![(Type::UInt, "value".into()).into()])
+                                    .return_type(Type::UInt)
+                                    .body(|body| {
+                                        body.statement(|statement| {
+                                            statement.return_value(|value| {
+                                                value.operation(|operation| {
+                                                    operation.plus(
+                                                        |left| left.variable("value"),
+                                                        |right| right.variable("offset"),
+                                                    )
+                                                })
+                                            })
+                                        })
+                                        .build()
+                                    })
+                            })
+                        })
+                })
+            })
+            .build();
+
+        resolve_slots(&mut ast);
+
+        let Node::VariableDeclaration {
+            value: Expression::Lambda(lambda),
+            ..
+        } = &ast.nodes[2]
+        else {
+            panic!("expected a lambda declaration");
+        };
+        let Some(resolved) = &lambda.resolved else {
+            panic!("expected the lambda to be resolved");
+        };
+        let Function::CustomFunction { body, .. } = resolved.function.as_ref() else {
+            panic!("expected a custom function");
+        };
+        // only the variable the body uses is captured
+        assert_eq!(resolved.captures, vec![1]);
+        assert_eq!(
+            body[0],
+            StatementBuilder::default().return_value(|value| {
+                value.operation(|operation| {
+                    operation.plus(
+                        |left| left.local_variable("value", 1),
+                        |right| right.local_variable("offset", 0),
+                    )
+                })
+            })
+        );
+    }
+
+    #[test]
+    fn resolve_call_of_local_function() {
+        let mut ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("log")
+                        .with_assignment(|value| {
+                            value.lambda(|lambda| {
+                                lambda
+                                    .parameters(Vec::new())
+                                    .void()
+                                    .body(|body| body.build())
+                            })
+                        })
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| call.function_id("log").no_parameters().build())
+            })
+            .statement(|statement| {
+                statement.function_call(|call| call.function_id("print").no_parameters().build())
+            })
+            .build();
+
+        resolve_slots(&mut ast);
+
+        let slots: Vec<_> = ast.nodes[1..]
+            .iter()
+            .map(|node| match node {
+                Node::FunctionCall(function_call) => function_call.slot,
+                _ => panic!("expected a function call"),
+            })
+            .collect();
+        assert_eq!(slots, vec![Some(0), None]);
+    }
+}
@@ -1,6 +1,9 @@
 mod ast;
+mod bytecode;
 mod cli;
 mod evaluation;
+mod modules;
+mod optimisation;
 mod parsing;
 mod token_stream;
 mod type_checking;
@@ -11,9 +14,8 @@ use cli::match_command;
 
 #[cfg(not(tarpaulin_include))]
 fn main() {
-    if let Ok(_) = match_command(args().skip(1).collect()) {
-        exit(0);
-    } else {
-        exit(1);
+    match match_command(args().skip(1).collect()) {
+        Ok(()) => exit(0),
+        Err(exit_code) => exit(exit_code),
     }
 }
@@ -0,0 +1,873 @@
+mod resolve;
+mod type_aliases;
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    ast::{
+        builders::ast_builder::AstBuilder,
+        node::{Ast, Function, FunctionId, Span},
+        symbol::Symbol,
+    },
+    parsing::{parse_program, ParseError},
+    type_checking::Lint,
+};
+
+use self::{resolve::Namespace, type_aliases::TypeAliasResolver};
+
+/// an error in one of the files of a program that isn't tied to a position in the file
+#[derive(Debug, PartialEq)]
+pub struct ModuleError {
+    pub file: String,
+    pub message: String,
+}
+
+/// a warning found in one of a program's modules
+#[derive(Debug, PartialEq)]
+pub struct ModuleWarning {
+    pub file: String,
+    pub lint: Lint,
+    pub message: String,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LoadError {
+    Parse(ParseError),
+    Module(ModuleError),
+}
+
+/// a source file that has been loaded as part of a program.
+/// Functions declared in imported modules have ids namespaced by the module's file, ie `lib/math.bch::helper`
+#[derive(Debug)]
+struct Module {
+    file: String,
+    ast: Ast,
+    imports: Vec<usize>,
+}
+
+/// all of the modules a program is made up of
+#[derive(Debug)]
+pub struct Program {
+    modules: Vec<Module>,
+    entry: usize,
+}
+
+impl Program {
+    /// type check each module, with the functions and constants of the other modules visible.
+    /// Once the program type checks, the value of each constant is evaluated
+    pub fn type_check(&mut self) -> Result<(), Vec<ModuleError>> {
+        let errors: Vec<_> = self
+            .modules
+            .iter()
+            .enumerate()
+            .flat_map(|(index, module)| {
+                module
+                    .ast
+                    .type_check(&self.imports(index))
+                    .err()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|error| ModuleError {
+                        file: module.file.clone(),
+                        message: error.message,
+                    })
+            })
+            .collect();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        // the constants of earlier modules are folded first, so they can be used by later modules
+        for index in 0..self.modules.len() {
+            let imports = self.imports(index);
+            if let Err(error) = self.modules[index].ast.fold_constants(&imports) {
+                return Err(vec![ModuleError {
+                    file: self.modules[index].file.clone(),
+                    message: format!("Failed to evaluate a constant: {}", error),
+                }]);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// find the likely mistakes in each module that don't stop the program from running.
+    /// Only the entry module's functions are checked for never being called, as imported modules are libraries, whose
+    /// functions are there for any program to use
+    pub fn find_warnings(&self) -> Vec<ModuleWarning> {
+        self.modules
+            .iter()
+            .enumerate()
+            .flat_map(|(index, module)| {
+                let mut warnings = module.ast.find_unreachable_code();
+                warnings.extend(module.ast.find_unused_variables());
+                if index == self.entry {
+                    warnings.extend(module.ast.find_unused_functions(&self.imports(index)));
+                }
+                warnings.sort_by_key(|warning| warning.span.start_line);
+
+                warnings.into_iter().map(|warning| ModuleWarning {
+                    file: module.file.clone(),
+                    lint: warning.lint,
+                    message: warning.message,
+                    span: warning.span,
+                })
+            })
+            .collect()
+    }
+
+    /// the functions of every other module, and the constants and globals of the modules before the module at `index`.
+    /// Modules are stored with the modules they import first, so these are the variables the module can refer to
+    fn imports(&self, index: usize) -> Ast {
+        let functions = self
+            .modules
+            .iter()
+            .enumerate()
+            .filter(|(other_index, _)| *other_index != index)
+            .flat_map(|(_, other)| other.ast.functions.clone())
+            .collect();
+
+        let constants = self.modules[..index]
+            .iter()
+            .flat_map(|other| other.ast.constants.clone())
+            .collect();
+
+        let globals = self.modules[..index]
+            .iter()
+            .flat_map(|other| other.ast.globals.clone())
+            .collect();
+
+        Ast {
+            functions,
+            constants,
+            globals,
+            // types have already been resolved, so aliases aren't needed
+            type_aliases: Vec::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// combine the modules into a single ast which runs the entry module
+    pub fn link(self) -> Ast {
+        let mut functions = HashMap::new();
+        let mut constants = Vec::new();
+        let mut globals = Vec::new();
+        let mut type_aliases = Vec::new();
+        let mut nodes = Vec::new();
+
+        // modules are stored with the modules they import first, so constants and globals are in initialisation order
+        for (index, module) in self.modules.into_iter().enumerate() {
+            functions.extend(module.ast.functions);
+            constants.extend(module.ast.constants);
+            globals.extend(module.ast.globals);
+            type_aliases.extend(module.ast.type_aliases);
+
+            if index == self.entry {
+                nodes = module.ast.nodes;
+            }
+        }
+
+        Ast {
+            functions,
+            constants,
+            globals,
+            type_aliases,
+            nodes,
+        }
+    }
+}
+
+/// load the program beginning at `entry_file`, along with every module it imports. Each module is only loaded once,
+/// no matter how many times it is imported
+pub fn load_program(
+    entry_file: &str,
+    read_file: impl Fn(&Path) -> io::Result<String>,
+) -> Result<Program, Vec<LoadError>> {
+    let mut loader = ModuleLoader {
+        read_file,
+        loaded: HashMap::new(),
+        loading: Vec::new(),
+        modules: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let entry_path = normalize_path(Path::new(entry_file));
+    let entry = loader.load(entry_path, entry_file.to_owned(), None);
+
+    let Some(entry) = entry.filter(|_| loader.errors.is_empty()) else {
+        return Err(loader.errors);
+    };
+
+    let mut modules = loader.modules;
+    let mut errors = resolve_type_aliases(&mut modules);
+    errors.extend(resolve_function_ids(&mut modules));
+
+    if errors.is_empty() {
+        Ok(Program { modules, entry })
+    } else {
+        Err(errors.into_iter().map(LoadError::Module).collect())
+    }
+}
+
+struct ModuleLoader<F: Fn(&Path) -> io::Result<String>> {
+    read_file: F,
+    loaded: HashMap<PathBuf, usize>,
+    loading: Vec<PathBuf>,
+    modules: Vec<Module>,
+    errors: Vec<LoadError>,
+}
+
+impl<F: Fn(&Path) -> io::Result<String>> ModuleLoader<F> {
+    /// load the module at `path` and the modules it imports, returning the index of the loaded module.
+    /// `importer` is the file of the module importing this one, which is `None` for the entry module
+    fn load(&mut self, path: PathBuf, file: String, importer: Option<&str>) -> Option<usize> {
+        if let Some(index) = self.loaded.get(&path) {
+            return Some(*index);
+        }
+
+        let error_file = importer.unwrap_or(&file).to_owned();
+
+        if let Some(cycle_start) = self.loading.iter().position(|loading| *loading == path) {
+            let cycle: Vec<_> = self.loading[cycle_start..]
+                .iter()
+                .chain(std::iter::once(&path))
+                .map(|path| path.display().to_string())
+                .collect();
+            self.errors.push(LoadError::Module(ModuleError {
+                file: error_file,
+                message: format!("Import cycle: {}", cycle.join(" -> ")),
+            }));
+            return None;
+        }
+
+        let code = match (self.read_file)(&path) {
+            Ok(code) => code,
+            Err(error) => {
+                self.errors.push(LoadError::Module(ModuleError {
+                    file: error_file,
+                    message: format!("Could not read {}: {}", file, error),
+                }));
+                return None;
+            }
+        };
+
+        let tokens = match parse_program(&code, &file) {
+            Ok(tokens) => tokens,
+            Err(errors) => {
+                self.errors.extend(errors.into_iter().map(LoadError::Parse));
+                return None;
+            }
+        };
+
+        let builder = match AstBuilder::from_token_stream(tokens) {
+            Ok(builder) => builder,
+            Err(errors) => {
+                self.errors.extend(errors.into_iter().map(|error| {
+                    LoadError::Module(ModuleError {
+                        file: file.clone(),
+                        message: error.message,
+                    })
+                }));
+                return None;
+            }
+        };
+
+        self.loading.push(path.clone());
+
+        let parent = path.parent().unwrap_or(Path::new("")).to_owned();
+        let imports: Vec<usize> = builder
+            .imports()
+            .iter()
+            .filter_map(|import| {
+                let import_path = normalize_path(&parent.join(import));
+                let import_file = import_path.display().to_string();
+                self.load(import_path, import_file, Some(&file))
+            })
+            .collect();
+
+        self.loading.pop();
+
+        let mut ast = builder.build();
+
+        if importer.is_some() {
+            if !ast.nodes.is_empty() {
+                self.errors.push(LoadError::Module(ModuleError {
+                    file: file.clone(),
+                    message: "an imported module can only contain imports, constants, globals and function declarations".to_owned(),
+                }));
+            }
+
+            ast.functions = namespace_functions(&file, ast.functions);
+            for constant in &mut ast.constants {
+                constant.name = Symbol::intern(&format!("{}::{}", file, constant.name));
+            }
+            for global in &mut ast.globals {
+                global.name = Symbol::intern(&format!("{}::{}", file, global.name));
+            }
+        }
+
+        self.modules.push(Module { file, ast, imports });
+        let index = self.modules.len() - 1;
+        self.loaded.insert(path, index);
+
+        Some(index)
+    }
+}
+
+/// give the functions of an imported module ids that are unique to the module, so different modules can declare
+/// functions with the same name
+fn namespace_functions(
+    file: &str,
+    functions: HashMap<FunctionId, Function>,
+) -> HashMap<FunctionId, Function> {
+    functions
+        .into_values()
+        .map(|function| {
+            let Function::CustomFunction {
+                name,
+                type_parameters,
+                parameters,
+                return_type,
+                body,
+                span,
+                pure,
+                ..
+            } = function
+            else {
+                unreachable!("modules only declare custom functions");
+            };
+
+            let id = FunctionId(Symbol::intern(&format!("{}::{}", file, name)));
+
+            (
+                id,
+                Function::CustomFunction {
+                    id,
+                    name,
+                    type_parameters,
+                    parameters,
+                    return_type,
+                    body,
+                    span,
+                    pure,
+                },
+            )
+        })
+        .collect()
+}
+
+/// replace the named types in each module with the type aliases they refer to. Modules are stored with the modules
+/// they import first, so the aliases of imported modules are resolved before they are used
+fn resolve_type_aliases(modules: &mut [Module]) -> Vec<ModuleError> {
+    let mut errors = Vec::new();
+
+    for index in 0..modules.len() {
+        let module = &modules[index];
+        let mut module_errors = Vec::new();
+
+        let declared = module.ast.type_aliases.clone();
+        let mut resolver = TypeAliasResolver::new(
+            &declared,
+            module
+                .imports
+                .iter()
+                .flat_map(|import| &modules[*import].ast.type_aliases),
+        );
+
+        let type_aliases = resolver.resolve_declarations(&declared, &mut module_errors);
+        let mut ast = module.ast.clone();
+        resolver.resolve_ast(&mut ast, &mut module_errors);
+        ast.type_aliases = type_aliases;
+
+        errors.extend(module_errors.into_iter().map(|message| ModuleError {
+            file: modules[index].file.clone(),
+            message,
+        }));
+        modules[index].ast = ast;
+    }
+
+    errors
+}
+
+/// update each function call and constant access in each module to use the id of the declaration it refers to.
+/// A module can use its own declarations and the declarations of the modules it directly imports
+fn resolve_function_ids(modules: &mut [Module]) -> Vec<ModuleError> {
+    let mut errors = Vec::new();
+
+    let resolved: Vec<(Vec<_>, Ast)> = modules
+        .iter()
+        .map(|module| {
+            let namespace = Namespace::new(
+                &module.file,
+                declarations(&module.ast),
+                module.imports.iter().flat_map(|import| {
+                    let imported = &modules[*import];
+                    declarations(&imported.ast).map(|(name, id)| (name, id, imported.file.as_str()))
+                }),
+            );
+
+            let mut module_errors = Vec::new();
+            let mut ast = module.ast.clone();
+
+            namespace.resolve_nodes(&mut ast.nodes, &HashSet::new(), &mut module_errors);
+
+            for global in &mut ast.globals {
+                namespace.resolve_expression(
+                    &mut global.value,
+                    &HashSet::new(),
+                    &mut module_errors,
+                );
+            }
+
+            for constant in &mut ast.constants {
+                namespace.resolve_expression(
+                    &mut constant.value,
+                    &HashSet::new(),
+                    &mut module_errors,
+                );
+            }
+
+            for function in ast.functions.values_mut() {
+                if let Function::CustomFunction {
+                    parameters, body, ..
+                } = function
+                {
+                    let parameter_names = parameters
+                        .iter()
+                        .map(|parameter| parameter.name())
+                        .collect();
+
+                    namespace.resolve_nodes(body, &parameter_names, &mut module_errors);
+                }
+            }
+
+            (module_errors, ast)
+        })
+        .collect();
+
+    for (module, (module_errors, ast)) in modules.iter_mut().zip(resolved) {
+        errors.extend(module_errors);
+        module.ast = ast;
+    }
+
+    errors
+}
+
+/// the name and id of each function, constant and global declared in the ast. The constants and globals of
+/// imported modules are namespaced like functions, so their name is the part after the file
+fn declarations(ast: &Ast) -> impl Iterator<Item = (Symbol, Symbol)> + '_ {
+    let variable_ids = ast
+        .constants
+        .iter()
+        .map(|constant| constant.name)
+        .chain(ast.globals.iter().map(|global| global.name));
+
+    ast.functions
+        .values()
+        .map(|function| (function.name(), function.id().0))
+        .chain(variable_ids.map(|id| {
+            let id_text = id.as_str();
+            (
+                Symbol::intern(id_text.rsplit("::").next().unwrap_or(id_text)),
+                id,
+            )
+        }))
+}
+
+/// lexically remove `.` and `..` components from the path, so the same file imported through different relative
+/// paths is only loaded once
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io,
+        path::{Path, PathBuf},
+    };
+
+    use crate::{
+        ast::node::{
+            BinaryOperation, Constant, Expression, FunctionId, Node, Operation, Span, Type,
+            VariableDeclarationType,
+        },
+        parsing::ParseError,
+        type_checking::Lint,
+    };
+
+    use super::{load_program, normalize_path, LoadError, ModuleError, ModuleWarning};
+
+    fn read_from(files: &[(&str, &str)]) -> impl Fn(&Path) -> io::Result<String> {
+        let files: HashMap<PathBuf, String> = files
+            .iter()
+            .map(|(path, code)| (PathBuf::from(path), code.to_string()))
+            .collect();
+
+        move |path| {
+            files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "file not found"))
+        }
+    }
+
+    #[test]
+    fn normalize_relative_path() {
+        assert_eq!(
+            normalize_path(Path::new("./examples/lib/../math.bch")),
+            PathBuf::from("examples/math.bch")
+        );
+        assert_eq!(
+            normalize_path(Path::new("../math.bch")),
+            PathBuf::from("../math.bch")
+        );
+    }
+
+    #[test]
+    fn load_program_with_imports() {
+        let read_file = read_from(&[
+            (
+                "main.bch",
+                "import \"lib/math.bch\"; import \"lib/other.bch\"; print(double(2));",
+            ),
+            (
+                "lib/math.bch",
+                "import \"other.bch\"; function double(uint value) -> uint { return helper(value) + helper(value); }",
+            ),
+            (
+                "lib/other.bch",
+                "function helper(uint value) -> uint { return value; }",
+            ),
+        ]);
+
+        let mut program = load_program("main.bch", read_file).unwrap();
+
+        assert!(matches!(program.type_check(), Ok(())));
+
+        let ast = program.link();
+
+        // the module imported twice is only loaded once
+        assert_eq!(ast.functions.len(), 2);
+        assert!(ast
+            .functions
+            .contains_key(&FunctionId("lib/math.bch::double".into())));
+        assert!(ast
+            .functions
+            .contains_key(&FunctionId("lib/other.bch::helper".into())));
+    }
+
+    #[test]
+    fn load_program_same_import_written_differently() {
+        let read_file = read_from(&[
+            (
+                "main.bch",
+                "import \"./lib.bch\"; import \"lib.bch\"; print(helper());",
+            ),
+            ("lib.bch", "function helper() -> uint { return 1; }"),
+        ]);
+
+        let mut program = load_program("main.bch", read_file).unwrap();
+
+        assert!(matches!(program.type_check(), Ok(())));
+        assert_eq!(program.link().functions.len(), 1);
+    }
+
+    #[test]
+    fn load_program_same_function_name_in_different_modules() {
+        let read_file = read_from(&[
+            (
+                "main.bch",
+                "import \"lib.bch\"; function helper() -> uint { return 1; } print(helper() + from_lib());",
+            ),
+            (
+                "lib.bch",
+                "function helper() -> uint { return 2; } function from_lib() -> uint { return helper(); }",
+            ),
+        ]);
+
+        let mut program = load_program("main.bch", read_file).unwrap();
+
+        assert!(matches!(program.type_check(), Ok(())));
+
+        let ast = program.link();
+
+        assert_eq!(ast.functions.len(), 3);
+    }
+
+    #[test]
+    fn load_program_imported_constants() {
+        let read_file = read_from(&[
+            (
+                "main.bch",
+                "import \"lib.bch\"; const TOTAL: uint = LIMIT + scale(2); print(TOTAL);",
+            ),
+            (
+                "lib.bch",
+                "const LIMIT: uint = 10; function scale(uint value) -> uint { return value + LIMIT; }",
+            ),
+        ]);
+
+        let mut program = load_program("main.bch", read_file).unwrap();
+
+        assert!(matches!(program.type_check(), Ok(())));
+
+        let ast = program.link();
+
+        assert_eq!(
+            ast.constants,
+            vec![
+                Constant {
+                    name: "lib.bch::LIMIT".into(),
+                    const_type: Type::UInt,
+                    value: 10.into(),
+                    line: 1,
+                },
+                Constant {
+                    name: "TOTAL".into(),
+                    const_type: Type::UInt,
+                    value: 22.into(),
+                    line: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn load_program_unreachable_code_warning() {
+        let read_file = read_from(&[
+            ("main.bch", "import \"lib.bch\";\nprint(sign(2));"),
+            (
+                "lib.bch",
+                "function sign(uint value) -> uint {\n    return 1;\n    print(value);\n}",
+            ),
+        ]);
+
+        let program = load_program("main.bch", read_file).unwrap();
+
+        assert_eq!(
+            program.find_warnings(),
+            vec![ModuleWarning {
+                file: "lib.bch".to_owned(),
+                lint: Lint::UnreachableCode,
+                message: "unreachable code; it comes after the return at line 2".to_owned(),
+                span: Span {
+                    start_line: 3,
+                    end_line: 3
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn load_program_unused_function_warning() {
+        let read_file = read_from(&[
+            (
+                "main.bch",
+                "import \"lib.bch\";\nfunction helper() {}\nprint(double(2));",
+            ),
+            (
+                "lib.bch",
+                "function double(uint value) -> uint { return value + value; }\nfunction unused() {}",
+            ),
+        ]);
+
+        let program = load_program("main.bch", read_file).unwrap();
+
+        // the unused function of the imported module isn't reported
+        assert_eq!(
+            program.find_warnings(),
+            vec![ModuleWarning {
+                file: "main.bch".to_owned(),
+                lint: Lint::UnusedFunctions,
+                message: "function `helper` is never called".to_owned(),
+                span: Span {
+                    start_line: 2,
+                    end_line: 2
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn load_program_imported_globals() {
+        let read_file = read_from(&[
+            (
+                "main.bch",
+                "import \"lib.bch\"; increment(); count = count + 1; print(count);",
+            ),
+            (
+                "lib.bch",
+                "global mut count: uint = 0; function increment() { count = count + 1; }",
+            ),
+        ]);
+
+        let mut program = load_program("main.bch", read_file).unwrap();
+
+        assert!(matches!(program.type_check(), Ok(())));
+
+        let ast = program.link();
+
+        assert_eq!(ast.globals[0].name, "lib.bch::count");
+        assert_eq!(
+            ast.nodes[1],
+            Node::Assignment {
+                var_name: "lib.bch::count".into(),
+                value: Expression::Operation(Operation::Binary {
+                    operation: BinaryOperation::Plus,
+                    left: Box::new(Expression::VariableAccess("lib.bch::count".into())),
+                    right: Box::new(1.into()),
+                }),
+                line: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn load_program_imported_type_aliases() {
+        let read_file = read_from(&[
+            (
+                "main.bch",
+                "import \"lib.bch\"; Count total = 1; print(total);",
+            ),
+            ("lib.bch", "type Count = uint;"),
+        ]);
+
+        let mut program = load_program("main.bch", read_file).unwrap();
+
+        assert!(matches!(program.type_check(), Ok(())));
+
+        let ast = program.link();
+
+        assert!(matches!(
+            &ast.nodes[0],
+            Node::VariableDeclaration {
+                var_type: VariableDeclarationType::Type(Type::Alias(alias)),
+                ..
+            } if alias.name == "Count" && *alias.aliased_type == Type::UInt
+        ));
+    }
+
+    #[test]
+    fn load_program_unknown_type() {
+        let read_file = read_from(&[("main.bch", "Count total = 1;")]);
+
+        let result = load_program("main.bch", read_file);
+
+        assert!(
+            matches!(result, Err(e) if e == vec![LoadError::Module(ModuleError {
+                file: "main.bch".to_owned(),
+                message: "Unknown type Count".to_owned(),
+            })])
+        );
+    }
+
+    #[test]
+    fn load_program_import_cycle() {
+        let read_file = read_from(&[
+            ("main.bch", "import \"a.bch\";"),
+            ("a.bch", "import \"b.bch\";"),
+            ("b.bch", "import \"a.bch\";"),
+        ]);
+
+        let result = load_program("main.bch", read_file);
+
+        assert!(
+            matches!(result, Err(e) if e == vec![LoadError::Module(ModuleError {
+                file: "b.bch".to_owned(),
+                message: "Import cycle: a.bch -> b.bch -> a.bch".to_owned(),
+            })])
+        );
+    }
+
+    #[test]
+    fn load_program_missing_import() {
+        let read_file = read_from(&[("main.bch", "import \"missing.bch\";")]);
+
+        let result = load_program("main.bch", read_file);
+
+        assert!(
+            matches!(result, Err(e) if e == vec![LoadError::Module(ModuleError {
+                file: "main.bch".to_owned(),
+                message: "Could not read missing.bch: file not found".to_owned(),
+            })])
+        );
+    }
+
+    #[test]
+    fn load_program_errors_name_module_file() {
+        let read_file = read_from(&[
+            ("main.bch", "import \"lib.bch\";"),
+            ("lib.bch", "function broken() -> uint { return true; }\n#"),
+        ]);
+
+        let result = load_program("main.bch", read_file);
+
+        assert!(
+            matches!(result, Err(e) if e == vec![LoadError::Parse(ParseError {
+                error: "Unexpected character `#`".to_owned(),
+                file: "lib.bch".to_owned(),
+                line: 2,
+                character_range: 1..1,
+            })])
+        );
+    }
+
+    #[test]
+    fn type_check_errors_name_module_file() {
+        let read_file = read_from(&[
+            ("main.bch", "import \"lib.bch\";"),
+            ("lib.bch", "function broken() -> uint { return true; }"),
+        ]);
+
+        let mut program = load_program("main.bch", read_file).unwrap();
+
+        assert!(
+            matches!(program.type_check(), Err(e) if e == vec![ModuleError {
+                file: "lib.bch".to_owned(),
+                message: "broken expects a return type of UInt, but you returned a Boolean value".to_owned(),
+            }])
+        );
+    }
+
+    #[test]
+    fn imported_module_with_statements() {
+        let read_file = read_from(&[
+            ("main.bch", "import \"lib.bch\";"),
+            ("lib.bch", "print(1);"),
+        ]);
+
+        let result = load_program("main.bch", read_file);
+
+        assert!(
+            matches!(result, Err(e) if e == vec![LoadError::Module(ModuleError {
+                file: "lib.bch".to_owned(),
+                message: "an imported module can only contain imports, constants, globals and function declarations".to_owned(),
+            })])
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    node::{Expression, FunctionCall, FunctionId, FunctionParameter, Node, Operation},
+    symbol::Symbol,
+};
+
+use super::ModuleError;
+
+/// the functions and constants that can be referred to by name from within a module. A module's own
+/// declarations take precedence over the declarations of the modules it imports
+pub(super) struct Namespace<'a> {
+    file: &'a str,
+    declarations: HashMap<Symbol, Vec<(Symbol, &'a str)>>,
+}
+
+impl<'a> Namespace<'a> {
+    /// `own_declarations` are pairs of the name and id of each declaration,
+    /// and `imported_declarations` also hold the file each declaration comes from
+    pub(super) fn new(
+        file: &'a str,
+        own_declarations: impl Iterator<Item = (Symbol, Symbol)>,
+        imported_declarations: impl Iterator<Item = (Symbol, Symbol, &'a str)>,
+    ) -> Self {
+        let mut declarations: HashMap<Symbol, Vec<(Symbol, &str)>> = own_declarations
+            .map(|(name, id)| (name, vec![(id, file)]))
+            .collect();
+
+        let own_names: HashSet<Symbol> = declarations.keys().copied().collect();
+
+        for (name, id, imported_file) in imported_declarations {
+            if own_names.contains(&name) {
+                continue;
+            }
+
+            // a module imported more than once, even through differently written paths, declares the same ids
+            let candidates = declarations.entry(name).or_default();
+            if candidates.iter().all(|(candidate, _)| *candidate != id) {
+                candidates.push((id, imported_file));
+            }
+        }
+
+        Self { file, declarations }
+    }
+
+    /// the id of the declaration `name` refers to, or `None` if it isn't declared in this namespace
+    fn resolve(
+        &self,
+        name: Symbol,
+        local_variables: &HashSet<Symbol>,
+    ) -> Option<Result<Symbol, ModuleError>> {
+        // local variables take precedence over declarations
+        if local_variables.contains(&name) {
+            return None;
+        }
+
+        match self.declarations.get(&name)?.as_slice() {
+            [(id, _)] => Some(Ok(*id)),
+            candidates => Some(Err(ModuleError {
+                file: self.file.to_owned(),
+                message: format!(
+                    "{} is ambiguous, it is defined in {}",
+                    name,
+                    candidates
+                        .iter()
+                        .map(|(_, file)| *file)
+                        .collect::<Vec<_>>()
+                        .join(" and ")
+                ),
+            })),
+        }
+    }
+
+    /// replace the function and constant names referenced in `nodes` with the ids of the declarations they refer to
+    pub(super) fn resolve_nodes(
+        &self,
+        nodes: &mut [Node],
+        local_variables: &HashSet<Symbol>,
+        errors: &mut Vec<ModuleError>,
+    ) {
+        let mut local_variables = local_variables.clone();
+
+        for node in nodes {
+            match node {
+                Node::VariableDeclaration {
+                    var_name, value, ..
+                } => {
+                    self.resolve_expression(value, &local_variables, errors);
+                    local_variables.insert(*var_name);
+                }
+                Node::DestructuringDeclaration {
+                    var_names, value, ..
+                } => {
+                    self.resolve_expression(value, &local_variables, errors);
+                    local_variables.extend(var_names.iter().copied());
+                }
+                Node::FunctionReturn { return_value, .. } => {
+                    if let Some(return_value) = return_value {
+                        self.resolve_expression(return_value, &local_variables, errors);
+                    }
+                }
+                Node::FunctionCall(function_call) => {
+                    self.resolve_function_call(function_call, &local_variables, errors)
+                }
+                Node::IfStatement(if_statement) => {
+                    self.resolve_expression(
+                        &mut if_statement.check_expression,
+                        &local_variables,
+                        errors,
+                    );
+                    self.resolve_nodes(&mut if_statement.if_block, &local_variables, errors);
+
+                    for else_if_block in &mut if_statement.else_if_blocks {
+                        self.resolve_expression(&mut else_if_block.check, &local_variables, errors);
+                        self.resolve_nodes(&mut else_if_block.block, &local_variables, errors);
+                    }
+
+                    if let Some(else_block) = &mut if_statement.else_block {
+                        self.resolve_nodes(else_block, &local_variables, errors);
+                    }
+                }
+                Node::Assignment {
+                    var_name, value, ..
+                } => {
+                    self.resolve_expression(value, &local_variables, errors);
+
+                    match self.resolve(*var_name, &local_variables) {
+                        Some(Ok(id)) => *var_name = id,
+                        Some(Err(error)) => errors.push(error),
+                        None => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn resolve_function_call(
+        &self,
+        function_call: &mut FunctionCall,
+        local_variables: &HashSet<Symbol>,
+        errors: &mut Vec<ModuleError>,
+    ) {
+        match self.resolve(function_call.function_id.0, local_variables) {
+            Some(Ok(id)) => function_call.function_id = FunctionId(id),
+            Some(Err(error)) => errors.push(error),
+            None => {}
+        }
+
+        for parameter in &mut function_call.parameters {
+            self.resolve_expression(parameter, local_variables, errors);
+        }
+    }
+
+    pub(super) fn resolve_expression(
+        &self,
+        expression: &mut Expression,
+        local_variables: &HashSet<Symbol>,
+        errors: &mut Vec<ModuleError>,
+    ) {
+        match expression {
+            // a resolved local variable can't refer to a module's declarations
+            Expression::ValueLiteral(_) | Expression::LocalVariableAccess { .. } => {}
+            Expression::FunctionCall(function_call) => {
+                self.resolve_function_call(function_call, local_variables, errors)
+            }
+            Expression::Operation(Operation::Unary { value, .. }) => {
+                self.resolve_expression(value, local_variables, errors)
+            }
+            Expression::Operation(Operation::Binary { left, right, .. }) => {
+                self.resolve_expression(left, local_variables, errors);
+                self.resolve_expression(right, local_variables, errors);
+            }
+            Expression::VariableAccess(name) => match self.resolve(*name, local_variables) {
+                Some(Ok(id)) => *name = id,
+                Some(Err(error)) => errors.push(error),
+                None => {}
+            },
+            Expression::Lambda(lambda) => {
+                let mut local_variables = local_variables.clone();
+                local_variables.extend(lambda.parameters.iter().map(FunctionParameter::name));
+
+                self.resolve_nodes(&mut lambda.body, &local_variables, errors);
+            }
+            Expression::Tuple(elements) => {
+                for element in elements {
+                    self.resolve_expression(element, local_variables, errors);
+                }
+            }
+            Expression::TupleAccess { tuple, .. } => {
+                self.resolve_expression(tuple, local_variables, errors)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::ast::{
+        builders::ast_builder::AstBuilder,
+        node::{FunctionReturnType, FunctionType, Type},
+    };
+
+    use super::Namespace;
+
+    fn call(ast: AstBuilder, function_id: &'static str) -> AstBuilder {
+        ast.statement(|statement| {
+            statement.function_call(|call| call.function_id(function_id).no_parameters().build())
+        })
+    }
+
+    fn print(ast: AstBuilder, variable: &'static str) -> AstBuilder {
+        ast.statement(|statement| {
+            statement.function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| parameter.variable(variable))
+                    .build()
+            })
+        })
+    }
+
+    #[test]
+    fn resolve_own_function_before_imported_function() {
+        let namespace = Namespace::new(
+            "main.bch",
+            [("helper".into(), "helper".into())].into_iter(),
+            [("helper".into(), "lib.bch::helper".into(), "lib.bch")].into_iter(),
+        );
+
+        let mut nodes = call(AstBuilder::default(), "helper").build().nodes;
+        let mut errors = Vec::new();
+
+        namespace.resolve_nodes(&mut nodes, &HashSet::new(), &mut errors);
+
+        assert!(errors.is_empty());
+        assert_eq!(nodes, call(AstBuilder::default(), "helper").build().nodes);
+    }
+
+    #[test]
+    fn resolve_imported_function() {
+        let namespace = Namespace::new(
+            "main.bch",
+            [].into_iter(),
+            [("helper".into(), "lib.bch::helper".into(), "lib.bch")].into_iter(),
+        );
+
+        let ast = print(AstBuilder::default(), "helper").statement(|statement| {
+            statement.var_declaration(|declaration| {
+                declaration
+                    .infer_type()
+                    .name("helper")
+                    .with_assignment(|value| value.value_literal(10.into()))
+            })
+        });
+        let mut nodes = print(ast, "helper").build().nodes;
+        let mut errors = Vec::new();
+
+        namespace.resolve_nodes(&mut nodes, &HashSet::new(), &mut errors);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            nodes[0],
+            print(AstBuilder::default(), "lib.bch::helper")
+                .build()
+                .nodes[0]
+        );
+        // the local variable shadows the imported function
+        assert_eq!(
+            nodes[2],
+            print(AstBuilder::default(), "helper").build().nodes[0]
+        );
+    }
+
+    #[test]
+    fn resolve_lambda_parameters_shadow_functions() {
+        let namespace = Namespace::new(
+            "main.bch",
+            [].into_iter(),
+            [("helper".into(), "lib.bch::helper".into(), "lib.bch")].into_iter(),
+        );
+
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|run| {
+                    run.function_id("run")
+                        .parameter(|parameter| {
+                            parameter.lambda(|lambda| {
+                                lambda
+                                    .parameters(vec![(
+                                        Type::Function(FunctionType {
+                                            parameters: Vec::new(),
+                                            return_type: Box::new(FunctionReturnType::Void),
+                                        }),
+                                        "helper".into(),
+                                    )
+                                        .into()])
+                                    .void()
+                                    .body(|body| call(body, "helper").build())
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+        let mut nodes = ast.nodes.clone();
+        let mut errors = Vec::new();
+
+        namespace.resolve_nodes(&mut nodes, &HashSet::new(), &mut errors);
+
+        assert!(errors.is_empty());
+        assert_eq!(nodes, ast.nodes);
+    }
+
+    #[test]
+    fn resolve_ambiguous_function() {
+        let namespace = Namespace::new(
+            "main.bch",
+            [].into_iter(),
+            [
+                ("helper".into(), "first.bch::helper".into(), "first.bch"),
+                ("helper".into(), "second.bch::helper".into(), "second.bch"),
+            ]
+            .into_iter(),
+        );
+
+        let mut nodes = call(AstBuilder::default(), "helper").build().nodes;
+        let mut errors = Vec::new();
+
+        namespace.resolve_nodes(&mut nodes, &HashSet::new(), &mut errors);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].file, "main.bch");
+        assert_eq!(
+            errors[0].message,
+            "helper is ambiguous, it is defined in first.bch and second.bch"
+        );
+    }
+
+    #[test]
+    fn resolve_function_imported_twice() {
+        let namespace = Namespace::new(
+            "main.bch",
+            [].into_iter(),
+            [
+                ("helper".into(), "lib.bch::helper".into(), "lib.bch"),
+                ("helper".into(), "lib.bch::helper".into(), "lib.bch"),
+            ]
+            .into_iter(),
+        );
+
+        let mut nodes = call(AstBuilder::default(), "helper").build().nodes;
+        let mut errors = Vec::new();
+
+        namespace.resolve_nodes(&mut nodes, &HashSet::new(), &mut errors);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            nodes,
+            call(AstBuilder::default(), "lib.bch::helper").build().nodes
+        );
+    }
+}
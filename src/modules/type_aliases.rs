@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    node::{
+        Ast, Expression, Function, FunctionParameter, FunctionReturnType, FunctionType, Node,
+        Operation, Type, TypeAlias, VariableDeclarationType,
+    },
+    symbol::Symbol,
+};
+
+/// replaces the named types in a module with the type aliases they name. A module can use its own type aliases
+/// and the type aliases of the modules it directly imports, which are expected to already be resolved
+pub(super) struct TypeAliasResolver<'a> {
+    declared: HashMap<Symbol, &'a Type>,
+    imported: HashMap<Symbol, &'a TypeAlias>,
+    resolved: HashMap<Symbol, TypeAlias>,
+    resolving: Vec<Symbol>,
+    /// the aliases that couldn't be resolved, whose errors have already been reported
+    invalid: HashSet<Symbol>,
+}
+
+/// why a type couldn't be resolved
+enum ResolveError {
+    /// a problem that hasn't been reported yet
+    Message(String),
+    /// the type uses an alias that couldn't be resolved, which has already been reported
+    Reported,
+}
+
+impl ResolveError {
+    fn report(self, errors: &mut Vec<String>) {
+        if let ResolveError::Message(message) = self {
+            errors.push(message);
+        }
+    }
+}
+
+impl<'a> TypeAliasResolver<'a> {
+    pub(super) fn new(
+        declared: &'a [TypeAlias],
+        imported: impl Iterator<Item = &'a TypeAlias>,
+    ) -> Self {
+        let mut imported_aliases = HashMap::new();
+        for alias in imported {
+            imported_aliases.entry(alias.name).or_insert(alias);
+        }
+
+        Self {
+            declared: declared
+                .iter()
+                .map(|alias| (alias.name, alias.aliased_type.as_ref()))
+                .collect(),
+            imported: imported_aliases,
+            resolved: HashMap::new(),
+            resolving: Vec::new(),
+            invalid: HashSet::new(),
+        }
+    }
+
+    /// the module's own type aliases, with the types they alias resolved
+    pub(super) fn resolve_declarations(
+        &mut self,
+        declared: &[TypeAlias],
+        errors: &mut Vec<String>,
+    ) -> Vec<TypeAlias> {
+        declared
+            .iter()
+            .filter_map(|alias| match self.resolve_alias(alias.name) {
+                Ok(alias) => Some(alias),
+                Err(error) => {
+                    error.report(errors);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn resolve_alias(&mut self, name: Symbol) -> Result<TypeAlias, ResolveError> {
+        if let Some(alias) = self.resolved.get(&name) {
+            return Ok(alias.clone());
+        }
+
+        if self.invalid.contains(&name) {
+            return Err(ResolveError::Reported);
+        }
+
+        let Some(aliased_type) = self.declared.get(&name).cloned() else {
+            return match self.imported.get(&name) {
+                Some(alias) => Ok((*alias).clone()),
+                None => Err(ResolveError::Message(format!("Unknown type {}", name))),
+            };
+        };
+
+        if let Some(start) = self.resolving.iter().position(|alias| *alias == name) {
+            // every alias in the cycle is invalid, but the cycle is only reported once
+            self.invalid.extend(self.resolving[start..].iter().copied());
+            return Err(ResolveError::Message(format!(
+                "Type alias {} refers to itself",
+                name
+            )));
+        }
+
+        self.resolving.push(name);
+        let aliased_type = self.resolve_type(aliased_type);
+        self.resolving.pop();
+
+        let aliased_type = match aliased_type {
+            Ok(aliased_type) => aliased_type,
+            Err(error) => {
+                self.invalid.insert(name);
+                return Err(error);
+            }
+        };
+
+        let alias = TypeAlias {
+            name,
+            aliased_type: Box::new(aliased_type),
+        };
+        self.resolved.insert(name, alias.clone());
+
+        Ok(alias)
+    }
+
+    fn resolve_type(&mut self, type_: &Type) -> Result<Type, ResolveError> {
+        match type_ {
+            Type::Named(name) => Ok(Type::Alias(self.resolve_alias(*name)?)),
+            Type::Function(function_type) => Ok(Type::Function(FunctionType {
+                parameters: function_type
+                    .parameters
+                    .iter()
+                    .map(|parameter| self.resolve_type(parameter))
+                    .collect::<Result<_, _>>()?,
+                return_type: Box::new(self.resolve_return_type(&function_type.return_type)?),
+            })),
+            Type::Tuple(element_types) => Ok(Type::Tuple(
+                element_types
+                    .iter()
+                    .map(|element_type| self.resolve_type(element_type))
+                    .collect::<Result<_, _>>()?,
+            )),
+            Type::UInt | Type::Boolean | Type::Generic(_) | Type::Alias(_) | Type::Unknown => {
+                Ok(type_.clone())
+            }
+        }
+    }
+
+    fn resolve_return_type(
+        &mut self,
+        return_type: &FunctionReturnType,
+    ) -> Result<FunctionReturnType, ResolveError> {
+        match return_type {
+            FunctionReturnType::Type(return_type) => {
+                Ok(FunctionReturnType::Type(self.resolve_type(return_type)?))
+            }
+            FunctionReturnType::Void => Ok(FunctionReturnType::Void),
+        }
+    }
+
+    /// replace the type in place, keeping it as it is if it can't be resolved
+    fn resolve_in_place(&mut self, type_: &mut Type, errors: &mut Vec<String>) {
+        match self.resolve_type(type_) {
+            Ok(resolved) => *type_ = resolved,
+            Err(error) => error.report(errors),
+        }
+    }
+
+    fn resolve_return_type_in_place(
+        &mut self,
+        return_type: &mut FunctionReturnType,
+        errors: &mut Vec<String>,
+    ) {
+        if let FunctionReturnType::Type(return_type) = return_type {
+            self.resolve_in_place(return_type, errors);
+        }
+    }
+
+    fn resolve_parameters(
+        &mut self,
+        parameters: &mut [FunctionParameter],
+        errors: &mut Vec<String>,
+    ) {
+        for FunctionParameter::FunctionParameter { param_type, .. } in parameters {
+            self.resolve_in_place(param_type, errors);
+        }
+    }
+
+    /// replace each named type in the ast with the type alias it names
+    pub(super) fn resolve_ast(&mut self, ast: &mut Ast, errors: &mut Vec<String>) {
+        for function in ast.functions.values_mut() {
+            if let Function::CustomFunction {
+                parameters,
+                return_type,
+                body,
+                ..
+            } = function
+            {
+                self.resolve_parameters(parameters, errors);
+                self.resolve_return_type_in_place(return_type, errors);
+                self.resolve_nodes(body, errors);
+            }
+        }
+
+        for constant in &mut ast.constants {
+            self.resolve_in_place(&mut constant.const_type, errors);
+            self.resolve_expression(&mut constant.value, errors);
+        }
+
+        for global in &mut ast.globals {
+            self.resolve_in_place(&mut global.global_type, errors);
+            self.resolve_expression(&mut global.value, errors);
+        }
+
+        self.resolve_nodes(&mut ast.nodes, errors);
+    }
+
+    fn resolve_nodes(&mut self, nodes: &mut [Node], errors: &mut Vec<String>) {
+        for node in nodes {
+            match node {
+                Node::VariableDeclaration {
+                    var_type, value, ..
+                }
+                | Node::DestructuringDeclaration {
+                    var_type, value, ..
+                } => {
+                    if let VariableDeclarationType::Type(var_type) = var_type {
+                        self.resolve_in_place(var_type, errors);
+                    }
+                    self.resolve_expression(value, errors);
+                }
+                Node::FunctionReturn { return_value, .. } => {
+                    if let Some(return_value) = return_value {
+                        self.resolve_expression(return_value, errors);
+                    }
+                }
+                Node::FunctionCall(function_call) => {
+                    for parameter in &mut function_call.parameters {
+                        self.resolve_expression(parameter, errors);
+                    }
+                }
+                Node::IfStatement(if_statement) => {
+                    self.resolve_expression(&mut if_statement.check_expression, errors);
+                    self.resolve_nodes(&mut if_statement.if_block, errors);
+
+                    for else_if_block in &mut if_statement.else_if_blocks {
+                        self.resolve_expression(&mut else_if_block.check, errors);
+                        self.resolve_nodes(&mut else_if_block.block, errors);
+                    }
+
+                    if let Some(else_block) = &mut if_statement.else_block {
+                        self.resolve_nodes(else_block, errors);
+                    }
+                }
+                Node::Assignment { value, .. } => self.resolve_expression(value, errors),
+            }
+        }
+    }
+
+    fn resolve_expression(&mut self, expression: &mut Expression, errors: &mut Vec<String>) {
+        match expression {
+            Expression::ValueLiteral(_)
+            | Expression::VariableAccess(_)
+            | Expression::LocalVariableAccess { .. } => {}
+            Expression::FunctionCall(function_call) => {
+                for parameter in &mut function_call.parameters {
+                    self.resolve_expression(parameter, errors);
+                }
+            }
+            Expression::Operation(Operation::Unary { value, .. }) => {
+                self.resolve_expression(value, errors)
+            }
+            Expression::Operation(Operation::Binary { left, right, .. }) => {
+                self.resolve_expression(left, errors);
+                self.resolve_expression(right, errors);
+            }
+            Expression::Lambda(lambda) => {
+                self.resolve_parameters(&mut lambda.parameters, errors);
+                self.resolve_return_type_in_place(&mut lambda.return_type, errors);
+                self.resolve_nodes(&mut lambda.body, errors);
+            }
+            Expression::Tuple(elements) => {
+                for element in elements {
+                    self.resolve_expression(element, errors);
+                }
+            }
+            Expression::TupleAccess { tuple, .. } => self.resolve_expression(tuple, errors),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::node::{FunctionReturnType, FunctionType, Type, TypeAlias};
+
+    use super::TypeAliasResolver;
+
+    fn alias(name: &str, aliased_type: Type) -> TypeAlias {
+        TypeAlias {
+            name: name.into(),
+            aliased_type: Box::new(aliased_type),
+        }
+    }
+
+    #[test]
+    fn resolve_alias_of_alias() {
+        let declared = vec![
+            alias("Total", Type::Named("Count".into())),
+            alias("Count", Type::UInt),
+        ];
+        let mut resolver = TypeAliasResolver::new(&declared, [].into_iter());
+        let mut errors = Vec::new();
+
+        let resolved = resolver.resolve_declarations(&declared, &mut errors);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            resolved[0],
+            alias("Total", Type::Alias(alias("Count", Type::UInt)))
+        );
+        assert_eq!(resolved[0].aliased_type.to_string(), "Count (UInt)");
+    }
+
+    #[test]
+    fn resolve_imported_alias() {
+        let imported = alias("Count", Type::UInt);
+        let declared = vec![alias(
+            "Counter",
+            Type::Function(FunctionType {
+                parameters: vec![Type::Named("Count".into())],
+                return_type: Box::new(FunctionReturnType::Void),
+            }),
+        )];
+        let mut resolver = TypeAliasResolver::new(&declared, [&imported].into_iter());
+        let mut errors = Vec::new();
+
+        let resolved = resolver.resolve_declarations(&declared, &mut errors);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            resolved[0].aliased_type.as_ref(),
+            &Type::Function(FunctionType {
+                parameters: vec![Type::Alias(imported)],
+                return_type: Box::new(FunctionReturnType::Void),
+            })
+        );
+    }
+
+    #[test]
+    fn resolve_unknown_type() {
+        let declared = vec![alias("Count", Type::Named("Number".into()))];
+        let mut resolver = TypeAliasResolver::new(&declared, [].into_iter());
+        let mut errors = Vec::new();
+
+        resolver.resolve_declarations(&declared, &mut errors);
+
+        assert_eq!(errors, vec!["Unknown type Number".to_owned()]);
+    }
+
+    #[test]
+    fn resolve_cyclic_alias() {
+        let declared = vec![
+            alias("First", Type::Named("Second".into())),
+            alias("Second", Type::Named("First".into())),
+        ];
+        let mut resolver = TypeAliasResolver::new(&declared, [].into_iter());
+        let mut errors = Vec::new();
+
+        resolver.resolve_declarations(&declared, &mut errors);
+
+        assert_eq!(errors, vec!["Type alias First refers to itself".to_owned()]);
+    }
+
+    #[test]
+    fn resolve_uses_of_invalid_alias() {
+        let declared = vec![
+            alias("First", Type::Named("Second".into())),
+            alias("Second", Type::Named("First".into())),
+            alias("Third", Type::Named("First".into())),
+            alias("Count", Type::Named("Number".into())),
+            alias("Total", Type::Named("Count".into())),
+        ];
+        let mut resolver = TypeAliasResolver::new(&declared, [].into_iter());
+        let mut errors = Vec::new();
+
+        resolver.resolve_declarations(&declared, &mut errors);
+
+        let mut uses = vec![Type::Named("First".into()), Type::Named("Count".into())];
+        for type_ in &mut uses {
+            resolver.resolve_in_place(type_, &mut errors);
+        }
+
+        assert_eq!(
+            errors,
+            vec![
+                "Type alias First refers to itself".to_owned(),
+                "Unknown type Number".to_owned()
+            ]
+        );
+    }
+}
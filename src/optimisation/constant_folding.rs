@@ -0,0 +1,941 @@
+use std::collections::HashMap;
+
+use crate::ast::{
+    node::{
+        Ast, BinaryOperation, BoolValue, ElseIfBlock, Expression, Function, FunctionParameter,
+        IfStatement, Node, Operation, UIntValue, UnaryOperation, Value,
+    },
+    symbol::Symbol,
+};
+
+/// fold the operations on literal values in the whole program, and remove the if statement branches whose conditions
+/// fold to `false`
+pub(super) fn fold_ast(ast: &mut Ast) {
+    // constants hold their values by now, but function values are left as names so their bodies aren't copied
+    let constants = ast
+        .constants
+        .iter()
+        .filter_map(|constant| match &constant.value {
+            Expression::ValueLiteral(value) if !contains_function(value) => {
+                Some((constant.name, value.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut folder = Folder {
+        constants: &constants,
+        locals: Vec::new(),
+    };
+
+    for function in ast.functions.values_mut() {
+        if let Function::CustomFunction {
+            parameters, body, ..
+        } = function
+        {
+            folder.locals = parameters.iter().map(FunctionParameter::name).collect();
+            folder.fold_block(body);
+        }
+    }
+
+    folder.locals.clear();
+
+    for global in &mut ast.globals {
+        folder.fold_expression(&mut global.value);
+    }
+
+    folder.fold_block(&mut ast.nodes);
+}
+
+fn contains_function(value: &Value) -> bool {
+    match value {
+        Value::Function(_) => true,
+        Value::Tuple(elements) => elements.iter().any(contains_function),
+        Value::UInt(_) | Value::Boolean(_) => false,
+    }
+}
+
+struct Folder<'a> {
+    constants: &'a HashMap<Symbol, Value>,
+    /// the names of the local variables in scope, which shadow constants with the same name
+    locals: Vec<Symbol>,
+}
+
+impl Folder<'_> {
+    fn fold_block(&mut self, nodes: &mut Vec<Node>) {
+        let local_count = self.locals.len();
+        let mut folded = Vec::with_capacity(nodes.len());
+
+        for node in std::mem::take(nodes) {
+            match node {
+                Node::IfStatement(if_statement) => {
+                    folded.extend(self.fold_if_statement(if_statement));
+                }
+                mut node => {
+                    self.fold_node(&mut node);
+                    folded.push(node);
+                }
+            }
+        }
+
+        *nodes = folded;
+        self.locals.truncate(local_count);
+    }
+
+    fn fold_node(&mut self, node: &mut Node) {
+        match node {
+            Node::VariableDeclaration {
+                var_name, value, ..
+            } => {
+                self.fold_expression(value);
+                self.locals.push(*var_name);
+            }
+            Node::DestructuringDeclaration {
+                var_names, value, ..
+            } => {
+                self.fold_expression(value);
+                self.locals.extend(var_names.iter().copied());
+            }
+            Node::FunctionReturn { return_value, .. } => {
+                if let Some(return_value) = return_value {
+                    self.fold_expression(return_value);
+                }
+            }
+            Node::FunctionCall(function_call) => {
+                for parameter in &mut function_call.parameters {
+                    self.fold_expression(parameter);
+                }
+            }
+            Node::Assignment { value, .. } => self.fold_expression(value),
+            Node::IfStatement(_) => unreachable!("if statements are folded by their block"),
+        }
+    }
+
+    /// the statements that replace the if statement once the branches that can never run are removed
+    fn fold_if_statement(&mut self, if_statement: IfStatement) -> Vec<Node> {
+        let IfStatement {
+            check_expression,
+            if_block,
+            else_if_blocks,
+            mut else_block,
+            line,
+        } = if_statement;
+
+        let branches = std::iter::once(ElseIfBlock {
+            check: check_expression,
+            block: if_block,
+        })
+        .chain(else_if_blocks);
+
+        let mut kept = Vec::new();
+        let mut always_runs = false;
+        for ElseIfBlock {
+            mut check,
+            mut block,
+        } in branches
+        {
+            self.fold_expression(&mut check);
+
+            match check {
+                Expression::ValueLiteral(Value::Boolean(BoolValue(false))) => {}
+                // the branches after one that always runs are never reached, so it takes the place of the else block
+                Expression::ValueLiteral(Value::Boolean(BoolValue(true))) => {
+                    self.fold_block(&mut block);
+                    else_block = Some(block);
+                    always_runs = true;
+                    break;
+                }
+                check => {
+                    self.fold_block(&mut block);
+                    kept.push(ElseIfBlock { check, block });
+                }
+            }
+        }
+
+        if !always_runs {
+            if let Some(else_block) = &mut else_block {
+                self.fold_block(else_block);
+            }
+        }
+
+        let mut kept = kept.into_iter();
+        match (kept.next(), else_block) {
+            (Some(first), else_block) => vec![Node::IfStatement(IfStatement {
+                check_expression: first.check,
+                if_block: first.block,
+                else_if_blocks: kept.collect(),
+                else_block,
+                line,
+            })],
+            (None, None) => Vec::new(),
+            // the block's variables would outlive it if its statements replaced the if statement
+            (None, Some(block)) if declares_variables(&block) => {
+                vec![Node::IfStatement(IfStatement {
+                    check_expression: true.into(),
+                    if_block: block,
+                    else_if_blocks: Vec::new(),
+                    else_block: None,
+                    line,
+                })]
+            }
+            (None, Some(block)) => block,
+        }
+    }
+
+    fn fold_expression(&mut self, expression: &mut Expression) {
+        let unfolded = std::mem::replace(expression, Expression::Tuple(Vec::new()));
+        *expression = self.folded(unfolded);
+    }
+
+    fn folded(&mut self, expression: Expression) -> Expression {
+        match expression {
+            Expression::VariableAccess(name) => match self.constants.get(&name) {
+                Some(value) if !self.locals.contains(&name) => {
+                    Expression::ValueLiteral(value.clone())
+                }
+                _ => Expression::VariableAccess(name),
+            },
+            Expression::FunctionCall(mut function_call) => {
+                for parameter in &mut function_call.parameters {
+                    self.fold_expression(parameter);
+                }
+
+                Expression::FunctionCall(function_call)
+            }
+            Expression::Operation(Operation::Unary {
+                operation: UnaryOperation::Not,
+                value,
+            }) => match self.folded(*value) {
+                Expression::ValueLiteral(Value::Boolean(BoolValue(value))) => (!value).into(),
+                Expression::Operation(Operation::Unary {
+                    operation: UnaryOperation::Not,
+                    value,
+                }) => *value,
+                value => Expression::Operation(Operation::Unary {
+                    operation: UnaryOperation::Not,
+                    value: Box::new(value),
+                }),
+            },
+            Expression::Operation(Operation::Binary {
+                operation,
+                left,
+                right,
+            }) => fold_binary_operation(operation, self.folded(*left), self.folded(*right)),
+            Expression::Lambda(mut lambda) => {
+                // a lambda's body sees the variables in scope where it's created, as well as its parameters
+                let local_count = self.locals.len();
+                self.locals
+                    .extend(lambda.parameters.iter().map(FunctionParameter::name));
+                self.fold_block(&mut lambda.body);
+                self.locals.truncate(local_count);
+
+                Expression::Lambda(lambda)
+            }
+            Expression::Tuple(elements) => {
+                let elements: Vec<_> = elements
+                    .into_iter()
+                    .map(|element| self.folded(element))
+                    .collect();
+
+                if elements
+                    .iter()
+                    .all(|element| matches!(element, Expression::ValueLiteral(_)))
+                {
+                    Expression::ValueLiteral(Value::Tuple(
+                        elements
+                            .into_iter()
+                            .filter_map(|element| match element {
+                                Expression::ValueLiteral(value) => Some(value),
+                                _ => None,
+                            })
+                            .collect(),
+                    ))
+                } else {
+                    Expression::Tuple(elements)
+                }
+            }
+            Expression::TupleAccess { tuple, index } => match self.folded(*tuple) {
+                Expression::ValueLiteral(Value::Tuple(mut values)) if index < values.len() => {
+                    Expression::ValueLiteral(values.swap_remove(index))
+                }
+                tuple => Expression::TupleAccess {
+                    tuple: Box::new(tuple),
+                    index,
+                },
+            },
+            Expression::ValueLiteral(_) | Expression::LocalVariableAccess { .. } => expression,
+        }
+    }
+}
+
+/// the operation on its folded operands. A sum too large for a uint is left to fail when the program runs
+fn fold_binary_operation(
+    operation: BinaryOperation,
+    left: Expression,
+    right: Expression,
+) -> Expression {
+    match (operation, uint(&left), uint(&right)) {
+        (BinaryOperation::Plus, Some(left), Some(right)) if left.checked_add(right).is_some() => {
+            (left + right).into()
+        }
+        (BinaryOperation::GreaterThan, Some(left), Some(right)) => (left > right).into(),
+        // adding zero leaves the other operand's value, which is still evaluated for its side effects
+        (BinaryOperation::Plus, _, Some(0)) => left,
+        (BinaryOperation::Plus, Some(0), _) => right,
+        _ => Expression::Operation(Operation::Binary {
+            operation,
+            left: Box::new(left),
+            right: Box::new(right),
+        }),
+    }
+}
+
+fn uint(expression: &Expression) -> Option<u32> {
+    match expression {
+        Expression::ValueLiteral(Value::UInt(UIntValue(value))) => Some(*value),
+        _ => None,
+    }
+}
+
+fn declares_variables(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| {
+        matches!(
+            node,
+            Node::VariableDeclaration { .. } | Node::DestructuringDeclaration { .. }
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{
+        builders::{ast_builder::AstBuilder, statement_builder::StatementBuilder},
+        node::{Function, FunctionId, Node, Type, Value},
+    };
+
+    use super::fold_ast;
+
+    fn folded(ast: AstBuilder) -> Vec<Node> {
+        let mut ast = ast.build();
+
+        fold_ast(&mut ast);
+
+        ast.nodes
+    }
+
+    #[test]
+    fn fold_operations() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.plus(
+                                    |left| left.value_literal(1.into()),
+                                    |right| right.value_literal(2.into()),
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.not(|value| value.value_literal(true.into()))
+                            })
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.greater_than(
+                                    |left| left.value_literal(3.into()),
+                                    |right| right.value_literal(2.into()),
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.plus(
+                                    |left| left.value_literal(1.into()),
+                                    |right| {
+                                        right.operation(|operation| {
+                                            operation.plus(
+                                                |left| left.value_literal(2.into()),
+                                                |right| right.value_literal(3.into()),
+                                            )
+                                        })
+                                    },
+                                )
+                            })
+                        })
+                        .build()
+                })
+            });
+
+        let expected = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.value_literal(3.into()))
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.value_literal(false.into()))
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.value_literal(true.into()))
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.value_literal(6.into()))
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(folded(ast), expected.nodes);
+    }
+
+    #[test]
+    fn fold_leaves_overflow_to_runtime() {
+        let ast = || {
+            AstBuilder::default().statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.plus(
+                                    |left| left.value_literal(u32::MAX.into()),
+                                    |right| right.value_literal(1.into()),
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+        };
+
+        assert_eq!(folded(ast()), ast().build().nodes);
+    }
+
+    #[test]
+    fn fold_simplifies_expressions() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.plus(
+                                    |left| left.variable("x"),
+                                    |right| right.value_literal(0.into()),
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.plus(
+                                    |left| left.value_literal(0.into()),
+                                    |right| {
+                                        right.function_call(|call| {
+                                            call.function_id("next").no_parameters().build()
+                                        })
+                                    },
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.not(|value| {
+                                    value.operation(|operation| {
+                                        operation.not(|value| value.variable("flag"))
+                                    })
+                                })
+                            })
+                        })
+                        .build()
+                })
+            });
+
+        let expected = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.variable("x"))
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("next").no_parameters().build()
+                            })
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.variable("flag"))
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(folded(ast), expected.nodes);
+    }
+
+    #[test]
+    fn fold_tuples() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.tuple(vec![
+                                Box::new(|element| {
+                                    element.operation(|operation| {
+                                        operation.plus(
+                                            |left| left.value_literal(1.into()),
+                                            |right| right.value_literal(1.into()),
+                                        )
+                                    })
+                                }),
+                                Box::new(|element| element.value_literal(true.into())),
+                            ])
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.tuple_access(
+                                |tuple| {
+                                    tuple.tuple(vec![
+                                        Box::new(|element| element.value_literal(1.into())),
+                                        Box::new(|element| element.value_literal(false.into())),
+                                    ])
+                                },
+                                1,
+                            )
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.tuple(vec![
+                                Box::new(|element| element.variable("x")),
+                                Box::new(|element| element.value_literal(1.into())),
+                            ])
+                        })
+                        .build()
+                })
+            });
+
+        let expected = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.value_literal(Value::Tuple(vec![2.into(), true.into()]))
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.value_literal(false.into()))
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.tuple(vec![
+                                Box::new(|element| element.variable("x")),
+                                Box::new(|element| element.value_literal(1.into())),
+                            ])
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(folded(ast), expected.nodes);
+    }
+
+    #[test]
+    fn fold_constants_unless_shadowed() {
+        let mut ast = AstBuilder::default()
+            .constant(|constant| {
+                constant
+                    .name("LIMIT")
+                    .const_type(Type::UInt)
+                    .value(|value| value.value_literal(10.into()))
+            })
+            .function_declaration(|function| {
+                function
+                    .name("limit")
+                    .parameters(vec![(Type::UInt, "LIMIT".into()).into()])
+                    .return_type(Type::UInt)
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.return_value(|value| value.variable("LIMIT"))
+                        })
+                        .build()
+                    })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.plus(
+                                    |left| left.variable("LIMIT"),
+                                    |right| right.value_literal(1.into()),
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        fold_ast(&mut ast);
+
+        let expected = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.value_literal(11.into()))
+                        .build()
+                })
+            })
+            .build();
+        assert_eq!(ast.nodes, expected.nodes);
+        let Some(Function::CustomFunction { body, .. }) =
+            ast.functions.get(&FunctionId("limit".into()))
+        else {
+            panic!("the function should still be declared");
+        };
+        assert_eq!(
+            body,
+            &vec![StatementBuilder::default().return_value(|value| value.variable("LIMIT"))]
+        );
+    }
+
+    #[test]
+    fn fold_removes_branches_that_never_run() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| {
+                            check.operation(|operation| {
+                                operation.greater_than(
+                                    |left| left.value_literal(1.into()),
+                                    |right| right.value_literal(2.into()),
+                                )
+                            })
+                        })
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.value_literal(1.into()))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.value_literal(false.into()))
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.value_literal(2.into()))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .else_if(
+                            |check| check.variable("flag"),
+                            |body| {
+                                body.statement(|statement| {
+                                    statement.function_call(|call| {
+                                        call.function_id("print")
+                                            .parameter(|parameter| {
+                                                parameter.value_literal(3.into())
+                                            })
+                                            .build()
+                                    })
+                                })
+                                .build()
+                            },
+                        )
+                        .else_block(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.value_literal(4.into()))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .build()
+                })
+            });
+
+        let expected = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.variable("flag"))
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.value_literal(3.into()))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .else_block(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.value_literal(4.into()))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(folded(ast), expected.nodes);
+    }
+
+    #[test]
+    fn fold_branch_that_always_runs() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.variable("flag"))
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.value_literal(1.into()))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .else_if(
+                            |check| {
+                                check.operation(|operation| {
+                                    operation.not(|value| value.value_literal(false.into()))
+                                })
+                            },
+                            |body| {
+                                body.statement(|statement| {
+                                    statement.function_call(|call| {
+                                        call.function_id("print")
+                                            .parameter(|parameter| {
+                                                parameter.value_literal(2.into())
+                                            })
+                                            .build()
+                                    })
+                                })
+                                .build()
+                            },
+                        )
+                        .else_if(
+                            |check| check.variable("other"),
+                            |body| {
+                                body.statement(|statement| {
+                                    statement.function_call(|call| {
+                                        call.function_id("print")
+                                            .parameter(|parameter| {
+                                                parameter.value_literal(3.into())
+                                            })
+                                            .build()
+                                    })
+                                })
+                                .build()
+                            },
+                        )
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.value_literal(true.into()))
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.value_literal(4.into()))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .build()
+                })
+            });
+
+        let expected = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.variable("flag"))
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.value_literal(1.into()))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .else_block(|body| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id("print")
+                                        .parameter(|parameter| parameter.value_literal(2.into()))
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.value_literal(4.into()))
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(folded(ast), expected.nodes);
+    }
+
+    #[test]
+    fn fold_keeps_block_of_declarations() {
+        let block = |body: AstBuilder| {
+            body.statement(|statement| {
+                statement.var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("x")
+                        .with_assignment(|value| value.value_literal(1.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.variable("x"))
+                        .build()
+                })
+            })
+            .build()
+        };
+        let ast = AstBuilder::default().statement(|statement| {
+            statement.if_statement(|if_statement| {
+                if_statement
+                    .check_expression(|check| check.value_literal(true.into()))
+                    .body(block)
+                    .else_block(|body| {
+                        body.statement(|statement| {
+                            statement.function_call(|call| {
+                                call.function_id("print")
+                                    .parameter(|parameter| parameter.value_literal(2.into()))
+                                    .build()
+                            })
+                        })
+                        .build()
+                    })
+                    .build()
+            })
+        });
+
+        let expected = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.value_literal(true.into()))
+                        .body(block)
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(folded(ast), expected.nodes);
+    }
+}
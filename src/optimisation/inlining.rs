@@ -0,0 +1,1031 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    node::{
+        Ast, BinaryOperation, ElseIfBlock, Expression, Function, FunctionCall, FunctionId,
+        FunctionParameter, IfStatement, Node, Operation,
+    },
+    scope::{referenced_names, referenced_names_in_nodes},
+    symbol::Symbol,
+};
+
+/// replace calls to small functions whose body only returns an expression with that expression. A call is only
+/// replaced if the expression evaluates the call's parameters in the order the call would, with the same side effects.
+/// Functions are inlined into each other before their callers, so a chain of small functions is inlined at once
+pub(super) fn inline_functions(ast: &mut Ast, threshold: usize) {
+    let references: HashMap<FunctionId, HashSet<Symbol>> = ast
+        .functions
+        .values()
+        .filter_map(|function| match function {
+            Function::CustomFunction { id, body, .. } => {
+                let mut names = HashSet::new();
+                referenced_names_in_nodes(body, &mut names);
+                Some((*id, names))
+            }
+            Function::Intrinsic { .. } => None,
+        })
+        .collect();
+
+    // sorted, so the program is inlined the same way on every run
+    let mut ids: Vec<_> = references.keys().copied().collect();
+    ids.sort_by_key(|id| id.0.as_str());
+
+    let mut order = Vec::new();
+    let mut visited = HashSet::new();
+    for id in ids {
+        callees_first(id, &references, &mut visited, &mut order);
+    }
+
+    let variables = ast
+        .constants
+        .iter()
+        .map(|constant| constant.name)
+        .chain(ast.globals.iter().map(|global| global.name))
+        .collect();
+    let mutable_globals = ast
+        .globals
+        .iter()
+        .filter(|global| global.mutable)
+        .map(|global| global.name)
+        .collect();
+
+    let mut inliner = Inliner {
+        inlinable: HashMap::new(),
+        variables: &variables,
+        mutable_globals: &mutable_globals,
+        locals: Vec::new(),
+    };
+
+    for id in order {
+        let Some(Function::CustomFunction {
+            parameters, body, ..
+        }) = ast.functions.get_mut(&id)
+        else {
+            continue;
+        };
+
+        inliner.locals = parameters.iter().map(FunctionParameter::name).collect();
+        inliner.inline_block(body);
+
+        if !is_recursive(id, &references) {
+            if let Some(inlinable) = inliner.inlinable(parameters, body, threshold) {
+                inliner.inlinable.insert(id, inlinable);
+            }
+        }
+    }
+
+    inliner.locals.clear();
+
+    for global in &mut ast.globals {
+        inliner.inline_expression(&mut global.value);
+    }
+
+    inliner.inline_block(&mut ast.nodes);
+}
+
+/// add the function to `order` after the functions it refers to, unless it's already been visited
+fn callees_first(
+    id: FunctionId,
+    references: &HashMap<FunctionId, HashSet<Symbol>>,
+    visited: &mut HashSet<FunctionId>,
+    order: &mut Vec<FunctionId>,
+) {
+    let Some(names) = references.get(&id) else {
+        return;
+    };
+
+    if !visited.insert(id) {
+        return;
+    }
+
+    let mut callees: Vec<_> = names.iter().map(|name| FunctionId(*name)).collect();
+    callees.sort_by_key(|callee| callee.0.as_str());
+    for callee in callees {
+        callees_first(callee, references, visited, order);
+    }
+
+    order.push(id);
+}
+
+/// whether the function can call itself, either directly or through the functions it refers to
+fn is_recursive(id: FunctionId, references: &HashMap<FunctionId, HashSet<Symbol>>) -> bool {
+    let mut visited = HashSet::new();
+    let mut to_visit: Vec<_> = references.get(&id).into_iter().flatten().copied().collect();
+
+    while let Some(name) = to_visit.pop() {
+        if name == id.0 {
+            return true;
+        }
+
+        if visited.insert(name) {
+            to_visit.extend(references.get(&FunctionId(name)).into_iter().flatten());
+        }
+    }
+
+    false
+}
+
+/// the number of expressions the expression is made of, including itself
+fn size(expression: &Expression) -> usize {
+    1 + match expression {
+        Expression::ValueLiteral(_)
+        | Expression::VariableAccess(_)
+        | Expression::LocalVariableAccess { .. }
+        | Expression::Lambda(_) => 0,
+        Expression::FunctionCall(function_call) => function_call.parameters.iter().map(size).sum(),
+        Expression::Operation(Operation::Unary { value, .. }) => size(value),
+        Expression::Operation(Operation::Binary { left, right, .. }) => size(left) + size(right),
+        Expression::Tuple(elements) => elements.iter().map(size).sum(),
+        Expression::TupleAccess { tuple, .. } => size(tuple),
+    }
+}
+
+/// something that happens while an inlined expression is evaluated
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Event {
+    /// the parameter at this position is read
+    Parameter(usize),
+    /// something that could have a side effect, fail, or observe the side effects of the call's parameters
+    Effect,
+}
+
+/// a function whose calls can be replaced by the expression it returns
+struct Inlinable {
+    parameters: Vec<Symbol>,
+    expression: Expression,
+    /// the names the expression refers to besides its parameters, which mustn't be shadowed where it's inlined
+    free_names: HashSet<Symbol>,
+    /// what happens when the expression is evaluated, in order
+    events: Vec<Event>,
+}
+
+impl Inlinable {
+    /// whether the call's parameters are evaluated in the same order, with the same side effects, once the expression
+    /// replaces the call. A parameter that isn't `movable` has to be read exactly once, in order, before anything else
+    /// happens, as the call would have evaluated it before the function's body
+    fn preserves_order(&self, movable: &[bool]) -> bool {
+        let mut fixed = (0..movable.len()).filter(|position| !movable[*position]);
+        let mut next = fixed.next();
+
+        for event in &self.events {
+            match *event {
+                Event::Parameter(position) if movable[position] => {}
+                Event::Parameter(position) if Some(position) == next => next = fixed.next(),
+                Event::Parameter(_) => return false,
+                Event::Effect if next.is_some() => return false,
+                Event::Effect => {}
+            }
+        }
+
+        next.is_none()
+    }
+
+    /// the expression with each parameter replaced by the value the call passes for it
+    fn substitute(&self, expression: &Expression, arguments: &[Expression]) -> Expression {
+        match expression {
+            Expression::VariableAccess(name) => {
+                match self
+                    .parameters
+                    .iter()
+                    .position(|parameter| parameter == name)
+                {
+                    Some(position) => arguments[position].clone(),
+                    None => expression.clone(),
+                }
+            }
+            Expression::FunctionCall(function_call) => Expression::FunctionCall(FunctionCall {
+                function_id: function_call.function_id,
+                parameters: function_call
+                    .parameters
+                    .iter()
+                    .map(|parameter| self.substitute(parameter, arguments))
+                    .collect(),
+                line: function_call.line,
+                slot: function_call.slot,
+            }),
+            Expression::Operation(Operation::Unary { operation, value }) => {
+                Expression::Operation(Operation::Unary {
+                    operation: *operation,
+                    value: Box::new(self.substitute(value, arguments)),
+                })
+            }
+            Expression::Operation(Operation::Binary {
+                operation,
+                left,
+                right,
+            }) => Expression::Operation(Operation::Binary {
+                operation: *operation,
+                left: Box::new(self.substitute(left, arguments)),
+                right: Box::new(self.substitute(right, arguments)),
+            }),
+            Expression::Tuple(elements) => Expression::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.substitute(element, arguments))
+                    .collect(),
+            ),
+            Expression::TupleAccess { tuple, index } => Expression::TupleAccess {
+                tuple: Box::new(self.substitute(tuple, arguments)),
+                index: *index,
+            },
+            Expression::ValueLiteral(_)
+            | Expression::LocalVariableAccess { .. }
+            | Expression::Lambda(_) => expression.clone(),
+        }
+    }
+}
+
+struct Inliner<'a> {
+    inlinable: HashMap<FunctionId, Inlinable>,
+    /// the constants and globals, which a call resolves to before a declared function with the same name
+    variables: &'a HashSet<Symbol>,
+    /// the globals whose value can change while an expression is evaluated
+    mutable_globals: &'a HashSet<Symbol>,
+    /// the names of the local variables in scope
+    locals: Vec<Symbol>,
+}
+
+impl Inliner<'_> {
+    /// the function's body as an inlinable expression, if the body is a single return small enough to be inlined.
+    /// A body that creates a lambda, or calls one of its parameters, is never inlined
+    fn inlinable(
+        &self,
+        parameters: &[FunctionParameter],
+        body: &[Node],
+        threshold: usize,
+    ) -> Option<Inlinable> {
+        let [Node::FunctionReturn {
+            return_value: Some(expression),
+            ..
+        }] = body
+        else {
+            return None;
+        };
+
+        if size(expression) > threshold {
+            return None;
+        }
+
+        let parameters: Vec<_> = parameters.iter().map(FunctionParameter::name).collect();
+
+        let mut names = HashSet::new();
+        referenced_names(expression, &mut names);
+        let mut events = Vec::new();
+        if !self.collect_events(expression, &parameters, &mut events) {
+            return None;
+        }
+
+        Some(Inlinable {
+            free_names: names
+                .into_iter()
+                .filter(|name| !parameters.contains(name))
+                .collect(),
+            parameters,
+            expression: expression.clone(),
+            events,
+        })
+    }
+
+    /// add what happens when the expression is evaluated to `events`, in order. Returns false if the expression can't
+    /// be inlined
+    fn collect_events(
+        &self,
+        expression: &Expression,
+        parameters: &[Symbol],
+        events: &mut Vec<Event>,
+    ) -> bool {
+        match expression {
+            Expression::ValueLiteral(_) => true,
+            Expression::VariableAccess(name) | Expression::LocalVariableAccess { name, .. } => {
+                if let Some(position) = parameters.iter().position(|parameter| parameter == name) {
+                    events.push(Event::Parameter(position));
+                } else if self.mutable_globals.contains(name) {
+                    events.push(Event::Effect);
+                }
+
+                true
+            }
+            Expression::FunctionCall(function_call) => {
+                if parameters.contains(&function_call.function_id.0) {
+                    return false;
+                }
+
+                let parameters_inlinable = function_call
+                    .parameters
+                    .iter()
+                    .all(|parameter| self.collect_events(parameter, parameters, events));
+                events.push(Event::Effect);
+
+                parameters_inlinable
+            }
+            Expression::Operation(Operation::Unary { value, .. }) => {
+                self.collect_events(value, parameters, events)
+            }
+            Expression::Operation(Operation::Binary {
+                operation,
+                left,
+                right,
+            }) => {
+                let inlinable = self.collect_events(left, parameters, events)
+                    && self.collect_events(right, parameters, events);
+
+                // a sum can be too large for a uint
+                if *operation == BinaryOperation::Plus {
+                    events.push(Event::Effect);
+                }
+
+                inlinable
+            }
+            Expression::Lambda(_) => false,
+            Expression::Tuple(elements) => elements
+                .iter()
+                .all(|element| self.collect_events(element, parameters, events)),
+            Expression::TupleAccess { tuple, .. } => self.collect_events(tuple, parameters, events),
+        }
+    }
+
+    /// whether evaluating the expression can't have a side effect or fail, and always results in the same value, so
+    /// it can be moved, repeated or dropped
+    fn is_movable(&self, expression: &Expression) -> bool {
+        match expression {
+            Expression::ValueLiteral(_) | Expression::LocalVariableAccess { .. } => true,
+            Expression::VariableAccess(name) => {
+                self.locals.contains(name) || !self.mutable_globals.contains(name)
+            }
+            Expression::Operation(Operation::Unary { value, .. }) => self.is_movable(value),
+            Expression::Operation(Operation::Binary {
+                operation: BinaryOperation::GreaterThan,
+                left,
+                right,
+            }) => self.is_movable(left) && self.is_movable(right),
+            Expression::Tuple(elements) => elements.iter().all(|element| self.is_movable(element)),
+            Expression::TupleAccess { tuple, .. } => self.is_movable(tuple),
+            Expression::Operation(Operation::Binary {
+                operation: BinaryOperation::Plus,
+                ..
+            })
+            | Expression::FunctionCall(_)
+            | Expression::Lambda(_) => false,
+        }
+    }
+
+    fn inline_block(&mut self, nodes: &mut [Node]) {
+        let local_count = self.locals.len();
+
+        for node in nodes {
+            match node {
+                Node::VariableDeclaration {
+                    var_name, value, ..
+                } => {
+                    self.inline_expression(value);
+                    self.locals.push(*var_name);
+                }
+                Node::DestructuringDeclaration {
+                    var_names, value, ..
+                } => {
+                    self.inline_expression(value);
+                    self.locals.extend(var_names.iter().copied());
+                }
+                Node::FunctionReturn { return_value, .. } => {
+                    if let Some(return_value) = return_value {
+                        self.inline_expression(return_value);
+                    }
+                }
+                // a call made as a statement can only be replaced by another call
+                Node::FunctionCall(function_call) => {
+                    for parameter in &mut function_call.parameters {
+                        self.inline_expression(parameter);
+                    }
+
+                    if let Some(Expression::FunctionCall(inlined)) = self.inlined(function_call) {
+                        *function_call = FunctionCall {
+                            line: function_call.line,
+                            ..inlined
+                        };
+                    }
+                }
+                Node::IfStatement(if_statement) => self.inline_if_statement(if_statement),
+                Node::Assignment { value, .. } => self.inline_expression(value),
+            }
+        }
+
+        self.locals.truncate(local_count);
+    }
+
+    fn inline_if_statement(&mut self, if_statement: &mut IfStatement) {
+        self.inline_expression(&mut if_statement.check_expression);
+        self.inline_block(&mut if_statement.if_block);
+
+        for ElseIfBlock { check, block } in &mut if_statement.else_if_blocks {
+            self.inline_expression(check);
+            self.inline_block(block);
+        }
+
+        if let Some(else_block) = &mut if_statement.else_block {
+            self.inline_block(else_block);
+        }
+    }
+
+    fn inline_expression(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::FunctionCall(function_call) => {
+                for parameter in &mut function_call.parameters {
+                    self.inline_expression(parameter);
+                }
+
+                if let Some(inlined) = self.inlined(function_call) {
+                    *expression = inlined;
+                }
+            }
+            Expression::Operation(Operation::Unary { value, .. }) => self.inline_expression(value),
+            Expression::Operation(Operation::Binary { left, right, .. }) => {
+                self.inline_expression(left);
+                self.inline_expression(right);
+            }
+            Expression::Lambda(lambda) => {
+                // a lambda's body sees the variables in scope where it's created, as well as its parameters
+                let local_count = self.locals.len();
+                self.locals
+                    .extend(lambda.parameters.iter().map(FunctionParameter::name));
+                self.inline_block(&mut lambda.body);
+                self.locals.truncate(local_count);
+            }
+            Expression::Tuple(elements) => {
+                for element in elements {
+                    self.inline_expression(element);
+                }
+            }
+            Expression::TupleAccess { tuple, .. } => self.inline_expression(tuple),
+            Expression::ValueLiteral(_)
+            | Expression::VariableAccess(_)
+            | Expression::LocalVariableAccess { .. } => {}
+        }
+    }
+
+    /// the expression that replaces the call, if the function it calls can be inlined here
+    fn inlined(&self, function_call: &FunctionCall) -> Option<Expression> {
+        let name = function_call.function_id.0;
+        // a local variable, constant or global with the function's name is called instead of the function
+        if self.locals.contains(&name) || self.variables.contains(&name) {
+            return None;
+        }
+
+        let inlinable = self.inlinable.get(&function_call.function_id)?;
+        if inlinable
+            .free_names
+            .iter()
+            .any(|name| self.locals.contains(name))
+        {
+            return None;
+        }
+
+        let movable: Vec<_> = function_call
+            .parameters
+            .iter()
+            .map(|parameter| self.is_movable(parameter))
+            .collect();
+        if !inlinable.preserves_order(&movable) {
+            return None;
+        }
+
+        Some(inlinable.substitute(&inlinable.expression, &function_call.parameters))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{
+        builders::{ast_builder::AstBuilder, expression_builder::ExpressionBuilder},
+        node::{Expression, Node, Type},
+    };
+
+    use super::inline_functions;
+
+    /// declare a function taking uint parameters named `parameters`, that returns `value`
+    fn returning(
+        ast: AstBuilder,
+        name: &'static str,
+        parameters: &[&'static str],
+        value: impl FnOnce(ExpressionBuilder) -> Expression,
+    ) -> AstBuilder {
+        let parameters = parameters
+            .iter()
+            .map(|parameter| (Type::UInt, (*parameter).into()).into())
+            .collect();
+
+        ast.function_declaration(|function| {
+            function
+                .name(name)
+                .parameters(parameters)
+                .return_type(Type::UInt)
+                .body(|body| {
+                    body.statement(|statement| statement.return_value(value))
+                        .build()
+                })
+        })
+    }
+
+    fn inlined(ast: AstBuilder) -> Vec<Node> {
+        let mut ast = ast.build();
+
+        inline_functions(&mut ast, 8);
+
+        ast.nodes
+    }
+
+    #[test]
+    fn inline_one_line_function() {
+        let ast = returning(AstBuilder::default(), "add", &["left", "right"], |value| {
+            value.operation(|operation| {
+                operation.plus(
+                    |left| left.variable("left"),
+                    |right| right.variable("right"),
+                )
+            })
+        })
+        .statement(|statement| {
+            statement.function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| {
+                        parameter.function_call(|call| {
+                            call.function_id("add")
+                                .parameter(|parameter| parameter.value_literal(1.into()))
+                                .parameter(|parameter| {
+                                    parameter.function_call(|call| {
+                                        call.function_id("next").no_parameters().build()
+                                    })
+                                })
+                                .build()
+                        })
+                    })
+                    .build()
+            })
+        });
+
+        let expected = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.plus(
+                                    |left| left.value_literal(1.into()),
+                                    |right| {
+                                        right.function_call(|call| {
+                                            call.function_id("next").no_parameters().build()
+                                        })
+                                    },
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(inlined(ast), expected.nodes);
+    }
+
+    #[test]
+    fn inline_chain_of_functions() {
+        let ast = returning(AstBuilder::default(), "double", &["value"], |value| {
+            value.operation(|operation| {
+                operation.plus(
+                    |left| left.variable("value"),
+                    |right| right.variable("value"),
+                )
+            })
+        });
+        let ast = returning(ast, "quadruple", &["value"], |value| {
+            value.function_call(|call| {
+                call.function_id("double")
+                    .parameter(|parameter| {
+                        parameter.function_call(|call| {
+                            call.function_id("double")
+                                .parameter(|parameter| parameter.variable("value"))
+                                .build()
+                        })
+                    })
+                    .build()
+            })
+        })
+        .statement(|statement| {
+            statement.function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| {
+                        parameter.function_call(|call| {
+                            call.function_id("quadruple")
+                                .parameter(|parameter| parameter.value_literal(2.into()))
+                                .build()
+                        })
+                    })
+                    .build()
+            })
+        });
+
+        // the doubled value can't be moved, as it's a sum that could fail, so only the inner call is inlined
+        let expected = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("double")
+                                    .parameter(|parameter| {
+                                        parameter.operation(|operation| {
+                                            operation.plus(
+                                                |left| left.value_literal(2.into()),
+                                                |right| right.value_literal(2.into()),
+                                            )
+                                        })
+                                    })
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(inlined(ast), expected.nodes);
+    }
+
+    #[test]
+    fn inline_keeps_parameters_evaluated_once() {
+        let call_with_effect = |ast: AstBuilder| {
+            ast.statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("double")
+                                    .parameter(|parameter| {
+                                        parameter.function_call(|call| {
+                                            call.function_id("next").no_parameters().build()
+                                        })
+                                    })
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+        };
+        let ast = returning(AstBuilder::default(), "double", &["value"], |value| {
+            value.operation(|operation| {
+                operation.plus(
+                    |left| left.variable("value"),
+                    |right| right.variable("value"),
+                )
+            })
+        });
+
+        assert_eq!(
+            inlined(call_with_effect(ast)),
+            call_with_effect(AstBuilder::default()).build().nodes
+        );
+    }
+
+    #[test]
+    fn inline_keeps_parameter_order() {
+        let effects_in_order = |ast: AstBuilder| {
+            ast.statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("swapped")
+                                    .parameter(|parameter| {
+                                        parameter.function_call(|call| {
+                                            call.function_id("first").no_parameters().build()
+                                        })
+                                    })
+                                    .parameter(|parameter| {
+                                        parameter.function_call(|call| {
+                                            call.function_id("second").no_parameters().build()
+                                        })
+                                    })
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+        };
+        let ast = returning(
+            AstBuilder::default(),
+            "swapped",
+            &["first", "second"],
+            |value| {
+                value.operation(|operation| {
+                    operation.greater_than(
+                        |left| left.variable("second"),
+                        |right| right.variable("first"),
+                    )
+                })
+            },
+        );
+        let ast = effects_in_order(ast).statement(|statement| {
+            statement.function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| {
+                        parameter.function_call(|call| {
+                            call.function_id("swapped")
+                                .parameter(|parameter| parameter.value_literal(1.into()))
+                                .parameter(|parameter| parameter.value_literal(2.into()))
+                                .build()
+                        })
+                    })
+                    .build()
+            })
+        });
+
+        let expected = effects_in_order(AstBuilder::default())
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.greater_than(
+                                    |left| left.value_literal(2.into()),
+                                    |right| right.value_literal(1.into()),
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(inlined(ast), expected.nodes);
+    }
+
+    #[test]
+    fn inline_keeps_effects_after_parameters() {
+        let call_with_effect = |ast: AstBuilder| {
+            ast.statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("logged")
+                                    .parameter(|parameter| {
+                                        parameter.function_call(|call| {
+                                            call.function_id("next").no_parameters().build()
+                                        })
+                                    })
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+        };
+        // the function prints before it reads its parameter, so the parameter's side effect has to come first
+        let ast = returning(AstBuilder::default(), "logged", &["value"], |value| {
+            value.operation(|operation| {
+                operation.plus(
+                    |left| {
+                        left.function_call(|call| call.function_id("log").no_parameters().build())
+                    },
+                    |right| right.variable("value"),
+                )
+            })
+        });
+        let ast = call_with_effect(ast).statement(|statement| {
+            statement.function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| {
+                        parameter.function_call(|call| {
+                            call.function_id("logged")
+                                .parameter(|parameter| parameter.value_literal(1.into()))
+                                .build()
+                        })
+                    })
+                    .build()
+            })
+        });
+
+        let expected = call_with_effect(AstBuilder::default())
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.plus(
+                                    |left| {
+                                        left.function_call(|call| {
+                                            call.function_id("log").no_parameters().build()
+                                        })
+                                    },
+                                    |right| right.value_literal(1.into()),
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(inlined(ast), expected.nodes);
+    }
+
+    #[test]
+    fn inline_skips_recursive_and_large_functions() {
+        let calls = |ast: AstBuilder| {
+            ast.statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("count")
+                                    .parameter(|parameter| parameter.value_literal(1.into()))
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("large")
+                                    .parameter(|parameter| parameter.value_literal(1.into()))
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+        };
+        let ast = returning(AstBuilder::default(), "count", &["value"], |value| {
+            value.function_call(|call| {
+                call.function_id("count")
+                    .parameter(|parameter| {
+                        parameter.operation(|operation| {
+                            operation.plus(
+                                |left| left.variable("value"),
+                                |right| right.value_literal(1.into()),
+                            )
+                        })
+                    })
+                    .build()
+            })
+        });
+        // value + (1 + (2 + (3 + (4 + 5))))
+        let ast = returning(ast, "large", &["value"], |value| {
+            let sum = (1..=4).rev().fold(
+                ExpressionBuilder::default().value_literal(5.into()),
+                |sum, constant| {
+                    ExpressionBuilder::default().operation(|operation| {
+                        operation.plus(|left| left.value_literal(constant.into()), |_| sum)
+                    })
+                },
+            );
+
+            value.operation(|operation| operation.plus(|left| left.variable("value"), |_| sum))
+        });
+
+        assert_eq!(
+            inlined(calls(ast)),
+            calls(AstBuilder::default()).build().nodes
+        );
+    }
+
+    #[test]
+    fn inline_skips_shadowed_names() {
+        let statements = |ast: AstBuilder| {
+            ast.statement(|statement| {
+                statement.var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("offset")
+                        .with_assignment(|value| value.value_literal(1.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("total")
+                                    .parameter(|parameter| parameter.value_literal(1.into()))
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+        };
+        let ast = returning(AstBuilder::default(), "total", &["value"], |value| {
+            value.operation(|operation| {
+                operation.plus(
+                    |left| left.variable("value"),
+                    |right| right.variable("offset"),
+                )
+            })
+        });
+
+        assert_eq!(
+            inlined(statements(ast)),
+            statements(AstBuilder::default()).build().nodes
+        );
+    }
+
+    #[test]
+    fn inline_reads_mutable_global_after_parameters() {
+        let call_with_effect = |ast: AstBuilder| {
+            ast.statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("with_count")
+                                    .parameter(|parameter| {
+                                        parameter.function_call(|call| {
+                                            call.function_id("bump").no_parameters().build()
+                                        })
+                                    })
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+        };
+        let ast = returning(AstBuilder::default(), "with_count", &["value"], |value| {
+            value.operation(|operation| {
+                operation.greater_than(
+                    |left| left.variable("count"),
+                    |right| right.variable("value"),
+                )
+            })
+        })
+        .global(|global| {
+            global
+                .name("count")
+                .global_type(Type::UInt)
+                .mutable()
+                .value(|value| value.value_literal(0.into()))
+        });
+        let ast = call_with_effect(ast).statement(|statement| {
+            statement.function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| {
+                        parameter.function_call(|call| {
+                            call.function_id("with_count")
+                                .parameter(|parameter| parameter.value_literal(1.into()))
+                                .build()
+                        })
+                    })
+                    .build()
+            })
+        });
+
+        let expected = call_with_effect(AstBuilder::default())
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.operation(|operation| {
+                                operation.greater_than(
+                                    |left| left.variable("count"),
+                                    |right| right.value_literal(1.into()),
+                                )
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        assert_eq!(inlined(ast), expected.nodes);
+    }
+
+    #[test]
+    fn inline_threshold_zero_turns_inlining_off() {
+        let call = |ast: AstBuilder| {
+            ast.statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|call| {
+                                call.function_id("one").no_parameters().build()
+                            })
+                        })
+                        .build()
+                })
+            })
+        };
+        let mut ast = call(returning(AstBuilder::default(), "one", &[], |value| {
+            value.value_literal(1.into())
+        }))
+        .build();
+
+        inline_functions(&mut ast, 0);
+
+        assert_eq!(ast.nodes, call(AstBuilder::default()).build().nodes);
+    }
+}
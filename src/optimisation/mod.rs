@@ -0,0 +1,40 @@
+//! Passes that rewrite a type checked `Ast` into a simpler program that behaves the same way, run with `-O`.
+//!
+//! - a pass never changes what a program prints, or the runtime error it fails with. An operation that would fail at
+//!   runtime is left for the runtime to report, although an error in an inlined function is reported at the line of
+//!   its call
+//! - passes run before variables are resolved to slots, so they only see variables by name
+
+mod constant_folding;
+mod inlining;
+
+use crate::ast::node::Ast;
+
+/// the default size of the largest function body inlined at its call sites
+const DEFAULT_INLINE_THRESHOLD: usize = 8;
+
+/// how a program is optimised
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptimisationOptions {
+    /// the largest function body, counted in expressions, that is inlined at its call sites. 0 turns inlining off
+    pub inline_threshold: usize,
+}
+
+impl Default for OptimisationOptions {
+    fn default() -> Self {
+        Self {
+            inline_threshold: DEFAULT_INLINE_THRESHOLD,
+        }
+    }
+}
+
+impl Ast {
+    /// simplify the program, ie fold `1 + 2` into `3`, remove the branches of if statements that can never run, and
+    /// replace calls to small functions with their bodies. The program is folded again once functions are inlined, as
+    /// their parameters are often literals
+    pub fn optimise(&mut self, options: &OptimisationOptions) {
+        constant_folding::fold_ast(self);
+        inlining::inline_functions(self, options.inline_threshold);
+        constant_folding::fold_ast(self);
+    }
+}
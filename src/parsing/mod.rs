@@ -1,7 +1,7 @@
 use std::ops::Range;
 
 use crate::{
-    ast::node::Type,
+    ast::{node::Type, symbol::Symbol},
     token_stream::token::{Token, TokenSource},
 };
 
@@ -23,13 +23,14 @@ struct Buffer {
 fn push_current_buffer(
     tokens: &mut Vec<TokenSource>,
     buffer: &mut Buffer,
+    file: &str,
 ) -> Result<(), Vec<ParseError>> {
     let len = buffer.value.len() as u32;
     let character_start = buffer.character_start.unwrap_or(0);
     if len > 0 {
         match Token::from_str(
             &buffer.value,
-            "my_file",
+            file,
             buffer.line,
             character_start..(character_start + len - 1),
         ) {
@@ -40,14 +41,12 @@ fn push_current_buffer(
                     0
                 };
 
-                tokens.push(
-                    TokenSource::new(
-                        token,
-                        "my_file",
-                        buffer.line,
-                        character_start..(character_start + len - 1),
-                    ),
-                )
+                tokens.push(TokenSource::new(
+                    token,
+                    file,
+                    buffer.line,
+                    character_start..(character_start + len - 1),
+                ))
             }
             Ok(None) => {}
             Err(error) => return Err(vec![error]),
@@ -76,50 +75,75 @@ impl Buffer {
     }
 }
 
-pub fn parse_program(code: &str) -> Result<Vec<TokenSource>, Vec<ParseError>> {
+/// parse the `code` of `file` into tokens
+pub fn parse_program(code: &str, file: &str) -> Result<Vec<TokenSource>, Vec<ParseError>> {
     let mut tokens = Vec::new();
     let mut buffer = Buffer::default();
 
     for (line_index, line) in code.lines().enumerate() {
         let line_index = (line_index + 1) as u32;
+        let mut in_string = false;
         for (column, char) in line.char_indices() {
             let column = (column + 1) as u32;
             match char {
+                '"' if in_string => {
+                    buffer.update('"', column, line_index);
+                    push_current_buffer(&mut tokens, &mut buffer, file)?;
+                    in_string = false;
+                }
+                _ if in_string => {
+                    buffer.update(char, column, line_index);
+                }
+                '"' => {
+                    push_current_buffer(&mut tokens, &mut buffer, file)?;
+                    buffer.update('"', column, line_index);
+                    in_string = true;
+                }
                 '=' => {
-                    push_current_buffer(&mut tokens, &mut buffer)?;
+                    push_current_buffer(&mut tokens, &mut buffer, file)?;
                     buffer.update('=', column, line_index);
-                    push_current_buffer(&mut tokens, &mut buffer)?;
+                    push_current_buffer(&mut tokens, &mut buffer, file)?;
                 }
                 '>' => {
                     if buffer.value == "-" {
                         buffer.update('>', column, line_index);
-                        push_current_buffer(&mut tokens, &mut buffer)?;
+                        push_current_buffer(&mut tokens, &mut buffer, file)?;
                     } else {
-                        push_current_buffer(&mut tokens, &mut buffer)?;
+                        push_current_buffer(&mut tokens, &mut buffer, file)?;
                         buffer.update('>', column, line_index);
                     }
                 }
                 _ if char.is_whitespace() => {
-                    push_current_buffer(&mut tokens, &mut buffer)?;
+                    push_current_buffer(&mut tokens, &mut buffer, file)?;
                 }
                 _ if char.is_ascii_punctuation() && char != '_' => {
-                    push_current_buffer(&mut tokens, &mut buffer)?;
+                    push_current_buffer(&mut tokens, &mut buffer, file)?;
                     buffer.update(char, column, line_index);
                 }
                 _ => {
                     if buffer.value.len() == 1 {
                         let char = buffer.value.chars().next().unwrap();
                         if char.is_ascii_punctuation() && char != '_' {
-                            push_current_buffer(&mut tokens, &mut buffer)?;
+                            push_current_buffer(&mut tokens, &mut buffer, file)?;
                         }
                     }
                     buffer.update(char, column, line_index);
                 }
             }
         }
+
+        if in_string {
+            let character_start = buffer.character_start.unwrap_or(1);
+            return Err(vec![ParseError {
+                error: "Unterminated string".to_owned(),
+                file: file.to_owned(),
+                line: line_index,
+                character_range: character_start..(line.len() as u32),
+            }]);
+        }
     }
 
-    push_current_buffer(&mut tokens, &mut buffer)?;
+    push_current_buffer(&mut tokens, &mut buffer, file)?;
 
     Ok(tokens)
 }
@@ -139,21 +163,36 @@ impl Token {
             "true" => Ok(Some(Token::TrueKeyword)),
             "false" => Ok(Some(Token::FalseKeyword)),
             "function" => Ok(Some(Token::FunctionKeyword)),
+            "fn" => Ok(Some(Token::FnKeyword)),
             "infer" => Ok(Some(Token::InferKeyword)),
             "if" => Ok(Some(Token::IfKeyword)),
             "else" => Ok(Some(Token::ElseKeyword)),
             "return" => Ok(Some(Token::ReturnKeyword)),
+            "import" => Ok(Some(Token::ImportKeyword)),
+            "const" => Ok(Some(Token::ConstKeyword)),
+            "global" => Ok(Some(Token::GlobalKeyword)),
+            "mut" => Ok(Some(Token::MutKeyword)),
+            "type" => Ok(Some(Token::TypeAliasKeyword)),
+            "pure" => Ok(Some(Token::PureKeyword)),
             "=" => Ok(Some(Token::AssignmentOperator)),
             "(" => Ok(Some(Token::LeftParenthesis)),
             ")" => Ok(Some(Token::RightParenthesis)),
             "{" => Ok(Some(Token::LeftCurleyBrace)),
             "}" => Ok(Some(Token::RightCurleyBrace)),
             ">" => Ok(Some(Token::RightAngle)),
+            "<" => Ok(Some(Token::LeftAngle)),
             "!" => Ok(Some(Token::NotOperator)),
             "+" => Ok(Some(Token::PlusOperator)),
             ";" => Ok(Some(Token::SemiColon)),
             "," => Ok(Some(Token::Comma)),
+            ":" => Ok(Some(Token::Colon)),
+            "." => Ok(Some(Token::Dot)),
             "->" => Ok(Some(Token::FunctionSignitureSplitter)),
+            _ if trimmed.len() > 1 && trimmed.starts_with('"') && trimmed.ends_with('"') => {
+                Ok(Some(Token::StringLiteral(
+                    trimmed[1..trimmed.len() - 1].to_owned(),
+                )))
+            }
             _ if s.len() == 1 && s.chars().next().unwrap().is_ascii_punctuation() && s != "_" => {
                 Err(ParseError {
                     error: format!("Unexpected character `{s}`"),
@@ -166,7 +205,7 @@ impl Token {
                 if let Ok(u32_value) = s.parse::<u32>() {
                     Ok(Some(Token::UIntValue(u32_value)))
                 } else {
-                    Ok(Some(Token::Identifier(trimmed.to_owned())))
+                    Ok(Some(Token::Identifier(Symbol::intern(trimmed))))
                 }
             }
         }
@@ -238,8 +277,9 @@ mod tests {
 
     #[test]
     fn parse_keywords() {
-        let code = "uint boolean true false function infer if else return";
-        let result = parse_program(code);
+        let code =
+            "uint boolean true false function infer if else return fn import const global mut type pure";
+        let result = parse_program(code, FILENAME);
 
         let mut prev_character = None;
 
@@ -270,14 +310,21 @@ mod tests {
                 get_range(Token::IfKeyword, &mut prev_character, "if", true),
                 get_range(Token::ElseKeyword, &mut prev_character, "else", true),
                 get_range(Token::ReturnKeyword, &mut prev_character, "return", true),
+                get_range(Token::FnKeyword, &mut prev_character, "fn", true),
+                get_range(Token::ImportKeyword, &mut prev_character, "import", true),
+                get_range(Token::ConstKeyword, &mut prev_character, "const", true),
+                get_range(Token::GlobalKeyword, &mut prev_character, "global", true),
+                get_range(Token::MutKeyword, &mut prev_character, "mut", true),
+                get_range(Token::TypeAliasKeyword, &mut prev_character, "type", true),
+                get_range(Token::PureKeyword, &mut prev_character, "pure", true),
             ])
         );
     }
 
     #[test]
     fn parse_special_tokens() {
-        let code = "(){}+>!=;,";
-        let result = parse_program(code);
+        let code = "(){}+><!=;,:.";
+        let result = parse_program(code, FILENAME);
 
         let mut prev_character = None;
 
@@ -290,10 +337,13 @@ mod tests {
                 get_range(Token::RightCurleyBrace, &mut prev_character, "}", false),
                 get_range(Token::PlusOperator, &mut prev_character, "+", false),
                 get_range(Token::RightAngle, &mut prev_character, ">", false),
+                get_range(Token::LeftAngle, &mut prev_character, "<", false),
                 get_range(Token::NotOperator, &mut prev_character, "!", false),
                 get_range(Token::AssignmentOperator, &mut prev_character, "=", false),
                 get_range(Token::SemiColon, &mut prev_character, ";", false),
                 get_range(Token::Comma, &mut prev_character, ",", false),
+                get_range(Token::Colon, &mut prev_character, ":", false),
+                get_range(Token::Dot, &mut prev_character, ".", false),
             ])
         );
     }
@@ -301,7 +351,7 @@ mod tests {
     #[test]
     fn parse_identifier() {
         let code = "uint myIdentifier0";
-        let result = parse_program(code);
+        let result = parse_program(code, FILENAME);
 
         let mut prev_character = None;
 
@@ -315,7 +365,7 @@ mod tests {
                     false
                 ),
                 get_range(
-                    Token::Identifier("myIdentifier0".to_owned()),
+                    Token::Identifier("myIdentifier0".into()),
                     &mut prev_character,
                     "myIdentifier0",
                     true
@@ -327,7 +377,7 @@ mod tests {
     #[test]
     fn parse_function_splitter() {
         let code = "hello ->";
-        let result = parse_program(code);
+        let result = parse_program(code, FILENAME);
 
         let mut prev_character = None;
 
@@ -335,7 +385,7 @@ mod tests {
             result,
             Ok(vec![
                 get_range(
-                    Token::Identifier("hello".to_owned()),
+                    Token::Identifier("hello".into()),
                     &mut prev_character,
                     "hello",
                     false
@@ -361,68 +411,236 @@ return my_var;
         let mut prev_character = None;
         let mut prev_line = None;
 
-        let result = parse_program(code);
+        let result = parse_program(code, FILENAME);
 
         assert_eq!(
             result,
             Ok(vec![
-                            get_range_with_line(
-                                Token::FunctionKeyword,
-                                &mut prev_character,
-                                &mut prev_line,
-                                "function",
-                                0,
-                                false
-                            ),
-                                get_range_with_line(
-            Token::Identifier("my_function".to_owned()),
-                                    &mut prev_character,
-                                    &mut prev_line,
-                                    "my_function",
-                                    0,
-                                    true
-                                ),
-                            get_range_with_line(
-                                Token::LeftParenthesis,
-                                &mut prev_character,
-                                &mut prev_line,
-                                "(",
-                                0,
-                                false
-                            ),
-                                get_range_with_line(Token::TypeKeyword(Type::UInt), &mut prev_character, &mut prev_line, "uint", 0, false),
-                                get_range_with_line(Token::Identifier("param_1".to_owned()), &mut prev_character, &mut prev_line, "param_1", 0, true),
-                                get_range_with_line(Token::Comma, &mut prev_character, &mut prev_line, ",", 0, false),
-                                get_range_with_line(Token::TypeKeyword(Type::Boolean), &mut prev_character, &mut prev_line, "boolean", 0, true),
-                                get_range_with_line(Token::Identifier("param_2".to_owned()), &mut prev_character, &mut prev_line, "param_2", 0, true),
-                                get_range_with_line(Token::RightParenthesis, &mut prev_character, &mut prev_line, ")", 0, false),
-                                get_range_with_line(Token::FunctionSignitureSplitter, &mut prev_character, &mut prev_line, "->", 0, true),
-                                get_range_with_line(Token::TypeKeyword(Type::UInt), &mut prev_character, &mut prev_line, "uint", 0, true),
-                                get_range_with_line(Token::LeftCurleyBrace, &mut prev_character, &mut prev_line, "{", 1, false),
-                                get_range_with_line(Token::InferKeyword, &mut prev_character, &mut prev_line, "infer", 1, false),
-                                get_range_with_line(Token::Identifier("my_var".to_owned()), &mut prev_character, &mut prev_line, "my_var", 0, true),
-                                get_range_with_line(Token::AssignmentOperator, &mut prev_character, &mut prev_line, "=", 0, true),
-                                get_range_with_line(
-            Token::Identifier("other_function".to_owned()),
-                                    &mut prev_character,
-                                    &mut prev_line,
-                                    "other_function",
-                                    0,
-                                    true
-                            ),
-                                get_range_with_line(Token::LeftParenthesis, &mut prev_character, &mut prev_line, "(", 0, false),
-                                get_range_with_line(Token::UIntValue(15), &mut prev_character, &mut prev_line, "15", 0, false),
-                                get_range_with_line(Token::Comma, &mut prev_character, &mut prev_line, ",", 0, false),
-                                get_range_with_line(Token::TrueKeyword, &mut prev_character, &mut prev_line, "true", 0, true),
-                                get_range_with_line(Token::Comma, &mut prev_character, &mut prev_line, ",", 0, false),
-                                get_range_with_line(Token::FalseKeyword, &mut prev_character, &mut prev_line, "false", 0, true),
-                                get_range_with_line(Token::RightParenthesis, &mut prev_character, &mut prev_line, ")", 0, false),
-                                get_range_with_line(Token::SemiColon, &mut prev_character, &mut prev_line, ";", 0, false),
-                                get_range_with_line(Token::ReturnKeyword, &mut prev_character, &mut prev_line, "return", 1, false),
-                                get_range_with_line(Token::Identifier("my_var".to_owned()), &mut prev_character, &mut prev_line, "my_var", 0, true),
-                                get_range_with_line(Token::SemiColon, &mut prev_character, &mut prev_line, ";", 0, false),
-                                get_range_with_line(Token::RightCurleyBrace, &mut prev_character, &mut prev_line, "}", 1, false),
-                        ])
+                get_range_with_line(
+                    Token::FunctionKeyword,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "function",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::Identifier("my_function".into()),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "my_function",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::LeftParenthesis,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "(",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::TypeKeyword(Type::UInt),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "uint",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::Identifier("param_1".into()),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "param_1",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::Comma,
+                    &mut prev_character,
+                    &mut prev_line,
+                    ",",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::TypeKeyword(Type::Boolean),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "boolean",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::Identifier("param_2".into()),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "param_2",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::RightParenthesis,
+                    &mut prev_character,
+                    &mut prev_line,
+                    ")",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::FunctionSignitureSplitter,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "->",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::TypeKeyword(Type::UInt),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "uint",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::LeftCurleyBrace,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "{",
+                    1,
+                    false
+                ),
+                get_range_with_line(
+                    Token::InferKeyword,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "infer",
+                    1,
+                    false
+                ),
+                get_range_with_line(
+                    Token::Identifier("my_var".into()),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "my_var",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::AssignmentOperator,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "=",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::Identifier("other_function".into()),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "other_function",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::LeftParenthesis,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "(",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::UIntValue(15),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "15",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::Comma,
+                    &mut prev_character,
+                    &mut prev_line,
+                    ",",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::TrueKeyword,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "true",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::Comma,
+                    &mut prev_character,
+                    &mut prev_line,
+                    ",",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::FalseKeyword,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "false",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::RightParenthesis,
+                    &mut prev_character,
+                    &mut prev_line,
+                    ")",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::SemiColon,
+                    &mut prev_character,
+                    &mut prev_line,
+                    ";",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::ReturnKeyword,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "return",
+                    1,
+                    false
+                ),
+                get_range_with_line(
+                    Token::Identifier("my_var".into()),
+                    &mut prev_character,
+                    &mut prev_line,
+                    "my_var",
+                    0,
+                    true
+                ),
+                get_range_with_line(
+                    Token::SemiColon,
+                    &mut prev_character,
+                    &mut prev_line,
+                    ";",
+                    0,
+                    false
+                ),
+                get_range_with_line(
+                    Token::RightCurleyBrace,
+                    &mut prev_character,
+                    &mut prev_line,
+                    "}",
+                    1,
+                    false
+                ),
+            ])
         );
     }
 
@@ -430,9 +648,43 @@ return my_var;
     fn parse_invalid_symbol() {
         let code = "   #";
 
-        let result = parse_program(code);
+        let result = parse_program(code, FILENAME);
         assert!(
             matches!(result, Err(err) if err == vec![ParseError{file: FILENAME.to_owned(), error: "Unexpected character `#`".to_owned(), line: 1, character_range: 4..4}])
         );
     }
+
+    #[test]
+    fn parse_string_literal() {
+        let code = "import \"lib/my file.bch\";";
+
+        let result = parse_program(code, FILENAME);
+
+        let mut prev_character = None;
+
+        assert_eq!(
+            result,
+            Ok(vec![
+                get_range(Token::ImportKeyword, &mut prev_character, "import", false),
+                get_range(
+                    Token::StringLiteral("lib/my file.bch".to_owned()),
+                    &mut prev_character,
+                    "\"lib/my file.bch\"",
+                    true
+                ),
+                get_range(Token::SemiColon, &mut prev_character, ";", false),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_unterminated_string() {
+        let code = "import \"lib.bch;";
+
+        let result = parse_program(code, FILENAME);
+
+        assert!(
+            matches!(result, Err(err) if err == vec![ParseError{file: FILENAME.to_owned(), error: "Unterminated string".to_owned(), line: 1, character_range: 8..16}])
+        );
+    }
 }
@@ -0,0 +1,148 @@
+use crate::ast::{
+    builders::{constant_builder::ConstantBuilder, expression_builder::ExpressionBuilder},
+    node::{Constant, Expression, Type},
+    symbol::Symbol,
+};
+
+use super::{
+    expression::create_expression,
+    token::{ensure_token, Token, TokenStreamError, Tokens},
+    types::take_type,
+};
+
+/// the name, type and value of a top level declaration
+pub(super) type Declaration = (
+    Symbol,
+    Type,
+    Box<dyn FnOnce(ExpressionBuilder) -> Expression>,
+);
+
+/// take a constant declaration, ie `const LIMIT: uint = 10000;`. The `const` keyword should already have been taken
+pub(super) fn take_constant(
+    tokens: &mut Tokens,
+) -> Result<Box<dyn FnOnce(ConstantBuilder) -> Constant>, Vec<TokenStreamError>> {
+    // the const keyword has just been taken
+    let line = tokens.line();
+    let (name, const_type, value) = take_declaration(tokens, "constant")?;
+
+    Ok(Box::new(move |constant_builder| {
+        constant_builder
+            .name(name)
+            .const_type(const_type)
+            .line(line)
+            .value(value)
+    }))
+}
+
+/// take the `NAME: type = value;` part of a top level declaration. `kind` is the kind of declaration, for error messages
+pub(super) fn take_declaration(
+    tokens: &mut Tokens,
+    kind: &str,
+) -> Result<Declaration, Vec<TokenStreamError>> {
+    let name = match tokens.pop_front() {
+        Some(Token::Identifier(name)) => name,
+        Some(token) => {
+            return Err(vec![TokenStreamError {
+                message: format!("expected {} name. found {}", kind, token),
+            }])
+        }
+        None => {
+            return Err(vec![TokenStreamError {
+                message: format!("expected {} name", kind),
+            }])
+        }
+    };
+
+    ensure_token(tokens, Token::Colon)?;
+
+    let Some(type_token) = tokens.pop_front() else {
+        return Err(vec![TokenStreamError {
+            message: format!("expected {} type", kind),
+        }]);
+    };
+    let declared_type = take_type(type_token, tokens, &[])?;
+
+    ensure_token(tokens, Token::AssignmentOperator)?;
+
+    let value = create_expression(tokens)?;
+
+    ensure_token(tokens, Token::SemiColon)?;
+
+    Ok((name, declared_type, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{
+            builders::ast_builder::AstBuilder,
+            node::{BinaryOperation, Expression, Operation, Type},
+        },
+        token_stream::token::Token,
+    };
+
+    /// const LIMIT: uint = OTHER + 1;
+    #[test]
+    fn constant_declaration() {
+        let tokens = vec![
+            Token::ConstKeyword,
+            Token::Identifier("LIMIT".into()),
+            Token::Colon,
+            Token::TypeKeyword(Type::UInt),
+            Token::AssignmentOperator,
+            Token::Identifier("OTHER".into()),
+            Token::PlusOperator,
+            Token::UIntValue(1),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().constant(|constant| {
+            constant.name("LIMIT").const_type(Type::UInt).value(|_| {
+                Expression::Operation(Operation::Binary {
+                    operation: BinaryOperation::Plus,
+                    left: Box::new(Expression::VariableAccess("OTHER".into())),
+                    right: Box::new(1.into()),
+                })
+            })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// const LIMIT = 1;
+    #[test]
+    fn constant_declaration_missing_type() {
+        let tokens = vec![
+            Token::ConstKeyword,
+            Token::Identifier("LIMIT".into()),
+            Token::AssignmentOperator,
+            Token::UIntValue(1),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(
+            matches!(result, Err(e) if e[0].message == "Expected Colon, found AssignmentOperator")
+        );
+    }
+
+    /// const LIMIT: uint = 1
+    #[test]
+    fn constant_declaration_missing_semicolon() {
+        let tokens = vec![
+            Token::ConstKeyword,
+            Token::Identifier("LIMIT".into()),
+            Token::Colon,
+            Token::TypeKeyword(Type::UInt),
+            Token::AssignmentOperator,
+            Token::UIntValue(1),
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(matches!(result, Err(e) if e.len() == 1 && e[0].message == "Expected SemiColon"));
+    }
+}
@@ -1,17 +1,17 @@
-use std::collections::VecDeque;
-
 use crate::ast::{
     builders::expression_builder::ExpressionBuilder,
     node::{BinaryOperation, Expression},
+    symbol::Symbol,
 };
 
 use super::{
     function_call::take_function_call,
-    token::{Token, TokenStreamError},
+    function_declaration::{check_parameter_names, take_function_signature},
+    token::{get_block_statements, Token, TokenStreamError, Tokens},
 };
 
 pub(super) fn create_expression(
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
 ) -> Result<Box<dyn FnOnce(ExpressionBuilder) -> Expression>, Vec<TokenStreamError>> {
     let mut expression = None;
     loop {
@@ -29,6 +29,7 @@ pub(super) fn create_expression(
             | Some(Token::UIntValue(_))
             | Some(Token::Identifier(_))
             | Some(Token::NotOperator)
+            | Some(Token::FunctionKeyword)
                 if expression.is_some() =>
             {
                 return Err(vec![TokenStreamError {
@@ -56,6 +57,19 @@ pub(super) fn create_expression(
             Some(Token::Identifier(identifier)) => {
                 expression = Some(take_identifier_expression(identifier, tokens)?)
             }
+            Some(Token::FunctionKeyword) => expression = Some(take_lambda_expression(tokens)?),
+            Some(Token::LeftParenthesis) if expression.is_none() => {
+                expression = Some(take_tuple_expression(tokens)?)
+            }
+            Some(Token::Dot) => {
+                if let Some(some_expression) = expression {
+                    expression = Some(take_tuple_access_expression(some_expression, tokens)?);
+                } else {
+                    return Err(vec![TokenStreamError {
+                        message: "Expected expression".to_owned(),
+                    }]);
+                }
+            }
             Some(Token::NotOperator) => {
                 let value_expr = create_expression(tokens)?;
                 expression = Some(Box::new(move |builder: ExpressionBuilder| {
@@ -103,7 +117,7 @@ pub(super) fn create_expression(
 
 fn take_value_expression(
     value_expression: Box<dyn FnOnce(ExpressionBuilder) -> Expression>,
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
 ) -> Result<Box<dyn FnOnce(ExpressionBuilder) -> Expression>, Vec<TokenStreamError>> {
     match tokens.pop_front() {
         None => Ok(value_expression),
@@ -123,7 +137,7 @@ fn take_value_expression(
 fn take_binary_operation_expression(
     operation: BinaryOperation,
     left_expression: Box<dyn FnOnce(ExpressionBuilder) -> Expression>,
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
 ) -> Result<Box<dyn FnOnce(ExpressionBuilder) -> Expression>, Vec<TokenStreamError>> {
     let right_expression = create_expression(tokens)?;
 
@@ -139,12 +153,12 @@ fn take_binary_operation_expression(
 
 /// take an expression from the `tokens` that begins with an identifier. Either a `Token::Variable` or `Token::FunctionCall`
 fn take_identifier_expression(
-    identifier: String,
-    tokens: &mut VecDeque<Token>,
+    identifier: Symbol,
+    tokens: &mut Tokens,
 ) -> Result<Box<dyn FnOnce(ExpressionBuilder) -> Expression>, Vec<TokenStreamError>> {
     match tokens.pop_front() {
         None => Ok(Box::new(move |expression_builder| {
-            expression_builder.variable(&identifier)
+            expression_builder.variable(identifier)
         })),
         Some(Token::LeftParenthesis) => {
             tokens.push_front(Token::LeftParenthesis);
@@ -153,15 +167,97 @@ fn take_identifier_expression(
         Some(token) => {
             tokens.push_front(token);
             Ok(Box::new(move |expression_builder| {
-                expression_builder.variable(&identifier)
+                expression_builder.variable(identifier)
             }))
         }
     }
 }
 
+/// take an anonymous function expression, ie `function(uint value) -> uint { return value; }`.
+/// The `function` keyword has already been taken
+fn take_lambda_expression(
+    tokens: &mut Tokens,
+) -> Result<Box<dyn FnOnce(ExpressionBuilder) -> Expression>, Vec<TokenStreamError>> {
+    let start_line = tokens.line();
+    let (parameters, return_type) = take_function_signature(tokens, &[])?;
+
+    let statements = get_block_statements(tokens)?;
+    let end_line = tokens.line();
+
+    check_parameter_names(&parameters, &format!("the lambda at line {}", start_line))?;
+
+    Ok(Box::new(move |expression_builder: ExpressionBuilder| {
+        expression_builder.lambda(|mut lambda_builder| {
+            lambda_builder = lambda_builder
+                .parameters(parameters)
+                .span(start_line, end_line);
+
+            if let Some(return_type) = return_type {
+                lambda_builder = lambda_builder.return_type(return_type);
+            } else {
+                lambda_builder = lambda_builder.void();
+            }
+
+            lambda_builder.body(|mut body| {
+                for statement in statements {
+                    body = body.statement(statement);
+                }
+
+                body.build()
+            })
+        })
+    }))
+}
+
+/// take a tuple expression, ie `(1, true)`. The `(` has already been taken
+fn take_tuple_expression(
+    tokens: &mut Tokens,
+) -> Result<Box<dyn FnOnce(ExpressionBuilder) -> Expression>, Vec<TokenStreamError>> {
+    let mut elements = Vec::new();
+
+    loop {
+        elements.push(create_expression(tokens)?);
+
+        match tokens.pop_front() {
+            Some(Token::Comma) => {}
+            Some(Token::RightParenthesis) if elements.len() > 1 => break,
+            Some(Token::RightParenthesis) => {
+                return Err(vec![TokenStreamError {
+                    message: "a tuple needs at least two elements".to_owned(),
+                }])
+            }
+            _ => {
+                return Err(vec![TokenStreamError {
+                    message: "expected , or )".to_owned(),
+                }])
+            }
+        }
+    }
+
+    Ok(Box::new(move |expression_builder: ExpressionBuilder| {
+        expression_builder.tuple(elements)
+    }))
+}
+
+/// take the index of a tuple element, ie the `0` in `pair.0`. The `.` has already been taken
+fn take_tuple_access_expression(
+    tuple_expression: Box<dyn FnOnce(ExpressionBuilder) -> Expression>,
+    tokens: &mut Tokens,
+) -> Result<Box<dyn FnOnce(ExpressionBuilder) -> Expression>, Vec<TokenStreamError>> {
+    let Some(Token::UIntValue(index)) = tokens.pop_front() else {
+        return Err(vec![TokenStreamError {
+            message: "expected tuple element index after .".to_owned(),
+        }]);
+    };
+
+    Ok(Box::new(move |expression_builder: ExpressionBuilder| {
+        expression_builder.tuple_access(tuple_expression, index as usize)
+    }))
+}
+
 fn take_function_call_expression(
-    tokens: &mut VecDeque<Token>,
-    identifier: String,
+    tokens: &mut Tokens,
+    identifier: Symbol,
 ) -> Result<Box<dyn FnOnce(ExpressionBuilder) -> Expression>, Vec<TokenStreamError>> {
     let function_call = take_function_call(identifier, tokens)?;
 
@@ -182,9 +278,9 @@ mod tests {
     fn type_decl_variable_declaration_assign_function_call() {
         let tokens = vec![
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::SemiColon,
@@ -216,9 +312,9 @@ mod tests {
     fn type_declare_variable_declaration_assign_function_call_with_single_paremeter() {
         let tokens = vec![
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TrueKeyword,
             Token::RightParenthesis,
@@ -252,13 +348,13 @@ mod tests {
     ) {
         let tokens = vec![
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TrueKeyword,
             Token::Comma,
-            Token::Identifier("second_function".to_owned()),
+            Token::Identifier("second_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::RightParenthesis,
@@ -300,13 +396,13 @@ mod tests {
     ) {
         let tokens = vec![
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TrueKeyword,
             Token::Comma,
-            Token::Identifier("second_function".to_owned()),
+            Token::Identifier("second_function".into()),
             Token::LeftParenthesis,
             Token::TrueKeyword,
             Token::RightParenthesis,
@@ -348,9 +444,9 @@ mod tests {
     fn variable_declaration_assign_function_call_with_three_parameters() {
         let tokens = vec![
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TrueKeyword,
             Token::Comma,
@@ -389,9 +485,9 @@ mod tests {
     fn function_call_requires_comma_between_params() {
         let tokens = vec![
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TrueKeyword,
             Token::FalseKeyword,
@@ -410,9 +506,9 @@ mod tests {
     fn function_call_fails_when_no_expression() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::Comma,
             Token::RightParenthesis,
@@ -429,9 +525,9 @@ mod tests {
     fn function_call_plus_operation() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::UIntValue(10),
             Token::PlusOperator,
@@ -470,9 +566,9 @@ mod tests {
     fn function_call_not_operation() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::NotOperator,
             Token::TrueKeyword,
@@ -508,9 +604,9 @@ mod tests {
     fn function_call_greater_than_operation() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::UIntValue(10),
             Token::RightAngle,
@@ -549,7 +645,7 @@ mod tests {
     fn multiple_plus_operations() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
             Token::UIntValue(10),
             Token::PlusOperator,
@@ -590,13 +686,13 @@ mod tests {
     fn greater_than_function_calls() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("function_1".to_owned()),
+            Token::Identifier("function_1".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::RightAngle,
-            Token::Identifier("function_2".to_owned()),
+            Token::Identifier("function_2".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::SemiColon,
@@ -614,10 +710,20 @@ mod tests {
                             operation.greater_than(
                                 |left| {
                                     left.function_call(|function_call| {
-                                        function_call.function_id("function_1").no_parameters().build()
+                                        function_call
+                                            .function_id("function_1")
+                                            .no_parameters()
+                                            .build()
+                                    })
+                                },
+                                |right| {
+                                    right.function_call(|function_call| {
+                                        function_call
+                                            .function_id("function_2")
+                                            .no_parameters()
+                                            .build()
                                     })
                                 },
-                                |right| right.function_call(|function_call| function_call.function_id("function_2").no_parameters().build()),
                             )
                         })
                     })
@@ -629,7 +735,12 @@ mod tests {
 
     #[test]
     fn expression_no_tokens() {
-        let tokens = vec![Token::InferKeyword, Token::Identifier("my_var".to_owned()), Token::AssignmentOperator, Token::SemiColon];
+        let tokens = vec![
+            Token::InferKeyword,
+            Token::Identifier("my_var".into()),
+            Token::AssignmentOperator,
+            Token::SemiColon,
+        ];
 
         let result = AstBuilder::from_token_stream(tokens);
 
@@ -638,7 +749,13 @@ mod tests {
 
     #[test]
     fn expression_plus_without_left() {
-        let tokens = vec![Token::InferKeyword, Token::Identifier("my_var".to_owned()), Token::AssignmentOperator, Token::PlusOperator, Token::SemiColon];
+        let tokens = vec![
+            Token::InferKeyword,
+            Token::Identifier("my_var".into()),
+            Token::AssignmentOperator,
+            Token::PlusOperator,
+            Token::SemiColon,
+        ];
 
         let result = AstBuilder::from_token_stream(tokens);
 
@@ -647,7 +764,13 @@ mod tests {
 
     #[test]
     fn expression_greater_than_without_left() {
-        let tokens = vec![Token::InferKeyword, Token::Identifier("my_var".to_owned()), Token::AssignmentOperator, Token::RightAngle, Token::SemiColon];
+        let tokens = vec![
+            Token::InferKeyword,
+            Token::Identifier("my_var".into()),
+            Token::AssignmentOperator,
+            Token::RightAngle,
+            Token::SemiColon,
+        ];
 
         let result = AstBuilder::from_token_stream(tokens);
 
@@ -656,10 +779,197 @@ mod tests {
 
     #[test]
     fn expression_unexpected_token() {
-        let tokens = vec![Token::InferKeyword, Token::Identifier("my_var".to_owned()), Token::AssignmentOperator, Token::LeftCurleyBrace, Token::SemiColon];
+        let tokens = vec![
+            Token::InferKeyword,
+            Token::Identifier("my_var".into()),
+            Token::AssignmentOperator,
+            Token::LeftCurleyBrace,
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "unexpected token LeftCurleyBrace")
+        );
+    }
+
+    /// infer add_offset = function(uint value) -> uint { return value + offset; };
+    #[test]
+    fn variable_declaration_assign_lambda() {
+        let tokens = vec![
+            Token::InferKeyword,
+            Token::Identifier("add_offset".into()),
+            Token::AssignmentOperator,
+            Token::FunctionKeyword,
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::Identifier("value".into()),
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::TypeKeyword(Type::UInt),
+            Token::LeftCurleyBrace,
+            Token::ReturnKeyword,
+            Token::Identifier("value".into()),
+            Token::PlusOperator,
+            Token::Identifier("offset".into()),
+            Token::SemiColon,
+            Token::RightCurleyBrace,
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().statement(|statement| {
+            statement.var_declaration(|var_declaration| {
+                var_declaration
+                    .infer_type()
+                    .name("add_offset")
+                    .with_assignment(|value| {
+                        value.lambda(|lambda| {
+                            lambda
+                                .parameters(vec![(Type::UInt, "value".into()).into()])
+                                .return_type(Type::UInt)
+                                .body(|body| {
+                                    body.statement(|statement| {
+                                        statement.return_value(|return_value| {
+                                            return_value.operation(|operation| {
+                                                operation.plus(
+                                                    |left| left.variable("value"),
+                                                    |right| right.variable("offset"),
+                                                )
+                                            })
+                                        })
+                                    })
+                                    .build()
+                                })
+                        })
+                    })
+            })
+        });
+
+        assert!(matches!(dbg!(result), Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// apply(function() { print(1); });
+    #[test]
+    fn function_call_with_lambda_parameter() {
+        let tokens = vec![
+            Token::Identifier("apply".into()),
+            Token::LeftParenthesis,
+            Token::FunctionKeyword,
+            Token::LeftParenthesis,
+            Token::RightParenthesis,
+            Token::LeftCurleyBrace,
+            Token::Identifier("print".into()),
+            Token::LeftParenthesis,
+            Token::UIntValue(1),
+            Token::RightParenthesis,
+            Token::SemiColon,
+            Token::RightCurleyBrace,
+            Token::RightParenthesis,
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().statement(|statement| {
+            statement.function_call(|function_call| {
+                function_call
+                    .function_id("apply")
+                    .parameter(|param| {
+                        param.lambda(|lambda| {
+                            lambda.parameters(Vec::new()).void().body(|body| {
+                                body.statement(|statement| {
+                                    statement.function_call(|function_call| {
+                                        function_call
+                                            .function_id("print")
+                                            .parameter(|_| 1.into())
+                                            .build()
+                                    })
+                                })
+                                .build()
+                            })
+                        })
+                    })
+                    .build()
+            })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// infer second = pair(1, 2).1 + 3;
+    #[test]
+    fn tuple_access_plus_operation() {
+        let tokens = vec![
+            Token::InferKeyword,
+            Token::Identifier("second".into()),
+            Token::AssignmentOperator,
+            Token::Identifier("pair".into()),
+            Token::LeftParenthesis,
+            Token::UIntValue(1),
+            Token::Comma,
+            Token::UIntValue(2),
+            Token::RightParenthesis,
+            Token::Dot,
+            Token::UIntValue(1),
+            Token::PlusOperator,
+            Token::UIntValue(3),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().statement(|statement| {
+            statement.var_declaration(|var_declaration| {
+                var_declaration
+                    .infer_type()
+                    .name("second")
+                    .with_assignment(|value| {
+                        value.operation(|operation| {
+                            operation.plus(
+                                |left| {
+                                    left.tuple_access(
+                                        |tuple| {
+                                            tuple.function_call(|function_call| {
+                                                function_call
+                                                    .function_id("pair")
+                                                    .parameter(|_| 1.into())
+                                                    .parameter(|_| 2.into())
+                                                    .build()
+                                            })
+                                        },
+                                        1,
+                                    )
+                                },
+                                |_| 3.into(),
+                            )
+                        })
+                    })
+            })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// infer single = (1);
+    #[test]
+    fn tuple_single_element() {
+        let tokens = vec![
+            Token::InferKeyword,
+            Token::Identifier("single".into()),
+            Token::AssignmentOperator,
+            Token::LeftParenthesis,
+            Token::UIntValue(1),
+            Token::RightParenthesis,
+            Token::SemiColon,
+        ];
 
         let result = AstBuilder::from_token_stream(tokens);
 
-        assert!(matches!(result, Err(e) if e.len() == 1 && e[0].message == "unexpected token LeftCurleyBrace"));
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "a tuple needs at least two elements")
+        );
     }
 }
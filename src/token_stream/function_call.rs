@@ -1,16 +1,21 @@
 use std::collections::VecDeque;
 
-use crate::ast::{builders::function_call_builder::FunctionCallBuilder, node::FunctionCall};
+use crate::ast::{
+    builders::function_call_builder::FunctionCallBuilder, node::FunctionCall, symbol::Symbol,
+};
 
 use super::{
     expression::create_expression,
-    token::{ensure_token, Token, TokenStreamError},
+    token::{ensure_token, Token, TokenStreamError, Tokens},
 };
 
 pub(super) fn take_function_call(
-    identifier: String,
-    tokens: &mut VecDeque<Token>,
+    identifier: Symbol,
+    tokens: &mut Tokens,
 ) -> Result<Box<dyn FnOnce(FunctionCallBuilder) -> FunctionCall>, Vec<TokenStreamError>> {
+    // the function's name has just been taken
+    let line = tokens.line();
+
     ensure_token(tokens, Token::LeftParenthesis)?;
 
     let mut params = VecDeque::new();
@@ -26,7 +31,7 @@ pub(super) fn take_function_call(
             }
             Some(Token::RightParenthesis) => {
                 return Ok(Box::new(move |mut function_call| {
-                    function_call = function_call.function_id(&identifier);
+                    function_call = function_call.function_id(identifier).line(line);
                     if params.is_empty() {
                         function_call = function_call.no_parameters();
                     } else {
@@ -69,7 +74,7 @@ mod tests {
 
     #[test]
     fn function_call_missing_left_parenthesis() {
-        let tokens = vec![Token::Identifier("my_function".to_owned())];
+        let tokens = vec![Token::Identifier("my_function".into())];
 
         let result = AstBuilder::from_token_stream(tokens);
 
@@ -81,7 +86,7 @@ mod tests {
     #[test]
     fn function_call_missing_right_parenthesis() {
         let tokens = vec![
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
         ];
 
@@ -95,7 +100,7 @@ mod tests {
     #[test]
     fn function_call_missing_comma() {
         let tokens = vec![
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TrueKeyword,
             Token::LeftCurleyBrace,
@@ -1,16 +1,24 @@
-use std::collections::VecDeque;
-
-use crate::ast::{
-    builders::function_declaration_builder::FunctionDeclarationBuilder,
-    node::{FunctionDeclaration, FunctionParameter},
+use crate::{
+    ast::{
+        builders::function_declaration_builder::FunctionDeclarationBuilder,
+        node::{FunctionDeclaration, FunctionParameter, Type},
+        symbol::Symbol,
+    },
+    evaluation::intrinsics::get_intrinsic_functions,
 };
 
-use super::token::{ensure_token, get_block_statements, Token, TokenStreamError};
+use super::{
+    token::{ensure_token, get_block_statements, Token, TokenStreamError, Tokens},
+    types::{is_type_start, take_type, take_type_parameters},
+};
 
 pub(super) fn build_function_declaration(
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
 ) -> Result<Box<dyn FnOnce(FunctionDeclarationBuilder) -> FunctionDeclaration>, Vec<TokenStreamError>>
 {
+    // the function keyword has just been taken
+    let start_line = tokens.line();
+
     let function_name = match tokens.pop_front() {
         None => {
             return Err(vec![TokenStreamError {
@@ -25,6 +33,116 @@ pub(super) fn build_function_declaration(
         }
     };
 
+    let type_parameters = match tokens.front() {
+        Some(Token::LeftAngle) => {
+            tokens.pop_front();
+            take_type_parameters(tokens)?
+        }
+        _ => Vec::new(),
+    };
+
+    let (params, return_type) = take_function_signature(tokens, &type_parameters)?;
+
+    let statements = get_block_statements(tokens)?;
+    let end_line = tokens.line();
+
+    check_parameter_names(
+        &params,
+        &format!("`{}` at line {}", function_name, start_line),
+    )?;
+
+    Ok(Box::new(move |mut function_declaration_builder| {
+        function_declaration_builder = function_declaration_builder
+            .name(function_name)
+            .span(start_line, end_line)
+            .type_parameters(type_parameters)
+            .parameters(params);
+
+        if let Some(return_type) = return_type {
+            function_declaration_builder = function_declaration_builder.return_type(return_type);
+        } else {
+            function_declaration_builder = function_declaration_builder.void();
+        }
+
+        function_declaration_builder.body(|mut body| {
+            for statement in statements {
+                body = body.statement(statement);
+            }
+
+            body.build()
+        })
+    }))
+}
+
+/// check that each parameter of a function or lambda has a different name. `owner` describes the function
+pub(super) fn check_parameter_names(
+    parameters: &[FunctionParameter],
+    owner: &str,
+) -> Result<(), Vec<TokenStreamError>> {
+    let errors: Vec<_> = parameters
+        .iter()
+        .enumerate()
+        .filter_map(|(index, parameter)| {
+            let first = parameters[..index]
+                .iter()
+                .position(|other| other.name() == parameter.name())?;
+
+            Some(TokenStreamError {
+                message: format!(
+                    "{} declares parameter `{}` twice, as parameters {} and {}",
+                    owner,
+                    parameter.name(),
+                    first + 1,
+                    index + 1
+                ),
+            })
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// check that no two functions have the same name, and that no function has the name of an intrinsic function
+pub(super) fn check_function_names(functions: &[FunctionDeclaration]) -> Vec<TokenStreamError> {
+    let intrinsic_functions = get_intrinsic_functions();
+
+    functions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, function)| {
+            if intrinsic_functions.contains_key(&function.id) {
+                return Some(TokenStreamError {
+                    message: format!(
+                        "function `{}` at line {} redefines the intrinsic function `{}`, which is built into beach",
+                        function.name, function.span.start_line, function.name
+                    ),
+                });
+            }
+
+            let first = functions[..index]
+                .iter()
+                .find(|other| other.name == function.name)?;
+
+            Some(TokenStreamError {
+                message: format!(
+                    "function `{}` is declared twice, at line {} and line {}",
+                    function.name, first.span.start_line, function.span.start_line
+                ),
+            })
+        })
+        .collect()
+}
+
+/// take the parameters and optional return type of a function, up to and including the `{` that begins the function body.
+/// `type_parameters` are the names of the function's type parameters, which can be used as types in the signature
+pub(super) fn take_function_signature(
+    tokens: &mut Tokens,
+    type_parameters: &[Symbol],
+) -> Result<(Vec<FunctionParameter>, Option<Type>), Vec<TokenStreamError>> {
     ensure_token(tokens, Token::LeftParenthesis)?;
 
     let mut params = Vec::new();
@@ -43,12 +161,13 @@ pub(super) fn build_function_declaration(
             Some(Token::Comma) => {
                 found_comma = true;
             }
-            Some(Token::TypeKeyword(_)) if !found_comma && params.len() > 0 => {
+            Some(token) if is_type_start(&token) && !found_comma && params.len() > 0 => {
                 return Err(vec![TokenStreamError {
                     message: "expected , or )".to_owned(),
                 }])
             }
-            Some(Token::TypeKeyword(type_)) => {
+            Some(token) if is_type_start(&token) => {
+                let type_ = take_type(token, tokens, type_parameters)?;
                 match tokens.pop_front() {
                     None => {
                         return Err(vec![TokenStreamError {
@@ -91,8 +210,8 @@ pub(super) fn build_function_declaration(
                     message: "Expected return type".to_owned(),
                 }]);
             }
-            Some(Token::TypeKeyword(type_)) => {
-                return_type = Some(type_);
+            Some(token) if is_type_start(&token) => {
+                return_type = Some(take_type(token, tokens, type_parameters)?);
                 ensure_token(tokens, Token::LeftCurleyBrace)?;
             }
             Some(_) => {
@@ -109,27 +228,7 @@ pub(super) fn build_function_declaration(
         }
     }
 
-    let statements = get_block_statements(tokens)?;
-
-    Ok(Box::new(move |mut function_declaration_builder| {
-        function_declaration_builder = function_declaration_builder
-            .name(&function_name)
-            .parameters(params);
-
-        if let Some(return_type) = return_type {
-            function_declaration_builder = function_declaration_builder.return_type(return_type);
-        } else {
-            function_declaration_builder = function_declaration_builder.void();
-        }
-
-        function_declaration_builder.body(|mut body| {
-            for statement in statements {
-                body = body.statement(statement);
-            }
-
-            body.build()
-        })
-    }))
+    Ok((params, return_type))
 }
 
 #[cfg(test)]
@@ -137,7 +236,7 @@ mod tests {
     use crate::{
         ast::{
             builders::ast_builder::AstBuilder,
-            node::{FunctionParameter, Type},
+            node::{FunctionParameter, FunctionReturnType, FunctionType, Type},
         },
         token_stream::token::Token,
     };
@@ -151,11 +250,11 @@ mod tests {
     fn function_declaration_no_parameters_no_return_value() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
-            Token::Identifier("print".to_owned()),
+            Token::Identifier("print".into()),
             Token::LeftParenthesis,
             Token::UIntValue(1),
             Token::RightParenthesis,
@@ -194,7 +293,7 @@ mod tests {
     fn function_declaration_empty_body() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
@@ -222,13 +321,13 @@ mod tests {
     fn function_declaration_parameters_no_return_value() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TypeKeyword(Type::UInt),
-            Token::Identifier("param_1".to_owned()),
+            Token::Identifier("param_1".into()),
             Token::Comma,
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("param_2".to_owned()),
+            Token::Identifier("param_2".into()),
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
             Token::ReturnKeyword,
@@ -244,11 +343,11 @@ mod tests {
                 .parameters(vec![
                     FunctionParameter::FunctionParameter {
                         param_type: Type::UInt,
-                        param_name: "param_1".to_owned(),
+                        param_name: "param_1".into(),
                     },
                     FunctionParameter::FunctionParameter {
                         param_type: Type::Boolean,
-                        param_name: "param_2".to_owned(),
+                        param_name: "param_2".into(),
                     },
                 ])
                 .void()
@@ -266,13 +365,13 @@ mod tests {
     fn function_declaration_parameters_return_value() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TypeKeyword(Type::UInt),
-            Token::Identifier("param_1".to_owned()),
+            Token::Identifier("param_1".into()),
             Token::Comma,
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("param_2".to_owned()),
+            Token::Identifier("param_2".into()),
             Token::RightParenthesis,
             Token::FunctionSignitureSplitter,
             Token::TypeKeyword(Type::Boolean),
@@ -291,11 +390,11 @@ mod tests {
                 .parameters(vec![
                     FunctionParameter::FunctionParameter {
                         param_type: Type::UInt,
-                        param_name: "param_1".to_owned(),
+                        param_name: "param_1".into(),
                     },
                     FunctionParameter::FunctionParameter {
                         param_type: Type::Boolean,
-                        param_name: "param_2".to_owned(),
+                        param_name: "param_2".into(),
                     },
                 ])
                 .return_type(Type::Boolean)
@@ -337,7 +436,7 @@ mod tests {
     fn function_declaration_missing_left_parenthesis() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::RightParenthesis,
         ];
 
@@ -353,7 +452,7 @@ mod tests {
     fn function_declaration_missing_left_parenthesis_no_tokens() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
         ];
 
         let result = AstBuilder::from_token_stream(tokens);
@@ -368,15 +467,16 @@ mod tests {
     fn function_declaration_missing_type() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
-            Token::Identifier("my_param".to_owned()),
+            Token::Identifier("my_param".into()),
         ];
 
         let result = AstBuilder::from_token_stream(tokens);
 
+        // my_param could name a type alias, so the parameter name is missing
         assert!(
-            matches!(result, Err(e) if e.len() == 1 && e[0].message == "expected type, ',', or )")
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "expected parameter name")
         );
     }
 
@@ -385,7 +485,7 @@ mod tests {
     fn function_declaration_missing_param_name() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TypeKeyword(Type::Boolean),
             Token::RightParenthesis,
@@ -403,7 +503,7 @@ mod tests {
     fn function_declaration_missing_param_name_no_tokens() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TypeKeyword(Type::Boolean),
         ];
@@ -420,12 +520,12 @@ mod tests {
     fn function_declaration_missing_comma() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("my_param".to_owned()),
+            Token::Identifier("my_param".into()),
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("other_param".to_owned()),
+            Token::Identifier("other_param".into()),
             Token::RightParenthesis,
         ];
 
@@ -439,7 +539,7 @@ mod tests {
     fn function_declaration_missing_right_parenthesis() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::LeftCurleyBrace,
         ];
@@ -456,7 +556,7 @@ mod tests {
     fn function_declaration_missing_right_parenthesis_no_tokens() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
         ];
 
@@ -470,7 +570,7 @@ mod tests {
     fn function_declaration_missing_left_curley_brace() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::InferKeyword,
@@ -486,7 +586,7 @@ mod tests {
     fn function_declaration_missing_left_curley_brace_no_tokens() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
         ];
@@ -502,7 +602,7 @@ mod tests {
     fn function_declaration_missing_right_curley_brace() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
@@ -521,7 +621,7 @@ mod tests {
     fn function_declaration_missing_return_type() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::FunctionSignitureSplitter,
@@ -538,7 +638,7 @@ mod tests {
     fn function_declaration_missing_return_type_no_tokens() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::FunctionSignitureSplitter,
@@ -548,4 +648,223 @@ mod tests {
 
         assert!(matches!(result, Err(e) if e.len() == 1 && e[0].message == "Expected return type"));
     }
+
+    /// function apply(fn(uint) -> uint operation) -> fn(uint) {}
+    #[test]
+    fn function_declaration_function_types() {
+        let tokens = vec![
+            Token::FunctionKeyword,
+            Token::Identifier("apply".into()),
+            Token::LeftParenthesis,
+            Token::FnKeyword,
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::TypeKeyword(Type::UInt),
+            Token::Identifier("operation".into()),
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::FnKeyword,
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::RightParenthesis,
+            Token::LeftCurleyBrace,
+            Token::RightCurleyBrace,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().function_declaration(|function_declaration| {
+            function_declaration
+                .name("apply")
+                .parameters(vec![FunctionParameter::FunctionParameter {
+                    param_type: Type::Function(FunctionType {
+                        parameters: vec![Type::UInt],
+                        return_type: Box::new(FunctionReturnType::Type(Type::UInt)),
+                    }),
+                    param_name: "operation".into(),
+                }])
+                .return_type(Type::Function(FunctionType {
+                    parameters: vec![Type::UInt],
+                    return_type: Box::new(FunctionReturnType::Void),
+                }))
+                .body(|body| body.build())
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// function pick<T, U>(T first, fn(T) -> U map) -> U {}
+    #[test]
+    fn function_declaration_type_parameters() {
+        let tokens = vec![
+            Token::FunctionKeyword,
+            Token::Identifier("pick".into()),
+            Token::LeftAngle,
+            Token::Identifier("T".into()),
+            Token::Comma,
+            Token::Identifier("U".into()),
+            Token::RightAngle,
+            Token::LeftParenthesis,
+            Token::Identifier("T".into()),
+            Token::Identifier("first".into()),
+            Token::Comma,
+            Token::FnKeyword,
+            Token::LeftParenthesis,
+            Token::Identifier("T".into()),
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::Identifier("U".into()),
+            Token::Identifier("map".into()),
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::Identifier("U".into()),
+            Token::LeftCurleyBrace,
+            Token::RightCurleyBrace,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().function_declaration(|function_declaration| {
+            function_declaration
+                .name("pick")
+                .type_parameters(vec!["T".into(), "U".into()])
+                .parameters(vec![
+                    FunctionParameter::FunctionParameter {
+                        param_type: Type::Generic("T".into()),
+                        param_name: "first".into(),
+                    },
+                    FunctionParameter::FunctionParameter {
+                        param_type: Type::Function(FunctionType {
+                            parameters: vec![Type::Generic("T".into())],
+                            return_type: Box::new(FunctionReturnType::Type(Type::Generic(
+                                "U".into(),
+                            ))),
+                        }),
+                        param_name: "map".into(),
+                    },
+                ])
+                .return_type(Type::Generic("U".into()))
+                .body(|body| body.build())
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// function pick(T first) {}
+    #[test]
+    fn function_declaration_named_type() {
+        let tokens = vec![
+            Token::FunctionKeyword,
+            Token::Identifier("pick".into()),
+            Token::LeftParenthesis,
+            Token::Identifier("T".into()),
+            Token::Identifier("first".into()),
+            Token::RightParenthesis,
+            Token::LeftCurleyBrace,
+            Token::RightCurleyBrace,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        // names that aren't type parameters refer to type aliases, which are resolved once the module is loaded
+        let expected = AstBuilder::default().function_declaration(|function_declaration| {
+            function_declaration
+                .name("pick")
+                .void()
+                .parameters(vec![FunctionParameter::FunctionParameter {
+                    param_type: Type::Named("T".into()),
+                    param_name: "first".into(),
+                }])
+                .body(|body| body.build())
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// function pick<T, T>() {}
+    #[test]
+    fn function_declaration_duplicate_type_parameter() {
+        let tokens = vec![
+            Token::FunctionKeyword,
+            Token::Identifier("pick".into()),
+            Token::LeftAngle,
+            Token::Identifier("T".into()),
+            Token::Comma,
+            Token::Identifier("T".into()),
+            Token::RightAngle,
+            Token::LeftParenthesis,
+            Token::RightParenthesis,
+            Token::LeftCurleyBrace,
+            Token::RightCurleyBrace,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(
+            matches!(result, Err(e) if e[0].message == "type parameter T is declared more than once")
+        );
+    }
+
+    fn empty_function(name: &str) -> Vec<Token> {
+        vec![
+            Token::FunctionKeyword,
+            Token::Identifier(name.into()),
+            Token::LeftParenthesis,
+            Token::RightParenthesis,
+            Token::LeftCurleyBrace,
+            Token::RightCurleyBrace,
+        ]
+    }
+
+    /// function helper() {}
+    /// function helper() {}
+    #[test]
+    fn function_declaration_duplicate_function() {
+        let tokens = [empty_function("helper"), empty_function("helper")].concat();
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "function `helper` is declared twice, at line 0 and line 0")
+        );
+    }
+
+    /// function print() {}
+    #[test]
+    fn function_declaration_redefines_intrinsic() {
+        let result = AstBuilder::from_token_stream(empty_function("print"));
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "function `print` at line 0 redefines the intrinsic function `print`, which is built into beach")
+        );
+    }
+
+    /// function add(uint value, bool enabled, uint value) {}
+    #[test]
+    fn function_declaration_duplicate_parameter() {
+        let tokens = vec![
+            Token::FunctionKeyword,
+            Token::Identifier("add".into()),
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::Identifier("value".into()),
+            Token::Comma,
+            Token::TypeKeyword(Type::Boolean),
+            Token::Identifier("enabled".into()),
+            Token::Comma,
+            Token::TypeKeyword(Type::UInt),
+            Token::Identifier("value".into()),
+            Token::RightParenthesis,
+            Token::LeftCurleyBrace,
+            Token::RightCurleyBrace,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "`add` at line 0 declares parameter `value` twice, as parameters 1 and 3")
+        );
+    }
 }
@@ -0,0 +1,115 @@
+use crate::ast::{builders::global_builder::GlobalBuilder, node::Global};
+
+use super::{
+    constant::take_declaration,
+    token::{Token, TokenStreamError, Tokens},
+};
+
+/// take a global variable declaration, ie `global mut count: uint = 0;`.
+/// The `global` keyword should already have been taken
+pub(super) fn take_global(
+    tokens: &mut Tokens,
+) -> Result<Box<dyn FnOnce(GlobalBuilder) -> Global>, Vec<TokenStreamError>> {
+    // the global keyword has just been taken
+    let line = tokens.line();
+    let mutable = if tokens.front() == Some(&Token::MutKeyword) {
+        tokens.pop_front();
+        true
+    } else {
+        false
+    };
+
+    let (name, global_type, value) = take_declaration(tokens, "global")?;
+
+    Ok(Box::new(move |global_builder| {
+        let global_builder = global_builder
+            .name(name)
+            .global_type(global_type)
+            .line(line);
+
+        if mutable {
+            global_builder.mutable()
+        } else {
+            global_builder
+        }
+        .value(value)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{builders::ast_builder::AstBuilder, node::Type},
+        token_stream::token::Token,
+    };
+
+    /// global count: uint = 0;
+    #[test]
+    fn global_declaration() {
+        let tokens = vec![
+            Token::GlobalKeyword,
+            Token::Identifier("count".into()),
+            Token::Colon,
+            Token::TypeKeyword(Type::UInt),
+            Token::AssignmentOperator,
+            Token::UIntValue(0),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().global(|global| {
+            global
+                .name("count")
+                .global_type(Type::UInt)
+                .value(|_| 0.into())
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// global mut count: uint = 0;
+    #[test]
+    fn mutable_global_declaration() {
+        let tokens = vec![
+            Token::GlobalKeyword,
+            Token::MutKeyword,
+            Token::Identifier("count".into()),
+            Token::Colon,
+            Token::TypeKeyword(Type::UInt),
+            Token::AssignmentOperator,
+            Token::UIntValue(0),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().global(|global| {
+            global
+                .name("count")
+                .global_type(Type::UInt)
+                .mutable()
+                .value(|_| 0.into())
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// global mut: uint = 0;
+    #[test]
+    fn global_declaration_missing_name() {
+        let tokens = vec![
+            Token::GlobalKeyword,
+            Token::MutKeyword,
+            Token::Colon,
+            Token::TypeKeyword(Type::UInt),
+            Token::AssignmentOperator,
+            Token::UIntValue(0),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(matches!(result, Err(e) if e[0].message == "expected global name. found Colon"));
+    }
+}
@@ -1,5 +1,3 @@
-use std::collections::VecDeque;
-
 use crate::ast::{
     builders::{
         expression_builder::ExpressionBuilder, if_statement_builder::IfStatementBuilder,
@@ -10,11 +8,11 @@ use crate::ast::{
 
 use super::{
     expression::create_expression,
-    token::{ensure_token, get_block_statements, Token, TokenStreamError},
+    token::{ensure_token, get_block_statements, Token, TokenStreamError, Tokens},
 };
 
 pub(super) fn try_create_if_statement(
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
 ) -> Result<impl FnOnce(IfStatementBuilder) -> Node, Vec<TokenStreamError>> {
     ensure_token(tokens, Token::LeftParenthesis)?;
 
@@ -144,7 +142,7 @@ mod tests {
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
             Token::InferKeyword,
-            Token::Identifier("a".to_owned()),
+            Token::Identifier("a".into()),
             Token::AssignmentOperator,
             Token::FalseKeyword,
             Token::SemiColon,
@@ -185,7 +183,7 @@ mod tests {
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
             Token::InferKeyword,
-            Token::Identifier("a".to_owned()),
+            Token::Identifier("a".into()),
             Token::AssignmentOperator,
             Token::FalseKeyword,
             Token::SemiColon,
@@ -193,7 +191,7 @@ mod tests {
             Token::ElseKeyword,
             Token::LeftCurleyBrace,
             Token::InferKeyword,
-            Token::Identifier("b".to_owned()),
+            Token::Identifier("b".into()),
             Token::AssignmentOperator,
             Token::TrueKeyword,
             Token::SemiColon,
@@ -260,7 +258,7 @@ mod tests {
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
             Token::InferKeyword,
-            Token::Identifier("a".to_owned()),
+            Token::Identifier("a".into()),
             Token::AssignmentOperator,
             Token::FalseKeyword,
             Token::SemiColon,
@@ -272,7 +270,7 @@ mod tests {
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
             Token::InferKeyword,
-            Token::Identifier("b".to_owned()),
+            Token::Identifier("b".into()),
             Token::AssignmentOperator,
             Token::TrueKeyword,
             Token::SemiColon,
@@ -284,7 +282,7 @@ mod tests {
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
             Token::InferKeyword,
-            Token::Identifier("c".to_owned()),
+            Token::Identifier("c".into()),
             Token::AssignmentOperator,
             Token::TrueKeyword,
             Token::SemiColon,
@@ -292,7 +290,7 @@ mod tests {
             Token::ElseKeyword,
             Token::LeftCurleyBrace,
             Token::InferKeyword,
-            Token::Identifier("d".to_owned()),
+            Token::Identifier("d".into()),
             Token::AssignmentOperator,
             Token::TrueKeyword,
             Token::SemiColon,
@@ -379,7 +377,7 @@ mod tests {
             Token::TrueKeyword,
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::SemiColon,
@@ -388,12 +386,12 @@ mod tests {
             Token::TrueKeyword,
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::SemiColon,
             Token::RightCurleyBrace,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::SemiColon,
@@ -413,26 +411,22 @@ mod tests {
                 if_statement
                     .check_expression(|_| true.into())
                     .body(|body| {
-                        body.statement(|statement| {
-                            statement.function_call(function_call_builder.clone())
-                        })
-                        .statement(|statement| {
-                            statement.if_statement(|if_statement| {
-                                if_statement
-                                    .check_expression(|_| true.into())
-                                    .body(|body| {
-                                        body.statement(|statement| {
-                                            statement.function_call(function_call_builder.clone())
+                        body.statement(|statement| statement.function_call(function_call_builder))
+                            .statement(|statement| {
+                                statement.if_statement(|if_statement| {
+                                    if_statement
+                                        .check_expression(|_| true.into())
+                                        .body(|body| {
+                                            body.statement(|statement| {
+                                                statement.function_call(function_call_builder)
+                                            })
+                                            .build()
                                         })
                                         .build()
-                                    })
-                                    .build()
+                                })
                             })
-                        })
-                        .statement(|statement| {
-                            statement.function_call(function_call_builder.clone())
-                        })
-                        .build()
+                            .statement(|statement| statement.function_call(function_call_builder))
+                            .build()
                     })
                     .build()
             })
@@ -447,9 +441,9 @@ mod tests {
         let tokens = vec![
             Token::IfKeyword,
             Token::LeftParenthesis,
-            Token::Identifier("value_a".to_owned()),
+            Token::Identifier("value_a".into()),
             Token::RightAngle,
-            Token::Identifier("value_b".to_owned()),
+            Token::Identifier("value_b".into()),
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
             Token::RightCurleyBrace,
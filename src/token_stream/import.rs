@@ -0,0 +1,72 @@
+use super::token::{ensure_token, Token, TokenStreamError, Tokens};
+
+/// take the path of an import statement, ie `import "lib/math.bch";`. The `import` keyword should already have been taken
+pub(super) fn take_import(tokens: &mut Tokens) -> Result<String, Vec<TokenStreamError>> {
+    let path = match tokens.pop_front() {
+        Some(Token::StringLiteral(path)) => path,
+        Some(token) => {
+            return Err(vec![TokenStreamError {
+                message: format!("expected path of module to import. found {}", token),
+            }])
+        }
+        None => {
+            return Err(vec![TokenStreamError {
+                message: "expected path of module to import".to_owned(),
+            }])
+        }
+    };
+
+    ensure_token(tokens, Token::SemiColon)?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ast::builders::ast_builder::AstBuilder, token_stream::token::Token};
+
+    /// import "lib.bch";
+    #[test]
+    fn import_statement() {
+        let tokens = vec![
+            Token::ImportKeyword,
+            Token::StringLiteral("lib.bch".to_owned()),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(
+            matches!(result, Ok(ast_builder) if ast_builder == AstBuilder::default().import("lib.bch"))
+        );
+    }
+
+    /// import lib;
+    #[test]
+    fn import_statement_missing_path() {
+        let tokens = vec![
+            Token::ImportKeyword,
+            Token::Identifier("lib".into()),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(
+            matches!(result, Err(e) if e[0].message == "expected path of module to import. found Identifier(\"lib\")")
+        );
+    }
+
+    /// import "lib.bch"
+    #[test]
+    fn import_statement_missing_semicolon() {
+        let tokens = vec![
+            Token::ImportKeyword,
+            Token::StringLiteral("lib.bch".to_owned()),
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(matches!(result, Err(e) if e.len() == 1 && e[0].message == "Expected SemiColon"));
+    }
+}
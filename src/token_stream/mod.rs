@@ -1,7 +1,12 @@
+mod constant;
 mod expression;
 mod function_call;
 mod function_declaration;
+mod global;
 mod if_statement;
+mod import;
 mod statement;
 pub mod token;
+mod type_alias;
+mod types;
 mod variable_declaration;
@@ -1,27 +1,45 @@
-use std::collections::VecDeque;
-
 use crate::ast::{
     builders::{expression_builder::ExpressionBuilder, statement_builder::StatementBuilder},
-    node::{Expression, Node, VariableDeclarationType},
+    node::{Expression, Node, Type, VariableDeclarationType},
+    symbol::Symbol,
 };
 
 use super::{
     expression::create_expression,
     function_call::take_function_call,
     if_statement::try_create_if_statement,
-    token::{ensure_token, take_from_front_while, Token, TokenStreamError},
+    token::{ensure_token, Token, TokenStreamError, Tokens},
+    types::take_type,
     variable_declaration::try_create_variable_declaration,
 };
 
 pub(super) fn try_create_statement(
     first_token: Token,
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
 ) -> Result<Option<Box<dyn FnOnce(StatementBuilder) -> Node>>, Vec<TokenStreamError>> {
+    // the first token has just been taken
+    let line = tokens.line();
+
     let statement_type = match first_token {
+        Token::Identifier(identifier) if tokens.front() == Some(&Token::AssignmentOperator) => {
+            StatementType::Assignment(identifier)
+        }
+        // a variable declared with a type alias, ie `Count total = 0;` or `Pair (first, second) = pair;`
+        Token::Identifier(type_name)
+            if matches!(tokens.front(), Some(Token::Identifier(_)))
+                || starts_destructuring_pattern(tokens) =>
+        {
+            StatementType::VariableDeclaration(VariableDeclarationType::Type(Type::Named(
+                type_name,
+            )))
+        }
         Token::Identifier(identifier) => StatementType::FunctionCall(identifier),
         Token::TypeKeyword(type_) => {
             StatementType::VariableDeclaration(VariableDeclarationType::Type(type_))
         }
+        Token::FnKeyword | Token::LeftParenthesis => StatementType::VariableDeclaration(
+            VariableDeclarationType::Type(take_type(first_token, tokens, &[])?),
+        ),
         Token::InferKeyword => StatementType::VariableDeclaration(VariableDeclarationType::Infer),
         Token::IfKeyword => StatementType::If,
         Token::ReturnKeyword => StatementType::Return,
@@ -31,11 +49,37 @@ pub(super) fn try_create_statement(
         }
     };
 
-    try_start_statement(statement_type, tokens).map(|x| Some(x))
+    let statement = try_start_statement(statement_type, tokens)?;
+
+    Ok(Some(Box::new(
+        move |statement_builder: StatementBuilder| statement(statement_builder.line(line)),
+    )))
+}
+
+/// check if the tokens begin with the variables of a destructuring declaration, ie `(first, second) =`,
+/// rather than the arguments of a function call
+fn starts_destructuring_pattern(tokens: &Tokens) -> bool {
+    if tokens.front() != Some(&Token::LeftParenthesis) {
+        return false;
+    }
+
+    let pattern_end = tokens
+        .iter()
+        .skip(1)
+        .position(|token| !matches!(token, Token::Identifier(_) | Token::Comma));
+
+    matches!(
+        pattern_end.map(|end| (tokens.get(end + 1), tokens.get(end + 2))),
+        Some((
+            Some(Token::RightParenthesis),
+            Some(Token::AssignmentOperator)
+        ))
+    )
 }
 
 enum StatementType {
-    FunctionCall(String),
+    FunctionCall(Symbol),
+    Assignment(Symbol),
     VariableDeclaration(VariableDeclarationType),
     If,
     Return,
@@ -43,21 +87,30 @@ enum StatementType {
 
 fn try_start_statement(
     statement_type: StatementType,
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
 ) -> Result<Box<dyn FnOnce(StatementBuilder) -> Node>, Vec<TokenStreamError>> {
     match statement_type {
         // variable declaration
         StatementType::VariableDeclaration(var_decl_type) => {
             // variable declaration should end with a semicolon, take all the tokens until the first semicolon
+            // that isn't inside a lambda body, as a lambda body can contain it's own statements
             let mut found_semicolon = false;
-
-            let tokens: VecDeque<_> = take_from_front_while(tokens, |token| {
-                if !found_semicolon {
-                    found_semicolon = matches!(token, Token::SemiColon);
+            let mut found_lambda = false;
+            let mut lambda_depth = 0;
+
+            let tokens = tokens.take_while(|token| {
+                match token {
+                    Token::FunctionKeyword => found_lambda = true,
+                    Token::LeftCurleyBrace if found_lambda || lambda_depth > 0 => {
+                        found_lambda = false;
+                        lambda_depth += 1;
+                    }
+                    Token::RightCurleyBrace if lambda_depth > 0 => lambda_depth -= 1,
+                    Token::SemiColon if lambda_depth == 0 => found_semicolon = true,
+                    _ => {}
                 }
                 !found_semicolon
-            })
-            .into();
+            });
 
             // we got to the end of the tokens without a semicolon
             if !found_semicolon {
@@ -81,12 +134,31 @@ fn try_start_statement(
             }))
         }
         StatementType::Return => Ok(Box::new(take_return_statement(tokens)?)),
+        StatementType::Assignment(var_name) => {
+            Ok(Box::new(take_assignment_statement(var_name, tokens)?))
+        }
     }
 }
 
+/// take an assignment, ie `count = count + 1;`. The variable name should already have been taken
+fn take_assignment_statement(
+    var_name: Symbol,
+    tokens: &mut Tokens,
+) -> Result<Box<dyn FnOnce(StatementBuilder) -> Node>, Vec<TokenStreamError>> {
+    ensure_token(tokens, Token::AssignmentOperator)?;
+
+    let value = create_expression(tokens)?;
+
+    ensure_token(tokens, Token::SemiColon)?;
+
+    Ok(Box::new(move |statement_builder| {
+        statement_builder.assignment(var_name, value)
+    }))
+}
+
 fn take_function_call_statement(
-    identifier: String,
-    tokens: &mut VecDeque<Token>,
+    identifier: Symbol,
+    tokens: &mut Tokens,
 ) -> Result<Box<dyn FnOnce(StatementBuilder) -> Node>, Vec<TokenStreamError>> {
     let function_call = take_function_call(identifier, tokens)?;
 
@@ -98,7 +170,7 @@ fn take_function_call_statement(
 }
 
 fn take_return_statement(
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
 ) -> Result<Box<dyn FnOnce(StatementBuilder) -> Node>, Vec<TokenStreamError>> {
     match tokens.pop_front() {
         None => Err(vec![TokenStreamError {
@@ -128,7 +200,13 @@ fn build_return_statement(
 
 #[cfg(test)]
 mod tests {
-    use crate::{ast::builders::ast_builder::AstBuilder, token_stream::token::Token};
+    use crate::{
+        ast::{
+            builders::ast_builder::AstBuilder,
+            node::{FunctionReturnType, FunctionType, Type},
+        },
+        token_stream::token::Token,
+    };
 
     /// return;
     #[test]
@@ -164,13 +242,38 @@ mod tests {
         assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
     }
 
+    /// count = count + 1;
+    #[test]
+    fn assignment_statement() {
+        let tokens = vec![
+            Token::Identifier("count".into()),
+            Token::AssignmentOperator,
+            Token::Identifier("count".into()),
+            Token::PlusOperator,
+            Token::UIntValue(1),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().statement(|statement| {
+            statement.assignment("count", |value| {
+                value.operation(|operation| {
+                    operation.plus(|left| left.variable("count"), |_| 1.into())
+                })
+            })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
     /// print(my_function(true));
     #[test]
     fn function_call_statement() {
         let tokens = vec![
-            Token::Identifier("print".to_owned()),
+            Token::Identifier("print".into()),
             Token::LeftParenthesis,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TrueKeyword,
             Token::RightParenthesis,
@@ -211,7 +314,7 @@ mod tests {
     #[test]
     fn statement_function_call_expected_semicolon() {
         let tokens = vec![
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::TrueKeyword,
@@ -245,11 +348,95 @@ mod tests {
     fn statement_return_missing_semicolon() {
         let tokens = vec![Token::ReturnKeyword];
 
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(matches!(dbg!(result), Err(e) if e.len() >= 1 && e[0].message == "expected ;"));
+    }
+
+    /// fn(uint) -> boolean my_var = my_function;
+    #[test]
+    fn function_type_variable_declaration() {
+        let tokens = vec![
+            Token::FnKeyword,
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::TypeKeyword(Type::Boolean),
+            Token::Identifier("my_var".into()),
+            Token::AssignmentOperator,
+            Token::Identifier("my_function".into()),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().statement(|statement| {
+            statement.var_declaration(|var_declaration| {
+                var_declaration
+                    .declare_type(Type::Function(FunctionType {
+                        parameters: vec![Type::UInt],
+                        return_type: Box::new(FunctionReturnType::Type(Type::Boolean)),
+                    }))
+                    .name("my_var")
+                    .with_assignment(|value| value.variable("my_function"))
+            })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// Count total = 0;
+    #[test]
+    fn type_alias_variable_declaration() {
+        let tokens = vec![
+            Token::Identifier("Count".into()),
+            Token::Identifier("total".into()),
+            Token::AssignmentOperator,
+            Token::UIntValue(0),
+            Token::SemiColon,
+        ];
 
         let result = AstBuilder::from_token_stream(tokens);
 
-        assert!(
-            matches!(dbg!(result), Err(e) if e.len() >= 1 && e[0].message == "expected ;")
-        );
+        let expected = AstBuilder::default().statement(|statement| {
+            statement.var_declaration(|var_declaration| {
+                var_declaration
+                    .declare_type(Type::Named("Count".into()))
+                    .name("total")
+                    .with_assignment(|value| value.value_literal(0.into()))
+            })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// Pair (first, second) = pair;
+    #[test]
+    fn type_alias_destructuring_declaration() {
+        let tokens = vec![
+            Token::Identifier("Pair".into()),
+            Token::LeftParenthesis,
+            Token::Identifier("first".into()),
+            Token::Comma,
+            Token::Identifier("second".into()),
+            Token::RightParenthesis,
+            Token::AssignmentOperator,
+            Token::Identifier("pair".into()),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().statement(|statement| {
+            statement.var_declaration(|var_declaration| {
+                var_declaration
+                    .declare_type(Type::Named("Pair".into()))
+                    .destructure(&["first", "second"])
+                    .with_assignment(|value| value.variable("pair"))
+            })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
     }
 }
@@ -3,14 +3,23 @@ use std::{collections::VecDeque, fmt::Display, ops::Range};
 use crate::ast::{
     builders::{ast_builder::AstBuilder, statement_builder::StatementBuilder},
     node::{Node, Type},
+    symbol::Symbol,
 };
 
-use super::{function_declaration::build_function_declaration, statement::try_create_statement};
+use super::{
+    constant::take_constant,
+    function_declaration::{build_function_declaration, check_function_names},
+    global::take_global,
+    import::take_import,
+    statement::try_create_statement,
+    type_alias::take_type_alias,
+};
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum Token {
     FunctionKeyword,
-    Identifier(String),
+    FnKeyword,
+    Identifier(Symbol),
     LeftParenthesis,
     RightParenthesis,
     FunctionSignitureSplitter, // ->
@@ -29,7 +38,17 @@ pub enum Token {
     IfKeyword,
     ElseKeyword,
     RightAngle, // >
+    LeftAngle,  // <
     ReturnKeyword,
+    ImportKeyword,
+    StringLiteral(String),
+    ConstKeyword,
+    Colon,
+    GlobalKeyword,
+    MutKeyword,
+    TypeAliasKeyword,
+    PureKeyword,
+    Dot,
 }
 
 impl Display for Token {
@@ -52,12 +71,93 @@ impl TokenSource {
             token,
             file: file.to_owned(),
             line,
-            character_range
+            character_range,
         }
     }
+}
+
+/// the tokens that are still to be taken, along with the line of the source file each token is on
+#[derive(Debug, Default, PartialEq)]
+pub struct Tokens {
+    tokens: VecDeque<Token>,
+    lines: VecDeque<u32>,
+    /// the line of the last token taken, so that it can be put back
+    line: u32,
+}
+
+impl Tokens {
+    pub(super) fn pop_front(&mut self) -> Option<Token> {
+        let token = self.tokens.pop_front()?;
+        self.line = self.lines.pop_front().unwrap_or(self.line);
+        Some(token)
+    }
+
+    /// put back the last token taken
+    pub(super) fn push_front(&mut self, token: Token) {
+        self.tokens.push_front(token);
+        self.lines.push_front(self.line);
+    }
+
+    pub(super) fn front(&self) -> Option<&Token> {
+        self.tokens.front()
+    }
+
+    pub(super) fn get(&self, index: usize) -> Option<&Token> {
+        self.tokens.get(index)
+    }
+
+    pub(super) fn iter(&self) -> impl Iterator<Item = &Token> {
+        self.tokens.iter()
+    }
+
+    /// the line of the last token taken
+    pub(super) fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// take tokens from the front while the predicate holds. The first token it doesn't hold for is also taken
+    pub(super) fn take_while(&mut self, mut predicate: impl FnMut(&Token) -> bool) -> Tokens {
+        let mut taken = Tokens {
+            line: self.line,
+            ..Tokens::default()
+        };
+
+        while let Some(token) = self.pop_front() {
+            if predicate(&token) {
+                taken.tokens.push_back(token);
+                taken.lines.push_back(self.line);
+            } else {
+                break;
+            }
+        }
 
-    pub fn token(&self) -> &Token {
-        &self.token
+        taken
+    }
+}
+
+/// tokens that don't come from a source file, so are all given line 0
+impl From<Vec<Token>> for Tokens {
+    fn from(tokens: Vec<Token>) -> Self {
+        Tokens {
+            lines: tokens.iter().map(|_| 0).collect(),
+            tokens: tokens.into(),
+            line: 0,
+        }
+    }
+}
+
+impl From<Vec<TokenSource>> for Tokens {
+    fn from(sources: Vec<TokenSource>) -> Self {
+        let (tokens, lines) = sources
+            .into_iter()
+            .map(|source| (source.token, source.line))
+            .unzip();
+
+        Tokens {
+            tokens,
+            lines,
+            line: 0,
+        }
     }
 }
 
@@ -67,10 +167,10 @@ pub struct TokenStreamError {
 }
 
 impl AstBuilder {
-    pub fn from_token_stream(tokens: Vec<Token>) -> Result<Self, Vec<TokenStreamError>> {
+    pub fn from_token_stream(tokens: impl Into<Tokens>) -> Result<Self, Vec<TokenStreamError>> {
         let mut errors = Vec::new();
         let mut builder = AstBuilder::default();
-        let mut tokens: VecDeque<Token> = tokens.into();
+        let mut tokens: Tokens = tokens.into();
         while let Some(next_token) = tokens.pop_front() {
             match next_token {
                 Token::FunctionKeyword => match build_function_declaration(&mut tokens) {
@@ -79,6 +179,32 @@ impl AstBuilder {
                         builder = builder.function_declaration(function_declaration);
                     }
                 },
+                Token::PureKeyword => match ensure_token(&mut tokens, Token::FunctionKeyword)
+                    .and_then(|()| build_function_declaration(&mut tokens))
+                {
+                    Err(function_decl_errors) => errors.extend(function_decl_errors),
+                    Ok(function_declaration) => {
+                        builder = builder.function_declaration(|declaration| {
+                            function_declaration(declaration.pure())
+                        });
+                    }
+                },
+                Token::ConstKeyword => match take_constant(&mut tokens) {
+                    Err(constant_errors) => errors.extend(constant_errors),
+                    Ok(constant) => builder = builder.constant(constant),
+                },
+                Token::GlobalKeyword => match take_global(&mut tokens) {
+                    Err(global_errors) => errors.extend(global_errors),
+                    Ok(global) => builder = builder.global(global),
+                },
+                Token::TypeAliasKeyword => match take_type_alias(&mut tokens) {
+                    Err(type_alias_errors) => errors.extend(type_alias_errors),
+                    Ok((name, aliased_type)) => builder = builder.type_alias(name, aliased_type),
+                },
+                Token::ImportKeyword => match take_import(&mut tokens) {
+                    Err(import_errors) => errors.extend(import_errors),
+                    Ok(path) => builder = builder.import(&path),
+                },
                 _ => match try_create_statement(next_token, &mut tokens) {
                     Err(statement_errors) => {
                         errors.extend(statement_errors);
@@ -96,6 +222,8 @@ impl AstBuilder {
             };
         }
 
+        errors.extend(check_function_names(builder.function_declarations()));
+
         if errors.is_empty() {
             Ok(builder)
         } else {
@@ -104,27 +232,8 @@ impl AstBuilder {
     }
 }
 
-pub(super) fn take_from_front_while<T, TPredicate: FnMut(&T) -> bool>(
-    items: &mut VecDeque<T>,
-    mut predicate: TPredicate,
-) -> Vec<T> {
-    let mut to_return = Vec::with_capacity(items.len());
-
-    while let Some(item) = items.pop_front() {
-        if predicate(&item) {
-            to_return.push(item);
-        } else {
-            break;
-        }
-    }
-
-    to_return.shrink_to_fit();
-
-    to_return
-}
-
 pub(super) fn ensure_token(
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
     expected: Token,
 ) -> Result<(), Vec<TokenStreamError>> {
     match tokens.pop_front() {
@@ -139,7 +248,7 @@ pub(super) fn ensure_token(
 }
 
 pub(super) fn get_block_statements(
-    tokens: &mut VecDeque<Token>,
+    tokens: &mut Tokens,
 ) -> Result<Vec<Box<dyn FnOnce(StatementBuilder) -> Node>>, Vec<TokenStreamError>> {
     let mut require_end_curly_brace = false;
     let mut statements = Vec::new();
@@ -181,6 +290,7 @@ mod tests {
         ast::{
             builders::ast_builder::AstBuilder,
             node::{FunctionParameter, Type},
+            symbol::Symbol,
         },
         token_stream::token::Token,
     };
@@ -196,32 +306,32 @@ mod tests {
     fn function_declaration_and_statements() {
         let tokens = vec![
             Token::FunctionKeyword,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("param_1".to_owned()),
+            Token::Identifier("param_1".into()),
             Token::Comma,
             Token::TypeKeyword(Type::UInt),
-            Token::Identifier("param_2".to_owned()),
+            Token::Identifier("param_2".into()),
             Token::RightParenthesis,
             Token::FunctionSignitureSplitter,
             Token::TypeKeyword(Type::UInt),
             Token::LeftCurleyBrace,
-            Token::Identifier("print".to_owned()),
+            Token::Identifier("print".into()),
             Token::LeftParenthesis,
-            Token::Identifier("param_1".to_owned()),
+            Token::Identifier("param_1".into()),
             Token::RightParenthesis,
             Token::SemiColon,
-            Token::Identifier("print".to_owned()),
+            Token::Identifier("print".into()),
             Token::LeftParenthesis,
-            Token::Identifier("param_2".to_owned()),
+            Token::Identifier("param_2".into()),
             Token::RightParenthesis,
             Token::SemiColon,
             Token::ReturnKeyword,
             Token::UIntValue(1),
             Token::SemiColon,
             Token::RightCurleyBrace,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::TrueKeyword,
             Token::Comma,
@@ -239,11 +349,11 @@ mod tests {
                     .parameters(vec![
                         FunctionParameter::FunctionParameter {
                             param_type: Type::Boolean,
-                            param_name: "param_1".to_owned(),
+                            param_name: "param_1".into(),
                         },
                         FunctionParameter::FunctionParameter {
                             param_type: Type::UInt,
-                            param_name: "param_2".to_owned(),
+                            param_name: "param_2".into(),
                         },
                     ])
                     .return_type(Type::UInt)
@@ -281,6 +391,60 @@ mod tests {
         assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
     }
 
+    /// pure function one() -> uint {
+    ///     return 1;
+    /// }
+    #[test]
+    fn pure_function_declaration() {
+        let tokens = vec![
+            Token::PureKeyword,
+            Token::FunctionKeyword,
+            Token::Identifier("one".into()),
+            Token::LeftParenthesis,
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::TypeKeyword(Type::UInt),
+            Token::LeftCurleyBrace,
+            Token::ReturnKeyword,
+            Token::UIntValue(1),
+            Token::SemiColon,
+            Token::RightCurleyBrace,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().function_declaration(|function_declaration| {
+            function_declaration
+                .name("one")
+                .parameters(Vec::new())
+                .return_type(Type::UInt)
+                .pure()
+                .body(|body| {
+                    body.statement(|statement| statement.return_value(|_| 1.into()))
+                        .build()
+                })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// pure print(1);
+    #[test]
+    fn pure_without_function() {
+        let tokens = vec![
+            Token::PureKeyword,
+            Token::Identifier("print".into()),
+            Token::LeftParenthesis,
+            Token::UIntValue(1),
+            Token::RightParenthesis,
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(result.is_err());
+    }
+
     /// function fibonnacci(uint lower, uint higher, uint limit) -> uint
     /// {
     ///     infer next = lower + higher;
@@ -299,80 +463,80 @@ mod tests {
     /// fibonnacci(0, 1, 10000);
     #[test]
     fn fibonacci() {
-        let fibonacci_name = "fibonacci".to_owned();
-        let lower_name = "lower".to_owned();
-        let higher_name = "higher".to_owned();
-        let limit_name = "limit".to_owned();
-        let next_name = "next".to_owned();
+        let fibonacci_name = Symbol::intern("fibonacci");
+        let lower_name = Symbol::intern("lower");
+        let higher_name = Symbol::intern("higher");
+        let limit_name = Symbol::intern("limit");
+        let next_name = Symbol::intern("next");
         let tokens = vec![
             // function fibonacci(uint lower, uint higher, uint limit) -> uint {
             Token::FunctionKeyword,
-            Token::Identifier(fibonacci_name.clone()),
+            Token::Identifier(fibonacci_name),
             Token::LeftParenthesis,
             Token::TypeKeyword(Type::UInt),
-            Token::Identifier(lower_name.clone()),
+            Token::Identifier(lower_name),
             Token::Comma,
             Token::TypeKeyword(Type::UInt),
-            Token::Identifier(higher_name.clone()),
+            Token::Identifier(higher_name),
             Token::Comma,
             Token::TypeKeyword(Type::UInt),
-            Token::Identifier(limit_name.clone()),
+            Token::Identifier(limit_name),
             Token::RightParenthesis,
             Token::FunctionSignitureSplitter,
             Token::TypeKeyword(Type::UInt),
             Token::LeftCurleyBrace,
             // infer next = lower + higher;
             Token::InferKeyword,
-            Token::Identifier(next_name.clone()),
+            Token::Identifier(next_name),
             Token::AssignmentOperator,
-            Token::Identifier(lower_name.clone()),
+            Token::Identifier(lower_name),
             Token::PlusOperator,
-            Token::Identifier(higher_name.clone()),
+            Token::Identifier(higher_name),
             Token::SemiColon,
             // if (next > limit) { return next; }
             Token::IfKeyword,
             Token::LeftParenthesis,
-            Token::Identifier(next_name.clone()),
+            Token::Identifier(next_name),
             Token::RightAngle,
-            Token::Identifier(limit_name.clone()),
+            Token::Identifier(limit_name),
             Token::RightParenthesis,
             Token::LeftCurleyBrace,
             Token::ReturnKeyword,
-            Token::Identifier(next_name.clone()),
+            Token::Identifier(next_name),
             Token::SemiColon,
             Token::RightCurleyBrace,
             // print(next);
-            Token::Identifier("print".to_owned()),
+            Token::Identifier("print".into()),
             Token::LeftParenthesis,
-            Token::Identifier(next_name.to_owned()),
+            Token::Identifier(next_name),
             Token::RightParenthesis,
             Token::SemiColon,
             // fibonacci(higher, next, limit);
-            Token::Identifier(fibonacci_name.clone()),
+            Token::Identifier(fibonacci_name),
             Token::LeftParenthesis,
-            Token::Identifier(higher_name.clone()),
+            Token::Identifier(higher_name),
             Token::Comma,
-            Token::Identifier(next_name.clone()),
+            Token::Identifier(next_name),
             Token::Comma,
-            Token::Identifier(limit_name.clone()),
+            Token::Identifier(limit_name),
             Token::RightParenthesis,
             Token::SemiColon,
             // }
             Token::RightCurleyBrace,
             // print(0);
-            Token::Identifier("print".to_owned()),
+            Token::Identifier("print".into()),
             Token::LeftParenthesis,
             Token::UIntValue(0),
             Token::RightParenthesis,
             Token::SemiColon,
             // print(1);
-            Token::Identifier("print".to_owned()),
+            Token::Identifier("print".into()),
             Token::LeftParenthesis,
             Token::UIntValue(1),
             Token::RightParenthesis,
             Token::SemiColon,
             // fibonacci(0, 1, 10000);
-            Token::Identifier(fibonacci_name.clone()),
+            Token::Identifier(fibonacci_name),
             Token::LeftParenthesis,
             Token::UIntValue(0),
             Token::Comma,
@@ -392,15 +556,15 @@ mod tests {
                     .parameters(vec![
                         FunctionParameter::FunctionParameter {
                             param_type: Type::UInt,
-                            param_name: "lower".to_owned(),
+                            param_name: "lower".into(),
                         },
                         FunctionParameter::FunctionParameter {
                             param_type: Type::UInt,
-                            param_name: "higher".to_owned(),
+                            param_name: "higher".into(),
                         },
                         FunctionParameter::FunctionParameter {
                             param_type: Type::UInt,
-                            param_name: "limit".to_owned(),
+                            param_name: "limit".into(),
                         },
                     ])
                     .return_type(Type::UInt)
@@ -0,0 +1,113 @@
+use crate::ast::{node::Type, symbol::Symbol};
+
+use super::{
+    token::{ensure_token, Token, TokenStreamError, Tokens},
+    types::take_type,
+};
+
+/// take the name and aliased type of a type alias, ie `type Count = uint;`. The `type` keyword should already have been taken
+pub(super) fn take_type_alias(
+    tokens: &mut Tokens,
+) -> Result<(Symbol, Type), Vec<TokenStreamError>> {
+    let name = match tokens.pop_front() {
+        Some(Token::Identifier(name)) => name,
+        Some(token) => {
+            return Err(vec![TokenStreamError {
+                message: format!("expected type alias name. found {}", token),
+            }])
+        }
+        None => {
+            return Err(vec![TokenStreamError {
+                message: "expected type alias name".to_owned(),
+            }])
+        }
+    };
+
+    ensure_token(tokens, Token::AssignmentOperator)?;
+
+    let Some(type_token) = tokens.pop_front() else {
+        return Err(vec![TokenStreamError {
+            message: "expected aliased type".to_owned(),
+        }]);
+    };
+    let aliased_type = take_type(type_token, tokens, &[])?;
+
+    ensure_token(tokens, Token::SemiColon)?;
+
+    Ok((name, aliased_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{
+            builders::ast_builder::AstBuilder,
+            node::{FunctionReturnType, FunctionType, Type},
+        },
+        token_stream::token::Token,
+    };
+
+    /// type Count = uint;
+    #[test]
+    fn type_alias() {
+        let tokens = vec![
+            Token::TypeAliasKeyword,
+            Token::Identifier("Count".into()),
+            Token::AssignmentOperator,
+            Token::TypeKeyword(Type::UInt),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().type_alias("Count", Type::UInt);
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// type Counter = fn(Count) -> Count;
+    #[test]
+    fn type_alias_of_function_type() {
+        let tokens = vec![
+            Token::TypeAliasKeyword,
+            Token::Identifier("Counter".into()),
+            Token::AssignmentOperator,
+            Token::FnKeyword,
+            Token::LeftParenthesis,
+            Token::Identifier("Count".into()),
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::Identifier("Count".into()),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().type_alias(
+            "Counter",
+            Type::Function(FunctionType {
+                parameters: vec![Type::Named("Count".into())],
+                return_type: Box::new(FunctionReturnType::Type(Type::Named("Count".into()))),
+            }),
+        );
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// type Count uint;
+    #[test]
+    fn type_alias_missing_assignment() {
+        let tokens = vec![
+            Token::TypeAliasKeyword,
+            Token::Identifier("Count".into()),
+            Token::TypeKeyword(Type::UInt),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(
+            matches!(result, Err(e) if e[0].message == "Expected AssignmentOperator, found TypeKeyword(UInt)")
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use crate::ast::{
+    node::{FunctionReturnType, FunctionType, Type},
+    symbol::Symbol,
+};
+
+use super::token::{ensure_token, Token, TokenStreamError, Tokens};
+
+/// take a type that begins with `first_token`. Either a `Token::TypeKeyword`, a function type, ie `fn(uint, boolean) -> uint`,
+/// a tuple type, ie `(uint, boolean)`, the name of one of the `type_parameters` in scope, or the name of a type alias
+pub(super) fn take_type(
+    first_token: Token,
+    tokens: &mut Tokens,
+    type_parameters: &[Symbol],
+) -> Result<Type, Vec<TokenStreamError>> {
+    match first_token {
+        Token::TypeKeyword(type_) => Ok(type_),
+        Token::FnKeyword => take_function_type(tokens, type_parameters),
+        Token::LeftParenthesis => take_tuple_type(tokens, type_parameters),
+        Token::Identifier(name) if type_parameters.contains(&name) => Ok(Type::Generic(name)),
+        Token::Identifier(name) => Ok(Type::Named(name)),
+        token => Err(vec![TokenStreamError {
+            message: format!("expected type, found {}", token),
+        }]),
+    }
+}
+
+/// check if the token can begin a type
+pub(super) fn is_type_start(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::TypeKeyword(_) | Token::FnKeyword | Token::LeftParenthesis | Token::Identifier(_)
+    )
+}
+
+/// take the type parameters of a generic function, ie `<T, U>`. The `<` should already have been taken
+pub(super) fn take_type_parameters(
+    tokens: &mut Tokens,
+) -> Result<Vec<Symbol>, Vec<TokenStreamError>> {
+    let mut type_parameters = Vec::new();
+    let mut found_comma = false;
+
+    loop {
+        match tokens.pop_front() {
+            None => {
+                return Err(vec![TokenStreamError {
+                    message: "expected type parameter or >".to_owned(),
+                }])
+            }
+            Some(Token::RightAngle) if !found_comma && !type_parameters.is_empty() => break,
+            Some(Token::Comma) if !found_comma && !type_parameters.is_empty() => {
+                found_comma = true;
+            }
+            Some(Token::Identifier(name)) if found_comma || type_parameters.is_empty() => {
+                if type_parameters.contains(&name) {
+                    return Err(vec![TokenStreamError {
+                        message: format!("type parameter {} is declared more than once", name),
+                    }]);
+                }
+                type_parameters.push(name);
+                found_comma = false;
+            }
+            Some(token) => {
+                return Err(vec![TokenStreamError {
+                    message: format!("expected type parameter, ',', or >, found {}", token),
+                }])
+            }
+        }
+    }
+
+    Ok(type_parameters)
+}
+
+fn take_function_type(
+    tokens: &mut Tokens,
+    type_parameters: &[Symbol],
+) -> Result<Type, Vec<TokenStreamError>> {
+    ensure_token(tokens, Token::LeftParenthesis)?;
+
+    let mut parameters = Vec::new();
+    let mut found_comma = false;
+
+    loop {
+        match tokens.pop_front() {
+            None => {
+                return Err(vec![TokenStreamError {
+                    message: "expected type or )".to_owned(),
+                }])
+            }
+            Some(Token::RightParenthesis) if !found_comma => break,
+            Some(Token::Comma) if !found_comma && !parameters.is_empty() => {
+                found_comma = true;
+            }
+            Some(token) if is_type_start(&token) && (found_comma || parameters.is_empty()) => {
+                parameters.push(take_type(token, tokens, type_parameters)?);
+                found_comma = false;
+            }
+            Some(token) => {
+                return Err(vec![TokenStreamError {
+                    message: format!("expected type, ',', or ), found {}", token),
+                }])
+            }
+        }
+    }
+
+    let return_type = match tokens.pop_front() {
+        Some(Token::FunctionSignitureSplitter) => match tokens.pop_front() {
+            None => {
+                return Err(vec![TokenStreamError {
+                    message: "Expected return type".to_owned(),
+                }])
+            }
+            Some(token) => FunctionReturnType::Type(take_type(token, tokens, type_parameters)?),
+        },
+        Some(token) => {
+            tokens.push_front(token);
+            FunctionReturnType::Void
+        }
+        None => FunctionReturnType::Void,
+    };
+
+    Ok(Type::Function(FunctionType {
+        parameters,
+        return_type: Box::new(return_type),
+    }))
+}
+
+/// take a tuple type, ie `(uint, boolean)`. The `(` should already have been taken
+fn take_tuple_type(
+    tokens: &mut Tokens,
+    type_parameters: &[Symbol],
+) -> Result<Type, Vec<TokenStreamError>> {
+    let mut element_types = Vec::new();
+    let mut found_comma = false;
+
+    loop {
+        match tokens.pop_front() {
+            None => {
+                return Err(vec![TokenStreamError {
+                    message: "expected type or )".to_owned(),
+                }])
+            }
+            Some(Token::RightParenthesis) if !found_comma && element_types.len() > 1 => break,
+            Some(Token::RightParenthesis) if !found_comma => {
+                return Err(vec![TokenStreamError {
+                    message: "a tuple type needs at least two element types".to_owned(),
+                }])
+            }
+            Some(Token::Comma) if !found_comma && !element_types.is_empty() => {
+                found_comma = true;
+            }
+            Some(token) if is_type_start(&token) && (found_comma || element_types.is_empty()) => {
+                element_types.push(take_type(token, tokens, type_parameters)?);
+                found_comma = false;
+            }
+            Some(token) => {
+                return Err(vec![TokenStreamError {
+                    message: format!("expected type, ',', or ), found {}", token),
+                }])
+            }
+        }
+    }
+
+    Ok(Type::Tuple(element_types))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::node::{FunctionReturnType, FunctionType, Type},
+        token_stream::token::{Token, Tokens},
+    };
+
+    use super::take_type;
+
+    #[test]
+    fn take_type_keyword() {
+        let mut tokens = Tokens::default();
+
+        let result = take_type(Token::TypeKeyword(Type::UInt), &mut tokens, &[]);
+
+        assert!(matches!(result, Ok(Type::UInt)));
+    }
+
+    /// fn(uint, boolean) -> uint
+    #[test]
+    fn take_function_type() {
+        let mut tokens = Tokens::from(vec![
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::Comma,
+            Token::TypeKeyword(Type::Boolean),
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::TypeKeyword(Type::UInt),
+            Token::Identifier("my_var".into()),
+        ]);
+
+        let result = take_type(Token::FnKeyword, &mut tokens, &[]);
+
+        let expected = Type::Function(FunctionType {
+            parameters: vec![Type::UInt, Type::Boolean],
+            return_type: Box::new(FunctionReturnType::Type(Type::UInt)),
+        });
+
+        assert!(matches!(result, Ok(function_type) if function_type == expected));
+        assert_eq!(
+            tokens,
+            Tokens::from(vec![Token::Identifier("my_var".into())])
+        );
+    }
+
+    /// fn(fn(uint))
+    #[test]
+    fn take_void_function_type_with_function_parameter() {
+        let mut tokens = Tokens::from(vec![
+            Token::LeftParenthesis,
+            Token::FnKeyword,
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::RightParenthesis,
+            Token::RightParenthesis,
+        ]);
+
+        let result = take_type(Token::FnKeyword, &mut tokens, &[]);
+
+        let expected = Type::Function(FunctionType {
+            parameters: vec![Type::Function(FunctionType {
+                parameters: vec![Type::UInt],
+                return_type: Box::new(FunctionReturnType::Void),
+            })],
+            return_type: Box::new(FunctionReturnType::Void),
+        });
+
+        assert!(matches!(result, Ok(function_type) if function_type == expected));
+    }
+
+    /// (uint, fn(uint) -> boolean)
+    #[test]
+    fn take_tuple_type() {
+        let mut tokens = Tokens::from(vec![
+            Token::TypeKeyword(Type::UInt),
+            Token::Comma,
+            Token::FnKeyword,
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::RightParenthesis,
+            Token::FunctionSignitureSplitter,
+            Token::TypeKeyword(Type::Boolean),
+            Token::RightParenthesis,
+            Token::Identifier("my_var".into()),
+        ]);
+
+        let result = take_type(Token::LeftParenthesis, &mut tokens, &[]);
+
+        let expected = Type::Tuple(vec![
+            Type::UInt,
+            Type::Function(FunctionType {
+                parameters: vec![Type::UInt],
+                return_type: Box::new(FunctionReturnType::Type(Type::Boolean)),
+            }),
+        ]);
+
+        assert!(matches!(result, Ok(tuple_type) if tuple_type == expected));
+        assert_eq!(
+            tokens,
+            Tokens::from(vec![Token::Identifier("my_var".into())])
+        );
+    }
+
+    /// (uint)
+    #[test]
+    fn take_tuple_type_single_element() {
+        let mut tokens = Tokens::from(vec![
+            Token::TypeKeyword(Type::UInt),
+            Token::RightParenthesis,
+        ]);
+
+        let result = take_type(Token::LeftParenthesis, &mut tokens, &[]);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "a tuple type needs at least two element types")
+        );
+    }
+
+    #[test]
+    fn take_function_type_missing_comma() {
+        let mut tokens = Tokens::from(vec![
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::TypeKeyword(Type::UInt),
+            Token::RightParenthesis,
+        ]);
+
+        let result = take_type(Token::FnKeyword, &mut tokens, &[]);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "expected type, ',', or ), found TypeKeyword(UInt)")
+        );
+    }
+}
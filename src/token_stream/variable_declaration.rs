@@ -1,23 +1,28 @@
-use std::collections::VecDeque;
-
 use crate::ast::{
     builders::variable_declaration_builder::VariableDeclarationBuilder,
     node::{Node, VariableDeclarationType},
+    symbol::Symbol,
 };
 
 use super::{
     expression::create_expression,
-    token::{Token, TokenStreamError},
+    token::{Token, TokenStreamError, Tokens},
 };
 
 pub(super) fn try_create_variable_declaration(
     var_decl_type: VariableDeclarationType,
-    mut tokens: VecDeque<Token>,
+    mut tokens: Tokens,
 ) -> Result<impl FnOnce(VariableDeclarationBuilder) -> Node, Vec<TokenStreamError>> {
-    let Some(Token::Identifier(name)) = tokens.pop_front() else {
-        return Err(vec![TokenStreamError {
-            message: "expected variable identifier".to_owned(),
-        }]);
+    let target = match tokens.pop_front() {
+        Some(Token::Identifier(name)) => DeclarationTarget::Variable(name),
+        Some(Token::LeftParenthesis) => {
+            DeclarationTarget::Destructure(take_destructuring_pattern(&mut tokens)?)
+        }
+        _ => {
+            return Err(vec![TokenStreamError {
+                message: "expected variable identifier".to_owned(),
+            }])
+        }
     };
 
     if !matches!(tokens.pop_front(), Some(Token::AssignmentOperator)) {
@@ -37,11 +42,51 @@ pub(super) fn try_create_variable_declaration(
                 var_decl_builder = var_decl_builder.declare_type(var_type);
             }
         }
-        var_decl_builder = var_decl_builder.name(&name);
+        var_decl_builder = match target {
+            DeclarationTarget::Variable(name) => var_decl_builder.name(name),
+            DeclarationTarget::Destructure(names) => var_decl_builder.destructure(&names),
+        };
         var_decl_builder.with_assignment(expression_fn)
     })
 }
 
+/// what a variable declaration declares
+enum DeclarationTarget {
+    Variable(Symbol),
+    /// a variable for each element of a tuple
+    Destructure(Vec<Symbol>),
+}
+
+/// take the names of the variables in a destructuring declaration, ie `(quotient, remainder)`.
+/// The `(` should already have been taken
+fn take_destructuring_pattern(tokens: &mut Tokens) -> Result<Vec<Symbol>, Vec<TokenStreamError>> {
+    let mut names = Vec::new();
+
+    loop {
+        let Some(Token::Identifier(name)) = tokens.pop_front() else {
+            return Err(vec![TokenStreamError {
+                message: "expected variable identifier".to_owned(),
+            }]);
+        };
+        names.push(name);
+
+        match tokens.pop_front() {
+            Some(Token::Comma) => {}
+            Some(Token::RightParenthesis) if names.len() > 1 => return Ok(names),
+            Some(Token::RightParenthesis) => {
+                return Err(vec![TokenStreamError {
+                    message: "a destructuring declaration needs at least two variables".to_owned(),
+                }])
+            }
+            _ => {
+                return Err(vec![TokenStreamError {
+                    message: "expected , or )".to_owned(),
+                }])
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -54,7 +99,7 @@ mod tests {
     fn infer_boolean_variable_declaration_from_token_stream() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
             Token::TrueKeyword,
             Token::SemiColon,
@@ -74,14 +119,111 @@ mod tests {
         assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
     }
 
+    /// infer (quotient, remainder) = divide(7, 2);
+    #[test]
+    fn destructuring_variable_declaration() {
+        let tokens = vec![
+            Token::InferKeyword,
+            Token::LeftParenthesis,
+            Token::Identifier("quotient".into()),
+            Token::Comma,
+            Token::Identifier("remainder".into()),
+            Token::RightParenthesis,
+            Token::AssignmentOperator,
+            Token::Identifier("divide".into()),
+            Token::LeftParenthesis,
+            Token::UIntValue(7),
+            Token::Comma,
+            Token::UIntValue(2),
+            Token::RightParenthesis,
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().statement(|statement| {
+            statement.var_declaration(|var_decl| {
+                var_decl
+                    .infer_type()
+                    .destructure(&["quotient", "remainder"])
+                    .with_assignment(|value| {
+                        value.function_call(|function_call| {
+                            function_call
+                                .function_id("divide")
+                                .parameter(|_| 7.into())
+                                .parameter(|_| 2.into())
+                                .build()
+                        })
+                    })
+            })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// (uint, boolean) pair = (1, true);
+    #[test]
+    fn tuple_variable_declaration() {
+        let tokens = vec![
+            Token::LeftParenthesis,
+            Token::TypeKeyword(Type::UInt),
+            Token::Comma,
+            Token::TypeKeyword(Type::Boolean),
+            Token::RightParenthesis,
+            Token::Identifier("pair".into()),
+            Token::AssignmentOperator,
+            Token::LeftParenthesis,
+            Token::UIntValue(1),
+            Token::Comma,
+            Token::TrueKeyword,
+            Token::RightParenthesis,
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        let expected = AstBuilder::default().statement(|statement| {
+            statement.var_declaration(|var_decl| {
+                var_decl
+                    .declare_type(Type::Tuple(vec![Type::UInt, Type::Boolean]))
+                    .name("pair")
+                    .with_assignment(|value| {
+                        value.tuple(vec![Box::new(|_| 1.into()), Box::new(|_| true.into())])
+                    })
+            })
+        });
+
+        assert!(matches!(result, Ok(ast_builder) if ast_builder == expected));
+    }
+
+    /// infer (single) = pair;
+    #[test]
+    fn destructuring_variable_declaration_single_variable() {
+        let tokens = vec![
+            Token::InferKeyword,
+            Token::LeftParenthesis,
+            Token::Identifier("single".into()),
+            Token::RightParenthesis,
+            Token::AssignmentOperator,
+            Token::Identifier("pair".into()),
+            Token::SemiColon,
+        ];
+
+        let result = AstBuilder::from_token_stream(tokens);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "a destructuring declaration needs at least two variables")
+        );
+    }
+
     /// boolean my_var = my_other_var;
     #[test]
     fn type_decl_variable_declaration_assign_variable_name() {
         let tokens = vec![
             Token::TypeKeyword(Type::Boolean),
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_other_var".to_owned()),
+            Token::Identifier("my_other_var".into()),
             Token::SemiColon,
         ];
 
@@ -104,9 +246,9 @@ mod tests {
     fn var_declaration_greater_than_operatior_with_variable_name() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("other_var".to_owned()),
+            Token::Identifier("other_var".into()),
             Token::RightAngle,
             Token::UIntValue(11),
             Token::SemiColon,
@@ -138,9 +280,9 @@ mod tests {
     fn var_declaration_greater_than_with_function_call() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_var".to_owned()),
+            Token::Identifier("my_var".into()),
             Token::AssignmentOperator,
-            Token::Identifier("my_function".to_owned()),
+            Token::Identifier("my_function".into()),
             Token::LeftParenthesis,
             Token::RightParenthesis,
             Token::RightAngle,
@@ -191,9 +333,9 @@ mod tests {
     fn variable_declaration_missing_assignment_operator() {
         let tokens = vec![
             Token::InferKeyword,
-            Token::Identifier("my_variable".to_owned()),
+            Token::Identifier("my_variable".into()),
             Token::TrueKeyword,
-            Token::SemiColon
+            Token::SemiColon,
         ];
 
         let result = AstBuilder::from_token_stream(tokens);
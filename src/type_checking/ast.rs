@@ -1,45 +1,185 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::{ast::node::Ast, evaluation::intrinsics::get_intrinsic_functions};
+use crate::{
+    ast::node::{Ast, Function},
+    evaluation::intrinsics::get_intrinsic_functions,
+};
 
-use super::{nodes::node::type_check_nodes, TypeCheckingError};
+use super::{
+    global::{check_assignments, Declaration},
+    nodes::node::type_check_nodes,
+    FunctionEffects, TypeCheckingError,
+};
 
 impl Ast {
-    pub fn type_check(&self) -> Result<(), Vec<TypeCheckingError>> {
+    /// type check the ast with the functions, constants and globals of the `imports` also visible.
+    /// Imports are expected to be type checked as part of their own module
+    pub fn type_check(&self, imports: &Ast) -> Result<(), Vec<TypeCheckingError>> {
         let intrinsic_functions = get_intrinsic_functions();
 
         let functions: HashMap<_, _> = self
             .functions
             .iter()
-            .map(|(id, function)| (id.clone(), function.clone()))
+            .map(|(id, function)| (*id, function.clone()))
             .chain(intrinsic_functions)
+            .chain(
+                imports
+                    .functions
+                    .iter()
+                    .map(|(id, function)| (*id, function.clone())),
+            )
             .collect();
 
-        let function_errors = functions
+        let global_names: HashSet<_> = imports
+            .globals
+            .iter()
+            .chain(&self.globals)
+            .map(|global| global.name)
+            .collect();
+
+        let mut variable_types: HashMap<_, _> = imports
+            .constants
+            .iter()
+            .map(|constant| (constant.name, constant.const_type.clone()))
+            .collect();
+
+        let effects = FunctionEffects::new(&functions);
+
+        // each constant can only use the constants declared before it
+        let mut declaration_errors = Vec::new();
+        for constant in &self.constants {
+            if let Err(errors) =
+                constant.type_check(&functions, &variable_types, &effects, &global_names)
+            {
+                declaration_errors.extend(errors);
+            }
+
+            variable_types.insert(constant.name, constant.const_type.clone());
+        }
+
+        variable_types.extend(
+            imports
+                .globals
+                .iter()
+                .map(|global| (global.name, global.global_type.clone())),
+        );
+
+        // globals are initialised in order, after the constants and the globals of imported modules
+        for (index, global) in self.globals.iter().enumerate() {
+            let uninitialised = self.globals[index..]
+                .iter()
+                .map(|global| global.name)
+                .collect();
+
+            if let Err(errors) = global.type_check(&functions, &variable_types, &uninitialised) {
+                declaration_errors.extend(errors);
+            }
+
+            variable_types.insert(global.name, global.global_type.clone());
+        }
+
+        let function_errors = self
+            .functions
             .values()
-            .filter_map(|function| function.type_check(&functions).err())
+            .filter_map(|function| {
+                function
+                    .type_check_with_captures(&functions, &variable_types)
+                    .err()
+            })
             .flat_map(|x| x);
 
-        let body_errors: Vec<_> = type_check_nodes(&self.nodes, &functions, &HashMap::new(), None)
-            .err()
-            .unwrap_or_default()
+        let mut body_errors: Vec<_> = declaration_errors
             .into_iter()
+            .chain(
+                type_check_nodes(&self.nodes, &functions, &variable_types, None)
+                    .err()
+                    .unwrap_or_default(),
+            )
             .chain(function_errors)
             .collect();
 
+        self.check_assignments(imports, &mut body_errors);
+        self.check_pure_functions(&effects, &mut body_errors);
+
         if body_errors.is_empty() {
             Ok(())
         } else {
             Err(body_errors)
         }
     }
+
+    /// check that the functions declared `pure` can't have side effects, including through the functions they call
+    fn check_pure_functions(&self, effects: &FunctionEffects, errors: &mut Vec<TypeCheckingError>) {
+        let mut pure_functions: Vec<_> = self
+            .functions
+            .values()
+            .filter_map(|function| match function {
+                Function::CustomFunction {
+                    id,
+                    name,
+                    span,
+                    pure: true,
+                    ..
+                } => Some((id, name, span)),
+                _ => None,
+            })
+            .collect();
+        pure_functions.sort_by_key(|(id, _, span)| (span.start_line, id.0.as_str()));
+
+        for (id, name, span) in pure_functions {
+            if let Some(explanation) = effects.explain(id) {
+                errors.push(TypeCheckingError {
+                    message: format!(
+                        "function `{}` at line {} is declared pure, but it {}",
+                        name, span.start_line, explanation
+                    ),
+                });
+            }
+        }
+    }
+
+    /// check that only mutable globals are assigned to
+    fn check_assignments(&self, imports: &Ast, errors: &mut Vec<TypeCheckingError>) {
+        let declarations: HashMap<_, _> = imports
+            .constants
+            .iter()
+            .chain(&self.constants)
+            .map(|constant| (constant.name, Declaration::Constant))
+            .chain(imports.globals.iter().chain(&self.globals).map(|global| {
+                (
+                    global.name,
+                    Declaration::Global {
+                        mutable: global.mutable,
+                    },
+                )
+            }))
+            .collect();
+
+        check_assignments(&self.nodes, &HashSet::new(), &declarations, errors);
+
+        for function in self.functions.values() {
+            if let Function::CustomFunction {
+                parameters, body, ..
+            } = function
+            {
+                let parameter_names = parameters
+                    .iter()
+                    .map(|parameter| parameter.name().to_owned())
+                    .collect();
+
+                check_assignments(body, &parameter_names, &declarations, errors);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use crate::ast::{
         builders::ast_builder::AstBuilder,
-        node::{FunctionParameter, Type},
+        node::{Ast, Function, FunctionId, FunctionParameter, FunctionReturnType, Span, Type},
     };
 
     #[test]
@@ -49,7 +189,7 @@ mod tests {
                 fn_decl
                     .parameters(vec![FunctionParameter::FunctionParameter {
                         param_type: Type::Boolean,
-                        param_name: "my_param".to_owned(),
+                        param_name: "my_param".into(),
                     }])
                     .return_type(Type::Boolean)
                     .name("my_function")
@@ -78,7 +218,7 @@ mod tests {
             })
             .build();
 
-        let result = ast.type_check();
+        let result = ast.type_check(&Ast::default());
 
         assert!(matches!(result, Ok(())));
     }
@@ -90,7 +230,7 @@ mod tests {
                 fn_decl
                     .parameters(vec![FunctionParameter::FunctionParameter {
                         param_type: Type::Boolean,
-                        param_name: "my_param".to_owned(),
+                        param_name: "my_param".into(),
                     }])
                     .return_type(Type::Boolean)
                     .name("my_function")
@@ -106,8 +246,302 @@ mod tests {
             })
             .build();
 
-        let result = ast.type_check();
+        let result = ast.type_check(&Ast::default());
 
         assert!(matches!(result, Err(e) if e.len() == 2));
     }
+
+    #[test]
+    fn type_check_ast_imported_functions() {
+        let imported_function = Function::CustomFunction {
+            id: FunctionId("lib.bch::helper".into()),
+            name: "helper".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Type(Type::UInt),
+            // imported functions are not type checked again
+            body: Vec::new(),
+            span: Span::default(),
+            pure: false,
+        };
+
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.function_call(|fn_call| {
+                    fn_call
+                        .function_id("print")
+                        .parameter(|parameter| {
+                            parameter.function_call(|fn_call| {
+                                fn_call
+                                    .function_id("lib.bch::helper")
+                                    .no_parameters()
+                                    .build()
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build();
+
+        let imports = Ast {
+            functions: HashMap::from_iter([(*imported_function.id(), imported_function)]),
+            ..Ast::default()
+        };
+
+        let result = ast.type_check(&imports);
+
+        assert!(matches!(result, Ok(())));
+        assert!(ast.type_check(&Ast::default()).is_err());
+    }
+
+    #[test]
+    fn type_check_ast_constants_visible_in_functions() {
+        let ast = AstBuilder::default()
+            .constant(|constant| {
+                constant
+                    .name("LIMIT")
+                    .const_type(Type::UInt)
+                    .value(|expression| expression.value_literal(10.into()))
+            })
+            .function_declaration(|fn_decl| {
+                fn_decl
+                    .parameters(Vec::new())
+                    .return_type(Type::UInt)
+                    .name("limit")
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.return_value(|expression| expression.variable("LIMIT"))
+                        })
+                        .build()
+                    })
+            })
+            .build();
+
+        let result = ast.type_check(&Ast::default());
+
+        assert!(matches!(result, Ok(())));
+    }
+
+    #[test]
+    fn type_check_ast_constant_uses_later_constant() {
+        let ast = AstBuilder::default()
+            .constant(|constant| {
+                constant
+                    .name("FIRST")
+                    .const_type(Type::UInt)
+                    .value(|expression| expression.variable("SECOND"))
+            })
+            .constant(|constant| {
+                constant
+                    .name("SECOND")
+                    .const_type(Type::UInt)
+                    .value(|expression| expression.value_literal(10.into()))
+            })
+            .build();
+
+        let result = ast.type_check(&Ast::default());
+
+        assert!(matches!(result, Err(e) if e.len() == 1));
+    }
+
+    #[test]
+    fn type_check_ast_globals_visible_in_functions() {
+        let ast = AstBuilder::default()
+            .global(|global| {
+                global
+                    .name("count")
+                    .global_type(Type::UInt)
+                    .mutable()
+                    .value(|expression| expression.value_literal(0.into()))
+            })
+            .function_declaration(|fn_decl| {
+                fn_decl
+                    .parameters(Vec::new())
+                    .return_type(Type::UInt)
+                    .name("increment")
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.assignment("count", |expression| {
+                                expression.operation(|operation| {
+                                    operation.plus(|left| left.variable("count"), |_| 1.into())
+                                })
+                            })
+                        })
+                        .statement(|statement| {
+                            statement.return_value(|expression| expression.variable("count"))
+                        })
+                        .build()
+                    })
+            })
+            .build();
+
+        let result = ast.type_check(&Ast::default());
+
+        assert!(matches!(result, Ok(())));
+    }
+
+    #[test]
+    fn type_check_ast_constant_uses_global() {
+        let ast = AstBuilder::default()
+            .global(|global| {
+                global
+                    .name("count")
+                    .global_type(Type::UInt)
+                    .value(|expression| expression.value_literal(0.into()))
+            })
+            .constant(|constant| {
+                constant
+                    .name("LIMIT")
+                    .const_type(Type::UInt)
+                    .value(|expression| expression.variable("count"))
+            })
+            .build();
+
+        let result = ast.type_check(&Ast::default());
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Could not find variable with name count")
+        );
+    }
+
+    #[test]
+    fn type_check_ast_pure_function() {
+        let ast = AstBuilder::default()
+            .function_declaration(|fn_decl| {
+                fn_decl
+                    .name("log")
+                    .parameters(Vec::new())
+                    .void()
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.function_call(|fn_call| {
+                                fn_call
+                                    .function_id("print")
+                                    .parameter(|_| 1.into())
+                                    .line(3)
+                                    .build()
+                            })
+                        })
+                        .build()
+                    })
+            })
+            .function_declaration(|fn_decl| {
+                fn_decl
+                    .name("report")
+                    .parameters(Vec::new())
+                    .void()
+                    .span(5, 9)
+                    .pure()
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.function_call(|fn_call| {
+                                fn_call.function_id("log").no_parameters().line(7).build()
+                            })
+                        })
+                        .build()
+                    })
+            })
+            .function_declaration(|fn_decl| {
+                fn_decl
+                    .name("one")
+                    .parameters(Vec::new())
+                    .pure()
+                    .return_type(Type::UInt)
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.return_value(|expression| expression.value_literal(1.into()))
+                        })
+                        .build()
+                    })
+            })
+            .build();
+
+        let result = ast.type_check(&Ast::default());
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "function `report` at line 5 is declared pure, but it calls log at line 7, which calls print at line 3")
+        );
+    }
+
+    #[test]
+    fn type_check_ast_constant_calls_pure_function_reading_global() {
+        let ast = AstBuilder::default()
+            .global(|global| {
+                global
+                    .name("count")
+                    .global_type(Type::UInt)
+                    .value(|value| value.value_literal(1.into()))
+            })
+            .function_declaration(|fn_decl| {
+                fn_decl
+                    .name("read_count")
+                    .parameters(Vec::new())
+                    .pure()
+                    .return_type(Type::UInt)
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.return_value(|expression| expression.variable("count"))
+                        })
+                        .build()
+                    })
+            })
+            .constant(|constant| {
+                constant
+                    .name("COUNT")
+                    .const_type(Type::UInt)
+                    .value(|value| {
+                        value.function_call(|call| {
+                            call.function_id("read_count").no_parameters().build()
+                        })
+                    })
+            })
+            .build();
+
+        let result = ast.type_check(&Ast::default());
+
+        // the function is pure, but the global it reads isn't known at compile time
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "The value of constant COUNT must be known at compile time, but it calls read_count, which reads the global count")
+        );
+    }
+
+    #[test]
+    fn type_check_ast_local_variable_redeclaring_global() {
+        let ast = AstBuilder::default()
+            .global(|global| {
+                global
+                    .name("total")
+                    .global_type(Type::UInt)
+                    .mutable()
+                    .value(|value| value.value_literal(1.into()))
+            })
+            .function_declaration(|fn_decl| {
+                fn_decl
+                    .name("reset")
+                    .parameters(Vec::new())
+                    .void()
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.var_declaration(|declaration| {
+                                declaration
+                                    .infer_type()
+                                    .name("total")
+                                    .with_assignment(|value| value.value_literal(3.into()))
+                            })
+                        })
+                        .statement(|statement| {
+                            statement.assignment("total", |value| value.value_literal(5.into()))
+                        })
+                        .build()
+                    })
+            })
+            .build();
+
+        let result = ast.type_check(&Ast::default());
+
+        // the failed declaration doesn't make the assignment to the global look like one to a local variable
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Variable total is already defined")
+        );
+    }
 }
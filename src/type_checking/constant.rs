@@ -0,0 +1,225 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    node::{Constant, Function, FunctionId, Type},
+    symbol::Symbol,
+};
+
+use super::{
+    purity::{find_global_read, Effects},
+    verify_type, FunctionEffects, TypeCheckingError,
+};
+
+impl Constant {
+    /// type check the constant's initialiser, with only the `constants` declared before it visible.
+    /// The initialiser is evaluated at compile time, so it can only call pure functions, and they can't read `globals`
+    pub fn type_check(
+        &self,
+        functions: &HashMap<FunctionId, Function>,
+        constants: &HashMap<Symbol, Type>,
+        effects: &FunctionEffects,
+        globals: &HashSet<Symbol>,
+    ) -> Result<(), Vec<TypeCheckingError>> {
+        self.value.type_check(functions, constants)?;
+
+        verify_type(
+            self.value.get_type(functions, constants),
+            self.const_type.clone(),
+        )
+        .map_err(|err| vec![err])?;
+
+        let mut calls = Effects::default();
+        calls.collect_expression(&self.value);
+
+        if let Some(call) = calls
+            .calls
+            .into_iter()
+            .find(|call| !effects.is_pure(&call.function_id))
+        {
+            return Err(vec![TypeCheckingError {
+                message: format!(
+                    "The value of constant {} must be known at compile time, but it calls {}, which is not a pure function",
+                    self.name, call.function_id
+                ),
+            }]);
+        }
+
+        match find_global_read(&self.value, functions, globals) {
+            Some((function_id, global)) => Err(vec![TypeCheckingError {
+                message: format!(
+                    "The value of constant {} must be known at compile time, but it calls {}, which reads the global {}",
+                    self.name, function_id, global
+                ),
+            }]),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::{
+        ast::node::{
+            BinaryOperation, Constant, Expression, Function, FunctionCall, FunctionId,
+            FunctionReturnType, Node, Operation, Span, Type,
+        },
+        evaluation::intrinsics::get_intrinsic_functions,
+        type_checking::FunctionEffects,
+    };
+
+    #[test]
+    fn type_check_constant_success() {
+        let constant = Constant {
+            name: "DOUBLE_LIMIT".into(),
+            const_type: Type::UInt,
+            value: Expression::Operation(Operation::Binary {
+                operation: BinaryOperation::Plus,
+                left: Box::new(Expression::VariableAccess("LIMIT".into())),
+                right: Box::new(Expression::VariableAccess("LIMIT".into())),
+            }),
+            line: 0,
+        };
+
+        let result = constant.type_check(
+            &HashMap::new(),
+            &HashMap::from_iter([("LIMIT".into(), Type::UInt)]),
+            &FunctionEffects::default(),
+            &HashSet::new(),
+        );
+
+        assert!(matches!(result, Ok(())));
+    }
+
+    #[test]
+    fn type_check_constant_incorrect_type() {
+        let constant = Constant {
+            name: "LIMIT".into(),
+            const_type: Type::Boolean,
+            value: 10.into(),
+            line: 0,
+        };
+
+        let result = constant.type_check(
+            &HashMap::new(),
+            &HashMap::new(),
+            &FunctionEffects::default(),
+            &HashSet::new(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Expected type to be Boolean, but found UInt")
+        );
+    }
+
+    #[test]
+    fn type_check_constant_pure_function_call() {
+        let function = Function::CustomFunction {
+            id: FunctionId("square".into()),
+            name: "square".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![Node::FunctionReturn {
+                return_value: Some(4.into()),
+                line: 0,
+            }],
+            span: Span::default(),
+            pure: false,
+        };
+        let functions = HashMap::from_iter([(*function.id(), function)]);
+
+        let constant = Constant {
+            name: "AREA".into(),
+            const_type: Type::UInt,
+            value: Expression::FunctionCall(FunctionCall {
+                function_id: FunctionId("square".into()),
+                parameters: Vec::new(),
+                line: 0,
+                slot: None,
+            }),
+            line: 0,
+        };
+
+        let result = constant.type_check(
+            &functions,
+            &HashMap::new(),
+            &FunctionEffects::new(&functions),
+            &HashSet::new(),
+        );
+
+        assert!(matches!(result, Ok(())));
+    }
+
+    #[test]
+    fn type_check_constant_impure_function_call() {
+        let function = Function::CustomFunction {
+            id: FunctionId("noisy".into()),
+            name: "noisy".into(),
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![
+                Node::FunctionCall(FunctionCall {
+                    function_id: FunctionId("print".into()),
+                    parameters: vec![1.into()],
+                    line: 0,
+                    slot: None,
+                }),
+                Node::FunctionReturn {
+                    return_value: Some(4.into()),
+                    line: 0,
+                },
+            ],
+            span: Span::default(),
+            pure: false,
+        };
+        let functions: HashMap<_, _> = get_intrinsic_functions()
+            .into_iter()
+            .chain([(*function.id(), function)])
+            .collect();
+
+        let constant = Constant {
+            name: "LIMIT".into(),
+            const_type: Type::UInt,
+            value: Expression::FunctionCall(FunctionCall {
+                function_id: FunctionId("noisy".into()),
+                parameters: Vec::new(),
+                line: 0,
+                slot: None,
+            }),
+            line: 0,
+        };
+
+        let result = constant.type_check(
+            &functions,
+            &HashMap::new(),
+            &FunctionEffects::new(&functions),
+            &HashSet::new(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "The value of constant LIMIT must be known at compile time, but it calls noisy, which is not a pure function")
+        );
+    }
+
+    #[test]
+    fn type_check_constant_later_constant_not_visible() {
+        let constant = Constant {
+            name: "FIRST".into(),
+            const_type: Type::UInt,
+            value: Expression::VariableAccess("SECOND".into()),
+            line: 0,
+        };
+
+        let result = constant.type_check(
+            &HashMap::new(),
+            &HashMap::new(),
+            &FunctionEffects::default(),
+            &HashSet::new(),
+        );
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::ast::node::{IfStatement, Node};
+
+/// whether every path through the nodes ends in a return statement. The check expressions of if statements aren't
+/// evaluated, so `if (true) { return 1; }` on its own doesn't return on every path
+pub(super) fn always_returns(nodes: &[Node]) -> bool {
+    nodes.iter().any(node_always_returns)
+}
+
+/// whether every path through the node ends in a return statement, so the nodes after it can't run
+pub(super) fn node_always_returns(node: &Node) -> bool {
+    match node {
+        Node::FunctionReturn { .. } => true,
+        Node::IfStatement(if_statement) => if_statement.always_returns(),
+        Node::VariableDeclaration { .. }
+        | Node::DestructuringDeclaration { .. }
+        | Node::FunctionCall(_)
+        | Node::Assignment { .. } => false,
+    }
+}
+
+impl IfStatement {
+    /// an if statement returns on every path when it has an else block, and every one of its blocks does
+    fn always_returns(&self) -> bool {
+        let Some(else_block) = &self.else_block else {
+            return false;
+        };
+
+        always_returns(&self.if_block)
+            && self
+                .else_if_blocks
+                .iter()
+                .all(|else_if_block| always_returns(&else_if_block.block))
+            && always_returns(else_block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{
+        builders::{ast_builder::AstBuilder, if_statement_builder::IfStatementBuilder},
+        node::Ast,
+    };
+
+    use super::always_returns;
+
+    fn return_void(ast: AstBuilder) -> Ast {
+        ast.statement(|statement| statement.return_void()).build()
+    }
+
+    fn if_statement(if_statement: IfStatementBuilder) -> IfStatementBuilder {
+        if_statement.check_expression(|check| check.value_literal(true.into()))
+    }
+
+    #[test]
+    fn always_returns_empty() {
+        assert!(!always_returns(&[]));
+    }
+
+    #[test]
+    fn always_returns_return_before_end() {
+        let ast = AstBuilder::default()
+            .statement(|statement| statement.return_void())
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.value_literal(1.into()))
+                        .build()
+                })
+            })
+            .build();
+
+        assert!(always_returns(&ast.nodes));
+    }
+
+    #[test]
+    fn always_returns_if_without_else() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_block| if_statement(if_block).body(return_void).build())
+            })
+            .build();
+
+        assert!(!always_returns(&ast.nodes));
+    }
+
+    #[test]
+    fn always_returns_every_branch() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_block| {
+                    if_statement(if_block)
+                        .body(return_void)
+                        .else_if(|check| check.value_literal(false.into()), return_void)
+                        .else_block(return_void)
+                        .build()
+                })
+            })
+            .build();
+
+        assert!(always_returns(&ast.nodes));
+    }
+
+    #[test]
+    fn always_returns_else_if_missing_return() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_block| {
+                    if_statement(if_block)
+                        .body(return_void)
+                        .else_if(|check| check.value_literal(false.into()), AstBuilder::build)
+                        .else_block(return_void)
+                        .build()
+                })
+            })
+            .build();
+
+        assert!(!always_returns(&ast.nodes));
+    }
+
+    #[test]
+    fn always_returns_nested_if() {
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.if_statement(|if_block| {
+                    if_statement(if_block)
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.if_statement(|nested| {
+                                    if_statement(nested)
+                                        .body(return_void)
+                                        .else_block(return_void)
+                                        .build()
+                                })
+                            })
+                            .build()
+                        })
+                        .else_block(return_void)
+                        .build()
+                })
+            })
+            .build();
+
+        assert!(always_returns(&ast.nodes));
+    }
+}
@@ -1,36 +1,92 @@
 use std::collections::HashMap;
 
-use crate::ast::node::{
-    Expression, Function, FunctionId, FunctionReturnType, Type,
+use crate::ast::{
+    node::{Expression, Function, FunctionId, FunctionReturnType, Lambda, Type},
+    symbol::Symbol,
 };
 
 use super::TypeCheckingError;
 
 impl Expression {
+    /// the type of the value of the expression, or `None` if it doesn't have one, ie a call to a void function.
+    /// Expressions that fail to type check have the unknown type, so their mistake is only reported once
     pub fn get_type(
         &self,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &HashMap<String, Type>,
+        local_variables: &HashMap<Symbol, Type>,
     ) -> Option<Type> {
         match self {
             Expression::ValueLiteral(value) => Some(value.get_type()),
             Expression::FunctionCall(function_call) => {
-                let function = &functions[&function_call.function_id];
-
-                match function.return_type() {
+                let return_type = match local_variables
+                    .get(&function_call.function_id.0)
+                    .map(Type::resolved)
+                {
+                    Some(Type::Function(function_type)) => {
+                        function_type.return_type.as_ref().clone()
+                    }
+                    Some(_) => return Some(Type::Unknown),
+                    None => {
+                        let Some(function) = functions.get(&function_call.function_id) else {
+                            return Some(Type::Unknown);
+                        };
+                        let param_types: Vec<Option<Type>> = function_call
+                            .parameters
+                            .iter()
+                            .map(|param| param.get_type(functions, local_variables))
+                            .collect();
+                        let Ok(bindings) = function.infer_type_parameters(&param_types) else {
+                            return Some(Type::Unknown);
+                        };
+
+                        function.return_type().substitute(&bindings)
+                    }
+                };
+
+                match return_type {
                     FunctionReturnType::Void => None,
-                    FunctionReturnType::Type(return_type) => Some(return_type.clone()),
+                    FunctionReturnType::Type(return_type) => Some(return_type),
                 }
             }
             Expression::Operation(operation) => Some(operation.get_type()),
-            Expression::VariableAccess(var_name) => local_variables.get(var_name).copied(),
+            Expression::VariableAccess(var_name)
+            | Expression::LocalVariableAccess { name: var_name, .. } => Some(
+                local_variables
+                    .get(var_name)
+                    .cloned()
+                    .or_else(|| functions.get(&FunctionId(*var_name))?.get_type())
+                    .unwrap_or(Type::Unknown),
+            ),
+            Expression::Lambda(lambda) => lambda.to_function().get_type(),
+            Expression::Tuple(elements) => Some(Type::Tuple(
+                elements
+                    .iter()
+                    .map(|element| {
+                        element
+                            .get_type(functions, local_variables)
+                            .unwrap_or(Type::Unknown)
+                    })
+                    .collect(),
+            )),
+            Expression::TupleAccess { tuple, index } => {
+                let element_type = match tuple
+                    .get_type(functions, local_variables)
+                    .as_ref()
+                    .map(Type::resolved)
+                {
+                    Some(Type::Tuple(element_types)) => element_types.get(*index).cloned(),
+                    _ => None,
+                };
+
+                Some(element_type.unwrap_or(Type::Unknown))
+            }
         }
     }
 
     pub fn type_check(
         &self,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &HashMap<String, Type>,
+        local_variables: &HashMap<Symbol, Type>,
     ) -> Result<(), Vec<TypeCheckingError>> {
         match self {
             Expression::ValueLiteral(_) => Ok(()),
@@ -38,32 +94,128 @@ impl Expression {
                 function_call.type_check(functions, local_variables)
             }
             Expression::Operation(operation) => operation.type_check(functions, local_variables),
-            Expression::VariableAccess(var_name) => {
-                type_check_variable_access(var_name, local_variables).map_err(|err| vec![err])
+            Expression::VariableAccess(var_name)
+            | Expression::LocalVariableAccess { name: var_name, .. } => {
+                type_check_variable_access(*var_name, functions, local_variables)
+                    .map_err(|err| vec![err])
+            }
+            Expression::Lambda(lambda) => type_check_lambda(lambda, functions, local_variables),
+            Expression::Tuple(elements) => type_check_tuple(elements, functions, local_variables),
+            Expression::TupleAccess { tuple, index } => {
+                type_check_tuple_access(tuple, *index, functions, local_variables)
             }
         }
     }
 }
 
 fn type_check_variable_access(
-    var_name: &str,
-    local_variables: &HashMap<String, Type>,
+    var_name: Symbol,
+    functions: &HashMap<FunctionId, Function>,
+    local_variables: &HashMap<Symbol, Type>,
 ) -> Result<(), TypeCheckingError> {
-    local_variables
-        .get(var_name)
-        .ok_or_else(|| TypeCheckingError {
-            message: format!("Could not find variable with name {}", var_name),
-        })
-        .map(|_| ())
+    if local_variables.contains_key(&var_name) {
+        return Ok(());
+    }
+
+    // functions can be used as values
+    match functions.get(&FunctionId(var_name)) {
+        Some(function) if function.get_type().is_none() => Err(TypeCheckingError {
+            message: format!(
+                "{} is a generic function, so cannot be used as a value",
+                var_name
+            ),
+        }),
+        Some(_) => Ok(()),
+        None => Err(TypeCheckingError::unknown_variable(
+            var_name,
+            functions,
+            local_variables,
+        )),
+    }
+}
+
+/// type check each element of the tuple, which all need to have a value
+fn type_check_tuple(
+    elements: &[Expression],
+    functions: &HashMap<FunctionId, Function>,
+    local_variables: &HashMap<Symbol, Type>,
+) -> Result<(), Vec<TypeCheckingError>> {
+    let mut errors = Vec::new();
+
+    for (index, element) in elements.iter().enumerate() {
+        match element.type_check(functions, local_variables) {
+            Err(element_errors) => errors.extend(element_errors),
+            Ok(()) if element.get_type(functions, local_variables).is_none() => {
+                errors.push(TypeCheckingError {
+                    message: format!("Element {} of the tuple has no value", index),
+                })
+            }
+            Ok(()) => {}
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// check that the accessed expression is a tuple with an element at the `index`
+fn type_check_tuple_access(
+    tuple: &Expression,
+    index: usize,
+    functions: &HashMap<FunctionId, Function>,
+    local_variables: &HashMap<Symbol, Type>,
+) -> Result<(), Vec<TypeCheckingError>> {
+    tuple.type_check(functions, local_variables)?;
+
+    let message = match tuple.get_type(functions, local_variables) {
+        None => "Cannot access an element of a void value".to_owned(),
+        Some(Type::Unknown) => return Ok(()),
+        Some(tuple_type) => match tuple_type.resolved() {
+            Type::Tuple(element_types) if index < element_types.len() => return Ok(()),
+            Type::Tuple(element_types) => format!(
+                "Cannot access element {} of {}, as it only has {} elements",
+                index,
+                tuple_type,
+                element_types.len()
+            ),
+            _ => format!(
+                "Cannot access element {} of {}, as it isn't a tuple",
+                index, tuple_type
+            ),
+        },
+    };
+
+    Err(vec![TypeCheckingError { message }])
+}
+
+/// type check the lambda body, with the enclosing local variables captured
+fn type_check_lambda(
+    lambda: &Lambda,
+    functions: &HashMap<FunctionId, Function>,
+    local_variables: &HashMap<Symbol, Type>,
+) -> Result<(), Vec<TypeCheckingError>> {
+    let function = lambda.to_function();
+
+    // the lambda needs to be in the functions map so that return statements can find it
+    let mut functions = functions.clone();
+    functions.insert(*function.id(), function.clone());
+
+    function.type_check_with_captures(&functions, local_variables)
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
-    use crate::ast::node::{
-        Expression, Function, FunctionCall, FunctionId, FunctionParameter,
-        FunctionReturnType, Operation, Type, UnaryOperation,
+    use crate::ast::{
+        builders::ast_builder::AstBuilder,
+        node::{
+            BinaryOperation, Expression, Function, FunctionCall, FunctionId, FunctionParameter,
+            FunctionReturnType, FunctionType, Lambda, Node, Operation, Span, Type, UnaryOperation,
+        },
     };
 
     use super::type_check_variable_access;
@@ -91,18 +243,23 @@ mod tests {
     #[test]
     fn expression_get_type_function_call_return_value() {
         let expression = Expression::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: Vec::new(),
+            line: 0,
+            slot: None,
         });
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Type(Type::UInt),
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
@@ -114,18 +271,23 @@ mod tests {
     #[test]
     fn expression_get_type_function_call_void() {
         let expression = Expression::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: Vec::new(),
+            line: 0,
+            slot: None,
         });
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
@@ -136,9 +298,9 @@ mod tests {
 
     #[test]
     fn expression_get_type_variable_access() {
-        let expression = Expression::VariableAccess("my_var".to_owned());
+        let expression = Expression::VariableAccess("my_var".into());
 
-        let local_variables = HashMap::from_iter([("my_var".to_owned(), Type::Boolean)]);
+        let local_variables = HashMap::from_iter([("my_var".into(), Type::Boolean)]);
 
         let result = expression.get_type(&HashMap::new(), &local_variables);
 
@@ -147,18 +309,18 @@ mod tests {
 
     #[test]
     fn expression_get_type_variable_access_missing_variable() {
-        let expression = Expression::VariableAccess("my_var".to_owned());
+        let expression = Expression::VariableAccess("my_var".into());
 
         let result = expression.get_type(&HashMap::new(), &HashMap::new());
 
-        assert_eq!(result, None);
+        assert_eq!(result, Some(Type::Unknown));
     }
 
     #[test]
     fn test_type_check_variable_access_success() {
-        let variables = HashMap::from_iter([("my_var".to_owned(), Type::Boolean)]);
+        let variables = HashMap::from_iter([("my_var".into(), Type::Boolean)]);
 
-        let result = type_check_variable_access("my_var", &variables);
+        let result = type_check_variable_access("my_var".into(), &HashMap::new(), &variables);
 
         assert!(matches!(result, Ok(_)));
     }
@@ -166,13 +328,40 @@ mod tests {
     #[test]
     fn test_type_check_variable_access_failure() {
         let variables = HashMap::new();
-        let result = type_check_variable_access("my_var", &variables);
+        let result = type_check_variable_access("my_var".into(), &HashMap::new(), &variables);
 
         assert!(
             matches!(result, Err(e) if e.message == "Could not find variable with name my_var")
         );
     }
 
+    #[test]
+    fn test_type_check_variable_access_suggestion() {
+        let variables = HashMap::from_iter([("limit".into(), Type::UInt)]);
+        let result = type_check_variable_access("limt".into(), &HashMap::new(), &variables);
+
+        assert!(
+            matches!(result, Err(e) if e.message == "Could not find variable with name limt\nhelp: did you mean \"limit\"?")
+        );
+    }
+
+    #[test]
+    fn test_type_check_variable_access_one_letter_no_suggestion() {
+        let functions = AstBuilder::default()
+            .function_declaration(|function| {
+                function
+                    .name("f")
+                    .parameters(Vec::new())
+                    .void()
+                    .body(AstBuilder::build)
+            })
+            .build()
+            .functions;
+        let result = type_check_variable_access("x".into(), &functions, &HashMap::new());
+
+        assert!(matches!(result, Err(e) if e.message == "Could not find variable with name x"));
+    }
+
     #[test]
     fn expression_type_check_value_literal() {
         let expression: Expression = true.into();
@@ -185,18 +374,23 @@ mod tests {
     #[test]
     fn expression_type_check_function_call_successful() {
         let expression = Expression::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: Vec::new(),
+            line: 0,
+            slot: None,
         });
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Type(Type::Boolean),
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
@@ -208,21 +402,26 @@ mod tests {
     #[test]
     fn expression_type_check_function_call_failure() {
         let expression = Expression::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: Vec::new(),
+            line: 0,
+            slot: None,
         });
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::Boolean,
-                    param_name: "param".to_owned(),
+                    param_name: "param".into(),
                 }],
                 return_type: FunctionReturnType::Type(Type::Boolean),
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
@@ -257,9 +456,9 @@ mod tests {
 
     #[test]
     fn expression_type_check_variable_access_successful() {
-        let expression = Expression::VariableAccess("my_var".to_owned());
+        let expression = Expression::VariableAccess("my_var".into());
 
-        let local_variables = HashMap::from_iter([("my_var".to_owned(), Type::Boolean)]);
+        let local_variables = HashMap::from_iter([("my_var".into(), Type::Boolean)]);
 
         let result = expression.type_check(&HashMap::new(), &local_variables);
 
@@ -269,10 +468,170 @@ mod tests {
     #[test]
 
     fn expression_type_check_variable_access_failure() {
-        let expression = Expression::VariableAccess("my_var".to_owned());
+        let expression = Expression::VariableAccess("my_var".into());
 
         let result = expression.type_check(&HashMap::new(), &HashMap::new());
 
         assert!(matches!(result, Err(_)));
     }
+
+    #[test]
+    fn expression_get_type_function_name() {
+        let expression = Expression::VariableAccess("my_function".into());
+
+        let functions = HashMap::from_iter([(
+            FunctionId("my_function".into()),
+            Function::CustomFunction {
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
+                parameters: vec![(Type::UInt, "value".into()).into()],
+                return_type: FunctionReturnType::Type(Type::Boolean),
+                body: Vec::new(),
+                span: Span::default(),
+                pure: false,
+            },
+        )]);
+
+        let result = expression.get_type(&functions, &HashMap::new());
+
+        assert_eq!(
+            result,
+            Some(Type::Function(FunctionType {
+                parameters: vec![Type::UInt],
+                return_type: Box::new(FunctionReturnType::Type(Type::Boolean)),
+            }))
+        );
+    }
+
+    #[test]
+    fn expression_get_type_lambda() {
+        let expression = Expression::Lambda(Lambda {
+            parameters: vec![(Type::Boolean, "value".into()).into()],
+            return_type: FunctionReturnType::Void,
+            body: Vec::new(),
+            span: Span::default(),
+            resolved: None,
+        });
+
+        let result = expression.get_type(&HashMap::new(), &HashMap::new());
+
+        assert_eq!(
+            result,
+            Some(Type::Function(FunctionType {
+                parameters: vec![Type::Boolean],
+                return_type: Box::new(FunctionReturnType::Void),
+            }))
+        );
+    }
+
+    #[test]
+    fn expression_type_check_lambda_captured_variable() {
+        let expression = Expression::Lambda(Lambda {
+            parameters: vec![(Type::UInt, "value".into()).into()],
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![Node::FunctionReturn {
+                return_value: Some(Expression::Operation(Operation::Binary {
+                    operation: BinaryOperation::Plus,
+                    left: Box::new(Expression::VariableAccess("value".into())),
+                    right: Box::new(Expression::VariableAccess("offset".into())),
+                })),
+                line: 0,
+            }],
+            span: Span::default(),
+            resolved: None,
+        });
+
+        let local_variables = HashMap::from_iter([("offset".into(), Type::UInt)]);
+
+        let result = expression.type_check(&HashMap::new(), &local_variables);
+
+        assert!(matches!(result, Ok(())));
+    }
+
+    #[test]
+    fn expression_type_check_lambda_incorrect_return_type() {
+        let expression = Expression::Lambda(Lambda {
+            parameters: Vec::new(),
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![Node::FunctionReturn {
+                return_value: Some(true.into()),
+                line: 0,
+            }],
+            span: Span::default(),
+            resolved: None,
+        });
+
+        let result = expression.type_check(&HashMap::new(), &HashMap::new());
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "anonymous function expects a return type of UInt, but you returned a Boolean value")
+        );
+    }
+
+    #[test]
+    fn test_type_check_variable_access_generic_function() {
+        let functions = HashMap::from_iter([(
+            FunctionId("print".into()),
+            Function::Intrinsic {
+                id: FunctionId("print".into()),
+                name: "print".into(),
+                type_parameters: vec!["T".into()],
+                parameters: vec![FunctionParameter::FunctionParameter {
+                    param_type: Type::Generic("T".into()),
+                    param_name: "value".into(),
+                }],
+                return_type: FunctionReturnType::Void,
+            },
+        )]);
+
+        let result = type_check_variable_access("print".into(), &functions, &HashMap::new());
+
+        assert!(
+            matches!(result, Err(e) if e.message == "print is a generic function, so cannot be used as a value")
+        );
+    }
+
+    #[test]
+    fn expression_get_type_tuple_access() {
+        let expression = Expression::TupleAccess {
+            tuple: Box::new(Expression::Tuple(vec![1.into(), true.into()])),
+            index: 1,
+        };
+
+        let result = expression.get_type(&HashMap::new(), &HashMap::new());
+
+        assert_eq!(result, Some(Type::Boolean));
+    }
+
+    #[test]
+    fn type_check_tuple_access_out_of_range() {
+        let expression = Expression::TupleAccess {
+            tuple: Box::new(Expression::VariableAccess("pair".into())),
+            index: 2,
+        };
+
+        let result = expression.type_check(
+            &HashMap::new(),
+            &HashMap::from_iter([("pair".into(), Type::Tuple(vec![Type::UInt, Type::Boolean]))]),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Cannot access element 2 of (UInt, Boolean), as it only has 2 elements")
+        );
+    }
+
+    #[test]
+    fn type_check_tuple_access_not_tuple() {
+        let expression = Expression::TupleAccess {
+            tuple: Box::new(1.into()),
+            index: 0,
+        };
+
+        let result = expression.type_check(&HashMap::new(), &HashMap::new());
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Cannot access element 0 of UInt, as it isn't a tuple")
+        );
+    }
 }
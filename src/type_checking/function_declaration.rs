@@ -1,52 +1,69 @@
 use std::collections::HashMap;
 
-use crate::ast::node::{Function, FunctionId, FunctionParameter, FunctionReturnType};
+use crate::ast::{
+    node::{Function, FunctionId, FunctionReturnType, FunctionType, Type},
+    symbol::Symbol,
+};
 
-use super::{nodes::node::type_check_nodes, TypeCheckingError};
+use super::{control_flow::always_returns, nodes::node::type_check_nodes, TypeCheckingError};
 
 impl Function {
-    pub fn type_check(
+    /// type check the function body with `captured_variables` visible alongside the function's parameters
+    pub fn type_check_with_captures(
         &self,
         functions: &HashMap<FunctionId, Function>,
+        captured_variables: &HashMap<Symbol, Type>,
     ) -> Result<(), Vec<TypeCheckingError>> {
         let Function::CustomFunction {
             id,
+            name,
             parameters,
             body,
             return_type,
+            span,
             ..
         } = self
         else {
             return Ok(());
         };
 
-        let local_variables: HashMap<_, _> = parameters
-            .iter()
-            .filter_map(|param| match param {
-                FunctionParameter::FunctionParameter {
-                    param_type,
-                    param_name,
-                } => Some((param_name.clone(), *param_type)),
-                FunctionParameter::IntrinsicAny { .. } => None,
-            })
-            .collect();
-
-        let found_return_type =
-            match type_check_nodes(&body, functions, &local_variables, Some(&id)) {
-                Err(errors) => return Err(errors),
-                Ok(return_type) => return_type,
-            };
-
-        match (found_return_type, return_type) {
-            (None, FunctionReturnType::Type(_)) => Err(vec![TypeCheckingError {
-                message: "expected return value, but void was returned".to_owned(),
+        let mut local_variables = captured_variables.clone();
+        local_variables.extend(
+            parameters
+                .iter()
+                .map(|param| (param.name().to_owned(), param.param_type().clone())),
+        );
+
+        type_check_nodes(&body, functions, &local_variables, Some(&id))?;
+
+        // the returned values are type checked at each return statement, which leaves the paths without one
+        match return_type {
+            FunctionReturnType::Type(_) if !always_returns(body) => Err(vec![TypeCheckingError {
+                message: format!(
+                    "not all paths of {} return a value; its closing brace at line {} can be reached without a return",
+                    name, span.end_line
+                ),
             }]),
-            (Some(_), FunctionReturnType::Void) => unreachable!("return statement type checking should validate that some can't be returned from void function"),
-            // expect return type checking to happen at the return site
-            (Some(_), FunctionReturnType::Type(_)) => Ok(()),
-            (None, FunctionReturnType::Void) => Ok(()),
+            FunctionReturnType::Type(_) | FunctionReturnType::Void => Ok(()),
         }
     }
+
+    /// the type of the function when it's used as a value. Generic functions don't have a type
+    /// until their type parameters are inferred at a call site, so can't be used as values
+    pub fn get_type(&self) -> Option<Type> {
+        if !self.type_parameters().is_empty() {
+            return None;
+        }
+
+        Some(Type::Function(FunctionType {
+            parameters: self
+                .parameters()
+                .iter()
+                .map(|param| param.param_type().clone())
+                .collect(),
+            return_type: Box::new(self.return_type().clone()),
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -54,33 +71,32 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::ast::node::{
-        Expression, Function, FunctionId, FunctionParameter, FunctionReturnType, Node, Type,
+        Expression, Function, FunctionId, FunctionParameter, FunctionReturnType, IfStatement, Node,
+        Span, Type,
     };
 
     #[test]
     fn type_check_function_success() {
         let function = Function::CustomFunction {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
-            parameters: vec![
-                FunctionParameter::FunctionParameter {
-                    param_type: Type::Boolean,
-                    param_name: "my_var".to_owned(),
-                },
-                // only here to get the test coverage
-                FunctionParameter::IntrinsicAny {
-                    param_name: "intrinsic_any".to_owned(),
-                },
-            ],
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
+            parameters: vec![FunctionParameter::FunctionParameter {
+                param_type: Type::Boolean,
+                param_name: "my_var".into(),
+            }],
             return_type: FunctionReturnType::Type(Type::Boolean),
             body: vec![Node::FunctionReturn {
-                return_value: Some(Expression::VariableAccess("my_var".to_owned())),
+                return_value: Some(Expression::VariableAccess("my_var".into())),
+                line: 0,
             }],
+            span: Span::default(),
+            pure: false,
         };
 
-        let functions = HashMap::from_iter([(function.id().clone(), function.clone())]);
+        let functions = HashMap::from_iter([(*function.id(), function.clone())]);
 
-        let result = function.type_check(&functions);
+        let result = function.type_check_with_captures(&functions, &HashMap::new());
 
         assert!(matches!(result, Ok(())));
     }
@@ -88,13 +104,14 @@ mod tests {
     #[test]
     fn type_check_intrinsic_function() {
         let function = Function::Intrinsic {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: vec![],
             return_type: FunctionReturnType::Void,
         };
 
-        let result = function.type_check(&HashMap::new());
+        let result = function.type_check_with_captures(&HashMap::new(), &HashMap::new());
 
         assert!(matches!(result, Ok(())));
     }
@@ -102,21 +119,25 @@ mod tests {
     #[test]
     fn type_check_function_failure() {
         let function = Function::CustomFunction {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: vec![FunctionParameter::FunctionParameter {
                 param_type: Type::Boolean,
-                param_name: "my_var".to_owned(),
+                param_name: "my_var".into(),
             }],
             return_type: FunctionReturnType::Type(Type::Boolean),
             body: vec![Node::FunctionReturn {
                 return_value: Some(10.into()),
+                line: 0,
             }],
+            span: Span::default(),
+            pure: false,
         };
 
-        let functions = HashMap::from_iter([(function.id().clone(), function.clone())]);
+        let functions = HashMap::from_iter([(*function.id(), function.clone())]);
 
-        let result = function.type_check(&functions);
+        let result = function.type_check_with_captures(&functions, &HashMap::new());
 
         assert!(matches!(result, Err(_)));
     }
@@ -124,36 +145,116 @@ mod tests {
     #[test]
     fn type_check_missing_return_value() {
         let function = Function::CustomFunction {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: Vec::new(),
             return_type: FunctionReturnType::Type(Type::Boolean),
             body: Vec::new(),
+            span: Span {
+                start_line: 1,
+                end_line: 2,
+            },
+            pure: false,
         };
 
-        let functions = HashMap::from_iter([(function.id().clone(), function.clone())]);
+        let functions = HashMap::from_iter([(*function.id(), function.clone())]);
 
-        let result = function.type_check(&functions);
+        let result = function.type_check_with_captures(&functions, &HashMap::new());
 
         assert!(
-            matches!(dbg!(result), Err(e) if e.len() == 1 && e[0].message == "expected return value, but void was returned")
+            matches!(dbg!(result), Err(e) if e.len() == 1 && e[0].message == "not all paths of my_function return a value; its closing brace at line 2 can be reached without a return")
         )
     }
 
+    #[test]
+    fn type_check_return_in_one_branch() {
+        let function = Function::CustomFunction {
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
+            parameters: vec![FunctionParameter::FunctionParameter {
+                param_type: Type::Boolean,
+                param_name: "my_var".into(),
+            }],
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![Node::IfStatement(IfStatement {
+                check_expression: Expression::VariableAccess("my_var".into()),
+                if_block: vec![Node::FunctionReturn {
+                    return_value: Some(1.into()),
+                    line: 0,
+                }],
+                else_if_blocks: Vec::new(),
+                else_block: None,
+                line: 2,
+            })],
+            span: Span {
+                start_line: 1,
+                end_line: 5,
+            },
+            pure: false,
+        };
+
+        let functions = HashMap::from_iter([(*function.id(), function.clone())]);
+
+        let result = function.type_check_with_captures(&functions, &HashMap::new());
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "not all paths of my_function return a value; its closing brace at line 5 can be reached without a return")
+        )
+    }
+
+    #[test]
+    fn type_check_return_in_every_branch() {
+        let function = Function::CustomFunction {
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
+            parameters: vec![FunctionParameter::FunctionParameter {
+                param_type: Type::Boolean,
+                param_name: "my_var".into(),
+            }],
+            return_type: FunctionReturnType::Type(Type::UInt),
+            body: vec![Node::IfStatement(IfStatement {
+                check_expression: Expression::VariableAccess("my_var".into()),
+                if_block: vec![Node::FunctionReturn {
+                    return_value: Some(1.into()),
+                    line: 0,
+                }],
+                else_if_blocks: Vec::new(),
+                else_block: Some(vec![Node::FunctionReturn {
+                    return_value: Some(2.into()),
+                    line: 0,
+                }]),
+                line: 2,
+            })],
+            span: Span::default(),
+            pure: false,
+        };
+
+        let functions = HashMap::from_iter([(*function.id(), function.clone())]);
+
+        let result = function.type_check_with_captures(&functions, &HashMap::new());
+
+        assert!(matches!(result, Ok(())));
+    }
+
     #[test]
     fn type_check_void_no_return() {
         let function = Function::CustomFunction {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: Vec::new(),
             return_type: FunctionReturnType::Void,
             body: Vec::new(),
+            span: Span::default(),
+            pure: false,
         };
 
-        let functions = HashMap::from_iter([(function.id().clone(), function.clone())]);
-
-        let result = function.type_check(&functions);
+        let functions = HashMap::from_iter([(*function.id(), function.clone())]);
 
+        let result = function.type_check_with_captures(&functions, &HashMap::new());
 
         assert!(matches!(result, Ok(())));
     }
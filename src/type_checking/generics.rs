@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use crate::ast::{
+    node::{Function, FunctionReturnType, FunctionType, Type},
+    symbol::Symbol,
+};
+
+use super::TypeCheckingError;
+
+impl Type {
+    /// replace any type parameters with the types they are bound to
+    pub fn substitute(&self, bindings: &HashMap<Symbol, Type>) -> Type {
+        match self {
+            Type::Generic(name) => bindings.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Type::Function(function_type) => Type::Function(FunctionType {
+                parameters: function_type
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.substitute(bindings))
+                    .collect(),
+                return_type: Box::new(function_type.return_type.substitute(bindings)),
+            }),
+            Type::Tuple(element_types) => Type::Tuple(
+                element_types
+                    .iter()
+                    .map(|element_type| element_type.substitute(bindings))
+                    .collect(),
+            ),
+            // aliases are declared outside of generic functions, so can't contain type parameters
+            Type::UInt | Type::Boolean | Type::Named(_) | Type::Alias(_) | Type::Unknown => {
+                self.clone()
+            }
+        }
+    }
+
+    fn contains_type_parameter(&self) -> bool {
+        match self {
+            Type::Generic(_) => true,
+            Type::Function(function_type) => {
+                function_type
+                    .parameters
+                    .iter()
+                    .any(Type::contains_type_parameter)
+                    || matches!(function_type.return_type.as_ref(), FunctionReturnType::Type(return_type) if return_type.contains_type_parameter())
+            }
+            Type::Tuple(element_types) => element_types.iter().any(Type::contains_type_parameter),
+            Type::UInt | Type::Boolean | Type::Named(_) | Type::Alias(_) | Type::Unknown => false,
+        }
+    }
+}
+
+impl FunctionReturnType {
+    pub fn substitute(&self, bindings: &HashMap<Symbol, Type>) -> FunctionReturnType {
+        match self {
+            FunctionReturnType::Type(return_type) => {
+                FunctionReturnType::Type(return_type.substitute(bindings))
+            }
+            FunctionReturnType::Void => FunctionReturnType::Void,
+        }
+    }
+}
+
+/// bind the type parameters in `expected` by matching it against the `found` type.
+/// Structural mismatches are left to be reported when the parameter types are verified
+fn bind_type_parameters(
+    expected: &Type,
+    found: &Type,
+    bindings: &mut HashMap<Symbol, Type>,
+    function_name: Symbol,
+) -> Result<(), TypeCheckingError> {
+    match (expected.resolved(), found.resolved()) {
+        // a type parameter bound to an unknown type can't conflict, as the mistake has already been reported
+        (Type::Generic(name), _) => match bindings.get(name) {
+            Some(bound_type) if bound_type.contains_unknown() || found.contains_unknown() => Ok(()),
+            Some(bound_type) if bound_type != found => Err(TypeCheckingError {
+                message: format!(
+                    "Type parameter {} of {} was inferred as both {} and {}",
+                    name, function_name, bound_type, found
+                ),
+            }),
+            Some(_) => Ok(()),
+            None => {
+                bindings.insert(*name, found.clone());
+                Ok(())
+            }
+        },
+        (Type::Function(expected), Type::Function(found))
+            if expected.parameters.len() == found.parameters.len() =>
+        {
+            for (expected, found) in expected.parameters.iter().zip(found.parameters.iter()) {
+                bind_type_parameters(expected, found, bindings, function_name)?;
+            }
+
+            match (expected.return_type.as_ref(), found.return_type.as_ref()) {
+                (FunctionReturnType::Type(expected), FunctionReturnType::Type(found)) => {
+                    bind_type_parameters(expected, found, bindings, function_name)
+                }
+                _ => Ok(()),
+            }
+        }
+        (Type::Tuple(expected), Type::Tuple(found)) if expected.len() == found.len() => {
+            for (expected, found) in expected.iter().zip(found.iter()) {
+                bind_type_parameters(expected, found, bindings, function_name)?;
+            }
+
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+impl Function {
+    /// infer the type of each of the function's type parameters from the types of the arguments it is called with
+    pub fn infer_type_parameters(
+        &self,
+        argument_types: &[Option<Type>],
+    ) -> Result<HashMap<Symbol, Type>, Vec<TypeCheckingError>> {
+        let mut bindings = HashMap::new();
+        let mut errors = Vec::new();
+
+        if self.type_parameters().is_empty() {
+            return Ok(bindings);
+        }
+
+        for (parameter, argument_type) in self.parameters().iter().zip(argument_types) {
+            match argument_type {
+                None if parameter.param_type().contains_type_parameter() => {
+                    errors.push(TypeCheckingError {
+                        message: format!("Expected parameter {} to be present", parameter.name()),
+                    })
+                }
+                None => {}
+                Some(argument_type) => {
+                    if let Err(error) = bind_type_parameters(
+                        parameter.param_type(),
+                        argument_type,
+                        &mut bindings,
+                        self.name(),
+                    ) {
+                        errors.push(error);
+                    }
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        errors.extend(
+            self.type_parameters()
+                .iter()
+                .filter(|type_parameter| !bindings.contains_key(type_parameter))
+                .map(|type_parameter| TypeCheckingError {
+                    message: format!(
+                        "Could not infer type parameter {} of {}",
+                        type_parameter,
+                        self.name()
+                    ),
+                }),
+        );
+
+        if errors.is_empty() {
+            Ok(bindings)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::ast::{
+        builders::{
+            ast_builder::AstBuilder, function_declaration_builder::FunctionDeclarationBuilder,
+        },
+        node::{Function, FunctionId, FunctionReturnType, FunctionType, Type},
+        symbol::Symbol,
+    };
+
+    /// a function `pick` with the type parameter `T`
+    fn generic_function(
+        parameters: Vec<Type>,
+        return_type: impl FnOnce(FunctionDeclarationBuilder) -> FunctionDeclarationBuilder,
+    ) -> Function {
+        let parameters = parameters
+            .into_iter()
+            .enumerate()
+            .map(|(i, param_type)| (param_type, Symbol::intern(&format!("param_{}", i))).into())
+            .collect();
+
+        AstBuilder::default()
+            .function_declaration(|function| {
+                return_type(
+                    function
+                        .name("pick")
+                        .type_parameters(vec!["T".into()])
+                        .parameters(parameters),
+                )
+                .body(AstBuilder::build)
+            })
+            .build()
+            .functions
+            .remove(&FunctionId("pick".into()))
+            .expect("pick to be declared")
+    }
+
+    #[test]
+    fn substitute_function_type() {
+        let type_ = Type::Function(FunctionType {
+            parameters: vec![Type::Generic("T".into()), Type::Boolean],
+            return_type: Box::new(FunctionReturnType::Type(Type::Generic("T".into()))),
+        });
+
+        let result = type_.substitute(&HashMap::from_iter([("T".into(), Type::UInt)]));
+
+        assert_eq!(
+            result,
+            Type::Function(FunctionType {
+                parameters: vec![Type::UInt, Type::Boolean],
+                return_type: Box::new(FunctionReturnType::Type(Type::UInt)),
+            })
+        );
+    }
+
+    #[test]
+    fn infer_type_parameters_success() {
+        let function = generic_function(
+            vec![Type::Generic("T".into()), Type::Generic("T".into())],
+            |function| function.return_type(Type::Generic("T".into())),
+        );
+
+        let result = function.infer_type_parameters(&[Some(Type::UInt), Some(Type::UInt)]);
+
+        assert!(
+            matches!(result, Ok(bindings) if bindings == HashMap::from_iter([("T".into(), Type::UInt)]))
+        );
+    }
+
+    #[test]
+    fn infer_type_parameters_from_function_type() {
+        let function = generic_function(
+            vec![Type::Function(FunctionType {
+                parameters: Vec::new(),
+                return_type: Box::new(FunctionReturnType::Type(Type::Generic("T".into()))),
+            })],
+            |function| function.void(),
+        );
+
+        let result = function.infer_type_parameters(&[Some(Type::Function(FunctionType {
+            parameters: Vec::new(),
+            return_type: Box::new(FunctionReturnType::Type(Type::Boolean)),
+        }))]);
+
+        assert!(
+            matches!(result, Ok(bindings) if bindings == HashMap::from_iter([("T".into(), Type::Boolean)]))
+        );
+    }
+
+    #[test]
+    fn infer_type_parameters_conflict() {
+        let function = generic_function(
+            vec![Type::Generic("T".into()), Type::Generic("T".into())],
+            |function| function.void(),
+        );
+
+        let result = function.infer_type_parameters(&[Some(Type::UInt), Some(Type::Boolean)]);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Type parameter T of pick was inferred as both UInt and Boolean")
+        );
+    }
+
+    #[test]
+    fn infer_type_parameters_unknown_argument() {
+        let function = generic_function(
+            vec![Type::Generic("T".into()), Type::Generic("T".into())],
+            |function| function.void(),
+        );
+
+        let result = function.infer_type_parameters(&[Some(Type::Unknown), Some(Type::Boolean)]);
+
+        assert!(matches!(result, Ok(bindings) if bindings[&"T".into()] == Type::Unknown));
+    }
+
+    #[test]
+    fn infer_type_parameters_not_inferable() {
+        let function = generic_function(Vec::new(), |function| {
+            function.return_type(Type::Generic("T".into()))
+        });
+
+        let result = function.infer_type_parameters(&[]);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Could not infer type parameter T of pick")
+        );
+    }
+
+    #[test]
+    fn infer_type_parameters_missing_argument_type() {
+        let function =
+            generic_function(vec![Type::Generic("T".into())], |function| function.void());
+
+        let result = function.infer_type_parameters(&[None]);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Expected parameter param_0 to be present")
+        );
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    node::{Expression, Function, FunctionId, FunctionParameter, Global, Node, Operation, Type},
+    symbol::Symbol,
+};
+
+use super::{purity::Effects, verify_type, TypeCheckingError};
+
+/// how a variable visible in every scope was declared
+pub(super) enum Declaration {
+    Constant,
+    Global { mutable: bool },
+}
+
+impl Global {
+    /// type check the global's initialiser, with the constants and the globals declared before it in `variables`.
+    /// `uninitialised` holds the globals that haven't been initialised when the initialiser runs, which it can't use,
+    /// either directly or through the functions it calls
+    pub fn type_check(
+        &self,
+        functions: &HashMap<FunctionId, Function>,
+        variables: &HashMap<Symbol, Type>,
+        uninitialised: &HashSet<Symbol>,
+    ) -> Result<(), Vec<TypeCheckingError>> {
+        self.check_initialisation_order(functions, uninitialised)?;
+
+        self.value.type_check(functions, variables)?;
+
+        verify_type(
+            self.value.get_type(functions, variables),
+            self.global_type.clone(),
+        )
+        .map_err(|err| vec![err])
+    }
+
+    fn check_initialisation_order(
+        &self,
+        functions: &HashMap<FunctionId, Function>,
+        uninitialised: &HashSet<Symbol>,
+    ) -> Result<(), Vec<TypeCheckingError>> {
+        let mut effects = initialisation_effects();
+        effects.collect_expression(&self.value);
+
+        if let Some(variable) = effects
+            .variables
+            .iter()
+            .find(|variable| uninitialised.contains(*variable))
+        {
+            return Err(vec![TypeCheckingError {
+                message: format!(
+                    "The initialiser of global {} uses {} before it is initialised",
+                    self.name, variable
+                ),
+            }]);
+        }
+
+        let mut visited = HashSet::new();
+        for call in &effects.calls {
+            if let Some(variable) =
+                find_uninitialised_use(&call.function_id, functions, uninitialised, &mut visited)
+            {
+                return Err(vec![TypeCheckingError {
+                    message: format!(
+                        "The initialiser of global {} calls {}, which uses {} before it is initialised",
+                        self.name, call.function_id, variable
+                    ),
+                }]);
+            }
+        }
+
+        for function_id in function_values(&effects, functions) {
+            if let Some(variable) =
+                find_uninitialised_use(&function_id, functions, uninitialised, &mut visited)
+            {
+                return Err(vec![TypeCheckingError {
+                    message: format!(
+                        "The initialiser of global {} uses {}, which can be called before {} is initialised",
+                        self.name, function_id, variable
+                    ),
+                }]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// the effects of running an initialiser. A function value it creates can be called by a later initialiser, through
+/// the global holding it, so the bodies of lambdas are collected as if they're called
+fn initialisation_effects<'a>() -> Effects<'a> {
+    Effects {
+        lambda_bodies: true,
+        ..Effects::default()
+    }
+}
+
+/// the declared functions that are read as values, rather than called
+fn function_values(
+    effects: &Effects,
+    functions: &HashMap<FunctionId, Function>,
+) -> Vec<FunctionId> {
+    effects
+        .variables
+        .iter()
+        .map(|variable| FunctionId(*variable))
+        .filter(|function_id| functions.contains_key(function_id))
+        .collect()
+}
+
+/// find a use of one of the `uninitialised` globals by the function, or the functions it calls or uses as values
+fn find_uninitialised_use(
+    function_id: &FunctionId,
+    functions: &HashMap<FunctionId, Function>,
+    uninitialised: &HashSet<Symbol>,
+    visited: &mut HashSet<FunctionId>,
+) -> Option<Symbol> {
+    let Some(Function::CustomFunction {
+        parameters, body, ..
+    }) = functions.get(function_id)
+    else {
+        return None;
+    };
+
+    if !visited.insert(*function_id) {
+        return None;
+    }
+
+    let mut effects = initialisation_effects();
+    effects.collect_body(parameters, body);
+
+    if let Some(variable) = effects
+        .variables
+        .iter()
+        .find(|variable| uninitialised.contains(*variable))
+    {
+        return Some(*variable);
+    }
+
+    effects
+        .calls
+        .iter()
+        .map(|call| call.function_id)
+        .chain(function_values(&effects, functions))
+        .find_map(|function_id| {
+            find_uninitialised_use(&function_id, functions, uninitialised, visited)
+        })
+}
+
+/// check that each assignment in `nodes` is to a mutable global
+pub(super) fn check_assignments(
+    nodes: &[Node],
+    local_variables: &HashSet<Symbol>,
+    declarations: &HashMap<Symbol, Declaration>,
+    errors: &mut Vec<TypeCheckingError>,
+) {
+    let mut local_variables = local_variables.clone();
+
+    for node in nodes {
+        match node {
+            Node::VariableDeclaration {
+                var_name, value, ..
+            } => {
+                check_expression_assignments(value, &local_variables, declarations, errors);
+                declare_local(&mut local_variables, *var_name, declarations);
+            }
+            Node::DestructuringDeclaration {
+                var_names, value, ..
+            } => {
+                check_expression_assignments(value, &local_variables, declarations, errors);
+                for var_name in var_names {
+                    declare_local(&mut local_variables, *var_name, declarations);
+                }
+            }
+            Node::FunctionReturn { return_value, .. } => {
+                if let Some(return_value) = return_value {
+                    check_expression_assignments(
+                        return_value,
+                        &local_variables,
+                        declarations,
+                        errors,
+                    );
+                }
+            }
+            Node::FunctionCall(function_call) => {
+                for parameter in &function_call.parameters {
+                    check_expression_assignments(parameter, &local_variables, declarations, errors);
+                }
+            }
+            Node::IfStatement(if_statement) => {
+                check_expression_assignments(
+                    &if_statement.check_expression,
+                    &local_variables,
+                    declarations,
+                    errors,
+                );
+                check_assignments(
+                    &if_statement.if_block,
+                    &local_variables,
+                    declarations,
+                    errors,
+                );
+
+                for else_if_block in &if_statement.else_if_blocks {
+                    check_expression_assignments(
+                        &else_if_block.check,
+                        &local_variables,
+                        declarations,
+                        errors,
+                    );
+                    check_assignments(&else_if_block.block, &local_variables, declarations, errors);
+                }
+
+                if let Some(else_block) = &if_statement.else_block {
+                    check_assignments(else_block, &local_variables, declarations, errors);
+                }
+            }
+            Node::Assignment {
+                var_name, value, ..
+            } => {
+                check_expression_assignments(value, &local_variables, declarations, errors);
+
+                let message = if local_variables.contains(var_name) {
+                    Some(format!(
+                        "Cannot assign to {}, only mutable globals can be assigned to",
+                        var_name
+                    ))
+                } else {
+                    match declarations.get(var_name) {
+                        Some(Declaration::Constant) => {
+                            Some(format!("Cannot assign to constant {}", var_name))
+                        }
+                        Some(Declaration::Global { mutable: false }) => Some(format!(
+                            "Cannot assign to global {}, as it isn't mutable. Declare it with `global mut` to allow assignment",
+                            var_name
+                        )),
+                        // unknown variables are reported when the assignment is type checked
+                        Some(Declaration::Global { mutable: true }) | None => None,
+                    }
+                };
+
+                if let Some(message) = message {
+                    errors.push(TypeCheckingError { message });
+                }
+            }
+        }
+    }
+}
+
+/// add a declared variable to the local variables. A variable with the name of a global or constant fails to type
+/// check, so the name still refers to the global or constant, and isn't reported again when it's assigned to
+fn declare_local(
+    local_variables: &mut HashSet<Symbol>,
+    var_name: Symbol,
+    declarations: &HashMap<Symbol, Declaration>,
+) {
+    if !declarations.contains_key(&var_name) {
+        local_variables.insert(var_name);
+    }
+}
+
+fn check_expression_assignments(
+    expression: &Expression,
+    local_variables: &HashSet<Symbol>,
+    declarations: &HashMap<Symbol, Declaration>,
+    errors: &mut Vec<TypeCheckingError>,
+) {
+    match expression {
+        Expression::ValueLiteral(_)
+        | Expression::VariableAccess(_)
+        | Expression::LocalVariableAccess { .. } => {}
+        Expression::FunctionCall(function_call) => {
+            for parameter in &function_call.parameters {
+                check_expression_assignments(parameter, local_variables, declarations, errors);
+            }
+        }
+        Expression::Operation(Operation::Unary { value, .. }) => {
+            check_expression_assignments(value, local_variables, declarations, errors)
+        }
+        Expression::Operation(Operation::Binary { left, right, .. }) => {
+            check_expression_assignments(left, local_variables, declarations, errors);
+            check_expression_assignments(right, local_variables, declarations, errors);
+        }
+        Expression::Lambda(lambda) => {
+            let mut local_variables = local_variables.clone();
+            local_variables.extend(lambda.parameters.iter().map(FunctionParameter::name));
+
+            check_assignments(&lambda.body, &local_variables, declarations, errors);
+        }
+        Expression::Tuple(elements) => {
+            for element in elements {
+                check_expression_assignments(element, local_variables, declarations, errors);
+            }
+        }
+        Expression::TupleAccess { tuple, .. } => {
+            check_expression_assignments(tuple, local_variables, declarations, errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::ast::{
+        builders::{
+            ast_builder::AstBuilder, expression_builder::ExpressionBuilder,
+            global_builder::GlobalBuilder,
+        },
+        node::{Expression, Global, Type},
+    };
+
+    use super::{check_assignments, Declaration};
+
+    fn global(name: &'static str, value: impl FnOnce(ExpressionBuilder) -> Expression) -> Global {
+        GlobalBuilder::default()
+            .name(name)
+            .global_type(Type::UInt)
+            .value(value)
+    }
+
+    /// add a function that returns `value`
+    fn function_returning(
+        ast: AstBuilder,
+        name: &'static str,
+        value: impl FnOnce(ExpressionBuilder) -> Expression,
+    ) -> AstBuilder {
+        ast.function_declaration(|function| {
+            function
+                .name(name)
+                .parameters(Vec::new())
+                .return_type(Type::UInt)
+                .body(|body| {
+                    body.statement(|statement| statement.return_value(value))
+                        .build()
+                })
+        })
+    }
+
+    #[test]
+    fn type_check_global_success() {
+        let result = global("second", |value| value.variable("first")).type_check(
+            &HashMap::new(),
+            &HashMap::from_iter([("first".into(), Type::UInt)]),
+            &HashSet::from_iter(["second".into()]),
+        );
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn type_check_global_uses_later_global() {
+        let result = global("first", |value| value.variable("second")).type_check(
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashSet::from_iter(["first".into(), "second".into()]),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "The initialiser of global first uses second before it is initialised")
+        );
+    }
+
+    #[test]
+    fn type_check_global_calls_function_using_later_global() {
+        let functions = function_returning(AstBuilder::default(), "read_second", |value| {
+            value.variable("second")
+        })
+        .build()
+        .functions;
+
+        let result = global("first", |value| {
+            value.function_call(|call| call.function_id("read_second").no_parameters().build())
+        })
+        .type_check(
+            &functions,
+            &HashMap::from_iter([("second".into(), Type::UInt)]),
+            &HashSet::from_iter(["first".into(), "second".into()]),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "The initialiser of global first calls read_second, which uses second before it is initialised")
+        );
+    }
+
+    #[test]
+    fn type_check_global_function_value_using_later_global() {
+        let functions = function_returning(AstBuilder::default(), "read_second", |value| {
+            value.variable("second")
+        })
+        .build()
+        .functions;
+
+        let result = global("first", |value| value.variable("read_second")).type_check(
+            &functions,
+            &HashMap::from_iter([("second".into(), Type::UInt)]),
+            &HashSet::from_iter(["first".into(), "second".into()]),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "The initialiser of global first uses read_second, which can be called before second is initialised")
+        );
+    }
+
+    #[test]
+    fn type_check_global_lambda_using_later_global() {
+        let functions = function_returning(AstBuilder::default(), "make", |value| {
+            value.lambda(|lambda| {
+                lambda
+                    .parameters(Vec::new())
+                    .return_type(Type::UInt)
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.return_value(|value| value.variable("second"))
+                        })
+                        .build()
+                    })
+            })
+        })
+        .build()
+        .functions;
+
+        let result = global("first", |value| {
+            value.function_call(|call| call.function_id("make").no_parameters().build())
+        })
+        .type_check(
+            &functions,
+            &HashMap::from_iter([("second".into(), Type::UInt)]),
+            &HashSet::from_iter(["first".into(), "second".into()]),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "The initialiser of global first calls make, which uses second before it is initialised")
+        );
+    }
+
+    #[test]
+    fn check_assignments_to_declarations() {
+        let declarations = HashMap::from_iter([
+            ("LIMIT".into(), Declaration::Constant),
+            ("count".into(), Declaration::Global { mutable: true }),
+            ("total".into(), Declaration::Global { mutable: false }),
+        ]);
+
+        let ast = ["count", "LIMIT", "total"]
+            .into_iter()
+            .fold(AstBuilder::default(), |ast, name| {
+                ast.statement(|statement| {
+                    statement.assignment(name, |value| value.value_literal(1.into()))
+                })
+            })
+            .build();
+        let mut errors = Vec::new();
+
+        check_assignments(&ast.nodes, &HashSet::new(), &declarations, &mut errors);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].message, "Cannot assign to constant LIMIT");
+        assert_eq!(
+            errors[1].message,
+            "Cannot assign to global total, as it isn't mutable. Declare it with `global mut` to allow assignment"
+        );
+    }
+
+    #[test]
+    fn check_assignments_to_local_variable() {
+        let declarations =
+            HashMap::from_iter([("count".into(), Declaration::Global { mutable: true })]);
+
+        // the parameter shadows the global
+        let ast = AstBuilder::default()
+            .statement(|statement| {
+                statement.assignment("count", |value| value.value_literal(1.into()))
+            })
+            .build();
+        let mut errors = Vec::new();
+
+        check_assignments(
+            &ast.nodes,
+            &HashSet::from_iter(["count".into()]),
+            &declarations,
+            &mut errors,
+        );
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "Cannot assign to count, only mutable globals can be assigned to"
+        );
+    }
+}
@@ -1,22 +1,142 @@
 mod ast;
+mod constant;
+mod control_flow;
 mod expression;
 mod function_declaration;
+mod generics;
+mod global;
 mod nodes;
 mod operation;
+mod purity;
+mod suggestions;
+mod unreachable;
+mod unused;
 mod value;
 
-use crate::ast::node::Type;
+use std::collections::HashMap;
+
+use crate::ast::{
+    node::{Function, FunctionId, Span, Type},
+    scope::OutOfScopeVariable,
+    symbol::Symbol,
+};
+
+pub use self::purity::FunctionEffects;
+
+use self::suggestions::{did_you_mean, visible_names};
+
+const UNKNOWN_VARIABLE: &str = "Could not find variable with name ";
 
 #[derive(Debug)]
 pub struct TypeCheckingError {
     pub message: String,
 }
 
+/// a problem that doesn't stop the program from running, but is likely a mistake
+#[derive(Debug, PartialEq)]
+pub struct TypeCheckingWarning {
+    pub lint: Lint,
+    pub message: String,
+    /// the lines of the source file the warning is about
+    pub span: Span,
+}
+
+/// the kinds of warning, which can each be silenced
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lint {
+    UnreachableCode,
+    UnusedVariables,
+    UnusedParameters,
+    UnusedFunctions,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::UnreachableCode,
+        Lint::UnusedVariables,
+        Lint::UnusedParameters,
+        Lint::UnusedFunctions,
+    ];
+
+    /// the name used for the lint on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnreachableCode => "unreachable-code",
+            Lint::UnusedVariables => "unused-variables",
+            Lint::UnusedParameters => "unused-parameters",
+            Lint::UnusedFunctions => "unused-functions",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+impl TypeCheckingError {
+    /// an error for a variable that isn't declared, suggesting a similarly named variable or function that is
+    fn unknown_variable(
+        var_name: Symbol,
+        functions: &HashMap<FunctionId, Function>,
+        local_variables: &HashMap<Symbol, Type>,
+    ) -> Self {
+        Self {
+            message: format!(
+                "{UNKNOWN_VARIABLE}{var_name}{}",
+                did_you_mean(var_name.as_str(), visible_names(functions, local_variables))
+            ),
+        }
+    }
+
+    /// an error for a call to a function that isn't declared, suggesting a similarly named function or variable that is
+    fn unknown_function(
+        function_id: &FunctionId,
+        functions: &HashMap<FunctionId, Function>,
+        local_variables: &HashMap<Symbol, Type>,
+    ) -> Self {
+        Self {
+            message: format!(
+                "Could not find function with name {function_id}{}",
+                did_you_mean(
+                    function_id.0.as_str(),
+                    visible_names(functions, local_variables)
+                )
+            ),
+        }
+    }
+
+    /// replace an unknown variable error with a scope-aware one if the variable was declared in one of the `ended_blocks`
+    fn with_scope(self, ended_blocks: &[OutOfScopeVariable]) -> Self {
+        let Some(var_name) = self
+            .message
+            .strip_prefix(UNKNOWN_VARIABLE)
+            .and_then(|message| message.lines().next())
+        else {
+            return self;
+        };
+
+        match ended_blocks
+            .iter()
+            .rev()
+            .find(|variable| variable.name == var_name)
+        {
+            Some(variable) => Self {
+                message: variable.to_string(),
+            },
+            None => self,
+        }
+    }
+}
+
 fn verify_type(actual_type: Option<Type>, expected_type: Type) -> Result<(), TypeCheckingError> {
     match actual_type {
         None => Err(TypeCheckingError {
             message: format!("Expected type to be {}, but none was found", expected_type),
         }),
+        // unknown types come from expressions whose errors have already been reported
+        Some(found_type) if found_type.contains_unknown() || expected_type.contains_unknown() => {
+            Ok(())
+        }
         Some(found_type) if found_type != expected_type => Err(TypeCheckingError {
             message: format!(
                 "Expected type to be {}, but found {}",
@@ -57,4 +177,14 @@ mod tests {
             matches!(result, Err(e) if e.message == "Expected type to be Boolean, but found UInt")
         );
     }
+
+    #[test]
+    fn verify_type_unknown() {
+        assert!(verify_type(Some(Type::Unknown), Type::Boolean).is_ok());
+        assert!(verify_type(
+            Some(Type::Tuple(vec![Type::UInt, Type::Unknown])),
+            Type::Tuple(vec![Type::UInt, Type::Boolean])
+        )
+        .is_ok());
+    }
 }
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        node::{Expression, Function, FunctionId, Type},
+        symbol::Symbol,
+    },
+    type_checking::{verify_type, TypeCheckingError},
+};
+
+/// type check the value assigned to the variable. Whether the variable can be assigned to is checked
+/// separately, as only mutable globals can be
+pub(super) fn type_check_assignment(
+    var_name: Symbol,
+    value: &Expression,
+    functions: &HashMap<FunctionId, Function>,
+    local_variables: &HashMap<Symbol, Type>,
+) -> Result<(), Vec<TypeCheckingError>> {
+    value.type_check(functions, local_variables)?;
+
+    let Some(var_type) = local_variables.get(&var_name) else {
+        return Err(vec![TypeCheckingError::unknown_variable(
+            var_name,
+            functions,
+            local_variables,
+        )]);
+    };
+
+    verify_type(value.get_type(functions, local_variables), var_type.clone())
+        .map_err(|err| vec![err])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::ast::node::Type;
+
+    use super::type_check_assignment;
+
+    #[test]
+    fn type_check_assignment_success() {
+        let result = type_check_assignment(
+            "count".into(),
+            &1.into(),
+            &HashMap::new(),
+            &HashMap::from_iter([("count".into(), Type::UInt)]),
+        );
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn type_check_assignment_incorrect_type() {
+        let result = type_check_assignment(
+            "count".into(),
+            &true.into(),
+            &HashMap::new(),
+            &HashMap::from_iter([("count".into(), Type::UInt)]),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Expected type to be UInt, but found Boolean")
+        );
+    }
+
+    #[test]
+    fn type_check_assignment_missing_variable() {
+        let result =
+            type_check_assignment("count".into(), &1.into(), &HashMap::new(), &HashMap::new());
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Could not find variable with name count")
+        );
+    }
+}
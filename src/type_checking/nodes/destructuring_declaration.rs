@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{
+        node::{Expression, Function, FunctionId, Type, VariableDeclarationType},
+        symbol::Symbol,
+    },
+    type_checking::{verify_type, TypeCheckingError},
+};
+
+/// type check a declaration of a variable for each element of a tuple, ie `infer (quotient, remainder) = divide(7, 2);`.
+/// The value has to be a tuple with an element for each variable
+pub(super) fn type_check_destructuring_declaration(
+    var_names: &[Symbol],
+    var_type: &VariableDeclarationType,
+    value: &Expression,
+    functions: &HashMap<FunctionId, Function>,
+    local_variables: &mut HashMap<Symbol, Type>,
+) -> Result<(), Vec<TypeCheckingError>> {
+    let mut errors = Vec::new();
+
+    let value_type = value.get_type(functions, local_variables);
+
+    if let Err(value_expression_errors) = value.type_check(functions, local_variables) {
+        errors.extend(value_expression_errors);
+    }
+
+    let tuple_type = match var_type {
+        VariableDeclarationType::Infer => {
+            if value_type.is_none() {
+                errors.push(TypeCheckingError {
+                    message: format!(
+                        "cannot assign void to variables {}",
+                        var_names
+                            .iter()
+                            .map(|name| name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                });
+            }
+            value_type
+        }
+        VariableDeclarationType::Type(expected_type) => {
+            if let Err(var_error) = verify_type(value_type, expected_type.clone()) {
+                errors.push(var_error);
+            }
+            Some(expected_type.clone())
+        }
+    };
+
+    // after a mistake, each variable is still declared, with an unknown type, so its uses aren't reported as well
+    let unknown_types = || vec![Type::Unknown; var_names.len()];
+    let element_types = match tuple_type.as_ref().map(Type::resolved) {
+        Some(Type::Tuple(element_types)) if element_types.len() == var_names.len() => {
+            element_types.clone()
+        }
+        Some(Type::Tuple(element_types)) => {
+            errors.push(TypeCheckingError {
+                message: format!(
+                    "Cannot destructure {} into {} variables, as it has {} elements",
+                    tuple_type.as_ref().expect("tuple type is present"),
+                    var_names.len(),
+                    element_types.len()
+                ),
+            });
+            unknown_types()
+        }
+        // the value's mistake has already been reported
+        Some(Type::Unknown) | None => unknown_types(),
+        Some(_) => {
+            errors.push(TypeCheckingError {
+                message: format!(
+                    "Cannot destructure {} into variables, as it isn't a tuple",
+                    tuple_type.as_ref().expect("tuple type is present")
+                ),
+            });
+            unknown_types()
+        }
+    };
+
+    for (index, var_name) in var_names.iter().enumerate() {
+        if local_variables.contains_key(var_name) || var_names[..index].contains(var_name) {
+            errors.push(TypeCheckingError {
+                message: format!("Variable {var_name} is already defined"),
+            });
+        } else {
+            local_variables.insert(*var_name, element_types[index].clone());
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::ast::{
+        builders::expression_builder::ExpressionBuilder,
+        node::{Expression, Type, VariableDeclarationType},
+        symbol::Symbol,
+    };
+
+    use super::type_check_destructuring_declaration;
+
+    fn names(names: &[&str]) -> Vec<Symbol> {
+        names.iter().map(|name| Symbol::intern(name)).collect()
+    }
+
+    fn pair() -> Expression {
+        ExpressionBuilder::default().tuple(vec![
+            Box::new(|count| count.value_literal(1.into())),
+            Box::new(|enabled| enabled.value_literal(true.into())),
+        ])
+    }
+
+    #[test]
+    fn type_check_destructuring_declaration_infer() {
+        let mut local_variables = HashMap::new();
+
+        let result = type_check_destructuring_declaration(
+            &names(&["count", "enabled"]),
+            &VariableDeclarationType::Infer,
+            &pair(),
+            &HashMap::new(),
+            &mut local_variables,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(
+            local_variables,
+            HashMap::from_iter([
+                ("count".into(), Type::UInt),
+                ("enabled".into(), Type::Boolean)
+            ])
+        );
+    }
+
+    #[test]
+    fn type_check_destructuring_declaration_incorrect_type() {
+        let result = type_check_destructuring_declaration(
+            &names(&["count", "enabled"]),
+            &VariableDeclarationType::Type(Type::Tuple(vec![Type::UInt, Type::UInt])),
+            &pair(),
+            &HashMap::new(),
+            &mut HashMap::new(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Expected type to be (UInt, UInt), but found (UInt, Boolean)")
+        );
+    }
+
+    #[test]
+    fn type_check_destructuring_declaration_wrong_arity() {
+        let mut local_variables = HashMap::new();
+
+        let result = type_check_destructuring_declaration(
+            &names(&["count", "enabled", "extra"]),
+            &VariableDeclarationType::Infer,
+            &pair(),
+            &HashMap::new(),
+            &mut local_variables,
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Cannot destructure (UInt, Boolean) into 3 variables, as it has 2 elements")
+        );
+        // the variables are still declared, so their uses aren't reported too
+        assert_eq!(
+            local_variables,
+            HashMap::from_iter([
+                ("count".into(), Type::Unknown),
+                ("enabled".into(), Type::Unknown),
+                ("extra".into(), Type::Unknown)
+            ])
+        );
+    }
+
+    #[test]
+    fn type_check_destructuring_declaration_not_tuple() {
+        let result = type_check_destructuring_declaration(
+            &names(&["count", "enabled"]),
+            &VariableDeclarationType::Infer,
+            &ExpressionBuilder::default().value_literal(1.into()),
+            &HashMap::new(),
+            &mut HashMap::new(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Cannot destructure UInt into variables, as it isn't a tuple")
+        );
+    }
+
+    #[test]
+    fn type_check_destructuring_declaration_duplicate_name() {
+        let result = type_check_destructuring_declaration(
+            &names(&["count", "count"]),
+            &VariableDeclarationType::Infer,
+            &pair(),
+            &HashMap::new(),
+            &mut HashMap::new(),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Variable count is already defined")
+        );
+    }
+}
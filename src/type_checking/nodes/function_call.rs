@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
 use crate::{
-    ast::node::{Function, FunctionCall, FunctionId, FunctionParameter, Type},
+    ast::{
+        node::{Function, FunctionCall, FunctionId, FunctionType, Type},
+        symbol::Symbol,
+    },
     type_checking::{verify_type, TypeCheckingError},
 };
 
@@ -9,7 +12,7 @@ impl FunctionCall {
     pub fn type_check(
         &self,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &HashMap<String, Type>,
+        local_variables: &HashMap<Symbol, Type>,
     ) -> Result<(), Vec<TypeCheckingError>> {
         let mut errors = Vec::new();
         let function = functions.get(&self.function_id);
@@ -22,8 +25,26 @@ impl FunctionCall {
                 .flat_map(|x| x),
         );
 
-        // if the found a valid function
-        if let Some(function) = function {
+        if let Some(variable_type) = local_variables.get(&self.function_id.0) {
+            // local variables take precedence over declared functions
+            if let Type::Function(function_type) = variable_type.resolved() {
+                errors.extend(self.type_check_function_type_parameters(
+                    function_type,
+                    functions,
+                    local_variables,
+                ));
+            } else if variable_type.contains_unknown() {
+                // the variable's declaration has already been reported
+            } else {
+                errors.push(TypeCheckingError {
+                    message: format!(
+                        "{} is a {} variable, which cannot be called",
+                        self.function_id, variable_type
+                    ),
+                });
+            }
+        } else if let Some(function) = function {
+            // if the found a valid function
             let function_params = function.parameters();
 
             // check we hav ethe correct number of parameters
@@ -38,27 +59,35 @@ impl FunctionCall {
                 });
             }
 
-            // check that all of our parameters expressions have the correct type for it's corresponding parameter
-            errors.extend(
-                self.parameters
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(i, param_expression)| {
-                        function_params.get(i).map(|function_param| {
-                            (
-                                function_param,
-                                param_expression.get_type(functions, local_variables),
+            let param_types: Vec<Option<Type>> = self
+                .parameters
+                .iter()
+                .map(|param_expression| param_expression.get_type(functions, local_variables))
+                .collect();
+
+            // infer the type parameters of generic functions before checking the parameters against them
+            match function.infer_type_parameters(&param_types) {
+                Err(inference_errors) => errors.extend(inference_errors),
+                Ok(bindings) => {
+                    // check that all of our parameters expressions have the correct type for it's corresponding parameter
+                    errors.extend(function_params.iter().zip(param_types).filter_map(
+                        |(function_param, param_type)| {
+                            verify_type(
+                                param_type,
+                                function_param.param_type().substitute(&bindings),
                             )
-                        })
-                    })
-                    .map(|(function_param, param_type)| function_param.verify_type(param_type))
-                    .filter_map(|result| result.err()),
-            );
+                            .err()
+                        },
+                    ));
+                }
+            }
         } else {
             // we didn't find a valid function, add an error
-            errors.push(TypeCheckingError {
-                message: format!("Could not find function with name {}", self.function_id),
-            });
+            errors.push(TypeCheckingError::unknown_function(
+                &self.function_id,
+                functions,
+                local_variables,
+            ));
         };
 
         if errors.is_empty() {
@@ -67,21 +96,41 @@ impl FunctionCall {
             Err(errors)
         }
     }
-}
 
-impl FunctionParameter {
-    pub fn verify_type(&self, found_type: Option<Type>) -> Result<(), TypeCheckingError> {
-        match self {
-            FunctionParameter::IntrinsicAny { .. } if found_type.is_none() => {
-                Err(TypeCheckingError {
-                    message: format!("Expected parameter {} to be present", self.name()),
-                })
-            }
-            FunctionParameter::IntrinsicAny { .. } => Ok(()),
-            FunctionParameter::FunctionParameter { param_type, .. } => {
-                verify_type(found_type, *param_type)
-            }
+    /// check the parameter expressions against the function type of the variable being called
+    fn type_check_function_type_parameters(
+        &self,
+        function_type: &FunctionType,
+        functions: &HashMap<FunctionId, Function>,
+        local_variables: &HashMap<Symbol, Type>,
+    ) -> Vec<TypeCheckingError> {
+        let mut errors = Vec::new();
+
+        if self.parameters.len() != function_type.parameters.len() {
+            errors.push(TypeCheckingError {
+                message: format!(
+                    "{} expects {} parameter(s), but you provided {}",
+                    self.function_id,
+                    function_type.parameters.len(),
+                    self.parameters.len()
+                ),
+            });
         }
+
+        errors.extend(
+            self.parameters
+                .iter()
+                .zip(function_type.parameters.iter())
+                .filter_map(|(param_expression, param_type)| {
+                    verify_type(
+                        param_expression.get_type(functions, local_variables),
+                        param_type.clone(),
+                    )
+                    .err()
+                }),
+        );
+
+        errors
     }
 }
 
@@ -91,74 +140,33 @@ mod tests {
 
     use crate::ast::node::{
         BinaryOperation, Expression, Function, FunctionCall, FunctionId, FunctionParameter,
-        FunctionReturnType, Node, Operation, Type,
+        FunctionReturnType, FunctionType, Node, Operation, Span, Type,
     };
 
-    #[test]
-    fn function_parameter_verify_success() {
-        let function_parameter = FunctionParameter::FunctionParameter {
-            param_type: Type::Boolean,
-            param_name: "hi".to_owned(),
-        };
-
-        let result = function_parameter.verify_type(Some(Type::Boolean));
-
-        assert!(matches!(result, Ok(_)));
-    }
-
-    #[test]
-    fn function_parameter_verify_intrinsic_any_success() {
-        let function_parameter = FunctionParameter::IntrinsicAny {
-            param_name: "hi".to_owned(),
-        };
-
-        let result = function_parameter.verify_type(Some(Type::UInt));
-
-        assert!(matches!(result, Ok(())));
-    }
-
-    #[test]
-    fn function_parameter_verify_failure() {
-        let function_parameter = FunctionParameter::FunctionParameter {
-            param_type: Type::Boolean,
-            param_name: "hi".to_owned(),
-        };
-
-        let result = function_parameter.verify_type(Some(Type::UInt));
-
-        assert!(matches!(result, Err(_)));
-    }
-
-    #[test]
-    fn function_parameter_verify_intrinsic_any_failure() {
-        let function_parameter = FunctionParameter::IntrinsicAny {
-            param_name: "hi".to_owned(),
-        };
-
-        let result = function_parameter.verify_type(None);
-
-        assert!(matches!(result, Err(_)));
-    }
-
     #[test]
     fn function_call_type_check_success() {
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::Boolean,
-                    param_name: "hi".to_owned(),
+                    param_name: "hi".into(),
                 }],
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
         let function_call = Node::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: vec![true.into()],
+            line: 0,
+            slot: None,
         });
 
         let result = function_call.type_check(&functions, &mut HashMap::new(), None);
@@ -169,28 +177,33 @@ mod tests {
     #[test]
     fn function_call_type_check_failure() {
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![
                     FunctionParameter::FunctionParameter {
                         param_type: Type::Boolean,
-                        param_name: "hi".to_owned(),
+                        param_name: "hi".into(),
                     },
                     FunctionParameter::FunctionParameter {
                         param_type: Type::UInt,
-                        param_name: "hi_2".to_owned(),
+                        param_name: "hi_2".into(),
                     },
                 ],
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
         let function_call = Node::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: vec![10.into(), true.into()],
+            line: 0,
+            slot: None,
         });
 
         let result = function_call.type_check(&functions, &mut HashMap::new(), None);
@@ -201,8 +214,10 @@ mod tests {
     #[test]
     fn function_call_type_check_missing_function() {
         let function_call = Node::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: vec![10.into()],
+            line: 0,
+            slot: None,
         });
 
         let result = function_call.type_check(&HashMap::new(), &mut HashMap::new(), None);
@@ -210,25 +225,60 @@ mod tests {
         assert!(matches!(result, Err(_)))
     }
 
+    #[test]
+    fn function_call_type_check_missing_function_suggestion() {
+        let functions = HashMap::from_iter([(
+            FunctionId("fibonnacci".into()),
+            Function::CustomFunction {
+                id: FunctionId("fibonnacci".into()),
+                name: "fibonnacci".into(),
+                type_parameters: Vec::new(),
+                parameters: Vec::new(),
+                return_type: FunctionReturnType::Void,
+                body: Vec::new(),
+                span: Span::default(),
+                pure: false,
+            },
+        )]);
+
+        let function_call = Node::FunctionCall(FunctionCall {
+            function_id: FunctionId("fibonacci".into()),
+            parameters: Vec::new(),
+            line: 0,
+            slot: None,
+        });
+
+        let result = function_call.type_check(&functions, &mut HashMap::new(), None);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Could not find function with name fibonacci\nhelp: did you mean \"fibonnacci\"?")
+        )
+    }
+
     #[test]
     fn function_call_type_check_incorrect_parameters_number() {
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::Boolean,
-                    param_name: "hi".to_owned(),
+                    param_name: "hi".into(),
                 }],
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
         let function_call = Node::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: vec![10.into(), true.into()],
+            line: 0,
+            slot: None,
         });
 
         let result = function_call.type_check(&functions, &mut HashMap::new(), None);
@@ -239,30 +289,177 @@ mod tests {
     #[test]
     fn function_call_type_check_parameter_expressions() {
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::UInt,
-                    param_name: "hi".to_owned(),
+                    param_name: "hi".into(),
                 }],
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
         let function_call = Node::FunctionCall(FunctionCall {
-            function_id: FunctionId("my_function".to_owned()),
+            function_id: FunctionId("my_function".into()),
             parameters: vec![Expression::Operation(Operation::Binary {
                 operation: BinaryOperation::Plus,
                 left: Box::new(true.into()),
                 right: Box::new(10.into()),
             })],
+            line: 0,
+            slot: None,
         });
 
         let result = function_call.type_check(&functions, &mut HashMap::new(), None);
 
         assert!(matches!(result, Err(_)));
     }
+
+    #[test]
+    fn function_call_type_check_function_variable() {
+        let local_variables = HashMap::from_iter([(
+            "my_function".into(),
+            Type::Function(FunctionType {
+                parameters: vec![Type::UInt],
+                return_type: Box::new(FunctionReturnType::Void),
+            }),
+        )]);
+
+        let function_call = Node::FunctionCall(FunctionCall {
+            function_id: FunctionId("my_function".into()),
+            parameters: vec![10.into()],
+            line: 0,
+            slot: None,
+        });
+
+        let result = function_call.type_check(&HashMap::new(), &mut local_variables.clone(), None);
+
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[test]
+    fn function_call_type_check_function_variable_incorrect_parameters() {
+        let local_variables = HashMap::from_iter([(
+            "my_function".into(),
+            Type::Function(FunctionType {
+                parameters: vec![Type::UInt],
+                return_type: Box::new(FunctionReturnType::Void),
+            }),
+        )]);
+
+        let function_call = Node::FunctionCall(FunctionCall {
+            function_id: FunctionId("my_function".into()),
+            parameters: vec![true.into(), 10.into()],
+            line: 0,
+            slot: None,
+        });
+
+        let result = function_call.type_check(&HashMap::new(), &mut local_variables.clone(), None);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 2 && e[0].message == "my_function expects 1 parameter(s), but you provided 2" && e[1].message == "Expected type to be UInt, but found Boolean")
+        );
+    }
+
+    #[test]
+    fn function_call_type_check_non_function_variable() {
+        let local_variables = HashMap::from_iter([("my_var".into(), Type::UInt)]);
+
+        let function_call = Node::FunctionCall(FunctionCall {
+            function_id: FunctionId("my_var".into()),
+            parameters: Vec::new(),
+            line: 0,
+            slot: None,
+        });
+
+        let result = function_call.type_check(&HashMap::new(), &mut local_variables.clone(), None);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "my_var is a UInt variable, which cannot be called")
+        );
+    }
+
+    fn identity_function() -> Function {
+        Function::CustomFunction {
+            id: FunctionId("identity".into()),
+            name: "identity".into(),
+            type_parameters: vec!["T".into()],
+            parameters: vec![FunctionParameter::FunctionParameter {
+                param_type: Type::Generic("T".into()),
+                param_name: "value".into(),
+            }],
+            return_type: FunctionReturnType::Type(Type::Generic("T".into())),
+            body: vec![Node::FunctionReturn {
+                return_value: Some(Expression::VariableAccess("value".into())),
+                line: 0,
+            }],
+            span: Span::default(),
+            pure: false,
+        }
+    }
+
+    #[test]
+    fn function_call_type_check_generic_function() {
+        let functions = HashMap::from_iter([(FunctionId("identity".into()), identity_function())]);
+
+        let function_call = Expression::FunctionCall(FunctionCall {
+            function_id: FunctionId("identity".into()),
+            parameters: vec![true.into()],
+            line: 0,
+            slot: None,
+        });
+
+        let result = function_call.type_check(&functions, &HashMap::new());
+
+        assert!(matches!(result, Ok(())));
+        assert_eq!(
+            function_call.get_type(&functions, &HashMap::new()),
+            Some(Type::Boolean)
+        );
+    }
+
+    #[test]
+    fn function_call_type_check_generic_function_conflicting_types() {
+        let functions = HashMap::from_iter([(
+            FunctionId("pick".into()),
+            Function::CustomFunction {
+                id: FunctionId("pick".into()),
+                name: "pick".into(),
+                type_parameters: vec!["T".into()],
+                parameters: vec![
+                    FunctionParameter::FunctionParameter {
+                        param_type: Type::Generic("T".into()),
+                        param_name: "first".into(),
+                    },
+                    FunctionParameter::FunctionParameter {
+                        param_type: Type::Generic("T".into()),
+                        param_name: "second".into(),
+                    },
+                ],
+                return_type: FunctionReturnType::Void,
+                body: Vec::new(),
+                span: Span::default(),
+                pure: false,
+            },
+        )]);
+
+        let function_call = Node::FunctionCall(FunctionCall {
+            function_id: FunctionId("pick".into()),
+            parameters: vec![10.into(), true.into()],
+            line: 0,
+            slot: None,
+        });
+
+        let result = function_call.type_check(&functions, &mut HashMap::new(), None);
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Type parameter T of pick was inferred as both UInt and Boolean")
+        );
+    }
 }
@@ -1,14 +1,17 @@
 use std::collections::HashMap;
 
 use crate::{
-    ast::node::{Expression, Function, FunctionId, FunctionReturnType, Type},
+    ast::{
+        node::{Expression, Function, FunctionId, FunctionReturnType, Type},
+        symbol::Symbol,
+    },
     type_checking::TypeCheckingError,
 };
 
 pub(super) fn type_check_return_value(
     return_value: Option<&Expression>,
     functions: &HashMap<FunctionId, Function>,
-    local_variables: &mut HashMap<String, Type>,
+    local_variables: &mut HashMap<Symbol, Type>,
     current_function: Option<&FunctionId>,
 ) -> Result<Option<Type>, Vec<TypeCheckingError>> {
     let mut errors = Vec::new();
@@ -26,7 +29,9 @@ pub(super) fn type_check_return_value(
             .get(current_function_id)
             .expect("current_function should only be set with valid functions");
 
-        match (function.return_type(), return_value_type) {
+        match (function.return_type(), &return_value_type) {
+            // the return value has errors of its own, which have already been reported
+            (_, Some(return_value_type)) if return_value_type.contains_unknown() => {}
             // void and some return value
             (FunctionReturnType::Void, Some(return_value_type)) => {
                 errors.push(TypeCheckingError {
@@ -49,7 +54,7 @@ pub(super) fn type_check_return_value(
             }
             // non void and incorrect return value
             (FunctionReturnType::Type(expected_return_type), Some(return_value_type))
-                if *expected_return_type != return_value_type =>
+                if expected_return_type != return_value_type =>
             {
                 errors.push(TypeCheckingError {
                     message: format!(
@@ -66,8 +71,9 @@ pub(super) fn type_check_return_value(
         }
     } else {
         // no current function, top level statements
-        match return_value_type {
+        match &return_value_type {
             None => {}
+            Some(return_value_type) if return_value_type.contains_unknown() => {}
             // can return uint from top level statements. It's the exit code
             Some(Type::UInt) => {}
             Some(return_value_type) => errors.push(TypeCheckingError {
@@ -89,13 +95,14 @@ mod tests {
 
     use crate::ast::node::{
         BinaryOperation, Expression, Function, FunctionId, FunctionReturnType, Node, Operation,
-        Type,
+        Span, Type,
     };
 
     #[test]
     fn type_check_return_value_successful_empty_call_stack() {
         let node = Node::FunctionReturn {
             return_value: Some(10.into()),
+            line: 0,
         };
 
         let result = node.type_check(&HashMap::new(), &mut HashMap::new(), None);
@@ -105,7 +112,10 @@ mod tests {
 
     #[test]
     fn type_check_return_void_successful_empty_call_stack() {
-        let node = Node::FunctionReturn { return_value: None };
+        let node = Node::FunctionReturn {
+            return_value: None,
+            line: 0,
+        };
 
         let result = node.type_check(&HashMap::new(), &mut HashMap::new(), None);
 
@@ -116,20 +126,24 @@ mod tests {
     fn type_check_return_value_successful_in_function() {
         let node = Node::FunctionReturn {
             return_value: Some(true.into()),
+            line: 0,
         };
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Type(Type::Boolean),
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
-        let current_function = FunctionId("my_function".to_owned());
+        let current_function = FunctionId("my_function".into());
 
         let result = node.type_check(&functions, &mut HashMap::new(), Some(&current_function));
 
@@ -138,20 +152,26 @@ mod tests {
 
     #[test]
     fn type_check_return_void_successfull_in_function() {
-        let node = Node::FunctionReturn { return_value: None };
+        let node = Node::FunctionReturn {
+            return_value: None,
+            line: 0,
+        };
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
-        let current_function = FunctionId("my_function".to_owned());
+        let current_function = FunctionId("my_function".into());
 
         let result = node.type_check(&functions, &mut HashMap::new(), Some(&current_function));
 
@@ -166,6 +186,7 @@ mod tests {
                 left: Box::new(10.into()),
                 right: Box::new(true.into()),
             })),
+            line: 0,
         };
 
         let result = node.type_check(&HashMap::new(), &mut HashMap::new(), None);
@@ -179,6 +200,7 @@ mod tests {
     fn type_check_return_top_level_incorrect_type() {
         let node = Node::FunctionReturn {
             return_value: Some(true.into()),
+            line: 0,
         };
 
         let result = node.type_check(&HashMap::new(), &mut HashMap::new(), None);
@@ -192,23 +214,27 @@ mod tests {
     fn type_check_return_incorrect_value_from_function() {
         let node = Node::FunctionReturn {
             return_value: Some(true.into()),
+            line: 0,
         };
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Type(Type::UInt),
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
         let result = node.type_check(
             &functions,
             &mut HashMap::new(),
-            Some(&FunctionId("my_function".to_owned())),
+            Some(&FunctionId("my_function".into())),
         );
 
         assert!(
@@ -216,25 +242,63 @@ mod tests {
         )
     }
 
+    #[test]
+    fn type_check_return_unknown_value_from_function() {
+        let node = Node::FunctionReturn {
+            return_value: Some(Expression::VariableAccess("nope".into())),
+            line: 0,
+        };
+
+        let functions = HashMap::from_iter([(
+            FunctionId("my_function".into()),
+            Function::CustomFunction {
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
+                parameters: Vec::new(),
+                return_type: FunctionReturnType::Type(Type::UInt),
+                body: Vec::new(),
+                span: Span::default(),
+                pure: false,
+            },
+        )]);
+
+        let result = node.type_check(
+            &functions,
+            &mut HashMap::new(),
+            Some(&FunctionId("my_function".into())),
+        );
+
+        assert!(
+            matches!(result, Err(e) if e.len() == 1 && e[0].message == "Could not find variable with name nope")
+        );
+    }
+
     #[test]
     fn type_check_return_void_from_function_that_expects_value() {
-        let node = Node::FunctionReturn { return_value: None };
+        let node = Node::FunctionReturn {
+            return_value: None,
+            line: 0,
+        };
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Type(Type::UInt),
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
         let result = node.type_check(
             &functions,
             &mut HashMap::new(),
-            Some(&FunctionId("my_function".to_owned())),
+            Some(&FunctionId("my_function".into())),
         );
 
         assert!(
@@ -246,23 +310,27 @@ mod tests {
     fn type_check_return_value_from_void_function() {
         let node = Node::FunctionReturn {
             return_value: Some(true.into()),
+            line: 0,
         };
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
         let result = node.type_check(
             &functions,
             &mut HashMap::new(),
-            Some(&FunctionId("my_function".to_owned())),
+            Some(&FunctionId("my_function".into())),
         );
 
         assert!(
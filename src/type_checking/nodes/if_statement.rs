@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
 use crate::{
-    ast::node::{ElseIfBlock, Function, FunctionId, IfStatement, Type},
+    ast::{
+        node::{ElseIfBlock, Function, FunctionId, IfStatement, Type},
+        symbol::Symbol,
+    },
     type_checking::{verify_type, TypeCheckingError},
 };
 
@@ -11,7 +14,7 @@ impl IfStatement {
     pub fn type_check(
         &self,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &HashMap<String, Type>,
+        local_variables: &HashMap<Symbol, Type>,
         current_function: Option<&FunctionId>,
     ) -> Result<Option<Type>, Vec<TypeCheckingError>> {
         let mut errors = Vec::new();
@@ -71,7 +74,7 @@ impl ElseIfBlock {
     pub fn type_check(
         &self,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &HashMap<String, Type>,
+        local_variables: &HashMap<Symbol, Type>,
         current_function: Option<&FunctionId>,
     ) -> Result<(), Vec<TypeCheckingError>> {
         let mut errors = Vec::new();
@@ -110,7 +113,7 @@ mod tests {
 
     use crate::ast::node::{
         BinaryOperation, ElseIfBlock, Expression, Function, FunctionId, FunctionReturnType,
-        IfStatement, Node, Operation, Type, VariableDeclarationType,
+        IfStatement, Node, Operation, Span, Type, VariableDeclarationType,
     };
 
     #[test]
@@ -119,22 +122,26 @@ mod tests {
             check_expression: true.into(),
             if_block: vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: true.into(),
                 block: vec![Node::VariableDeclaration {
                     var_type: VariableDeclarationType::Infer,
-                    var_name: "second_var".to_owned(),
+                    var_name: "second_var".into(),
                     value: false.into(),
+                    line: 0,
                 }],
             }],
             else_block: Some(vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "third_var".to_owned(),
+                var_name: "third_var".into(),
                 value: true.into(),
+                line: 0,
             }]),
+            line: 0,
         };
 
         let result = if_statement.type_check(&HashMap::new(), &HashMap::new(), None);
@@ -148,25 +155,30 @@ mod tests {
             check_expression: true.into(),
             if_block: vec![Node::FunctionReturn {
                 return_value: Some(true.into()),
+                line: 0,
             }],
             else_if_blocks: Vec::new(),
             else_block: None,
+            line: 0,
         });
 
         let function = Function::CustomFunction {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: Vec::new(),
             return_type: FunctionReturnType::Type(Type::Boolean),
             body: Vec::new(),
+            span: Span::default(),
+            pure: false,
         };
 
-        let functions = HashMap::from_iter([(function.id().clone(), function)]);
+        let functions = HashMap::from_iter([(*function.id(), function)]);
 
         let result = if_statement.type_check(
             &functions,
             &mut HashMap::new(),
-            Some(&FunctionId("my_function".to_owned())),
+            Some(&FunctionId("my_function".into())),
         );
 
         assert!(matches!(result, Ok(Some(Type::Boolean))));
@@ -178,22 +190,26 @@ mod tests {
             check_expression: 22.into(),
             if_block: vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: true.into(),
                 block: vec![Node::VariableDeclaration {
                     var_type: VariableDeclarationType::Infer,
-                    var_name: "second_var".to_owned(),
+                    var_name: "second_var".into(),
                     value: false.into(),
+                    line: 0,
                 }],
             }],
             else_block: Some(vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "third_var".to_owned(),
+                var_name: "third_var".into(),
                 value: true.into(),
+                line: 0,
             }]),
+            line: 0,
         };
 
         let result = if_statement.type_check(&HashMap::new(), &HashMap::new(), None);
@@ -209,22 +225,26 @@ mod tests {
             check_expression: true.into(),
             if_block: vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Type(Type::UInt),
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: true.into(),
                 block: vec![Node::VariableDeclaration {
                     var_type: VariableDeclarationType::Infer,
-                    var_name: "second_var".to_owned(),
+                    var_name: "second_var".into(),
                     value: false.into(),
+                    line: 0,
                 }],
             }],
             else_block: Some(vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "third_var".to_owned(),
+                var_name: "third_var".into(),
                 value: true.into(),
+                line: 0,
             }]),
+            line: 0,
         };
 
         let result = if_statement.type_check(&HashMap::new(), &HashMap::new(), None);
@@ -238,22 +258,26 @@ mod tests {
             check_expression: true.into(),
             if_block: vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: 32.into(),
                 block: vec![Node::VariableDeclaration {
                     var_type: VariableDeclarationType::Infer,
-                    var_name: "second_var".to_owned(),
+                    var_name: "second_var".into(),
                     value: false.into(),
+                    line: 0,
                 }],
             }],
             else_block: Some(vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "third_var".to_owned(),
+                var_name: "third_var".into(),
                 value: true.into(),
+                line: 0,
             }]),
+            line: 0,
         };
 
         let result = if_statement.type_check(&HashMap::new(), &HashMap::new(), None);
@@ -267,22 +291,26 @@ mod tests {
             check_expression: true.into(),
             if_block: vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: true.into(),
                 block: vec![Node::VariableDeclaration {
                     var_type: VariableDeclarationType::Type(Type::UInt),
-                    var_name: "second_var".to_owned(),
+                    var_name: "second_var".into(),
                     value: false.into(),
+                    line: 0,
                 }],
             }],
             else_block: Some(vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "third_var".to_owned(),
+                var_name: "third_var".into(),
                 value: true.into(),
+                line: 0,
             }]),
+            line: 0,
         };
 
         let result = if_statement.type_check(&HashMap::new(), &HashMap::new(), None);
@@ -296,22 +324,26 @@ mod tests {
             check_expression: true.into(),
             if_block: vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: true.into(),
                 block: vec![Node::VariableDeclaration {
                     var_type: VariableDeclarationType::Infer,
-                    var_name: "second_var".to_owned(),
+                    var_name: "second_var".into(),
                     value: false.into(),
+                    line: 0,
                 }],
             }],
             else_block: Some(vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Type(Type::UInt),
-                var_name: "third_var".to_owned(),
+                var_name: "third_var".into(),
                 value: true.into(),
+                line: 0,
             }]),
+            line: 0,
         };
 
         let result = if_statement.type_check(&HashMap::new(), &HashMap::new(), None);
@@ -329,22 +361,26 @@ mod tests {
             }),
             if_block: vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: true.into(),
                 block: vec![Node::VariableDeclaration {
                     var_type: VariableDeclarationType::Infer,
-                    var_name: "second_var".to_owned(),
+                    var_name: "second_var".into(),
                     value: false.into(),
+                    line: 0,
                 }],
             }],
             else_block: Some(vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "third_var".to_owned(),
+                var_name: "third_var".into(),
                 value: true.into(),
+                line: 0,
             }]),
+            line: 0,
         };
 
         let result = if_statement.type_check(&HashMap::new(), &HashMap::new(), None);
@@ -358,8 +394,9 @@ mod tests {
             check_expression: true.into(),
             if_block: vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "my_var".to_owned(),
+                var_name: "my_var".into(),
                 value: true.into(),
+                line: 0,
             }],
             else_if_blocks: vec![ElseIfBlock {
                 check: Expression::Operation(Operation::Binary {
@@ -369,15 +406,18 @@ mod tests {
                 }),
                 block: vec![Node::VariableDeclaration {
                     var_type: VariableDeclarationType::Infer,
-                    var_name: "second_var".to_owned(),
+                    var_name: "second_var".into(),
                     value: false.into(),
+                    line: 0,
                 }],
             }],
             else_block: Some(vec![Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Infer,
-                var_name: "third_var".to_owned(),
+                var_name: "third_var".into(),
                 value: true.into(),
+                line: 0,
             }]),
+            line: 0,
         };
 
         let result = if_statement.type_check(&HashMap::new(), &HashMap::new(), None);
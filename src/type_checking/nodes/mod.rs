@@ -1,4 +1,6 @@
+mod assignment;
+mod destructuring_declaration;
 pub mod function_call;
 mod function_return;
-pub mod node;
 pub mod if_statement;
+pub mod node;
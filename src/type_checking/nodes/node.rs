@@ -1,28 +1,46 @@
 use std::collections::HashMap;
 
 use crate::{
-    ast::node::{Expression, Function, FunctionId, Node, Type, VariableDeclarationType},
+    ast::{
+        node::{Expression, Function, FunctionId, Node, Type, VariableDeclarationType},
+        scope::if_statement_declarations,
+        symbol::Symbol,
+    },
     type_checking::{verify_type, TypeCheckingError},
 };
 
-use super::function_return::type_check_return_value;
+use super::{
+    assignment::type_check_assignment,
+    destructuring_declaration::type_check_destructuring_declaration,
+    function_return::type_check_return_value,
+};
 
 pub fn type_check_nodes(
     nodes: &[Node],
     functions: &HashMap<FunctionId, Function>,
-    local_variables: &HashMap<String, Type>,
+    local_variables: &HashMap<Symbol, Type>,
     current_function: Option<&FunctionId>,
 ) -> Result<Option<Type>, Vec<TypeCheckingError>> {
     let mut errors = Vec::new();
     let mut local_variables = local_variables.clone();
     let mut return_type = None;
+    // the variables of the blocks that have ended, for diagnostics when they're used after the block
+    let mut ended_blocks = Vec::new();
     for node in nodes {
         match node.type_check(functions, &mut local_variables, current_function) {
             Err(node_errors) => {
-                errors.extend(node_errors);
+                errors.extend(
+                    node_errors
+                        .into_iter()
+                        .map(|error| error.with_scope(&ended_blocks)),
+                );
             }
             Ok(found_return_type) => return_type = found_return_type,
         }
+
+        if let Node::IfStatement(if_statement) = node {
+            ended_blocks.extend(if_statement_declarations(if_statement));
+        }
     }
 
     if errors.is_empty() {
@@ -36,7 +54,7 @@ impl Node {
     pub fn type_check(
         &self,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &mut HashMap<String, Type>,
+        local_variables: &mut HashMap<Symbol, Type>,
         current_function: Option<&FunctionId>,
     ) -> Result<Option<Type>, Vec<TypeCheckingError>> {
         match self {
@@ -44,8 +62,9 @@ impl Node {
                 var_type,
                 var_name,
                 value,
+                ..
             } => Self::type_check_variable_declaration(
-                var_name,
+                *var_name,
                 var_type,
                 value,
                 functions,
@@ -53,7 +72,7 @@ impl Node {
             )
             .map(|_| None),
 
-            Node::FunctionReturn { return_value } => type_check_return_value(
+            Node::FunctionReturn { return_value, .. } => type_check_return_value(
                 return_value.as_ref(),
                 functions,
                 local_variables,
@@ -65,15 +84,31 @@ impl Node {
             Node::IfStatement(if_statement) => {
                 if_statement.type_check(functions, local_variables, current_function)
             }
+            Node::Assignment {
+                var_name, value, ..
+            } => type_check_assignment(*var_name, value, functions, local_variables).map(|_| None),
+            Node::DestructuringDeclaration {
+                var_type,
+                var_names,
+                value,
+                ..
+            } => type_check_destructuring_declaration(
+                var_names,
+                var_type,
+                value,
+                functions,
+                local_variables,
+            )
+            .map(|_| None),
         }
     }
 
     fn type_check_variable_declaration(
-        var_name: &str,
+        var_name: Symbol,
         var_type: &VariableDeclarationType,
         value: &Expression,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &mut HashMap<String, Type>,
+        local_variables: &mut HashMap<Symbol, Type>,
     ) -> Result<(), Vec<TypeCheckingError>> {
         let mut errors = Vec::new();
 
@@ -83,7 +118,7 @@ impl Node {
             errors.extend(value_expression_errors);
         }
 
-        let variable_already_exists = if local_variables.get(var_name).is_some() {
+        let variable_already_exists = if local_variables.get(&var_name).is_some() {
             errors.push(TypeCheckingError {
                 message: format!("Variable {var_name} is already defined"),
             });
@@ -94,23 +129,24 @@ impl Node {
 
         match var_type {
             VariableDeclarationType::Infer => {
-                if let Some(value_type) = value_type {
-                    // only insert variable if it isn't already declared as .insert overwrites the existing value. see https://github.com/rust-lang/rust/issues/82766
-                    if !variable_already_exists {
-                        local_variables.insert(var_name.to_owned(), value_type);
-                    }
-                } else {
+                if value_type.is_none() {
                     errors.push(TypeCheckingError {
                         message: format!("cannot assign void to variable {}", var_name),
                     });
                 }
+
+                // only insert variable if it isn't already declared as .insert overwrites the existing value. see https://github.com/rust-lang/rust/issues/82766
+                // A void value declares the variable as unknown, so its uses aren't reported too
+                if !variable_already_exists {
+                    local_variables.insert(var_name, value_type.unwrap_or(Type::Unknown));
+                }
             }
             VariableDeclarationType::Type(expected_type) => {
                 if !variable_already_exists {
-                    local_variables.insert(var_name.to_owned(), *expected_type);
+                    local_variables.insert(var_name, expected_type.clone());
                 }
 
-                if let Err(var_error) = verify_type(value_type, *expected_type) {
+                if let Err(var_error) = verify_type(value_type, expected_type.clone()) {
                     errors.push(var_error);
                 }
             }
@@ -133,9 +169,10 @@ mod tests {
             builders::if_statement_builder::IfStatementBuilder,
             node::{
                 Expression, Function, FunctionCall, FunctionId, FunctionReturnType, Node,
-                Operation, Type, UnaryOperation, VariableDeclarationType,
+                Operation, Span, Type, UnaryOperation, VariableDeclarationType,
             },
         },
+        evaluation::intrinsics::get_intrinsic_functions,
         type_checking::nodes::node::type_check_nodes,
     };
 
@@ -143,8 +180,9 @@ mod tests {
     fn type_check_variable_declaration_success() {
         let node = Node::VariableDeclaration {
             var_type: VariableDeclarationType::Type(Type::Boolean),
-            var_name: "my_var".to_owned(),
+            var_name: "my_var".into(),
             value: true.into(),
+            line: 0,
         };
 
         let mut local_variables = HashMap::new();
@@ -153,15 +191,19 @@ mod tests {
 
         assert!(matches!(result, Ok(_)));
 
-        assert!(matches!(local_variables.get("my_var"), Some(Type::Boolean)));
+        assert!(matches!(
+            local_variables.get(&"my_var".into()),
+            Some(Type::Boolean)
+        ));
     }
 
     #[test]
     fn type_check_variable_declaration_infer_success() {
         let node = Node::VariableDeclaration {
             var_type: VariableDeclarationType::Infer,
-            var_name: "my_var".to_owned(),
+            var_name: "my_var".into(),
             value: true.into(),
+            line: 0,
         };
 
         let mut local_variables = HashMap::new();
@@ -170,18 +212,22 @@ mod tests {
 
         assert!(matches!(result, Ok(_)));
 
-        assert!(matches!(local_variables.get("my_var"), Some(Type::Boolean)));
+        assert!(matches!(
+            local_variables.get(&"my_var".into()),
+            Some(Type::Boolean)
+        ));
     }
 
     #[test]
     fn type_check_variable_declaration_failure_existing_variable() {
         let node = Node::VariableDeclaration {
             var_type: VariableDeclarationType::Type(Type::Boolean),
-            var_name: "my_name".to_owned(),
+            var_name: "my_name".into(),
             value: true.into(),
+            line: 0,
         };
 
-        let mut local_variables = HashMap::from_iter([("my_name".to_owned(), Type::UInt)]);
+        let mut local_variables = HashMap::from_iter([("my_name".into(), Type::UInt)]);
 
         let result = node.type_check(&HashMap::new(), &mut local_variables, None);
 
@@ -190,18 +236,22 @@ mod tests {
         );
 
         // verify variable type wasn't overwritten
-        assert!(matches!(local_variables.get("my_name"), Some(Type::UInt)));
+        assert!(matches!(
+            local_variables.get(&"my_name".into()),
+            Some(Type::UInt)
+        ));
     }
 
     #[test]
     fn type_check_variable_declaration_failure_value_expression() {
         let node = Node::VariableDeclaration {
             var_type: VariableDeclarationType::Infer,
-            var_name: "my_name".to_owned(),
+            var_name: "my_name".into(),
             value: Expression::Operation(Operation::Unary {
                 operation: UnaryOperation::Not,
                 value: Box::new(10.into()),
             }),
+            line: 0,
         };
 
         let result = node.type_check(&HashMap::new(), &mut HashMap::new(), None);
@@ -213,25 +263,38 @@ mod tests {
     fn type_check_variable_declaration_failure_infer_void_function() {
         let node = Node::VariableDeclaration {
             var_type: VariableDeclarationType::Infer,
-            var_name: "my_value".to_owned(),
+            var_name: "my_value".into(),
             value: Expression::FunctionCall(FunctionCall {
-                function_id: FunctionId("my_function".to_owned()),
+                function_id: FunctionId("my_function".into()),
                 parameters: Vec::new(),
+                line: 0,
+                slot: None,
             }),
+            line: 0,
         };
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: Vec::new(),
                 return_type: FunctionReturnType::Void,
                 body: Vec::new(),
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
-        let result = node.type_check(&functions, &mut HashMap::new(), None);
+        let usage = Node::VariableDeclaration {
+            var_type: VariableDeclarationType::Infer,
+            var_name: "copy".into(),
+            value: Expression::VariableAccess("my_value".into()),
+            line: 0,
+        };
+
+        let result = type_check_nodes(&[node, usage], &functions, &HashMap::new(), None);
 
         assert!(
             matches!(result, Err(e) if e.len() == 1 && e[0].message == "cannot assign void to variable my_value".to_owned())
@@ -242,8 +305,9 @@ mod tests {
     fn type_check_variable_declaration_failure_incorrect_type() {
         let node = Node::VariableDeclaration {
             var_type: VariableDeclarationType::Type(Type::UInt),
-            var_name: "my_value".to_owned(),
+            var_name: "my_value".into(),
             value: true.into(),
+            line: 0,
         };
 
         let result = node.type_check(&HashMap::new(), &mut HashMap::new(), None);
@@ -257,8 +321,9 @@ mod tests {
     fn type_check_nodes_successful() {
         let nodes = vec![Node::VariableDeclaration {
             var_type: VariableDeclarationType::Infer,
-            var_name: "my_var".to_owned(),
+            var_name: "my_var".into(),
             value: true.into(),
+            line: 0,
         }];
 
         let result = type_check_nodes(&nodes, &HashMap::new(), &HashMap::new(), None);
@@ -270,23 +335,27 @@ mod tests {
     fn type_check_nodes_return_value() {
         let nodes = vec![Node::FunctionReturn {
             return_value: Some(true.into()),
+            line: 0,
         }];
 
         let function = Function::CustomFunction {
-            id: FunctionId("my_function".to_owned()),
-            name: "my_function".to_owned(),
+            id: FunctionId("my_function".into()),
+            name: "my_function".into(),
+            type_parameters: Vec::new(),
             parameters: Vec::new(),
             return_type: FunctionReturnType::Type(Type::Boolean),
             body: Vec::new(),
+            span: Span::default(),
+            pure: false,
         };
 
-        let functions = HashMap::from_iter([(function.id().clone(), function)]);
+        let functions = HashMap::from_iter([(*function.id(), function)]);
 
         let result = type_check_nodes(
             &nodes,
             &functions,
             &HashMap::new(),
-            Some(&FunctionId("my_function".to_owned())),
+            Some(&FunctionId("my_function".into())),
         );
 
         assert!(matches!(result, Ok(Some(Type::Boolean))));
@@ -297,13 +366,15 @@ mod tests {
         let nodes = vec![
             Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Type(Type::Boolean),
-                var_name: "var_1".to_owned(),
+                var_name: "var_1".into(),
                 value: 32.into(),
+                line: 0,
             },
             Node::VariableDeclaration {
                 var_type: VariableDeclarationType::Type(Type::Boolean),
-                var_name: "var_2".to_owned(),
+                var_name: "var_2".into(),
                 value: 32.into(),
+                line: 0,
             },
         ];
 
@@ -312,6 +383,65 @@ mod tests {
         assert!(matches!(result, Err(e) if e.len() == 2));
     }
 
+    #[test]
+    fn type_check_nodes_variable_out_of_scope() {
+        let nodes = vec![
+            IfStatementBuilder::new()
+                .line(2)
+                .check_expression(|_| true.into())
+                .else_if(
+                    |_| false.into(),
+                    |body| {
+                        body.statement(|statement| {
+                            statement.var_declaration(|var_declaration| {
+                                var_declaration
+                                    .infer_type()
+                                    .name("next")
+                                    .with_assignment(|_| 1.into())
+                            })
+                        })
+                        .build()
+                    },
+                )
+                .body(|body| body.build())
+                .build(),
+            Node::FunctionCall(FunctionCall {
+                function_id: FunctionId("print".into()),
+                parameters: vec![Expression::VariableAccess("next".into())],
+                line: 0,
+                slot: None,
+            }),
+        ];
+
+        let result = type_check_nodes(&nodes, &get_intrinsic_functions(), &HashMap::new(), None);
+
+        assert!(
+            matches!(result, Err(e) if e[0].message == "`next` is not in scope here; it was declared inside an else if block of the if statement at line 2")
+        );
+    }
+
+    #[test]
+    fn type_check_nodes_redeclare_after_block() {
+        let declaration = || Node::VariableDeclaration {
+            var_type: VariableDeclarationType::Infer,
+            var_name: "next".into(),
+            value: 1.into(),
+            line: 0,
+        };
+
+        let nodes = vec![
+            IfStatementBuilder::new()
+                .check_expression(|_| true.into())
+                .body(|body| body.statement(|_| declaration()).build())
+                .build(),
+            declaration(),
+        ];
+
+        let result = type_check_nodes(&nodes, &HashMap::new(), &HashMap::new(), None);
+
+        assert!(matches!(result, Ok(None)));
+    }
+
     #[test]
     fn test_if_statement_type_checking() {
         let node = IfStatementBuilder::new()
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use crate::ast::node::{
-    BinaryOperation, Expression, Function, FunctionId, Operation, Type, UnaryOperation,
+use crate::ast::{
+    node::{BinaryOperation, Expression, Function, FunctionId, Operation, Type, UnaryOperation},
+    symbol::Symbol,
 };
 
 use super::{verify_type, TypeCheckingError};
@@ -27,7 +28,7 @@ impl Operation {
     pub fn type_check(
         &self,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &HashMap<String, Type>,
+        local_variables: &HashMap<Symbol, Type>,
     ) -> Result<(), Vec<TypeCheckingError>> {
         match self {
             Operation::Unary { operation, value } => {
@@ -48,7 +49,7 @@ impl BinaryOperation {
         left: &Expression,
         right: &Expression,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &HashMap<String, Type>,
+        local_variables: &HashMap<Symbol, Type>,
     ) -> Result<(), Vec<TypeCheckingError>> {
         let mut errors = Vec::new();
 
@@ -90,7 +91,7 @@ impl UnaryOperation {
         &self,
         value: &Expression,
         functions: &HashMap<FunctionId, Function>,
-        local_variables: &HashMap<String, Type>,
+        local_variables: &HashMap<Symbol, Type>,
     ) -> Result<(), Vec<TypeCheckingError>> {
         let mut errors = Vec::new();
 
@@ -126,7 +127,7 @@ mod tests {
 
     use crate::ast::node::{
         BinaryOperation, Expression, Function, FunctionCall, FunctionId, FunctionParameter,
-        FunctionReturnType, Operation, Type, UnaryOperation,
+        FunctionReturnType, Operation, Span, Type, UnaryOperation,
     };
 
     #[test]
@@ -222,22 +223,27 @@ mod tests {
         let operation = Operation::Unary {
             operation: UnaryOperation::Not,
             value: Box::new(Expression::FunctionCall(FunctionCall {
-                function_id: FunctionId("my_function".to_owned()),
+                function_id: FunctionId("my_function".into()),
                 parameters: Vec::new(),
+                line: 0,
+                slot: None,
             })),
         };
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::Boolean,
-                    param_name: "my_param".to_owned(),
+                    param_name: "my_param".into(),
                 }],
                 return_type: FunctionReturnType::Type(Type::Boolean),
                 body: vec![],
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
@@ -253,26 +259,33 @@ mod tests {
         let operation = Operation::Binary {
             operation: BinaryOperation::GreaterThan,
             left: Box::new(Expression::FunctionCall(FunctionCall {
-                function_id: FunctionId("my_function".to_owned()),
+                function_id: FunctionId("my_function".into()),
                 parameters: Vec::new(),
+                line: 0,
+                slot: None,
             })),
             right: Box::new(Expression::FunctionCall(FunctionCall {
-                function_id: FunctionId("my_function".to_owned()),
+                function_id: FunctionId("my_function".into()),
                 parameters: Vec::new(),
+                line: 0,
+                slot: None,
             })),
         };
 
         let functions = HashMap::from_iter([(
-            FunctionId("my_function".to_owned()),
+            FunctionId("my_function".into()),
             Function::CustomFunction {
-                id: FunctionId("my_function".to_owned()),
-                name: "my_function".to_owned(),
+                id: FunctionId("my_function".into()),
+                name: "my_function".into(),
+                type_parameters: Vec::new(),
                 parameters: vec![FunctionParameter::FunctionParameter {
                     param_type: Type::Boolean,
-                    param_name: "my_param".to_owned(),
+                    param_name: "my_param".into(),
                 }],
                 return_type: FunctionReturnType::Type(Type::UInt),
                 body: vec![],
+                span: Span::default(),
+                pure: false,
             },
         )]);
 
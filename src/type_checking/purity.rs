@@ -0,0 +1,640 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{
+    ast::{
+        node::{
+            Ast, Expression, Function, FunctionCall, FunctionId, FunctionParameter, Node, Operation,
+        },
+        symbol::Symbol,
+    },
+    evaluation::intrinsics::get_intrinsic_functions,
+};
+
+/// the first global read by a function the expression calls, either in the function's body or in the functions it
+/// calls, along with the function the expression calls. Reading a global isn't a side effect, but its value isn't
+/// known until the program runs. A parameter with the same name as a global shadows it, so isn't a global read
+pub(super) fn find_global_read(
+    expression: &Expression,
+    functions: &HashMap<FunctionId, Function>,
+    globals: &HashSet<Symbol>,
+) -> Option<(FunctionId, Symbol)> {
+    let mut effects = Effects::default();
+    effects.collect_expression(expression);
+
+    let mut visited = HashSet::new();
+
+    effects.calls.into_iter().find_map(|call| {
+        let mut to_visit = vec![call.function_id];
+
+        while let Some(id) = to_visit.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+
+            let Some(Function::CustomFunction {
+                parameters, body, ..
+            }) = functions.get(&id)
+            else {
+                continue;
+            };
+
+            let mut found = Effects::default();
+            found.collect_body(parameters, body);
+
+            if let Some(global) = found
+                .variables
+                .into_iter()
+                .find(|variable| globals.contains(variable))
+            {
+                return Some((call.function_id, global));
+            }
+
+            to_visit.extend(found.calls.into_iter().map(|call| call.function_id));
+        }
+
+        None
+    })
+}
+
+/// a side effect calling a function can have
+#[derive(Clone, Debug, PartialEq)]
+pub enum SideEffect {
+    /// a call to an intrinsic function, which interacts with the outside world, ie `print`
+    CallsIntrinsic { function_id: FunctionId, line: u32 },
+    /// a call to a declared function that has a side effect
+    CallsEffectful { function_id: FunctionId, line: u32 },
+    /// a call to a function value, which could be any function
+    CallsValue { name: Symbol, line: u32 },
+    /// an assignment to a global variable
+    Assigns { name: Symbol, line: u32 },
+}
+
+impl SideEffect {
+    fn line(&self) -> u32 {
+        match self {
+            SideEffect::CallsIntrinsic { line, .. }
+            | SideEffect::CallsEffectful { line, .. }
+            | SideEffect::CallsValue { line, .. }
+            | SideEffect::Assigns { line, .. } => *line,
+        }
+    }
+}
+
+impl Display for SideEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SideEffect::CallsIntrinsic { function_id, line }
+            | SideEffect::CallsEffectful { function_id, line } => {
+                write!(f, "calls {} at line {}", function_id, line)
+            }
+            SideEffect::CallsValue { name, line } => {
+                write!(f, "calls the function value `{}` at line {}", name, line)
+            }
+            SideEffect::Assigns { name, line } => {
+                write!(f, "assigns to `{}` at line {}", name, line)
+            }
+        }
+    }
+}
+
+/// whether each declared function can have a side effect when it's called. A function is effectful if it calls an
+/// intrinsic function or a function value, assigns to a global, or calls a declared function that is effectful.
+/// Reading a global isn't a side effect, and a lambda's body only runs when the lambda is called
+#[derive(Debug, Default, PartialEq)]
+pub struct FunctionEffects {
+    /// the side effect each custom function has, or `None` if it's pure
+    effects: HashMap<FunctionId, Option<SideEffect>>,
+}
+
+impl FunctionEffects {
+    pub fn new(functions: &HashMap<FunctionId, Function>) -> Self {
+        let intrinsic_functions = get_intrinsic_functions();
+
+        let mut effects = HashMap::new();
+        // the calls each function makes to custom functions, in the order of their lines
+        let mut calls = HashMap::new();
+
+        for function in functions.values() {
+            let Function::CustomFunction {
+                id,
+                parameters,
+                body,
+                ..
+            } = function
+            else {
+                continue;
+            };
+
+            let mut found = Effects::default();
+            found.collect_body(parameters, body);
+
+            let mut custom_calls = Vec::new();
+            let mut side_effects: Vec<_> = found
+                .assignments
+                .iter()
+                .map(|(name, line)| SideEffect::Assigns {
+                    name: *name,
+                    line: *line,
+                })
+                .chain(found.value_calls.iter().map(|call| SideEffect::CallsValue {
+                    name: call.function_id.0,
+                    line: call.line,
+                }))
+                .collect();
+
+            for call in found.calls {
+                match functions.get(&call.function_id) {
+                    Some(Function::CustomFunction { .. }) => custom_calls.push(call),
+                    Some(Function::Intrinsic { .. }) => {
+                        side_effects.push(SideEffect::CallsIntrinsic {
+                            function_id: call.function_id,
+                            line: call.line,
+                        })
+                    }
+                    None if intrinsic_functions.contains_key(&call.function_id) => side_effects
+                        .push(SideEffect::CallsIntrinsic {
+                            function_id: call.function_id,
+                            line: call.line,
+                        }),
+                    None => side_effects.push(SideEffect::CallsValue {
+                        name: call.function_id.0,
+                        line: call.line,
+                    }),
+                }
+            }
+
+            side_effects.sort_by_key(SideEffect::line);
+            custom_calls.sort_by_key(|call| call.line);
+
+            effects.insert(*id, side_effects.into_iter().next());
+            calls.insert(*id, custom_calls);
+        }
+
+        // sorted, so the same side effect is reported on every run
+        let mut ids: Vec<_> = calls.keys().copied().collect();
+        ids.sort_by_key(|id| id.0.as_str());
+
+        // a function that calls an effectful function is effectful too, which can make its callers effectful
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for id in &ids {
+                if matches!(effects.get(id), Some(Some(_))) {
+                    continue;
+                }
+
+                let effectful_call = calls[id]
+                    .iter()
+                    .find(|call| matches!(effects.get(&call.function_id), Some(Some(_))));
+
+                if let Some(call) = effectful_call {
+                    effects.insert(
+                        *id,
+                        Some(SideEffect::CallsEffectful {
+                            function_id: call.function_id,
+                            line: call.line,
+                        }),
+                    );
+                    changed = true;
+                }
+            }
+        }
+
+        Self { effects }
+    }
+
+    /// whether calling the function can't have side effects. Intrinsic functions are never pure
+    pub fn is_pure(&self, function_id: &FunctionId) -> bool {
+        matches!(self.effects.get(function_id), Some(None))
+    }
+
+    /// the side effect the function has, found in its own body
+    pub fn side_effect(&self, function_id: &FunctionId) -> Option<&SideEffect> {
+        self.effects.get(function_id)?.as_ref()
+    }
+
+    /// the chain of calls from the function to the side effect that makes it effectful, ie
+    /// `calls log at line 7, which calls print at line 3`. `None` if the function is pure
+    pub fn explain(&self, function_id: &FunctionId) -> Option<String> {
+        let mut side_effect = self.side_effect(function_id)?;
+        let mut explanation = side_effect.to_string();
+
+        while let SideEffect::CallsEffectful { function_id, .. } = side_effect {
+            let Some(next) = self.side_effect(function_id) else {
+                break;
+            };
+
+            explanation.push_str(", which ");
+            explanation.push_str(&next.to_string());
+            side_effect = next;
+        }
+
+        Some(explanation)
+    }
+}
+
+impl Ast {
+    /// whether each of the program's functions can have side effects, which is used once its modules are linked
+    pub fn function_effects(&self) -> FunctionEffects {
+        FunctionEffects::new(&self.functions)
+    }
+}
+
+/// what happens when nodes are evaluated. The body of lambdas is skipped, since it only runs when the lambda is called,
+/// unless `lambda_bodies` is set. Local variables shadow the functions and globals with the same name, so calls and
+/// reads of them are told apart from those of declarations
+#[derive(Default)]
+pub(super) struct Effects<'a> {
+    /// whether the bodies of lambdas are collected too, as if each lambda is called where it's created
+    pub(super) lambda_bodies: bool,
+    /// the calls of names that aren't local variables, which are declared functions, or globals and constants
+    /// holding function values
+    pub(super) calls: Vec<&'a FunctionCall>,
+    /// the calls of local variables, which hold function values that could be any function
+    pub(super) value_calls: Vec<&'a FunctionCall>,
+    /// the variables that are read, other than local variables
+    pub(super) variables: Vec<Symbol>,
+    /// the variables assigned to, along with the line of each assignment
+    pub(super) assignments: Vec<(Symbol, u32)>,
+    /// the parameters and variables in scope where the nodes being collected run
+    pub(super) locals: Vec<Symbol>,
+}
+
+impl<'a> Effects<'a> {
+    /// collect a function's body, in which its parameters are local variables
+    pub(super) fn collect_body(&mut self, parameters: &[FunctionParameter], body: &'a [Node]) {
+        let locals = self.locals.len();
+        self.locals
+            .extend(parameters.iter().map(FunctionParameter::name));
+
+        self.collect_nodes(body);
+
+        self.locals.truncate(locals);
+    }
+
+    /// collect a block's statements. The variables it declares are local until the end of the block
+    pub(super) fn collect_nodes(&mut self, nodes: &'a [Node]) {
+        let locals = self.locals.len();
+
+        for node in nodes {
+            match node {
+                Node::VariableDeclaration {
+                    var_name, value, ..
+                } => {
+                    self.collect_expression(value);
+                    self.locals.push(*var_name);
+                }
+                Node::DestructuringDeclaration {
+                    var_names, value, ..
+                } => {
+                    self.collect_expression(value);
+                    self.locals.extend(var_names.iter().copied());
+                }
+                Node::FunctionReturn { return_value, .. } => {
+                    if let Some(return_value) = return_value {
+                        self.collect_expression(return_value)
+                    }
+                }
+                Node::FunctionCall(function_call) => self.collect_function_call(function_call),
+                Node::IfStatement(if_statement) => {
+                    self.collect_expression(&if_statement.check_expression);
+                    self.collect_nodes(&if_statement.if_block);
+
+                    for else_if_block in &if_statement.else_if_blocks {
+                        self.collect_expression(&else_if_block.check);
+                        self.collect_nodes(&else_if_block.block);
+                    }
+
+                    if let Some(else_block) = &if_statement.else_block {
+                        self.collect_nodes(else_block);
+                    }
+                }
+                Node::Assignment {
+                    var_name,
+                    value,
+                    line,
+                } => {
+                    self.assignments.push((*var_name, *line));
+                    self.collect_expression(value);
+                }
+            }
+        }
+
+        self.locals.truncate(locals);
+    }
+
+    pub(super) fn collect_expression(&mut self, expression: &'a Expression) {
+        match expression {
+            Expression::ValueLiteral(_) => {}
+            Expression::Lambda(lambda) => {
+                if self.lambda_bodies {
+                    self.collect_body(&lambda.parameters, &lambda.body);
+                }
+            }
+            Expression::VariableAccess(name) | Expression::LocalVariableAccess { name, .. } => {
+                if !self.locals.contains(name) {
+                    self.variables.push(*name)
+                }
+            }
+            Expression::FunctionCall(function_call) => self.collect_function_call(function_call),
+            Expression::Operation(Operation::Unary { value, .. }) => self.collect_expression(value),
+            Expression::Operation(Operation::Binary { left, right, .. }) => {
+                self.collect_expression(left);
+                self.collect_expression(right);
+            }
+            Expression::Tuple(elements) => {
+                for element in elements {
+                    self.collect_expression(element);
+                }
+            }
+            Expression::TupleAccess { tuple, .. } => self.collect_expression(tuple),
+        }
+    }
+
+    fn collect_function_call(&mut self, function_call: &'a FunctionCall) {
+        if self.locals.contains(&function_call.function_id.0) {
+            self.value_calls.push(function_call);
+        } else {
+            self.calls.push(function_call);
+            // calling a function value held by a global reads the global
+            self.variables.push(function_call.function_id.0);
+        }
+
+        for parameter in &function_call.parameters {
+            self.collect_expression(parameter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        ast::{
+            builders::{ast_builder::AstBuilder, expression_builder::ExpressionBuilder},
+            node::{Expression, FunctionId, FunctionReturnType, FunctionType, Type},
+        },
+        evaluation::intrinsics::get_intrinsic_functions,
+    };
+
+    use super::{find_global_read, FunctionEffects, SideEffect};
+
+    /// add a function that makes each call in `calls`, a callee and the line of its call
+    fn calling(
+        ast: AstBuilder,
+        name: &'static str,
+        calls: &'static [(&'static str, u32)],
+    ) -> AstBuilder {
+        ast.function_declaration(|function| {
+            function
+                .name(name)
+                .parameters(Vec::new())
+                .void()
+                .body(|body| {
+                    calls
+                        .iter()
+                        .fold(body, |body, (callee, line)| {
+                            body.statement(|statement| {
+                                statement.line(*line).function_call(|call| {
+                                    call.function_id(*callee).no_parameters().build()
+                                })
+                            })
+                        })
+                        .build()
+                })
+        })
+    }
+
+    /// add a function that returns `value`
+    fn returning(
+        ast: AstBuilder,
+        name: &'static str,
+        value: impl FnOnce(ExpressionBuilder) -> Expression,
+    ) -> AstBuilder {
+        ast.function_declaration(|function| {
+            function
+                .name(name)
+                .parameters(Vec::new())
+                .return_type(Type::UInt)
+                .body(|body| {
+                    body.statement(|statement| statement.line(4).return_value(value))
+                        .build()
+                })
+        })
+    }
+
+    fn effects_of(ast: AstBuilder) -> FunctionEffects {
+        let functions = get_intrinsic_functions()
+            .into_iter()
+            .chain(ast.build().functions)
+            .collect();
+
+        FunctionEffects::new(&functions)
+    }
+
+    #[test]
+    fn find_global_read_through_calls() {
+        let ast = returning(AstBuilder::default(), "reads_count", |value| {
+            value.variable("count")
+        });
+        let ast = returning(ast, "calls_reads_count", |value| {
+            value.function_call(|call| call.function_id("reads_count").no_parameters().build())
+        });
+        let ast = returning(ast, "constant", |value| value.value_literal(1.into())).build();
+
+        let expression = ExpressionBuilder::default().operation(|operation| {
+            operation.plus(
+                |left| {
+                    left.function_call(|call| call.function_id("constant").no_parameters().build())
+                },
+                |right| {
+                    right.function_call(|call| {
+                        call.function_id("calls_reads_count")
+                            .no_parameters()
+                            .build()
+                    })
+                },
+            )
+        });
+
+        assert_eq!(
+            find_global_read(
+                &expression,
+                &ast.functions,
+                &HashSet::from_iter(["count".into()])
+            ),
+            Some((FunctionId("calls_reads_count".into()), "count".into()))
+        );
+        assert_eq!(
+            find_global_read(&expression, &ast.functions, &HashSet::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn effects_of_functions() {
+        let ast = calling(AstBuilder::default(), "log", &[("print", 3)]);
+        let ast = calling(ast, "report", &[("log", 7)]);
+        let ast = calling(ast, "count", &[("count", 9)]);
+        let ast = ast.function_declaration(|function| {
+            function
+                .name("reset")
+                .parameters(Vec::new())
+                .void()
+                .body(|body| {
+                    body.statement(|statement| {
+                        statement
+                            .line(12)
+                            .assignment("total", |value| value.value_literal(0.into()))
+                    })
+                    .build()
+                })
+        });
+
+        let effects = effects_of(ast);
+
+        assert!(!effects.is_pure(&FunctionId("log".into())));
+        assert!(!effects.is_pure(&FunctionId("report".into())));
+        assert!(effects.is_pure(&FunctionId("count".into())));
+        assert!(!effects.is_pure(&FunctionId("print".into())));
+        assert_eq!(
+            effects.side_effect(&FunctionId("reset".into())),
+            Some(&SideEffect::Assigns {
+                name: "total".into(),
+                line: 12
+            })
+        );
+    }
+
+    #[test]
+    fn explain_effects_through_calls() {
+        let ast = calling(AstBuilder::default(), "log", &[("print", 3)]);
+        let ast = calling(ast, "report", &[("log", 7)]);
+        let ast = calling(ast, "main", &[("report", 11), ("report", 12)]);
+
+        let effects = effects_of(ast);
+
+        assert_eq!(
+            effects.explain(&FunctionId("main".into())).unwrap(),
+            "calls report at line 11, which calls log at line 7, which calls print at line 3"
+        );
+        assert_eq!(effects.explain(&FunctionId("count".into())), None);
+    }
+
+    #[test]
+    fn mutually_recursive_functions() {
+        let ast = calling(AstBuilder::default(), "even", &[("odd", 2)]);
+        let ast = calling(ast, "odd", &[("even", 5)]);
+        let ast = calling(ast, "ping", &[("pong", 8)]);
+        let ast = calling(ast, "pong", &[("ping", 11), ("print", 12)]);
+
+        let effects = effects_of(ast);
+
+        assert!(effects.is_pure(&FunctionId("even".into())));
+        assert!(effects.is_pure(&FunctionId("odd".into())));
+        assert_eq!(
+            effects.explain(&FunctionId("ping".into())).unwrap(),
+            "calls pong at line 8, which calls print at line 12"
+        );
+    }
+
+    #[test]
+    fn calling_function_value_is_effectful() {
+        let ast = returning(AstBuilder::default(), "apply", |value| {
+            value.function_call(|call| call.function_id("f").no_parameters().line(4).build())
+        });
+
+        let effects = effects_of(ast);
+
+        assert_eq!(
+            effects.explain(&FunctionId("apply".into())).unwrap(),
+            "calls the function value `f` at line 4"
+        );
+    }
+    #[test]
+    fn calling_parameter_shadowing_function_is_effectful() {
+        let function_type = Type::Function(FunctionType {
+            parameters: vec![Type::UInt],
+            return_type: Box::new(FunctionReturnType::Type(Type::UInt)),
+        });
+        let ast = AstBuilder::default()
+            .function_declaration(|function| {
+                function
+                    .name("helper")
+                    .parameters(vec![(Type::UInt, "value".into()).into()])
+                    .return_type(Type::UInt)
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.return_value(|value| value.variable("value"))
+                        })
+                        .build()
+                    })
+            })
+            .function_declaration(|function| {
+                function
+                    .name("apply")
+                    .parameters(vec![(function_type, "helper".into()).into()])
+                    .return_type(Type::UInt)
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.line(4).return_value(|value| {
+                                value.function_call(|call| {
+                                    call.function_id("helper")
+                                        .parameter(|parameter| parameter.value_literal(1.into()))
+                                        .line(4)
+                                        .build()
+                                })
+                            })
+                        })
+                        .build()
+                    })
+            })
+            .build();
+
+        let effects = FunctionEffects::new(&ast.functions);
+
+        // the parameter shadows the declared function, so the call could be to any function
+        assert_eq!(
+            effects.explain(&FunctionId("apply".into())).unwrap(),
+            "calls the function value `helper` at line 4"
+        );
+    }
+
+    #[test]
+    fn find_global_read_ignores_shadowing_parameter() {
+        let ast = AstBuilder::default()
+            .function_declaration(|function| {
+                function
+                    .name("identity")
+                    .parameters(vec![(Type::UInt, "count".into()).into()])
+                    .return_type(Type::UInt)
+                    .body(|body| {
+                        body.statement(|statement| {
+                            statement.return_value(|value| value.variable("count"))
+                        })
+                        .build()
+                    })
+            })
+            .build();
+
+        let expression = ExpressionBuilder::default().function_call(|call| {
+            call.function_id("identity")
+                .parameter(|parameter| parameter.value_literal(1.into()))
+                .build()
+        });
+
+        assert_eq!(
+            find_global_read(
+                &expression,
+                &ast.functions,
+                &HashSet::from_iter(["count".into()])
+            ),
+            None
+        );
+    }
+}
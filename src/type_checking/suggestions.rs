@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use crate::ast::{
+    node::{Function, FunctionId, Type},
+    symbol::Symbol,
+};
+
+/// the names that can be referred to from where `name` was used: the functions, including intrinsics, and the variables
+/// in scope. Constants, globals and functions of imported modules are suggested by the name they're declared with
+pub(super) fn visible_names<'a>(
+    functions: &'a HashMap<FunctionId, Function>,
+    local_variables: &'a HashMap<Symbol, Type>,
+) -> impl Iterator<Item = &'a str> {
+    functions
+        .values()
+        .map(|function| function.name().as_str())
+        .chain(local_variables.keys().map(|name| name.as_str()))
+        .map(|name| name.rsplit("::").next().unwrap_or(name))
+}
+
+/// a help line suggesting the candidate closest to `name`, if there is one close enough to be a likely typo
+pub(super) fn did_you_mean<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> String {
+    match closest_match(name, candidates) {
+        Some(candidate) => format!("\nhelp: did you mean \"{candidate}\"?"),
+        None => String::new(),
+    }
+}
+
+/// the candidate with the smallest edit distance from `name`. Longer names allow more edits, so a name is only matched
+/// when about a third of it has been mistyped at most. At least one character has to be kept, otherwise any one-letter
+/// name would be suggested for any other
+fn closest_match<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let length = name.chars().count();
+    let max_distance = (length / 3).max(1).min(length.saturating_sub(1));
+
+    candidates
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        // ties are broken alphabetically, so the suggestion doesn't depend on the order of the candidates
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// the number of characters that need to be inserted, removed, replaced, or swapped with their neighbour to turn one
+/// string into the other
+fn edit_distance(from: &str, to: &str) -> usize {
+    let from: Vec<_> = from.chars().collect();
+    let to: Vec<_> = to.chars().collect();
+
+    // distances[i][j] is the distance between the first i characters of `from` and the first j characters of `to`
+    let mut distances = vec![vec![0; to.len() + 1]; from.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=from.len() {
+        for j in 1..=to.len() {
+            let replace_cost = usize::from(from[i - 1] != to[j - 1]);
+
+            distances[i][j] = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + replace_cost);
+
+            if i > 1 && j > 1 && from[i - 1] == to[j - 2] && from[i - 2] == to[j - 1] {
+                distances[i][j] = distances[i][j].min(distances[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    distances[from.len()][to.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest_match, did_you_mean, edit_distance};
+
+    #[test]
+    fn edit_distance_of_typos() {
+        assert_eq!(edit_distance("fibonacci", "fibonacci"), 0);
+        assert_eq!(edit_distance("fibonacci", "fibonnacci"), 1);
+        assert_eq!(edit_distance("count", "conut"), 1);
+        assert_eq!(edit_distance("limit", "lmt"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn closest_match_picks_smallest_distance() {
+        let candidates = ["fibonnacci", "fibonacci_sequence", "print"];
+
+        assert_eq!(
+            closest_match("fibonacci", candidates.into_iter()),
+            Some("fibonnacci")
+        );
+    }
+
+    #[test]
+    fn closest_match_too_different() {
+        let candidates = ["print", "helper"];
+
+        assert_eq!(closest_match("total", candidates.into_iter()), None);
+    }
+
+    #[test]
+    fn closest_match_one_letter_name() {
+        let candidates = ["f", "xs"];
+
+        assert_eq!(closest_match("x", candidates.into_iter()), None);
+    }
+
+    #[test]
+    fn did_you_mean_help() {
+        assert_eq!(
+            did_you_mean("prnt", ["print"].into_iter()),
+            "\nhelp: did you mean \"print\"?"
+        );
+        assert_eq!(did_you_mean("prnt", ["helper"].into_iter()), "");
+    }
+}
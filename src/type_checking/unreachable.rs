@@ -0,0 +1,245 @@
+use crate::ast::node::{Ast, Expression, Function, Node, Operation, Span};
+
+use super::{control_flow::node_always_returns, Lint, TypeCheckingWarning};
+
+impl Ast {
+    /// find the statements that can never run, as they come after a return statement, or after an if statement that
+    /// returns on every path
+    pub fn find_unreachable_code(&self) -> Vec<TypeCheckingWarning> {
+        let mut warnings = Vec::new();
+
+        for function in self.functions.values() {
+            if let Function::CustomFunction { body, .. } = function {
+                find_in_nodes(body, &mut warnings);
+            }
+        }
+
+        for expression in self
+            .constants
+            .iter()
+            .map(|constant| &constant.value)
+            .chain(self.globals.iter().map(|global| &global.value))
+        {
+            find_in_expression(expression, &mut warnings);
+        }
+
+        find_in_nodes(&self.nodes, &mut warnings);
+
+        warnings.sort_by_key(|warning| warning.span.start_line);
+        warnings
+    }
+}
+
+fn find_in_nodes(nodes: &[Node], warnings: &mut Vec<TypeCheckingWarning>) {
+    for (index, node) in nodes.iter().enumerate() {
+        find_in_node(node, warnings);
+
+        if !node_always_returns(node) {
+            continue;
+        }
+
+        // the unreachable statements aren't checked themselves, as they're reported as a whole
+        let unreachable = &nodes[index + 1..];
+        if let (Some(first), Some(last)) = (unreachable.first(), unreachable.last()) {
+            let reason = match node {
+                Node::IfStatement(_) => format!(
+                    "the if statement at line {} returns on every path before it",
+                    node.line()
+                ),
+                _ => format!("it comes after the return at line {}", node.line()),
+            };
+
+            warnings.push(TypeCheckingWarning {
+                lint: Lint::UnreachableCode,
+                message: format!("unreachable code; {reason}"),
+                span: Span {
+                    start_line: first.line(),
+                    end_line: last.line(),
+                },
+            });
+        }
+
+        return;
+    }
+}
+
+fn find_in_node(node: &Node, warnings: &mut Vec<TypeCheckingWarning>) {
+    match node {
+        Node::VariableDeclaration { value, .. }
+        | Node::DestructuringDeclaration { value, .. }
+        | Node::Assignment { value, .. } => find_in_expression(value, warnings),
+        Node::FunctionReturn { return_value, .. } => {
+            if let Some(return_value) = return_value {
+                find_in_expression(return_value, warnings);
+            }
+        }
+        Node::FunctionCall(function_call) => {
+            for parameter in &function_call.parameters {
+                find_in_expression(parameter, warnings);
+            }
+        }
+        Node::IfStatement(if_statement) => {
+            find_in_expression(&if_statement.check_expression, warnings);
+            find_in_nodes(&if_statement.if_block, warnings);
+
+            for else_if_block in &if_statement.else_if_blocks {
+                find_in_expression(&else_if_block.check, warnings);
+                find_in_nodes(&else_if_block.block, warnings);
+            }
+
+            if let Some(else_block) = &if_statement.else_block {
+                find_in_nodes(else_block, warnings);
+            }
+        }
+    }
+}
+
+/// lambdas have their own bodies, which can contain unreachable code
+fn find_in_expression(expression: &Expression, warnings: &mut Vec<TypeCheckingWarning>) {
+    match expression {
+        Expression::ValueLiteral(_)
+        | Expression::VariableAccess(_)
+        | Expression::LocalVariableAccess { .. } => {}
+        Expression::FunctionCall(function_call) => {
+            for parameter in &function_call.parameters {
+                find_in_expression(parameter, warnings);
+            }
+        }
+        Expression::Operation(Operation::Unary { value, .. }) => {
+            find_in_expression(value, warnings)
+        }
+        Expression::Operation(Operation::Binary { left, right, .. }) => {
+            find_in_expression(left, warnings);
+            find_in_expression(right, warnings);
+        }
+        Expression::Lambda(lambda) => find_in_nodes(&lambda.body, warnings),
+        Expression::Tuple(elements) => {
+            for element in elements {
+                find_in_expression(element, warnings);
+            }
+        }
+        Expression::TupleAccess { tuple, .. } => find_in_expression(tuple, warnings),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{builders::ast_builder::AstBuilder, node::Span},
+        type_checking::{Lint, TypeCheckingWarning},
+    };
+
+    fn print(ast: AstBuilder, line: u32) -> AstBuilder {
+        ast.statement(|statement| {
+            statement.line(line).function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| parameter.value_literal(1.into()))
+                    .build()
+            })
+        })
+    }
+
+    fn return_void(ast: AstBuilder, line: u32) -> AstBuilder {
+        ast.statement(|statement| statement.line(line).return_void())
+    }
+
+    #[test]
+    fn unreachable_after_return() {
+        let ast = print(AstBuilder::default(), 1);
+        let ast = return_void(ast, 2);
+        let ast = print(print(ast, 3), 4);
+
+        let warnings = ast.build().find_unreachable_code();
+
+        assert_eq!(
+            warnings,
+            vec![TypeCheckingWarning {
+                lint: Lint::UnreachableCode,
+                message: "unreachable code; it comes after the return at line 2".to_owned(),
+                span: Span {
+                    start_line: 3,
+                    end_line: 4
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn unreachable_after_if_returning_on_every_path() {
+        let ast = AstBuilder::default().statement(|statement| {
+            statement.line(1).if_statement(|if_statement| {
+                if_statement
+                    .check_expression(|check| check.value_literal(true.into()))
+                    .body(|body| return_void(body, 2).build())
+                    .else_if(
+                        |check| check.value_literal(false.into()),
+                        |body| return_void(body, 4).build(),
+                    )
+                    .else_block(|body| return_void(body, 6).build())
+                    .build()
+            })
+        });
+
+        let warnings = print(ast, 8).build().find_unreachable_code();
+
+        assert_eq!(
+            warnings,
+            vec![TypeCheckingWarning {
+                lint: Lint::UnreachableCode,
+                message:
+                    "unreachable code; the if statement at line 1 returns on every path before it"
+                        .to_owned(),
+                span: Span {
+                    start_line: 8,
+                    end_line: 8
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn unreachable_inside_lambda() {
+        let warnings = AstBuilder::default()
+            .statement(|statement| {
+                statement.line(1).function_call(|call| {
+                    call.function_id("run")
+                        .parameter(|parameter| {
+                            parameter.lambda(|lambda| {
+                                lambda
+                                    .parameters(Vec::new())
+                                    .void()
+                                    .body(|body| print(return_void(body, 2), 3).build())
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build()
+            .find_unreachable_code();
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].span,
+            Span {
+                start_line: 3,
+                end_line: 3
+            }
+        );
+    }
+
+    #[test]
+    fn no_unreachable_code_after_if_without_else() {
+        let ast = AstBuilder::default().statement(|statement| {
+            statement.line(1).if_statement(|if_statement| {
+                if_statement
+                    .check_expression(|check| check.value_literal(true.into()))
+                    .body(|body| return_void(body, 2).build())
+                    .build()
+            })
+        });
+
+        let warnings = print(ast, 4).build().find_unreachable_code();
+
+        assert!(warnings.is_empty());
+    }
+}
@@ -0,0 +1,536 @@
+use std::collections::HashSet;
+
+use crate::ast::{
+    node::{Ast, Expression, Function, FunctionId, FunctionParameter, Node, Operation, Span},
+    symbol::Symbol,
+};
+
+use super::{Lint, TypeCheckingWarning};
+
+impl Ast {
+    /// find the variables and parameters that are declared but never read.
+    /// Names starting with `_` are never reported, so a parameter can be left unused on purpose
+    pub fn find_unused_variables(&self) -> Vec<TypeCheckingWarning> {
+        let mut walker = UsageWalker::default();
+
+        for function in self.functions.values() {
+            if let Function::CustomFunction {
+                name,
+                parameters,
+                body,
+                span,
+                ..
+            } = function
+            {
+                walker.walk_function(&format!("`{name}`"), parameters, body, span.start_line);
+            }
+        }
+
+        for expression in self
+            .constants
+            .iter()
+            .map(|constant| &constant.value)
+            .chain(self.globals.iter().map(|global| &global.value))
+        {
+            walker.walk_expression(expression);
+        }
+
+        walker.walk_block(&self.nodes);
+
+        let mut warnings = walker.warnings;
+        warnings.sort_by_key(|warning| warning.span.start_line);
+        warnings
+    }
+
+    /// find the functions declared in this ast that can never be called when it is run, as they aren't reachable from
+    /// its statements, constants or globals. `imports` holds the functions of the other modules, which can call back
+    /// into the functions of this ast when they're passed as values
+    pub fn find_unused_functions(&self, imports: &Ast) -> Vec<TypeCheckingWarning> {
+        let mut walker = UsageWalker::default();
+
+        walker.walk_block(&self.nodes);
+        for expression in self
+            .constants
+            .iter()
+            .map(|constant| &constant.value)
+            .chain(self.globals.iter().map(|global| &global.value))
+        {
+            walker.walk_expression(expression);
+        }
+
+        let mut reachable = HashSet::new();
+        let mut pending: Vec<_> = walker.references.drain().collect();
+
+        while let Some(reference) = pending.pop() {
+            let id = FunctionId(reference);
+            let Some(Function::CustomFunction {
+                parameters, body, ..
+            }) = self
+                .functions
+                .get(&id)
+                .or_else(|| imports.functions.get(&id))
+            else {
+                continue;
+            };
+
+            if !reachable.insert(id) {
+                continue;
+            }
+
+            walker.walk_function("", parameters, body, 0);
+            pending.extend(walker.references.drain());
+        }
+
+        let mut warnings: Vec<_> = self
+            .functions
+            .values()
+            .filter_map(|function| match function {
+                Function::CustomFunction { id, name, span, .. }
+                    if !reachable.contains(id) && !name.as_str().starts_with('_') =>
+                {
+                    Some(TypeCheckingWarning {
+                        lint: Lint::UnusedFunctions,
+                        message: format!("function `{name}` is never called"),
+                        span: *span,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        warnings.sort_by_key(|warning| warning.span.start_line);
+        warnings
+    }
+}
+
+/// a variable or parameter that is visible in the current scope
+struct Declaration {
+    name: Symbol,
+    used: bool,
+    lint: Lint,
+    message: String,
+    line: u32,
+}
+
+/// walks through the scopes of the program, recording which declarations are read.
+/// Names that don't refer to a declaration in scope are recorded in `references`, as they refer to functions, constants
+/// or globals
+#[derive(Default)]
+struct UsageWalker {
+    scopes: Vec<Vec<Declaration>>,
+    references: HashSet<Symbol>,
+    warnings: Vec<TypeCheckingWarning>,
+}
+
+impl UsageWalker {
+    /// walk the body of a function or lambda, whose parameters are declared in the scope of its body.
+    /// `owner` describes the function in the messages of its unused parameters
+    fn walk_function(
+        &mut self,
+        owner: &str,
+        parameters: &[FunctionParameter],
+        body: &[Node],
+        line: u32,
+    ) {
+        // a function can't see the variables of the scope it is declared in, unless it is a lambda capturing them
+        self.scopes.push(
+            parameters
+                .iter()
+                .map(|parameter| Declaration {
+                    name: parameter.name(),
+                    used: false,
+                    lint: Lint::UnusedParameters,
+                    message: format!("parameter `{}` of {owner} is never used", parameter.name()),
+                    line,
+                })
+                .collect(),
+        );
+
+        self.walk_nodes(body);
+        self.pop_scope();
+    }
+
+    fn walk_block(&mut self, nodes: &[Node]) {
+        self.scopes.push(Vec::new());
+        self.walk_nodes(nodes);
+        self.pop_scope();
+    }
+
+    fn walk_nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.walk_node(node);
+        }
+    }
+
+    fn walk_node(&mut self, node: &Node) {
+        match node {
+            Node::VariableDeclaration {
+                var_name,
+                value,
+                line,
+                ..
+            } => {
+                self.walk_expression(value);
+                self.declare_variable(*var_name, *line);
+            }
+            Node::DestructuringDeclaration {
+                var_names,
+                value,
+                line,
+                ..
+            } => {
+                self.walk_expression(value);
+                for var_name in var_names {
+                    self.declare_variable(*var_name, *line);
+                }
+            }
+            Node::FunctionReturn { return_value, .. } => {
+                if let Some(return_value) = return_value {
+                    self.walk_expression(return_value);
+                }
+            }
+            Node::FunctionCall(function_call) => {
+                self.use_name(function_call.function_id.0);
+                for parameter in &function_call.parameters {
+                    self.walk_expression(parameter);
+                }
+            }
+            Node::IfStatement(if_statement) => {
+                self.walk_expression(&if_statement.check_expression);
+                self.walk_block(&if_statement.if_block);
+
+                for else_if_block in &if_statement.else_if_blocks {
+                    self.walk_expression(&else_if_block.check);
+                    self.walk_block(&else_if_block.block);
+                }
+
+                if let Some(else_block) = &if_statement.else_block {
+                    self.walk_block(else_block);
+                }
+            }
+            // only globals can be assigned to, and assigning to one isn't reading it
+            Node::Assignment { value, .. } => self.walk_expression(value),
+        }
+    }
+
+    fn walk_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::ValueLiteral(_) => {}
+            Expression::VariableAccess(name) | Expression::LocalVariableAccess { name, .. } => {
+                self.use_name(*name)
+            }
+            Expression::FunctionCall(function_call) => {
+                self.use_name(function_call.function_id.0);
+                for parameter in &function_call.parameters {
+                    self.walk_expression(parameter);
+                }
+            }
+            Expression::Operation(Operation::Unary { value, .. }) => self.walk_expression(value),
+            Expression::Operation(Operation::Binary { left, right, .. }) => {
+                self.walk_expression(left);
+                self.walk_expression(right);
+            }
+            Expression::Lambda(lambda) => self.walk_function(
+                "the lambda",
+                &lambda.parameters,
+                &lambda.body,
+                lambda.span.start_line,
+            ),
+            Expression::Tuple(elements) => {
+                for element in elements {
+                    self.walk_expression(element);
+                }
+            }
+            Expression::TupleAccess { tuple, .. } => self.walk_expression(tuple),
+        }
+    }
+
+    fn declare_variable(&mut self, name: Symbol, line: u32) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(Declaration {
+                name,
+                used: false,
+                lint: Lint::UnusedVariables,
+                message: format!("variable `{name}` is never used"),
+                line,
+            });
+        }
+    }
+
+    /// mark the declaration `name` refers to as used. Variables can't shadow each other, so the first declaration found
+    /// is the one being referred to
+    fn use_name(&mut self, name: Symbol) {
+        let declaration = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut())
+            .find(|declaration| declaration.name == name);
+
+        match declaration {
+            Some(declaration) => declaration.used = true,
+            None => {
+                self.references.insert(name);
+            }
+        }
+    }
+
+    /// end the innermost scope, reporting its declarations that were never used
+    fn pop_scope(&mut self) {
+        let Some(scope) = self.scopes.pop() else {
+            return;
+        };
+
+        self.warnings.extend(
+            scope
+                .into_iter()
+                .filter(|declaration| {
+                    !declaration.used && !declaration.name.as_str().starts_with('_')
+                })
+                .map(|declaration| TypeCheckingWarning {
+                    lint: declaration.lint,
+                    message: declaration.message,
+                    span: Span {
+                        start_line: declaration.line,
+                        end_line: declaration.line,
+                    },
+                }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{
+            builders::ast_builder::AstBuilder,
+            node::{Ast, Span, Type},
+        },
+        type_checking::{Lint, TypeCheckingWarning},
+    };
+
+    #[test]
+    fn unused_variable() {
+        let warnings = AstBuilder::default()
+            .statement(|statement| {
+                statement.line(1).var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("used")
+                        .with_assignment(|value| value.value_literal(1.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.line(2).var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("unused")
+                        .with_assignment(|value| value.value_literal(1.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.function_call(|call| {
+                    call.function_id("print")
+                        .parameter(|parameter| parameter.variable("used"))
+                        .build()
+                })
+            })
+            .build()
+            .find_unused_variables();
+
+        assert_eq!(
+            warnings,
+            vec![TypeCheckingWarning {
+                lint: Lint::UnusedVariables,
+                message: "variable `unused` is never used".to_owned(),
+                span: Span {
+                    start_line: 2,
+                    end_line: 2
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn unused_variable_in_if_block() {
+        let warnings = AstBuilder::default()
+            .statement(|statement| {
+                statement.line(1).var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("limit")
+                        .with_assignment(|value| value.value_literal(1.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.line(2).if_statement(|if_statement| {
+                    if_statement
+                        .check_expression(|check| check.variable("limit"))
+                        .body(|body| {
+                            body.statement(|statement| {
+                                statement.line(3).var_declaration(|declaration| {
+                                    declaration
+                                        .infer_type()
+                                        .name("next")
+                                        .with_assignment(|value| value.value_literal(1.into()))
+                                })
+                            })
+                            .build()
+                        })
+                        .build()
+                })
+            })
+            .build()
+            .find_unused_variables();
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "variable `next` is never used");
+    }
+
+    #[test]
+    fn variable_used_by_lambda() {
+        let warnings = AstBuilder::default()
+            .statement(|statement| {
+                statement.line(1).var_declaration(|declaration| {
+                    declaration
+                        .infer_type()
+                        .name("offset")
+                        .with_assignment(|value| value.value_literal(1.into()))
+                })
+            })
+            .statement(|statement| {
+                statement.line(2).function_call(|call| {
+                    call.function_id("run")
+                        .parameter(|parameter| {
+                            parameter.lambda(|lambda| {
+                                lambda
+                                    .parameters(vec![(Type::UInt, "value".into()).into()])
+                                    .void()
+                                    .body(|body| {
+                                        body.statement(|statement| {
+                                            statement.function_call(|call| {
+                                                call.function_id("print")
+                                                    .parameter(|parameter| {
+                                                        parameter.variable("offset")
+                                                    })
+                                                    .build()
+                                            })
+                                        })
+                                        .statement(|statement| {
+                                            statement.function_call(|call| {
+                                                call.function_id("print")
+                                                    .parameter(|parameter| {
+                                                        parameter.variable("value")
+                                                    })
+                                                    .build()
+                                            })
+                                        })
+                                        .build()
+                                    })
+                            })
+                        })
+                        .build()
+                })
+            })
+            .build()
+            .find_unused_variables();
+
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn unused_parameter() {
+        let warnings = AstBuilder::default()
+            .function_declaration(|function| {
+                function
+                    .name("double")
+                    .parameters(vec![
+                        (Type::UInt, "value".into()).into(),
+                        (Type::UInt, "_ignored".into()).into(),
+                    ])
+                    .void()
+                    .span(10, 12)
+                    .body(|body| body.build())
+            })
+            .build()
+            .find_unused_variables();
+
+        assert_eq!(
+            warnings,
+            vec![TypeCheckingWarning {
+                lint: Lint::UnusedParameters,
+                message: "parameter `value` of `double` is never used".to_owned(),
+                span: Span {
+                    start_line: 10,
+                    end_line: 10
+                },
+            }]
+        );
+    }
+
+    /// declare a function with no parameters, that calls each of `calls`
+    fn calling(ast: AstBuilder, name: &'static str, calls: &'static [&'static str]) -> AstBuilder {
+        ast.function_declaration(|function| {
+            function
+                .name(name)
+                .parameters(Vec::new())
+                .void()
+                .body(|body| {
+                    calls
+                        .iter()
+                        .fold(body, |body, callee| {
+                            body.statement(|statement| {
+                                statement.function_call(|call| {
+                                    call.function_id(*callee).no_parameters().build()
+                                })
+                            })
+                        })
+                        .build()
+                })
+        })
+    }
+
+    #[test]
+    fn unused_function() {
+        let ast = AstBuilder::default().statement(|statement| {
+            statement.function_call(|call| call.function_id("main").no_parameters().build())
+        });
+        let ast = calling(ast, "main", &["helper"]);
+        let ast = calling(ast, "helper", &[]);
+        let ast = calling(ast, "unused", &["recursive"]);
+        let ast = calling(ast, "recursive", &["unused"]);
+
+        let warnings = ast.build().find_unused_functions(&Ast::default());
+
+        let mut messages: Vec<_> = warnings.iter().map(|warning| &warning.message).collect();
+        messages.sort();
+
+        assert_eq!(
+            messages,
+            vec![
+                "function `recursive` is never called",
+                "function `unused` is never called"
+            ]
+        );
+        assert!(warnings
+            .iter()
+            .all(|warning| warning.lint == Lint::UnusedFunctions));
+    }
+
+    #[test]
+    fn function_used_as_value() {
+        let ast = AstBuilder::default().statement(|statement| {
+            statement.function_call(|call| {
+                call.function_id("print")
+                    .parameter(|parameter| parameter.variable("helper"))
+                    .build()
+            })
+        });
+
+        let warnings = calling(ast, "helper", &[])
+            .build()
+            .find_unused_functions(&Ast::default());
+
+        assert!(warnings.is_empty());
+    }
+}
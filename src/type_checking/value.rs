@@ -5,6 +5,11 @@ impl Value {
         match self {
             Value::UInt(_) => Type::UInt,
             Value::Boolean(_) => Type::Boolean,
+            Value::Function(function_value) => function_value
+                .function
+                .get_type()
+                .expect("only functions with a type can be used as values"),
+            Value::Tuple(elements) => Type::Tuple(elements.iter().map(Value::get_type).collect()),
         }
     }
 }
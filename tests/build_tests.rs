@@ -0,0 +1,70 @@
+use std::{env, process::Command};
+
+#[test]
+fn built_example_runs_like_the_source() {
+    let output_file = env::temp_dir().join("beach_build_tests_overflow.bchc");
+    let output_file = output_file.to_str().unwrap();
+
+    let mut command = Command::new("cargo");
+    command.args(vec![
+        "run",
+        "build",
+        "--output",
+        output_file,
+        "./examples/overflow.bch",
+    ]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    assert!(output_result.unwrap().status.success());
+
+    let mut command = Command::new("cargo");
+    command.args(vec!["run", "run", output_file]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+
+    let stdout = output.stdout;
+
+    let expected = format!(
+        "Runtime error in {} at line 2: 1 + 4294967295 is too large for a uint
+    at add (line 2)
+    at total (line 6)
+    at top level (line 10)
+",
+        output_file
+    );
+
+    assert_eq!(String::from_utf8_lossy(&stdout), expected);
+}
+
+#[test]
+fn source_file_is_not_a_compiled_program() {
+    let output_file = env::temp_dir().join("beach_build_tests_source.bchc");
+    std::fs::copy("./examples/fibonacci.bch", &output_file).unwrap();
+    let output_file = output_file.to_str().unwrap();
+
+    let mut command = Command::new("cargo");
+    command.args(vec!["run", "run", output_file]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = output.stdout;
+
+    let expected = format!(
+        "Failed to run {}: not a compiled beach program\n",
+        output_file
+    );
+
+    assert_eq!(String::from_utf8_lossy(&stdout), expected);
+}
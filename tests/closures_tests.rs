@@ -0,0 +1,24 @@
+use std::process::Command;
+
+#[test]
+fn closures_example_executes_successfully() {
+    let mut command = Command::new("cargo");
+    command.args(vec!["run", "run", "./examples/closures.bch"]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert!(output.status.success());
+
+    let stdout = output.stdout;
+
+    let expected: Vec<u8> = "8
+14
+5
+"
+    .into();
+
+    assert_eq!(stdout, expected);
+}
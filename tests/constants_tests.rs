@@ -0,0 +1,24 @@
+use std::process::Command;
+
+#[test]
+fn constants_example_executes_successfully() {
+    let mut command = Command::new("cargo");
+    command.args(vec!["run", "run", "./examples/constants.bch"]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert!(output.status.success());
+
+    let stdout = output.stdout;
+
+    let expected: Vec<u8> = "20001
+true
+false
+"
+    .into();
+
+    assert_eq!(stdout, expected);
+}
@@ -0,0 +1,48 @@
+use std::process::Command;
+
+#[test]
+fn effects_example_executes_successfully() {
+    let mut command = Command::new("cargo");
+    command.args(vec!["run", "run", "./examples/effects.bch"]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert!(output.status.success());
+
+    let stdout = output.stdout;
+
+    let expected: Vec<u8> = "14\n".into();
+
+    assert_eq!(stdout, expected);
+}
+
+#[test]
+fn effects_example_explains_effects() {
+    let mut command = Command::new("cargo");
+    command.args(vec![
+        "run",
+        "check",
+        "--explain-effects",
+        "./examples/effects.bch",
+    ]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert!(output.status.success());
+
+    let stdout = output.stdout;
+
+    let expected: Vec<u8> = "double is pure
+sum_of_doubles is pure
+report is effectful: it calls print at line 10
+"
+    .into();
+
+    assert_eq!(stdout, expected);
+}
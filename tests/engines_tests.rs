@@ -0,0 +1,47 @@
+use std::process::{Command, Output};
+
+fn run_example(engine: &str, args: &[&str]) -> Output {
+    let mut command = Command::new("cargo");
+    command.args(["run", "run", engine]).args(args);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    output_result.unwrap()
+}
+
+fn assert_engines_match(args: &[&str]) {
+    let tree = run_example("--engine=tree", args);
+    let vm = run_example("--engine=vm", args);
+
+    assert_eq!(
+        String::from_utf8_lossy(&tree.stdout),
+        String::from_utf8_lossy(&vm.stdout)
+    );
+    assert_eq!(tree.status.code(), vm.status.code());
+}
+
+#[test]
+fn engines_match_on_examples() {
+    for example in [
+        "./examples/closures.bch",
+        "./examples/constants.bch",
+        "./examples/effects.bch",
+        "./examples/fibonacci.bch",
+        "./examples/folding.bch",
+        "./examples/generics.bch",
+        "./examples/globals.bch",
+        "./examples/imports/main.bch",
+        "./examples/recursion.bch",
+        "./examples/tuples.bch",
+        "./examples/type_aliases.bch",
+    ] {
+        assert_engines_match(&[example]);
+    }
+}
+
+#[test]
+fn engines_match_on_runtime_errors() {
+    assert_engines_match(&["./examples/overflow.bch"]);
+    assert_engines_match(&["--max-call-depth", "3", "./examples/recursion.bch"]);
+}
@@ -0,0 +1,25 @@
+use std::process::Command;
+
+#[test]
+fn generics_example_executes_successfully() {
+    let mut command = Command::new("cargo");
+    command.args(vec!["run", "run", "./examples/generics.bch"]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert!(output.status.success());
+
+    let stdout = output.stdout;
+
+    let expected: Vec<u8> = "5
+true
+10
+false
+"
+    .into();
+
+    assert_eq!(stdout, expected);
+}
@@ -14,7 +14,10 @@ fn help_command_prints_correctly() {
     let stdout = output.stdout;
     let expected: Vec<u8> = "usage: beach [command] [command_args]
 \thelp\tprints help information for the beach cli
-\trun\trun a beach program\n"
+\tbuild\tcompile a beach program to a .bchc file, which beach run can run
+\trun\trun a beach program
+\tast\tprint a beach program as it is run, after its modules are linked
+\tcheck\tcheck a beach program for errors without running it\n"
         .into();
 
     assert_eq!(stdout, expected);
@@ -35,7 +38,10 @@ fn help_command_prints_when_no_args_are_provided() {
     let stdout = output.stdout;
     let expected: Vec<u8> = "usage: beach [command] [command_args]
 \thelp\tprints help information for the beach cli
-\trun\trun a beach program\n"
+\tbuild\tcompile a beach program to a .bchc file, which beach run can run
+\trun\trun a beach program
+\tast\tprint a beach program as it is run, after its modules are linked
+\tcheck\tcheck a beach program for errors without running it\n"
         .into();
 
     assert_eq!(stdout, expected);
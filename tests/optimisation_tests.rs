@@ -0,0 +1,90 @@
+use std::process::{Command, Output};
+
+fn beach(args: &[&str]) -> Output {
+    let mut command = Command::new("cargo");
+    command.arg("run").args(args);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    output_result.unwrap()
+}
+
+fn assert_optimised_matches(engine: &str, example: &str) {
+    let unoptimised = beach(&["run", engine, example]);
+    let optimised = beach(&["run", engine, "-O", example]);
+
+    assert_eq!(
+        String::from_utf8_lossy(&unoptimised.stdout),
+        String::from_utf8_lossy(&optimised.stdout)
+    );
+    assert_eq!(unoptimised.status.code(), optimised.status.code());
+}
+
+#[test]
+fn optimised_examples_match() {
+    for example in [
+        "./examples/closures.bch",
+        "./examples/constants.bch",
+        "./examples/effects.bch",
+        "./examples/fibonacci.bch",
+        "./examples/folding.bch",
+        "./examples/generics.bch",
+        "./examples/globals.bch",
+        "./examples/imports/main.bch",
+        "./examples/recursion.bch",
+        "./examples/tuples.bch",
+        "./examples/type_aliases.bch",
+    ] {
+        assert_optimised_matches("--engine=tree", example);
+        assert_optimised_matches("--engine=vm", example);
+    }
+}
+
+/// the message of the runtime error the program stopped with, without the line it was reported at
+fn runtime_error(output: &Output) -> String {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let first_line = stdout.lines().next().unwrap_or_default();
+
+    first_line
+        .split_once(": ")
+        .map_or(first_line, |(_, message)| message)
+        .to_owned()
+}
+
+#[test]
+fn optimised_runtime_error_matches() {
+    // the overflow happens in a function that's inlined, so it's reported at the line of the call
+    for engine in ["--engine=tree", "--engine=vm"] {
+        let unoptimised = beach(&["run", engine, "./examples/overflow.bch"]);
+        let optimised = beach(&["run", engine, "-O", "./examples/overflow.bch"]);
+
+        assert_eq!(runtime_error(&unoptimised), runtime_error(&optimised));
+        assert_eq!(
+            runtime_error(&optimised),
+            "1 + 4294967295 is too large for a uint"
+        );
+        assert_eq!(unoptimised.status.code(), optimised.status.code());
+    }
+}
+
+#[test]
+fn folding_example_prints_optimised_ast() {
+    let output = beach(&["ast", "--optimized", "./examples/folding.bch"]);
+
+    assert!(output.status.success());
+
+    let expected: Vec<u8> = "const LIMIT: uint = 10;
+
+function within_limit(uint value) -> boolean {
+    return 10 > value;
+}
+
+infer pair = (3, true);
+print(pair.0);
+print(false);
+"
+    .into();
+
+    assert_eq!(output.stdout, expected);
+}
@@ -0,0 +1,26 @@
+use std::process::Command;
+
+#[test]
+fn overflow_example_reports_runtime_error() {
+    let mut command = Command::new("cargo");
+    command.args(vec!["run", "run", "./examples/overflow.bch"]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+
+    let stdout = output.stdout;
+
+    let expected: Vec<u8> =
+        "Runtime error in ./examples/overflow.bch at line 2: 1 + 4294967295 is too large for a uint
+    at add (line 2)
+    at total (line 6)
+    at top level (line 10)
+"
+        .into();
+
+    assert_eq!(stdout, expected);
+}
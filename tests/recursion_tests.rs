@@ -0,0 +1,56 @@
+use std::process::Command;
+
+#[test]
+fn recursion_example_executes_successfully() {
+    let mut command = Command::new("cargo");
+    command.args(vec!["run", "run", "./examples/recursion.bch"]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert!(output.status.success());
+
+    let stdout = output.stdout;
+
+    let expected: Vec<u8> = "1001
+1001
+"
+    .into();
+
+    assert_eq!(stdout, expected);
+}
+
+#[test]
+fn recursion_example_reports_stack_overflow() {
+    let mut command = Command::new("cargo");
+    command.args(vec![
+        "run",
+        "run",
+        "--max-call-depth",
+        "3",
+        "./examples/recursion.bch",
+    ]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+
+    let stdout = output.stdout;
+
+    // the tail calls of count_up don't nest, so only nested_count overflows
+    let expected: Vec<u8> = "1001
+Runtime error in ./examples/recursion.bch at line 14: stack overflow in nested_count (depth 4)
+    at nested_count (line 14)
+    at nested_count (line 14)
+    at nested_count (line 14)
+    at top level (line 18)
+"
+    .into();
+
+    assert_eq!(stdout, expected);
+}
@@ -0,0 +1,27 @@
+use std::process::Command;
+
+#[test]
+fn tuples_example_executes_successfully() {
+    let mut command = Command::new("cargo");
+    command.args(vec!["run", "run", "./examples/tuples.bch"]);
+
+    let output_result = command.output();
+
+    assert!(output_result.is_ok());
+    let output = output_result.unwrap();
+
+    assert!(output.status.success());
+
+    let stdout = output.stdout;
+
+    let expected: Vec<u8> = "7
+9
+(7, true)
+true
+14
+false
+"
+    .into();
+
+    assert_eq!(stdout, expected);
+}